@@ -0,0 +1,22 @@
+#ifndef PROJ2TREE_H
+#define PROJ2TREE_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/*
+ * Сканирует директорию path с параметрами options (JSON-объект с ключами длинных флагов CLI,
+ * например {"only_ext": "rs,toml", "no_gitignore": true}; может быть NULL).
+ * Возвращает JSON {"root", "tree", "files", "errors"} или {"error": "..."}.
+ * Результат освобождается через proj2tree_free_string.
+ */
+char *proj2tree_scan_project(const char *path, const char *options);
+
+void proj2tree_free_string(char *value);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
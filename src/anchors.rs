@@ -0,0 +1,228 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use serde_json::{Value, json};
+
+use crate::atomic_file;
+use crate::escape::{display_path, parse_display_path};
+use crate::git;
+use crate::schema;
+use crate::translit;
+use crate::tree::{NodeKind, TreeNode};
+
+// Явные якоря `<a id="...">` для файлов и директорий (--anchors) и ссылки на файлы
+// в репозитории (--link-base). Автоматические якоря рендереров зависят от платформы
+// и не различают `a-b.rs` и `a/b.rs`, поэтому идентификаторы назначаются здесь.
+// --anchor-map FILE сохраняет соответствие путь -> якорь в JSON для внешних ссылок
+// (вики, трекер) и читает его на следующем запуске: путь, который уже был в карте,
+// сохраняет свой якорь, даже если новый файл с похожим именем сдвинул бы суффиксы
+
+// Якорь дерева файлов для ссылок «к дереву» (--nav-links)
+pub const TREE_ID: &str = "file-tree";
+
+#[derive(Debug, Default)]
+pub struct SlugMap {
+    slugs: HashMap<PathBuf, String>,
+    used: HashSet<String>,
+    // Якоря, уже вставленные в документ: директория получает якорь один раз
+    placed: HashSet<PathBuf>,
+    // --ascii-paths: идентификаторы строятся по транслитерированному пути
+    ascii: bool,
+}
+
+impl SlugMap {
+    pub fn new(ascii: bool) -> SlugMap {
+        SlugMap { ascii, ..Default::default() }
+    }
+
+    // Идентификаторы назначаются в порядке дерева до вывода документа, чтобы суффиксы
+    // у совпадающих имен не зависели от порядка содержимого (--order deps)
+    pub fn assign_tree(&mut self, base_dir: &Path, nodes: &[TreeNode]) {
+        for node in nodes {
+            if matches!(node.kind, NodeKind::Omitted(_) | NodeKind::Truncated(_)) {
+                continue;
+            }
+            self.slug(&crate::relative_path(base_dir, &node.path));
+            if let NodeKind::Directory(children) = &node.kind {
+                self.assign_tree(base_dir, children);
+            }
+        }
+    }
+
+    // Идентификатор, занятый самим документом: файл с таким же получит суффикс
+    pub fn reserve(&mut self, id: &str) {
+        self.used.insert(id.to_string());
+    }
+
+    // Якоря из карты прошлого запуска занимаются до назначения новых. Якорь путей, которых
+    // больше нет, остается занятым до конца запуска и в новую карту не попадает
+    pub fn reuse(&mut self, previous: BTreeMap<PathBuf, String>) {
+        for (path, slug) in previous {
+            if slug.is_empty() || slug == TREE_ID || self.used.contains(&slug) || self.slugs.contains_key(&path) {
+                continue;
+            }
+            self.used.insert(slug.clone());
+            self.slugs.insert(path, slug);
+        }
+    }
+
+    // Идентификатор заголовка, который рендерер выводит сам (html): попадает в карту якорей
+    pub fn place(&mut self, relative_path: &Path) -> String {
+        self.placed.insert(relative_path.to_path_buf());
+        self.slug(relative_path)
+    }
+
+    pub fn slug(&mut self, relative_path: &Path) -> String {
+        if let Some(slug) = self.slugs.get(relative_path) {
+            return slug.clone();
+        }
+
+        // Как у GitHub: повторяющийся идентификатор получает суффикс -1, -2, ...
+        let base = if self.ascii {
+            slugify(&translit::ascii_path(relative_path))
+        } else {
+            slugify(relative_path)
+        };
+        let mut slug = base.clone();
+        let mut suffix = 0;
+        while self.used.contains(&slug) {
+            suffix += 1;
+            slug = format!("{}-{}", base, suffix);
+        }
+
+        self.used.insert(slug.clone());
+        self.slugs.insert(relative_path.to_path_buf(), slug.clone());
+        slug
+    }
+
+    // Строка якорей для заголовка файла: сначала еще не размещенные родительские директории
+    pub fn anchor_line(&mut self, relative_path: &Path) -> String {
+        let mut paths: Vec<&Path> = relative_path
+            .ancestors()
+            .skip(1)
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .collect();
+        paths.reverse();
+        paths.push(relative_path);
+
+        let mut line = String::new();
+        for path in paths {
+            if self.placed.insert(path.to_path_buf()) {
+                let _ = write!(line, "<a id=\"{}\"></a>", self.slug(path));
+            }
+        }
+        line
+    }
+}
+
+pub fn slugify(path: &Path) -> String {
+    let mut slug = String::new();
+    for c in display_path(path).chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() || c == '_' {
+            slug.push(c);
+        } else if !slug.ends_with('-') && !slug.is_empty() {
+            slug.push('-');
+        }
+    }
+
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() { "file".to_string() } else { slug.to_string() }
+}
+
+// Плейсхолдер {ref} в --link-base заменяется на текущий коммит, чтобы ссылки
+// указывали ровно на ту версию, с которой снят снимок
+pub fn resolve_link_base(link_base: &str, target_dir: &Path) -> String {
+    let link_base = link_base.trim_end_matches('/');
+    if !link_base.contains("{ref}") {
+        return link_base.to_string();
+    }
+
+    match git::head_commit(target_dir) {
+        Some(commit) => link_base.replace("{ref}", &commit),
+        None => {
+            tracing::warn!("не удалось определить коммит для {{ref}} в --link-base, используется HEAD");
+            link_base.replace("{ref}", "HEAD")
+        }
+    }
+}
+
+pub fn link_url(link_base: &str, relative_path: &Path) -> String {
+    let mut url = link_base.to_string();
+    for component in relative_path.components() {
+        url.push('/');
+        for byte in component.as_os_str().to_string_lossy().bytes() {
+            if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+                url.push(byte as char);
+            } else {
+                let _ = write!(url, "%{:02X}", byte);
+            }
+        }
+    }
+    url
+}
+
+// Карта прошлого запуска. Без файла карта пуста; испорченный файл не мешает генерации
+pub fn load_map(path: &Path) -> BTreeMap<PathBuf, String> {
+    let Ok(text) = fs::read_to_string(path) else {
+        return BTreeMap::new();
+    };
+    let map = serde_json::from_str::<Value>(&text).ok();
+    let Some(anchors) = map.as_ref().and_then(|map| map.get("anchors")) else {
+        tracing::warn!("{}: не удалось разобрать карту якорей, якоря назначаются заново", path.display());
+        return BTreeMap::new();
+    };
+    anchors
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(path, slug)| Some((parse_display_path(path), slug.as_str()?.to_string())))
+        .collect()
+}
+
+// В карту попадают только якоря, выведенные в документ: ссылка по карте не ведет в пустоту.
+// Путь документа записывается без `./` у выходного файла по умолчанию
+pub fn write_map(path: &Path, document: Option<&Path>, slugs: &SlugMap) -> io::Result<()> {
+    let anchors: BTreeMap<String, &str> = slugs
+        .placed
+        .iter()
+        .filter_map(|placed| Some((display_path(placed), slugs.slugs.get(placed)?.as_str())))
+        .collect();
+    let document = document.map(|document| {
+        let document: PathBuf = document.components().filter(|c| *c != Component::CurDir).collect();
+        display_path(&document)
+    });
+    let map = json!({ "schema_version": schema::ANCHORS.version, "document": document, "anchors": anchors });
+    atomic_file::write(path, format!("{}\n", map))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn link_url_joins_segments_without_doubling_slashes() {
+        let base = resolve_link_base("https://github.com/org/repo/blob/main/", Path::new("."));
+        assert_eq!(base, "https://github.com/org/repo/blob/main");
+        assert_eq!(link_url(&base, Path::new("src/main.rs")), "https://github.com/org/repo/blob/main/src/main.rs");
+        assert_eq!(link_url(&base, Path::new("src")), "https://github.com/org/repo/blob/main/src");
+    }
+
+    #[test]
+    fn link_url_percent_encodes_each_segment() {
+        let url = link_url("https://host/blob/main", Path::new("my dir/ü#1?.rs"));
+        assert_eq!(url, "https://host/blob/main/my%20dir/%C3%BC%231%3F.rs");
+        // Разделитель сегментов остается `/`, а `%` и `&` в имени экранируются
+        assert_eq!(link_url("https://host", Path::new("a%b/c&d")), "https://host/a%25b/c%26d");
+        assert_eq!(link_url("https://host", Path::new("safe-._~name")), "https://host/safe-._~name");
+    }
+
+    #[test]
+    fn ref_placeholder_falls_back_to_head_outside_git() {
+        let dir = std::env::temp_dir().join(format!("proj2tree-link-base-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        assert_eq!(resolve_link_base("https://host/blob/{ref}/", &dir), "https://host/blob/HEAD");
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,118 @@
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+use crate::atomic_file::AtomicFile;
+use crate::doc_lang::fill;
+use crate::git;
+use crate::gitattributes;
+use crate::notices;
+use crate::tar::{self, EntryKind};
+use crate::vfs::{MemoryFs, MemoryKind};
+use crate::{AppConfig, OutputFormat, load_config_layers, load_project_config_values, write_markdown_format};
+
+// Снимок проекта из tar-потока (`git archive HEAD | proj2tree --stdin-tar`): архив целиком
+// читается в MemoryFs, и обход и документ строятся тем же кодом, что и для диска.
+// --at REF читает такие же записи из объектов репозитория сам, не трогая рабочее дерево
+
+pub fn run_stdin_tar(app_config: AppConfig) -> io::Result<()> {
+    let entries = tar::read_archive(io::stdin().lock())?;
+    run_archive(app_config, entries, "--stdin-tar", None)
+}
+
+pub fn run_git_revision(app_config: AppConfig, reference: &str) -> io::Result<()> {
+    let entries = git::revision(Path::new(&app_config.target_dir), reference)?;
+    run_archive(app_config, entries, "--at", Some(reference))
+}
+
+fn run_archive(
+    mut app_config: AppConfig,
+    entries: Vec<tar::Entry>,
+    option: &str,
+    reference: Option<&str>,
+) -> io::Result<()> {
+    if app_config.format != OutputFormat::Markdown {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} поддерживает только формат markdown", option),
+        ));
+    }
+
+    let mut archive = MemoryFs::default();
+    for entry in entries {
+        let kind = match entry.kind {
+            EntryKind::File => MemoryKind::File,
+            EntryKind::Directory => MemoryKind::Directory,
+            EntryKind::Symlink => MemoryKind::Symlink,
+        };
+        archive.insert(entry.path, kind, entry.data);
+    }
+    tracing::info!("Прочитано записей из архива: {}", archive.len());
+
+    app_config.fs = Box::new(archive);
+    // Номер устройства архива в памяти не с чем сравнивать, а путей на диске (--paths) у него нет
+    app_config.root_device = None;
+    app_config.path_prefix = PathBuf::new();
+    app_config.source = Some(match reference {
+        Some(reference) => fill(app_config.doc.git_revision, &[&reference]),
+        None => app_config.doc.stdin_archive.to_string(),
+    });
+    // Команды summarizers получают путь на диске, а файлов архива там нет
+    if app_config.run_summarizers {
+        tracing::warn!("--run-summarizers не поддерживается для {}, файлы выводятся как есть", option);
+        app_config.run_summarizers = false;
+    }
+    // .gitattributes и конфигурация проекта (.proj2tree.toml, Cargo.toml) тоже берутся из архива
+    if app_config.gitattributes.is_some() {
+        let attributes = gitattributes::load(Path::new(""), &|path| app_config.fs.read_to_string(path).ok());
+        app_config.gitattributes = Some(attributes);
+    }
+    let app_config = &app_config;
+
+    let config = load_config_layers(load_project_config_values(Path::new(""), &|path| {
+        app_config.fs.read_to_string(path).ok()
+    }));
+
+    let gitignore_matcher = gitignore_matcher(app_config)?;
+
+    if app_config.print_to_console {
+        let mut stdout = BufWriter::new(io::stdout().lock());
+        write_markdown_format(Path::new(""), &mut stdout, app_config, &config, &gitignore_matcher)?;
+        stdout.flush()?;
+    } else {
+        let output_file = app_config.output_file.as_deref().unwrap_or("tree.md");
+        let mut writer = AtomicFile::create(Path::new(output_file))?;
+        write_markdown_format(Path::new(""), &mut writer, app_config, &config, &gitignore_matcher)?;
+        writer.commit()?;
+        tracing::info!("Результат сохранен в файл: {}", output_file);
+    }
+
+    notices::finish(app_config)
+}
+
+// .gitignore берется из архива, а файлы --ignore-file - с диска
+fn gitignore_matcher(app_config: &AppConfig) -> io::Result<Option<Gitignore>> {
+    let mut sources = Vec::new();
+    if !app_config.no_gitignore
+        && let Ok(content) = app_config.fs.read_to_string(Path::new(".gitignore"))
+    {
+        sources.push(content);
+    }
+    for file in &app_config.ignore_files {
+        let content = fs::read_to_string(file).map_err(|e| {
+            io::Error::new(e.kind(), format!("не удалось прочитать {}: {}", file.display(), e))
+        })?;
+        sources.push(content);
+    }
+    if sources.is_empty() {
+        return Ok(None);
+    }
+
+    let mut builder = GitignoreBuilder::new("");
+    builder.case_insensitive(app_config.case_insensitive).map_err(|e| io::Error::other(e.to_string()))?;
+    for line in sources.iter().flat_map(|content| content.lines()) {
+        let _ = builder.add_line(None, line);
+    }
+    Ok(builder.build().ok())
+}
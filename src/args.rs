@@ -0,0 +1,765 @@
+use clap::builder::BoolishValueParser;
+use clap::{Arg, ArgAction, Command};
+
+use crate::{chunk, dir_size, doc_lang, embed, parse_sample_spec};
+
+// Аргументы командной строки и подкоманды. В AppConfig их переводит app_config_from_matches
+
+pub fn cli() -> Command {
+    Command::new(env!("CARGO_PKG_NAME"))
+        .about(env!("CARGO_PKG_DESCRIPTION"))
+        .version(env!("CARGO_PKG_VERSION"))
+        .author(env!("CARGO_PKG_AUTHORS"))
+        .arg(
+            Arg::new("directory")
+                .help("Целевая директория для анализа и отдельные файлы, которые включаются всегда, в обход фильтров")
+                .value_name("PATH")
+                .num_args(1..)
+                .default_value(".")
+                .index(1),
+        )
+        .arg(
+            Arg::new("output")
+                .help("Выходной файл")
+                .short('o')
+                .long("output")
+                .value_name("FILE")
+                .env("PROJ2TREE_OUTPUT"),
+        )
+        .arg(
+            Arg::new("no-tree")
+                .help("Не выводить дерево файлов")
+                .short('T')
+                .long("no-tree")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_NO_TREE")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("no-contents")
+                .help("Не выводить содержимое файлов")
+                .short('C')
+                .long("no-contents")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_NO_CONTENTS")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("print")
+                .help("Вывести результат в консоль")
+                .short('p')
+                .long("print")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("no-pager")
+                .help("Не использовать пейджер при выводе в консоль")
+                .long("no-pager")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_NO_PAGER")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("no-gitignore")
+                .help("Не учитывать правила из .gitignore")
+                .short('G')
+                .long("no-gitignore")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_NO_GITIGNORE")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("ignore-file")
+                .help("Дополнительный файл правил в формате .gitignore (можно повторять), пути отсчитываются от целевой директории")
+                .long("ignore-file")
+                .value_name("PATH")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("flat")
+                .help("Выводить дерево плоским списком относительных путей")
+                .long("flat")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_FLAT")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("compact-tree")
+                .help("Выводить цепочки директорий с единственной поддиректорией одной строкой (src/app/services/)")
+                .long("compact-tree")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_COMPACT_TREE")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("split-tests")
+                .help("Выводить тесты (tests/, *_test.go, *.spec.ts и т.п.) отдельными деревом и разделом содержимого после основного кода")
+                .long("split-tests")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_SPLIT_TESTS")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("quick")
+                .help("Быстрый обзор: содержимое только README, манифестов, точек входа, конфигурации и кода из src-подобных директорий в пределах 64 КиБ, дерево до глубины 3")
+                .long("quick")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_QUICK")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("build-section")
+                .help("Выводить файлы сборки и CI (Dockerfile, Makefile, .github/workflows, Jenkinsfile) отдельным разделом")
+                .long("build-section")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_BUILD_SECTION")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("unsafe-full-secrets")
+                .help("Не маскировать значения в .env, ключах и других файлах с секретами")
+                .long("unsafe-full-secrets")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("run-summarizers")
+                .help("Запускать команды [[summarizers]] из конфигурации и выводить их результат вместо содержимого файлов")
+                .long("run-summarizers")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("full-lockfiles")
+                .help("Выводить lock-файлы (Cargo.lock, package-lock.json и т.п.) полностью, а не сводкой")
+                .long("full-lockfiles")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_FULL_LOCKFILES")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("lfs-fetch")
+                .help("Выводить содержимое файлов Git LFS вместо пометки об указателе (через git lfs)")
+                .long("lfs-fetch")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_LFS_FETCH")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("normalize-eol")
+                .help("Переводы строк во встроенном содержимом: lf, crlf или keep (как в файле)")
+                .long("normalize-eol")
+                .value_name("EOL")
+                .value_parser(["lf", "crlf", "keep"])
+                .default_value("keep")
+                .env("PROJ2TREE_NORMALIZE_EOL"),
+        )
+        .arg(
+            Arg::new("expand-tabs")
+                .help("Заменять табуляцию во встроенном содержимом пробелами до позиций, кратных N")
+                .long("expand-tabs")
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .env("PROJ2TREE_EXPAND_TABS"),
+        )
+        .arg(
+            Arg::new("dedent")
+                .help("Снимать общий для всех строк отступ во встроенном содержимом")
+                .long("dedent")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_DEDENT")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("max-width")
+                .help("Переносить строки длиннее N символов внутри блоков кода; продолжение начинается с ↪")
+                .long("max-width")
+                .value_name("N")
+                .value_parser(clap::value_parser!(u64).range(20..))
+                .env("PROJ2TREE_MAX_WIDTH"),
+        )
+        .arg(
+            Arg::new("fence-info")
+                .help("Шаблон строки после ``` у блоков файлов, например '{lang} title=\"{path}\" lineNumbers'. Подстановки: {lang}, {path}, {name}")
+                .long("fence-info")
+                .value_name("TEMPLATE")
+                .env("PROJ2TREE_FENCE_INFO"),
+        )
+        .arg(
+            Arg::new("min-fence")
+                .help("Минимальное число бэктиков в разделителях блоков кода (для рендереров, которые путают короткие вложенные блоки)")
+                .long("min-fence")
+                .value_name("N")
+                .value_parser(clap::value_parser!(u64).range(3..))
+                .default_value("3")
+                .env("PROJ2TREE_MIN_FENCE"),
+        )
+        .arg(
+            Arg::new("order")
+                .help("Порядок файлов в содержимом: path (по пути) или deps (зависимости раньше зависимых)")
+                .long("order")
+                .value_name("ORDER")
+                .value_parser(["path", "deps"])
+                .default_value("path")
+                .env("PROJ2TREE_ORDER"),
+        )
+        .arg(
+            Arg::new("stdin-tar")
+                .help("Читать проект из tar-потока на stdin (например, git archive HEAD | proj2tree --stdin-tar)")
+                .long("stdin-tar")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("at")
+                .help("Брать дерево и содержимое из ревизии git (тег, ветка, коммит) вместо рабочей директории")
+                .long("at")
+                .value_name("REF")
+                .conflicts_with("stdin-tar"),
+        )
+        .arg(
+            Arg::new("no-gitattributes")
+                .help("Не исключать файлы с атрибутами linguist-generated и linguist-vendored из .gitattributes")
+                .long("no-gitattributes")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_NO_GITATTRIBUTES")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("no-auto-exclude")
+                .help("Не исключать служебные директории по определенному типу проекта")
+                .long("no-auto-exclude")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_NO_AUTO_EXCLUDE")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("case-insensitive")
+                .help("Сравнивать имена в исключениях и .gitignore без учета регистра (по умолчанию в Windows и macOS)")
+                .long("case-insensitive")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_CASE_INSENSITIVE")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("only-ext")
+                .help("Включать только файлы с указанными расширениями (через запятую)")
+                .long("only-ext")
+                .value_name("EXT,...")
+                .value_delimiter(',')
+                .env("PROJ2TREE_ONLY_EXT"),
+        )
+        .arg(
+            Arg::new("full-tree")
+                .help("С --only-ext показывать в дереве все файлы, а фильтровать только содержимое")
+                .long("full-tree")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("filter")
+                .help("Включать только файлы, путь которых нечетко совпадает с запросом (как в fzf)")
+                .long("filter")
+                .value_name("QUERY")
+                .env("PROJ2TREE_FILTER"),
+        )
+        .arg(
+            Arg::new("one-file-system")
+                .help("Не переходить в директории на других файловых системах (точки монтирования)")
+                .long("one-file-system")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_ONE_FILE_SYSTEM")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("max-path-depth")
+                .help("Сворачивать директории на глубине N от целевой (1 - только ее содержимое)")
+                .long("max-path-depth")
+                .value_name("N")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .env("PROJ2TREE_MAX_PATH_DEPTH"),
+        )
+        .arg(
+            Arg::new("owner")
+                .help("Оставить в дереве только файлы и директории пользователя (имя или UID)")
+                .long("owner")
+                .value_name("USER")
+                .env("PROJ2TREE_OWNER"),
+        )
+        .arg(
+            Arg::new("skip-dir-over")
+                .help("Сворачивать директории, файлы которых в сумме больше SIZE (200M, 1G, байты)")
+                .long("skip-dir-over")
+                .value_name("SIZE")
+                .value_parser(dir_size::parse_size)
+                .env("PROJ2TREE_SKIP_DIR_OVER"),
+        )
+        .arg(
+            Arg::new("count-skipped")
+                .help("Показывать число файлов в исключенных директориях")
+                .long("count-skipped")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_COUNT_SKIPPED")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("mark-empty")
+                .help("Помечать в дереве пустые директории и директории только с .gitkeep")
+                .long("mark-empty")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_MARK_EMPTY")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("prune-empty")
+                .help("Не показывать пустые директории и директории только с .gitkeep")
+                .long("prune-empty")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_PRUNE_EMPTY")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("tree-tokens")
+                .help("Показывать в дереве примерное число токенов у файлов и сумму у директорий")
+                .long("tree-tokens")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_TREE_TOKENS")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("count-limit")
+                .help("Прекращать подсчет файлов в исключенной директории после N файлов")
+                .long("count-limit")
+                .value_name("N")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .default_value("10000")
+                .requires("count-skipped"),
+        )
+        .arg(
+            Arg::new("follow-reparse-points")
+                .help("Обходить junction и ссылки на директории в Windows (по умолчанию выводятся как `name/ ...`)")
+                .long("follow-reparse-points")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_FOLLOW_REPARSE_POINTS")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("skip-marker")
+                .help("Пометка исключенных элементов в дереве, например \"(excluded)\"")
+                .long("skip-marker")
+                .value_name("TEXT")
+                .default_value("...")
+                .env("PROJ2TREE_SKIP_MARKER"),
+        )
+        .arg(
+            Arg::new("show-skipped-files")
+                .help("Показывать пропущенные файлы в дереве с пометкой --skip-marker")
+                .long("show-skipped-files")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_SHOW_SKIPPED_FILES")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("update")
+                .help("Обновить в существующем выходном файле только разделы файлов с указанным префиксом пути")
+                .long("update")
+                .value_name("PATH"),
+        )
+        .arg(
+            Arg::new("title")
+                .help("Текст заголовка документа вместо «Структура проекта: <директория>»")
+                .long("title")
+                .value_name("TEXT")
+                .env("PROJ2TREE_TITLE"),
+        )
+        .arg(
+            Arg::new("header-file")
+                .help("Markdown-файл, содержимое которого вставляется после заголовка документа")
+                .long("header-file")
+                .value_name("FILE")
+                .env("PROJ2TREE_HEADER_FILE"),
+        )
+        .arg(
+            Arg::new("doc-lang")
+                .help("Язык заголовков и пометок документа: ru, en, de или два через '+' (ru+en) для двуязычных заголовков; сообщения в консоли остаются на русском")
+                .long("doc-lang")
+                .value_name("LANG")
+                .value_parser(doc_lang::parse_lang)
+                .default_value("ru")
+                .env("PROJ2TREE_DOC_LANG"),
+        )
+        .arg(
+            Arg::new("git-context")
+                .help("Указать в заголовке документа ветку, коммит, состояние рабочего дерева и адрес репозитория")
+                .long("git-context")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_GIT_CONTEXT")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("lossy-read")
+                .help("Выводить файлы с некорректным UTF-8, заменяя ошибочные байты на U+FFFD, с пометкой о числе замен")
+                .long("lossy-read")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_LOSSY_READ")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("check-syntax")
+                .help("Отмечать файлы, которые не разбираются: незакрытые скобки, строки и комментарии, некорректные JSON и TOML")
+                .long("check-syntax")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_CHECK_SYNTAX")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("no-merge-check")
+                .help("Не искать маркеры конфликтов слияния и файлы .orig/.rej")
+                .long("no-merge-check")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_NO_MERGE_CHECK")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("paths")
+                .help("Пути в заголовках и дереве: relative (от целевой директории), absolute или from-git-root (от корня репозитория)")
+                .long("paths")
+                .value_name("MODE")
+                .value_parser(["relative", "absolute", "from-git-root"])
+                .default_value("relative")
+                .env("PROJ2TREE_PATHS"),
+        )
+        .arg(
+            Arg::new("chunk")
+                .help("Разбить документ на пронумерованные части не длиннее N символов (chars:N) для чатов с ограничением размера сообщения")
+                .long("chunk")
+                .value_name("chars:N")
+                .value_parser(chunk::parse_spec)
+                .env("PROJ2TREE_CHUNK"),
+        )
+        .arg(
+            Arg::new("heading-offset")
+                .help("Сдвинуть уровни всех заголовков на N (для вставки в существующий документ)")
+                .long("heading-offset")
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("0")
+                .env("PROJ2TREE_HEADING_OFFSET"),
+        )
+        .arg(
+            Arg::new("nest-headings")
+                .help("Группировать заголовки файлов под заголовками директорий")
+                .long("nest-headings")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_NEST_HEADINGS")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("ascii-paths")
+                .help("Транслитерировать не-ASCII пути в заголовках и якорях (исходный путь - в скобках)")
+                .long("ascii-paths")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_ASCII_PATHS")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("anchors")
+                .help("Добавить якоря к файлам и директориям и ссылки на них в дереве")
+                .long("anchors")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_ANCHORS")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("anchor-map")
+                .help("Записать в FILE карту путь -> якорь (JSON) для внешних ссылок; якоря из прошлой карты сохраняются. Включает --anchors")
+                .long("anchor-map")
+                .value_name("FILE")
+                .env("PROJ2TREE_ANCHOR_MAP"),
+        )
+        .arg(
+            Arg::new("nav-links")
+                .help("Добавить к каждому файлу ссылки на дерево, предыдущий и следующий файл; включает --anchors")
+                .long("nav-links")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_NAV_LINKS")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("link-base")
+                .help("URL файлов в репозитории для ссылок из дерева ({ref} заменяется на текущий коммит); включает --anchors")
+                .long("link-base")
+                .value_name("URL")
+                .env("PROJ2TREE_LINK_BASE"),
+        )
+        .arg(
+            Arg::new("embed")
+                .help("Встроить ранее созданный снимок под виртуальной директорией (SNAPSHOT@PREFIX, можно повторять)")
+                .long("embed")
+                .value_name("SNAPSHOT@PREFIX")
+                .value_parser(embed::parse_spec)
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("sample-dir")
+                .help("Включать только N самых маленьких файлов из директории (DIR:N, можно повторять)")
+                .long("sample-dir")
+                .value_name("DIR:N")
+                .value_parser(parse_sample_spec)
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("max-entries-per-dir")
+                .help("Показывать в дереве и содержимом только первые N элементов каждой директории, остальные - строкой «… и еще K»")
+                .long("max-entries-per-dir")
+                .value_name("N")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .env("PROJ2TREE_MAX_ENTRIES_PER_DIR"),
+        )
+        .arg(
+            Arg::new("top-files")
+                .help("Добавить отчет о N крупнейших включенных и исключенных файлах")
+                .long("top-files")
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .env("PROJ2TREE_TOP_FILES"),
+        )
+        .arg(
+            Arg::new("density")
+                .help("Добавить в отчет --top-files плотность информации (сжимаемость) файлов и список вероятно сгенерированных")
+                .long("density")
+                .action(ArgAction::SetTrue)
+                .requires("top-files"),
+        )
+        .arg(
+            Arg::new("health")
+                .help("Добавить раздел о состоянии проекта: пустые файлы и директории, крупные и давно не изменявшиеся файлы, TODO/FIXME")
+                .long("health")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_HEALTH")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("health-large-size")
+                .help("Размер в байтах, начиная с которого --health считает файл крупным")
+                .long("health-large-size")
+                .value_name("BYTES")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .default_value("1048576")
+                .requires("health"),
+        )
+        .arg(
+            Arg::new("todos")
+                .help("Добавить раздел с заметками TODO/FIXME/HACK/XXX из комментариев: файл, строка и текст")
+                .long("todos")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_TODOS")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("todos-csv")
+                .help("Дополнительно записать заметки --todos в CSV-файл (path,line,marker,text)")
+                .long("todos-csv")
+                .value_name("FILE")
+                .requires("todos"),
+        )
+        .arg(
+            Arg::new("docker-context")
+                .help("Показать, что попадет в контекст сборки Docker: вместо .gitignore и исключений действует .dockerignore, в конце - раздел с инструкциями COPY/ADD")
+                .long("docker-context")
+                .value_name("DOCKERFILE")
+                .num_args(0..=1)
+                .require_equals(true)
+                .default_missing_value("Dockerfile")
+                .env("PROJ2TREE_DOCKER_CONTEXT"),
+        )
+        .arg(
+            Arg::new("import-graph")
+                .help("Добавить раздел со связями между файлами проекта по import/use/require: list (список) или mermaid (диаграмма)")
+                .long("import-graph")
+                .value_name("FORMAT")
+                .value_parser(["list", "mermaid"])
+                .num_args(0..=1)
+                .require_equals(true)
+                .default_missing_value("list")
+                .env("PROJ2TREE_IMPORT_GRAPH"),
+        )
+        .arg(
+            Arg::new("deps")
+                .help("Добавить раздел с зависимостями из Cargo.toml, package.json, pyproject.toml и go.mod: имена и требования к версиям")
+                .long("deps")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_DEPS")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("footer")
+                .help("Добавить в конец документа подвал: версия proj2tree, параметры запуска, время генерации и SHA-256 документа")
+                .long("footer")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_FOOTER")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("sidecar")
+                .help("Записать рядом с выходным файлом FILE.meta.json: параметры запуска, конфигурация, состояние git, файлы с хешами и время генерации")
+                .long("sidecar")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_SIDECAR")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("threads")
+                .help("Число потоков для обхода директорий (по умолчанию - число ядер, 1 - без параллелизма)")
+                .long("threads")
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .env("PROJ2TREE_THREADS"),
+        )
+        .arg(
+            Arg::new("format")
+                .help("Формат вывода: markdown (по умолчанию), obsidian <VAULT_DIR>, bundle <ARCHIVE.zip>, index <INDEX.json>, md-dir <OUT_DIR> или jsonl <EVENTS.jsonl>")
+                .long("format")
+                .num_args(1..=2)
+                .value_names(["FORMAT", "DEST"])
+                .env("PROJ2TREE_FORMAT"),
+        )
+        .arg(
+            Arg::new("print-schema")
+                .help("Вывести JSON Schema структурированного вывода и завершить работу: json, jsonl, index, sidecar, report, scan или anchors")
+                .long("print-schema")
+                .value_name("NAME")
+                .value_parser(["json", "jsonl", "index", "sidecar", "report", "scan", "anchors"]),
+        )
+        .arg(
+            Arg::new("theme")
+                .help("Тема документа html со встроенной подсветкой кода: light, dark или auto (по настройке системы)")
+                .long("theme")
+                .value_name("THEME")
+                .value_parser(["light", "dark", "auto"])
+                .env("PROJ2TREE_THEME"),
+        )
+        .arg(
+            Arg::new("css")
+                .help("Встроить в документ html свой CSS: после темы, а без --theme вместо встроенного оформления")
+                .long("css")
+                .value_name("FILE")
+                .env("PROJ2TREE_CSS"),
+        )
+        .arg(
+            Arg::new("bundle-format")
+                .help("Формат документа в архиве --format bundle: markdown (по умолчанию) или html")
+                .long("bundle-format")
+                .value_name("FORMAT")
+                .value_parser(["markdown", "html"]),
+        )
+        .arg(
+            Arg::new("report")
+                .help("Формат сводки предупреждений в конце запуска (в stderr): text или json")
+                .long("report")
+                .value_name("FORMAT")
+                .value_parser(["text", "json"])
+                .default_value("text")
+                .env("PROJ2TREE_REPORT"),
+        )
+        .arg(
+            Arg::new("fail-on-warning")
+                .help("Завершаться с ошибкой, если за время запуска были предупреждения (для CI)")
+                .long("fail-on-warning")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_FAIL_ON_WARNING")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .arg(
+            Arg::new("log-format")
+                .help("Формат логов в stderr: pretty или json (уровни задаются через RUST_LOG)")
+                .long("log-format")
+                .value_name("FORMAT")
+                .value_parser(["pretty", "json"])
+                .default_value("pretty")
+                .env("PROJ2TREE_LOG_FORMAT")
+                .global(true),
+        )
+        .arg(
+            Arg::new("stdio-api")
+                .help("Отвечать на JSON-запросы (get_tree, render_subtree, render_file) построчно через stdin/stdout, для расширений редакторов")
+                .long("stdio-api")
+                .action(ArgAction::SetTrue)
+                .env("PROJ2TREE_STDIO_API")
+                .value_parser(BoolishValueParser::new()),
+        )
+        .subcommand(
+            Command::new("unpack")
+                .about("Восстановить файлы из ранее сгенерированного документа")
+                .arg(
+                    Arg::new("snapshot")
+                        .help("Markdown-документ, созданный proj2tree")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("directory")
+                        .help("Директория, в которую будут записаны файлы")
+                        .short('d')
+                        .long("directory")
+                        .value_name("DIR")
+                        .default_value("."),
+                ),
+        )
+        .subcommand(
+            Command::new("daemon")
+                .about("Запустить долгоживущий процесс с JSON-RPC API (scan, get-tree, get-file, regenerate, get_tree, render_subtree, render_file) через Unix-сокет")
+                .arg(
+                    Arg::new("socket")
+                        .help("Путь к Unix-сокету")
+                        .long("socket")
+                        .value_name("PATH")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("directory")
+                        .help("Целевая директория для анализа")
+                        .default_value(".")
+                        .index(1),
+                ),
+        )
+        .subcommand(
+            Command::new("config")
+                .about("Работа с конфигурацией")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("diff")
+                        .about("Показать, какие файлы будут исключены или включены после изменения конфигурации, без генерации документа")
+                        .arg(
+                            Arg::new("set")
+                                .help("Изменение: key=value, key+=value (добавить в список) или key-=value (удалить из списка)")
+                                .long("set")
+                                .value_name("CHANGE")
+                                .action(ArgAction::Append)
+                                .required(true),
+                        )
+                        .arg(
+                            Arg::new("directory")
+                                .help("Целевая директория для анализа")
+                                .default_value(".")
+                                .index(1),
+                        ),
+                ),
+        )
+        .subcommand(
+            Command::new("batch")
+                .about("Сгенерировать снимки нескольких проектов (локальных или по URL) по манифесту TOML")
+                .arg(
+                    Arg::new("manifest")
+                        .help("Манифест со списком проектов ([[repo]] с path или url)")
+                        .required(true)
+                        .index(1),
+                ),
+        )
+        .subcommand(
+            Command::new("bench")
+                .about("Замерить время обхода, чтения, расчета fence и форматирования без записи результата")
+                .arg(
+                    Arg::new("directory")
+                        .help("Целевая директория для анализа")
+                        .default_value(".")
+                        .index(1),
+                ),
+        )
+}
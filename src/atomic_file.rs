@@ -0,0 +1,100 @@
+use std::fs::{self, File, Permissions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+// Выходные файлы пишутся во временный файл рядом с целевым и подменяют его переименованием:
+// читатель (watcher сайта документации, параллельный запуск) видит либо старый документ,
+// либо новый целиком. Временный файл лежит в той же директории, иначе rename между
+// файловыми системами не атомарен. Без commit (ошибка, паника) он удаляется, а старый
+// документ остается как был. В FIFO, устройство (`-o /dev/stdout`) и прочие не обычные
+// файлы пишется напрямую: переименование заменило бы сам специальный файл. Ссылка на
+// выходной файл остается ссылкой: подменяется файл, на который она указывает, а права
+// старого файла переносятся на новый
+
+pub struct AtomicFile {
+    path: PathBuf,
+    temp: Option<PathBuf>,
+    permissions: Option<Permissions>,
+    writer: Option<BufWriter<File>>,
+}
+
+impl AtomicFile {
+    pub fn create(path: &Path) -> io::Result<AtomicFile> {
+        let path = resolve(path);
+        let (temp, permissions) = match fs::metadata(&path) {
+            Ok(metadata) if !metadata.is_file() => (None, None),
+            Ok(metadata) => (Some(temp_path(&path)), Some(metadata.permissions())),
+            Err(_) => (Some(temp_path(&path)), None),
+        };
+        let file = File::create(temp.as_deref().unwrap_or(&path))?;
+        Ok(AtomicFile { path, temp, permissions, writer: Some(BufWriter::new(file)) })
+    }
+
+    // Данные сбрасываются на диск до переименования: после сбоя питания иначе можно
+    // получить новое имя с пустым содержимым
+    pub fn commit(mut self) -> io::Result<()> {
+        let writer = self.writer.take().expect("AtomicFile уже записан");
+        let Some(temp) = &self.temp else {
+            return writer.into_inner().map(drop).map_err(|e| e.into_error());
+        };
+        let result = writer
+            .into_inner()
+            .map_err(|e| e.into_error())
+            .and_then(|file| file.sync_all())
+            .and_then(|_| match self.permissions.take() {
+                Some(permissions) => fs::set_permissions(temp, permissions),
+                None => Ok(()),
+            })
+            .and_then(|_| fs::rename(temp, &self.path));
+        if result.is_err() {
+            let _ = fs::remove_file(temp);
+        }
+        result
+    }
+}
+
+impl Write for AtomicFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writer.as_mut().expect("AtomicFile уже записан").write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.as_mut().expect("AtomicFile уже записан").flush()
+    }
+}
+
+impl Drop for AtomicFile {
+    fn drop(&mut self) {
+        if self.writer.take().is_some()
+            && let Some(temp) = &self.temp
+        {
+            let _ = fs::remove_file(temp);
+        }
+    }
+}
+
+// Путь файла, который на самом деле будет заменен. Висячая ссылка указывает на файл,
+// которого еще нет: он создается по ее цели
+fn resolve(path: &Path) -> PathBuf {
+    if let Ok(real) = fs::canonicalize(path) {
+        return real;
+    }
+    match fs::read_link(path) {
+        Ok(target) => path.parent().unwrap_or(Path::new("")).join(target),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+// Содержимое целиком: замена fs::write для выходных файлов
+pub fn write(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let mut file = AtomicFile::create(path)?;
+    file.write_all(contents.as_ref())?;
+    file.commit()
+}
+
+// Скрытое имя с номером процесса: параллельные запуски с одним выходным файлом не пишут
+// в один временный файл, а обход дерева узнает свой по этому имени и не выводит его
+pub fn temp_path(path: &Path) -> PathBuf {
+    let name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    path.with_file_name(format!(".{}.{}.tmp", name, std::process::id()))
+}
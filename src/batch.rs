@@ -0,0 +1,219 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Component, Path, PathBuf};
+use std::process::{Command, Stdio};
+use serde::Deserialize;
+
+use crate::git;
+
+// `proj2tree batch repos.toml`: снимки нескольких проектов по манифесту. Каждый проект
+// обрабатывается отдельным запуском proj2tree с параметрами из манифеста, поэтому ошибка
+// в одном (нет директории, не клонируется, --fail-on-warning) не прерывает остальные.
+// Удаленные репозитории клонируются во временную директорию и удаляются после снимка.
+// В конце печатается сводка, запуск завершается с ошибкой, если хотя бы один снимок не удался
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Manifest {
+    #[serde(default)]
+    defaults: Defaults,
+    #[serde(default, rename = "repo")]
+    repos: Vec<Entry>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Defaults {
+    // Директория для документов, от директории манифеста
+    output_dir: Option<PathBuf>,
+    // Параметры командной строки для всех проектов, перед параметрами проекта
+    #[serde(default)]
+    args: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Entry {
+    name: Option<String>,
+    path: Option<PathBuf>,
+    url: Option<String>,
+    // Ветка или тег для клонирования url
+    branch: Option<String>,
+    output: Option<PathBuf>,
+    #[serde(default)]
+    args: Vec<String>,
+}
+
+enum Source {
+    Local(PathBuf),
+    Remote { url: String, branch: Option<String> },
+}
+
+struct Job {
+    name: String,
+    source: Source,
+    output: PathBuf,
+    args: Vec<String>,
+}
+
+pub fn run(manifest_path: &Path) -> io::Result<()> {
+    let content = fs::read_to_string(manifest_path).map_err(|e| {
+        io::Error::new(e.kind(), format!("не удалось прочитать {}: {}", manifest_path.display(), e))
+    })?;
+    let manifest: Manifest = toml::from_str(&content).map_err(|e| {
+        io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", manifest_path.display(), e))
+    })?;
+    let base_dir = manifest_path.parent().unwrap_or(Path::new(""));
+    let jobs = jobs(manifest, base_dir)?;
+    if jobs.is_empty() {
+        tracing::warn!("в {} нет проектов ([[repo]])", manifest_path.display());
+        return Ok(());
+    }
+    // Без git каждый url дал бы одну и ту же ошибку после снимков локальных проектов
+    if jobs.iter().any(|job| matches!(job.source, Source::Remote { .. })) && !git::binary_available() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "{}: для проектов с url нужен бинарный git, но он не найден в PATH; \
+                 установите git или укажите path к локальной копии",
+                manifest_path.display()
+            ),
+        ));
+    }
+
+    let program = std::env::current_exe()?;
+    let mut failures = 0;
+    let mut stdout = io::stdout().lock();
+    for (index, job) in jobs.iter().enumerate() {
+        tracing::info!("[{}/{}] {}", index + 1, jobs.len(), job.name);
+        match snapshot(&program, job) {
+            Ok(()) => writeln!(stdout, "ok     {} -> {}", job.name, job.output.display())?,
+            Err(e) => {
+                failures += 1;
+                writeln!(stdout, "ошибка {}: {}", job.name, e)?;
+            }
+        }
+    }
+    writeln!(stdout, "\nУспешно: {}, с ошибками: {}", jobs.len() - failures, failures)?;
+
+    if failures > 0 {
+        return Err(io::Error::other(format!("снимков с ошибками: {} из {}", failures, jobs.len())));
+    }
+    Ok(())
+}
+
+// Манифест проверяется целиком до первого снимка: опечатка в последней записи
+// не должна обнаруживаться через полчаса работы
+fn jobs(manifest: Manifest, base_dir: &Path) -> io::Result<Vec<Job>> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+    let output_dir = base_dir.join(manifest.defaults.output_dir.unwrap_or_default());
+    let mut names = HashSet::new();
+    let mut jobs = Vec::new();
+
+    for (index, entry) in manifest.repos.into_iter().enumerate() {
+        let source = match (entry.path, entry.url) {
+            (Some(path), None) => Source::Local(base_dir.join(path)),
+            (None, Some(url)) => Source::Remote { url, branch: entry.branch },
+            _ => return Err(invalid(format!("проект {}: нужен ровно один из ключей path и url", index + 1))),
+        };
+        let name = match entry.name {
+            Some(name) => name,
+            None => default_name(&source)
+                .ok_or_else(|| invalid(format!("проект {}: не удалось определить имя, укажите name", index + 1)))?,
+        };
+        // Имя становится именем директории клона, которая удаляется после снимка, и именем документа
+        if !is_plain_name(&name) {
+            return Err(invalid(format!(
+                "проект {}: имя '{}' должно быть одним именем файла без '/', '\\' и '..'",
+                index + 1,
+                name
+            )));
+        }
+        if let Source::Remote { url, branch } = &source
+            && let Some(value) = [Some(url), branch.as_ref()].into_iter().flatten().find(|value| value.starts_with('-'))
+        {
+            return Err(invalid(format!("проект {}: '{}' не может начинаться с '-'", index + 1, value)));
+        }
+        if !names.insert(name.clone()) {
+            return Err(invalid(format!("имя проекта '{}' встречается несколько раз", name)));
+        }
+
+        let output = output_dir.join(entry.output.unwrap_or_else(|| PathBuf::from(format!("{}.md", name))));
+        let args = manifest.defaults.args.iter().cloned().chain(entry.args).collect();
+        jobs.push(Job { name, source, output, args });
+    }
+    Ok(jobs)
+}
+
+fn is_plain_name(name: &str) -> bool {
+    let mut components = Path::new(name).components();
+    matches!(components.next(), Some(Component::Normal(_)))
+        && components.next().is_none()
+        && !name.contains(['/', '\\'])
+}
+
+// Имя по последнему компоненту пути или URL без .git
+fn default_name(source: &Source) -> Option<String> {
+    let name = match source {
+        Source::Local(path) => fs::canonicalize(path).ok()?.file_name()?.to_string_lossy().to_string(),
+        Source::Remote { url, .. } => {
+            let last = url.trim_end_matches('/').rsplit(['/', ':']).next()?;
+            last.strip_suffix(".git").unwrap_or(last).to_string()
+        }
+    };
+    (!name.is_empty()).then_some(name)
+}
+
+fn snapshot(program: &Path, job: &Job) -> io::Result<()> {
+    if let Some(parent) = job.output.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    match &job.source {
+        Source::Local(path) => {
+            if !path.is_dir() {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("'{}' не является существующей директорией", path.display()),
+                ));
+            }
+            generate(program, Path::new("."), path, job)
+        }
+        // Клон запускается из временной директории по имени проекта: в заголовке документа
+        // будет имя, а не временный путь
+        Source::Remote { url, branch } => {
+            let temp_dir = std::env::temp_dir().join(format!("proj2tree-batch-{}", std::process::id()));
+            let checkout = temp_dir.join(&job.name);
+            let _ = fs::remove_dir_all(&checkout);
+            let result = git::clone(url, branch.as_deref(), &checkout)
+                .and_then(|_| generate(program, &temp_dir, Path::new(&job.name), job));
+            let _ = fs::remove_dir_all(&temp_dir);
+            result
+        }
+    }
+}
+
+// Вывод запуска не смешивается с выводом остальных: в сводку попадает последняя строка stderr
+fn generate(program: &Path, current_dir: &Path, dir: &Path, job: &Job) -> io::Result<()> {
+    let output = Command::new(program)
+        .current_dir(current_dir)
+        .args(&job.args)
+        .arg("-o")
+        .arg(std::path::absolute(&job.output)?)
+        .arg(dir)
+        .stdin(Stdio::null())
+        .output()?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    for line in stderr.lines() {
+        tracing::debug!("{}: {}", job.name, line);
+    }
+    if !output.status.success() {
+        let reason = stderr.lines().rev().find(|line| !line.trim().is_empty()).unwrap_or("").trim();
+        return Err(io::Error::other(if reason.is_empty() {
+            format!("proj2tree: {}", output.status)
+        } else {
+            reason.to_string()
+        }));
+    }
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use ignore::gitignore::Gitignore;
+
+use crate::report::format_size;
+use crate::tree_only;
+use crate::{
+    AppConfig, Config, calculate_fence_length, is_binary_file, is_extension_allowed,
+    is_file_too_large, tree, write_content_block,
+};
+
+// `proj2tree bench`: тот же конвейер, что и при генерации документа, но без записи результата.
+// Время каждого этапа измеряется отдельно, чтобы было видно, где медленный проект его теряет
+
+#[derive(Default)]
+struct Timings {
+    walking: Duration,
+    reading: Duration,
+    fences: Duration,
+    writing: Duration,
+}
+
+pub fn run(base_dir: &Path, app_config: &AppConfig, config: &Config, gitignore_matcher: &Option<Gitignore>) -> io::Result<()> {
+    let mut timings = Timings::default();
+    let total = Instant::now();
+
+    let started = Instant::now();
+    let nodes = tree::build_tree(base_dir, app_config, config, gitignore_matcher)?;
+    let mut files = Vec::new();
+    tree::file_paths(&nodes, &mut files);
+    files.retain(|path| {
+        is_extension_allowed(path, app_config)
+            && !is_binary_file(path, config)
+            && !tree_only::is_tree_only(path, app_config, config)
+            && !is_file_too_large(path, app_config, config)
+    });
+    timings.walking = started.elapsed();
+
+    let started = Instant::now();
+    let contents: Vec<_> = files
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok().map(|content| (path, content)))
+        .collect();
+    timings.reading = started.elapsed();
+
+    let started = Instant::now();
+    for (_, content) in &contents {
+        calculate_fence_length(content);
+    }
+    timings.fences = started.elapsed();
+
+    // Форматирование (язык, сводки lock-файлов, маскирование секретов) пишется в никуда
+    let started = Instant::now();
+    let mut sink = io::sink();
+    if app_config.flat_tree {
+        tree::render_flat(base_dir, Path::new(""), &nodes, &mut sink, app_config)?;
+    } else {
+        tree::render_pretty(&nodes, &mut sink, "", app_config)?;
+    }
+    for (path, content) in &contents {
+        write_content_block(&mut sink, path, content, app_config, config)?;
+    }
+    timings.writing = started.elapsed();
+
+    let total = total.elapsed();
+    let bytes: usize = contents.iter().map(|(_, content)| content.len()).sum();
+
+    let mut stdout = io::stdout().lock();
+    writeln!(stdout, "Файлов: {} (прочитано {}, {})", files.len(), contents.len(), format_size(bytes as u64))?;
+    writeln!(stdout)?;
+    for (stage, duration) in [
+        ("обход", timings.walking),
+        ("чтение", timings.reading),
+        ("расчет fence", timings.fences),
+        ("форматирование и запись", timings.writing),
+    ] {
+        writeln!(stdout, "{:<24} {:>10.1} мс {:>5.1}%", stage, millis(duration), percent(duration, total))?;
+    }
+    writeln!(stdout, "{:<24} {:>10.1} мс", "всего", millis(total))?;
+
+    Ok(())
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn percent(part: Duration, total: Duration) -> f64 {
+    if total.is_zero() {
+        return 0.0;
+    }
+    part.as_secs_f64() / total.as_secs_f64() * 100.0
+}
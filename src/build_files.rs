@@ -0,0 +1,67 @@
+use std::path::{Component, Path};
+
+// Файлы сборки и CI (--build-section): Dockerfile, Makefile, конвейеры GitHub Actions,
+// GitLab CI, Jenkins и т.п. выводятся отдельным разделом после основного содержимого.
+// Проверяется путь от корня проекта, как и для тестов
+
+// Скрытые элементы, которые с --build-section не пропускаются
+const HIDDEN_NAMES: [&str; 4] = [".github", ".gitlab-ci.yml", ".circleci", ".travis.yml"];
+
+const FILE_NAMES: [&str; 7] = [
+    "makefile",
+    "gnumakefile",
+    ".gitlab-ci.yml",
+    ".travis.yml",
+    "azure-pipelines.yml",
+    "compose.yml",
+    "compose.yaml",
+];
+
+// Имена с вариантами: Dockerfile.dev, Jenkinsfile.release, docker-compose.override.yml
+const NAME_PREFIXES: [&str; 4] = ["dockerfile", "containerfile", "jenkinsfile", "docker-compose"];
+
+const EXTENSIONS: [&str; 2] = ["mk", "dockerfile"];
+
+// Каталоги с конфигурацией CI: все YAML-файлы в них относятся к сборке
+const CI_DIRS: [&[&str]; 2] = [&[".github", "workflows"], &[".circleci"]];
+
+pub fn is_hidden_build_name(name: &str) -> bool {
+    HIDDEN_NAMES.contains(&name)
+}
+
+// В .github остаются только конвейеры: шаблоны задач и прочее к сборке не относятся
+pub fn is_outside_workflows(relative_path: &Path) -> bool {
+    let names = normal_names(relative_path);
+    names.first().is_some_and(|first| first == ".github")
+        && names.get(1).is_some_and(|second| second != "workflows")
+}
+
+pub fn is_build_path(relative_path: &Path, is_file: bool) -> bool {
+    if !is_file {
+        return false;
+    }
+    let names = normal_names(relative_path);
+    let Some(name) = names.last().map(|name| name.to_lowercase()) else {
+        return false;
+    };
+
+    let is_yaml = name.ends_with(".yml") || name.ends_with(".yaml");
+    if is_yaml && CI_DIRS.iter().any(|dir| names.len() > dir.len() && names.iter().zip(dir.iter()).all(|(a, b)| a == b)) {
+        return true;
+    }
+
+    FILE_NAMES.contains(&name.as_str())
+        || NAME_PREFIXES.iter().any(|prefix| name.starts_with(prefix))
+        || Path::new(&name)
+            .extension()
+            .is_some_and(|extension| EXTENSIONS.contains(&extension.to_string_lossy().as_ref()))
+}
+
+fn normal_names(path: &Path) -> Vec<String> {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect()
+}
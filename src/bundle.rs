@@ -0,0 +1,225 @@
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::{Component, Path, PathBuf};
+use ignore::gitignore::Gitignore;
+
+use crate::atomic_file::AtomicFile;
+use crate::escape::{self, code_span, display_os_str, display_path};
+use crate::zip::ZipWriter;
+use crate::{AppConfig, Config, build_model, content_files, heading, relative_path, render, tree};
+
+const IMAGE_EXTENSIONS: [&str; 8] = ["png", "jpg", "jpeg", "gif", "bmp", "ico", "svg", "webp"];
+
+// Архив содержит документ (markdown или html, --bundle-format) и изображения, на которые
+// ссылаются включенные в него файлы: `![...](path)` в markdown и `src="path"` в html.
+// Ссылки разрешаются от директории файла, в архив попадают только изображения из дерева.
+// Они лежат в assets/ и перечислены в разделе «Изображения» в конце документа, поэтому
+// после распаковки открываются по относительным ссылкам
+pub fn write_bundle(
+    renderer: &dyn render::Renderer,
+    base_dir: &Path,
+    archive_path: &Path,
+    app_config: &AppConfig,
+    config: &Config,
+    gitignore_matcher: &Option<Gitignore>,
+) -> io::Result<()> {
+    let nodes = build_model(base_dir, app_config, config, gitignore_matcher)?;
+    let mut document = Vec::new();
+    renderer.render(&render::Snapshot::new(base_dir, &nodes, app_config, config), &mut document)?;
+
+    let images = referenced_images(base_dir, &nodes, app_config, config);
+    let html = renderer.name() == "html";
+    let mut assets = Vec::new();
+    let mut section = Vec::new();
+    for (relative_path, path) in &images {
+        let asset_name = format!("assets/{}", archive_entry_name(relative_path));
+        if assets.is_empty() {
+            write_images_heading(&mut section, html, app_config)?;
+        }
+        write_image(&mut section, relative_path, &asset_name, html, app_config)?;
+        assets.push((asset_name, app_config.fs.read(path)?));
+    }
+    // В html раздел встает перед закрывающим тегом, иначе браузер покажет его вне документа
+    let at = if html {
+        document.windows(7).rposition(|window| window == b"</body>").unwrap_or(document.len())
+    } else {
+        document.len()
+    };
+    document.splice(at..at, section);
+
+    let document_name = match renderer.name() {
+        "markdown" => "tree.md".to_string(),
+        name => format!("tree.{}", name),
+    };
+    let mut zip = ZipWriter::new(AtomicFile::create(archive_path)?);
+    zip.add_file(&document_name, &document)?;
+    for (name, data) in assets {
+        zip.add_file(&name, &data)?;
+    }
+    zip.finish()?.commit()
+}
+
+fn write_images_heading<W: Write>(writer: &mut W, html: bool, app_config: &AppConfig) -> io::Result<()> {
+    if html {
+        writeln!(writer, "<h2>{}</h2>", escape::html(app_config.doc.images))
+    } else {
+        writeln!(writer, "\n{} {}", heading(app_config, 2), app_config.doc.images)
+    }
+}
+
+fn write_image<W: Write>(
+    writer: &mut W,
+    relative_path: &Path,
+    asset_name: &str,
+    html: bool,
+    app_config: &AppConfig,
+) -> io::Result<()> {
+    let path = display_path(relative_path);
+    if html {
+        writeln!(writer, "<h3><code>{}</code></h3>", escape::html(&path))?;
+        let name = relative_path.file_name().map(display_os_str).unwrap_or_default();
+        writeln!(writer, "<p><img src=\"{}\" alt=\"{}\"></p>", escape::html(&percent_encode(asset_name)), escape::html(&name))
+    } else {
+        writeln!(writer, "\n{} {}\n", heading(app_config, 3), code_span(&path))?;
+        writeln!(writer, "![{}](<{}>)", alt_text(relative_path), percent_encode(asset_name))
+    }
+}
+
+// Изображения из дерева, на которые ссылаются файлы раздела содержимого: путь от корня
+// проекта и путь для чтения. Файлы читаются только ради ссылок, дерево не обходится заново
+fn referenced_images(
+    base_dir: &Path,
+    nodes: &[tree::TreeNode],
+    app_config: &AppConfig,
+    config: &Config,
+) -> BTreeMap<PathBuf, PathBuf> {
+    let mut files = Vec::new();
+    tree::file_paths(nodes, &mut files);
+    let images: BTreeMap<PathBuf, PathBuf> = files
+        .into_iter()
+        .filter(|path| is_image(path))
+        .map(|path| (relative_path(base_dir, &path), path))
+        .collect();
+
+    let mut referenced = BTreeMap::new();
+    if images.is_empty() || !app_config.include_contents {
+        return referenced;
+    }
+    for file in content_files(nodes, app_config, config) {
+        let Ok(content) = app_config.fs.read_to_string(&file) else {
+            continue;
+        };
+        let relative_file = relative_path(base_dir, &file);
+        for target in references(&content) {
+            if let Some((relative_path, path)) =
+                resolve_reference(&relative_file, target).and_then(|image| images.get_key_value(&image))
+            {
+                referenced.insert(relative_path.clone(), path.clone());
+            }
+        }
+    }
+    referenced
+}
+
+// Цели ссылок `](target)`, `](<target>)` и атрибутов `src="target"`
+fn references(content: &str) -> Vec<&str> {
+    let mut targets = Vec::new();
+    for (index, _) in content.match_indices("](") {
+        let rest = &content[index + 2..];
+        let target = match rest.strip_prefix('<') {
+            Some(rest) => rest.split('>').next(),
+            None => rest.split([')', ' ', '\n']).next(),
+        };
+        targets.extend(target);
+    }
+    for (index, _) in content.match_indices("src=") {
+        let rest = &content[index + 4..];
+        if let Some(quote) = rest.chars().next().filter(|c| *c == '"' || *c == '\'')
+            && let Some(target) = rest[1..].split(quote).next()
+        {
+            targets.push(target);
+        }
+    }
+    targets
+}
+
+// Путь изображения от корня проекта. Внешние ссылки, абсолютные пути, якоря и выход
+// за корень проекта не разрешаются
+fn resolve_reference(file: &Path, target: &str) -> Option<PathBuf> {
+    let target = target.split(['#', '?']).next()?;
+    if target.is_empty() || target.starts_with('/') || target.contains(':') {
+        return None;
+    }
+    let mut path = file.parent().map(Path::to_path_buf).unwrap_or_default();
+    for part in percent_decode(target).split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                if !path.pop() {
+                    return None;
+                }
+            }
+            part => path.push(part),
+        }
+    }
+    Some(path)
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let hex = bytes.get(index + 1..index + 3).and_then(|hex| std::str::from_utf8(hex).ok());
+        match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+            Some(byte) if bytes[index] == b'%' => {
+                result.push(byte);
+                index += 3;
+            }
+            _ => {
+                result.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&result).into_owned()
+}
+
+fn is_image(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.as_str()))
+}
+
+// Имена внутри ZIP всегда разделяются '/'
+pub fn archive_entry_name(relative_path: &Path) -> String {
+    relative_path
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(display_os_str(part)),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn alt_text(relative_path: &Path) -> String {
+    let name = relative_path.file_name().map(display_os_str).unwrap_or_default();
+    name.replace('[', "\\[").replace(']', "\\]")
+}
+
+pub fn percent_encode(path: &str) -> String {
+    let mut result = String::new();
+    for c in path.chars() {
+        match c {
+            ' ' | '%' | '<' | '>' | '(' | ')' | '#' | '?' | '\\' => {
+                let mut buffer = [0; 4];
+                for byte in c.encode_utf8(&mut buffer).bytes() {
+                    result.push_str(&format!("%{:02X}", byte));
+                }
+            }
+            c => result.push(c),
+        }
+    }
+    result
+}
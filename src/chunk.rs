@@ -0,0 +1,203 @@
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+use crate::atomic_file;
+use crate::doc_lang::{Strings, fill};
+
+// Разбиение документа на части для чатов с ограничением на размер сообщения (--chunk chars:N).
+// Части режутся по строкам: сначала перед заголовком, затем после пустой строки, и только
+// если таких мест нет - посреди блока кода, который тогда закрывается и открывается заново
+
+// Меньше не помещаются заголовок части и строки открытия и закрытия блока кода
+const MIN_CHARS: usize = 200;
+
+#[derive(Clone)]
+struct Fence {
+    open: String,
+    close: String,
+}
+
+struct Line {
+    text: String,
+    chars: usize,
+    // Блок кода, внутри которого находится строка (включая закрывающую строку блока)
+    fence: Option<Fence>,
+    heading: bool,
+    blank: bool,
+}
+
+pub fn parse_spec(value: &str) -> Result<usize, String> {
+    let count = value
+        .strip_prefix("chars:")
+        .ok_or_else(|| "ожидается chars:N, например chars:12000".to_string())?;
+    count
+        .parse::<usize>()
+        .ok()
+        .filter(|&count| count >= MIN_CHARS)
+        .ok_or_else(|| format!("'{}' должно быть числом не меньше {}", count, MIN_CHARS))
+}
+
+// Части с заголовком «Часть i/N», каждая не длиннее limit символов
+pub fn split(document: &str, limit: usize, doc: &Strings) -> Vec<String> {
+    // Запас под заголовок части с максимально длинными номерами
+    let reserve = fill(doc.part, &[&usize::MAX, &usize::MAX]).chars().count() + 2;
+    let budget = limit.saturating_sub(reserve);
+
+    let lines = annotate(document, budget / 2);
+    let mut bodies = Vec::new();
+    let mut start = 0;
+    let mut reopen: Option<Fence> = None;
+    let mut size = 0;
+    let mut i = 0;
+
+    while i < lines.len() {
+        if i == start {
+            size = reopen.as_ref().map_or(0, |fence| fence.open.chars().count());
+        }
+        let closing = lines.get(i + 1).and_then(|line| line.fence.as_ref()).map_or(0, |fence| fence.close.len());
+
+        if i == start || size + lines[i].chars + closing <= budget {
+            size += lines[i].chars;
+            i += 1;
+            continue;
+        }
+
+        let cut = best_cut(&lines, start, i, budget / 2);
+        let mut body = body(&lines[start..cut], reopen.as_ref());
+        reopen = lines[cut].fence.clone();
+        if let Some(fence) = &reopen {
+            if !body.ends_with('\n') {
+                body.push('\n');
+            }
+            body.push_str(&fence.close);
+        }
+        bodies.push(body);
+        start = cut;
+        i = cut;
+    }
+
+    if start < lines.len() {
+        bodies.push(body(&lines[start..], reopen.as_ref()));
+    }
+
+    let total = bodies.len();
+    bodies
+        .into_iter()
+        .enumerate()
+        .map(|(index, body)| format!("{}\n\n{}", fill(doc.part, &[&(index + 1), &total]), body))
+        .collect()
+}
+
+// tree.md -> tree.part1.md, tree.part2.md, ...
+pub fn part_path(output: &Path, index: usize) -> PathBuf {
+    let stem = output.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match output.extension() {
+        Some(extension) => format!("{}.part{}.{}", stem, index, extension.to_string_lossy()),
+        None => format!("{}.part{}", stem, index),
+    };
+    output.with_file_name(name)
+}
+
+pub fn is_part_of(name: &str, output: &Path) -> bool {
+    let Some(rest) = name.strip_prefix(&format!("{}.part", output.file_stem().unwrap_or_default().to_string_lossy())) else {
+        return false;
+    };
+    let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let suffix = output.extension().map(|extension| format!(".{}", extension.to_string_lossy())).unwrap_or_default();
+    digits > 0 && rest[digits..] == suffix
+}
+
+pub fn write_files(output: &Path, chunks: &[String]) -> io::Result<()> {
+    for (index, chunk) in chunks.iter().enumerate() {
+        atomic_file::write(&part_path(output, index + 1), chunk)?;
+    }
+    Ok(())
+}
+
+// В терминале следующая часть выводится по Enter, чтобы предыдущую успели скопировать.
+// При выводе в канал части идут подряд
+pub fn print<W: Write>(writer: &mut W, chunks: &[String]) -> io::Result<()> {
+    let interactive = io::stdout().is_terminal() && io::stdin().is_terminal();
+
+    for (index, chunk) in chunks.iter().enumerate() {
+        if index > 0 {
+            if interactive {
+                eprint!("-- Часть {}/{} выведена, Enter - следующая --", index, chunks.len());
+                let mut answer = String::new();
+                io::stdin().lock().read_line(&mut answer)?;
+            } else {
+                writeln!(writer)?;
+            }
+        }
+        write!(writer, "{}", chunk)?;
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+fn body(lines: &[Line], reopen: Option<&Fence>) -> String {
+    let mut body = reopen.map(|fence| fence.open.clone()).unwrap_or_default();
+    for line in lines {
+        body.push_str(&line.text);
+    }
+    body
+}
+
+// Место разреза в (start, end]: перед последним заголовком, иначе после последней пустой строки,
+// иначе в конце. Первые два варианта допустимы только вне блока кода и если часть при этом
+// заполнена хотя бы наполовину: иначе заголовок файла остается в части без содержимого
+fn best_cut(lines: &[Line], start: usize, end: usize, minimum: usize) -> usize {
+    let mut filled = 0;
+    let mut candidates = Vec::new();
+    for k in start + 1..=end {
+        filled += lines[k - 1].chars;
+        if filled >= minimum && lines[k].fence.is_none() {
+            candidates.push(k);
+        }
+    }
+
+    let heading = candidates.iter().rev().find(|&&k| lines[k].heading);
+    let blank = candidates.iter().rev().find(|&&k| lines[k - 1].blank && lines[k - 1].fence.is_none());
+    heading.or(blank).copied().unwrap_or(end)
+}
+
+// Строки документа с отметкой блока кода. Строки длиннее width делятся на куски,
+// чтобы любой кусок помещался в часть вместе со строками блока кода
+fn annotate(document: &str, width: usize) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut open: Option<(Fence, usize)> = None;
+
+    for text in document.split_inclusive('\n') {
+        let trimmed = text.trim_end_matches(['\n', '\r']);
+        let fence = open.as_ref().map(|(fence, _)| fence.clone());
+        let backticks = trimmed.len() - trimmed.trim_start_matches('`').len();
+
+        match &open {
+            None if backticks >= 3 => {
+                let fence = Fence {
+                    open: format!("{}\n", trimmed),
+                    close: format!("{}\n", "`".repeat(backticks)),
+                };
+                open = Some((fence, backticks));
+            }
+            Some((_, length)) if backticks >= *length && backticks == trimmed.len() => open = None,
+            _ => {}
+        }
+
+        let heading = fence.is_none() && trimmed.starts_with('#');
+        let blank = trimmed.trim().is_empty();
+        let chars: Vec<char> = text.chars().collect();
+        for (index, piece) in chars.chunks(width.max(1)).enumerate() {
+            let piece: String = piece.iter().collect();
+            lines.push(Line {
+                chars: piece.chars().count(),
+                text: piece,
+                fence: fence.clone(),
+                heading: heading && index == 0,
+                blank,
+            });
+        }
+    }
+
+    lines
+}
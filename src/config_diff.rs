@@ -0,0 +1,139 @@
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use ignore::gitignore::Gitignore;
+
+use crate::escape::display_path;
+use crate::{dotfiles, sampling, summarize, tree_only};
+use crate::{
+    AppConfig, Config, apply_env_overrides, content_files, load_builtin_config_value, load_project_config_values,
+    merge_config_values, project, relative_path, tree,
+};
+
+// `proj2tree config diff --set exclude_dirs+=generated`: какие файлы выпадут из документа
+// или попадут в него, если изменить конфигурацию, - без генерации самого документа.
+// Изменения применяются поверх итоговой конфигурации (встроенной и файлов проекта)
+// до переменных окружения, как если бы они были записаны в .proj2tree.toml
+
+enum Operation {
+    Set,
+    Append,
+    Remove,
+}
+
+pub fn run<W: Write>(
+    base_dir: &Path,
+    writer: &mut W,
+    changes: &[String],
+    auto_exclude: bool,
+    app_config: &AppConfig,
+    gitignore_matcher: &Option<Gitignore>,
+) -> io::Result<()> {
+    let project_values = load_project_config_values(base_dir, &|path| std::fs::read_to_string(path).ok());
+    let current_value = merge_config_values(load_builtin_config_value(), project_values);
+
+    let mut changed_value = current_value.clone();
+    for change in changes {
+        apply_change(&mut changed_value, change)?;
+    }
+
+    let current = included_files(base_dir, current_value, auto_exclude, app_config, gitignore_matcher)?;
+    let changed = included_files(base_dir, changed_value, auto_exclude, app_config, gitignore_matcher)?;
+
+    let excluded: Vec<_> = current.difference(&changed).collect();
+    let included: Vec<_> = changed.difference(&current).collect();
+
+    writeln!(writer, "Будут исключены: {}", excluded.len())?;
+    for path in &excluded {
+        writeln!(writer, "- {}", display_path(&relative_path(base_dir, path)))?;
+    }
+    writeln!(writer, "Будут включены: {}", included.len())?;
+    for path in &included {
+        writeln!(writer, "+ {}", display_path(&relative_path(base_dir, path)))?;
+    }
+    writeln!(writer, "Файлов с содержимым: {} -> {}", current.len(), changed.len())
+}
+
+// Файлы, содержимое которых попало бы в документ
+fn included_files(
+    base_dir: &Path,
+    value: toml::Value,
+    auto_exclude: bool,
+    app_config: &AppConfig,
+    gitignore_matcher: &Option<Gitignore>,
+) -> io::Result<BTreeSet<PathBuf>> {
+    let mut config: Config = value
+        .try_into()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, format!("некорректная конфигурация: {}", e)))?;
+    apply_env_overrides(&mut config);
+    tree_only::compile(&mut config);
+    dotfiles::compile(&mut config);
+    sampling::compile(&mut config);
+    summarize::compile(&mut config);
+    if auto_exclude {
+        project::apply_auto_exclusions(base_dir, &mut config);
+    }
+
+    // Кэши обхода зависят от конфигурации
+    app_config.sample_cache.lock().unwrap().clear();
+    app_config.small_dirs.lock().unwrap().clear();
+    app_config.empty_dirs.lock().unwrap().clear();
+
+    let nodes = tree::build_tree(base_dir, app_config, &config, gitignore_matcher)?;
+    Ok(content_files(&nodes, app_config, &config).into_iter().collect())
+}
+
+// `key=value` заменяет значение, `key+=value` и `key-=value` добавляют элемент в список
+// и удаляют из него. Значение разбирается как TOML (`max_file_size=1000`,
+// `exclude_dirs=["a", "b"]`), а если не разбирается - берется строкой
+fn apply_change(config: &mut toml::Value, change: &str) -> io::Result<()> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidInput, message);
+
+    let (key, value) = change
+        .split_once('=')
+        .ok_or_else(|| invalid(format!("изменение '{}' должно иметь вид key=value, key+=value или key-=value", change)))?;
+    let (key, operation) = if let Some(key) = key.strip_suffix('+') {
+        (key, Operation::Append)
+    } else if let Some(key) = key.strip_suffix('-') {
+        (key, Operation::Remove)
+    } else {
+        (key, Operation::Set)
+    };
+    let key = key.trim();
+    let value = parse_value(value.trim());
+
+    let table = config.as_table_mut().ok_or_else(|| invalid("конфигурация не является таблицей".to_string()))?;
+    match operation {
+        Operation::Set => {
+            table.insert(key.to_string(), value);
+        }
+        Operation::Append | Operation::Remove => {
+            let list = table
+                .entry(key.to_string())
+                .or_insert_with(|| toml::Value::Array(Vec::new()))
+                .as_array_mut()
+                .ok_or_else(|| invalid(format!("'{}' не является списком", key)))?;
+            let items = match value {
+                toml::Value::Array(items) => items,
+                item => vec![item],
+            };
+            for item in items {
+                if matches!(operation, Operation::Append) {
+                    if !list.contains(&item) {
+                        list.push(item);
+                    }
+                } else {
+                    list.retain(|existing| *existing != item);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn parse_value(text: &str) -> toml::Value {
+    toml::from_str::<toml::Table>(&format!("value = {}", text))
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| toml::Value::String(text.to_string()))
+}
@@ -0,0 +1,33 @@
+use std::path::{Path, PathBuf};
+use globset::{GlobBuilder, GlobMatcher};
+
+use crate::relative_path;
+
+// Порядок раздела содержимого из конфигурации: content_order = ["README.md", "src/**", "tests/**"].
+// Файл попадает в группу первого подходящего шаблона, группы выводятся в порядке шаблонов,
+// внутри группы и для файлов без шаблона - по алфавиту. `*` не переходит через `/`, `**` - переходит
+
+pub fn sort(base_dir: &Path, files: &mut [PathBuf], patterns: &[String], case_insensitive: bool) {
+    if patterns.is_empty() {
+        return;
+    }
+
+    let matchers: Vec<GlobMatcher> = patterns
+        .iter()
+        .filter_map(|pattern| {
+            match GlobBuilder::new(pattern).literal_separator(true).case_insensitive(case_insensitive).build() {
+                Ok(glob) => Some(glob.compile_matcher()),
+                Err(e) => {
+                    tracing::warn!("шаблон content_order '{}' пропущен: {}", pattern, e);
+                    None
+                }
+            }
+        })
+        .collect();
+
+    files.sort_by_cached_key(|path| {
+        let relative = relative_path(base_dir, path);
+        let group = matchers.iter().position(|matcher| matcher.is_match(&relative)).unwrap_or(matchers.len());
+        (group, relative)
+    });
+}
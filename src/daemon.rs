@@ -0,0 +1,484 @@
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Component, Path, PathBuf};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Mutex, PoisonError};
+use std::time::Instant;
+use ignore::gitignore::Gitignore;
+use serde_json::{Value, json};
+
+use crate::atomic_file;
+use crate::render::{self, Renderer};
+use crate::{
+    AppConfig, Config, anchors, content_files, content_language, create_ignore_matcher, load_config, project,
+    relative_path, scan, secrets, tree, write_file_content, write_file_heading, write_markdown_format,
+};
+
+// `proj2tree daemon --socket PATH`: долгоживущий процесс для плагинов редакторов и ботов,
+// которые вызывают proj2tree раз за разом. Конфигурация, .gitignore и дерево директории
+// держатся в памяти между запросами и перечитываются только по scan и regenerate.
+// Протокол - JSON-RPC 2.0 через Unix-сокет, по одному запросу и ответу на строку.
+// `proj2tree --stdio-api` говорит на том же протоколе через stdin/stdout: так расширение
+// редактора запускает процесс сам и не зависит от Unix-сокетов. Методы get_tree,
+// render_subtree и render_file отдают модель дерева и готовые фрагменты документа
+// для встраивания в webview
+
+const PARSE_ERROR: i32 = -32700;
+const INVALID_REQUEST: i32 = -32600;
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+const INTERNAL_ERROR: i32 = -32603;
+const SERVER_ERROR: i32 = -32000;
+
+// Состояние последнего сканирования
+struct Snapshot {
+    config: Config,
+    gitignore_matcher: Option<Gitignore>,
+    nodes: Vec<tree::TreeNode>,
+    files: Vec<PathBuf>,
+}
+
+struct Daemon<'r> {
+    base_dir: PathBuf,
+    app_config: AppConfig,
+    auto_exclude: bool,
+    snapshot: Option<Snapshot>,
+    // Форматы для render_subtree: встроенные и подключенные через run_with_renderers
+    renderers: &'r [Box<dyn Renderer>],
+}
+
+struct RpcError {
+    code: i32,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i32, message: impl Into<String>) -> RpcError {
+        RpcError { code, message: message.into() }
+    }
+}
+
+impl From<io::Error> for RpcError {
+    fn from(error: io::Error) -> RpcError {
+        RpcError::new(SERVER_ERROR, error.to_string())
+    }
+}
+
+#[cfg(unix)]
+pub fn serve(
+    socket: &Path,
+    base_dir: &Path,
+    app_config: AppConfig,
+    auto_exclude: bool,
+    renderers: &[Box<dyn Renderer>],
+) -> io::Result<()> {
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    // Сокет от упавшего процесса мешает bind; живой демон на нем не заменяется
+    if socket.exists() {
+        if UnixStream::connect(socket).is_ok() {
+            return Err(io::Error::new(
+                io::ErrorKind::AddrInUse,
+                format!("сокет {} уже обслуживается другим процессом", socket.display()),
+            ));
+        }
+        fs::remove_file(socket)?;
+    }
+
+    let listener = UnixListener::bind(socket)?;
+    tracing::info!("Демон слушает {} для директории {}", socket.display(), base_dir.display());
+
+    let daemon = Mutex::new(Daemon { base_dir: base_dir.to_path_buf(), app_config, auto_exclude, snapshot: None, renderers });
+    // Каждый клиент обслуживается в своем потоке, чтобы открытое соединение не задерживало
+    // остальных; запросы меняют общее состояние, поэтому выполняются под блокировкой по одному
+    std::thread::scope(|scope| {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    tracing::warn!("не удалось принять соединение: {}", e);
+                    continue;
+                }
+            };
+            let daemon = &daemon;
+            scope.spawn(move || {
+                let result = stream.try_clone().and_then(|reader| handle_connection(daemon, BufReader::new(reader), stream));
+                if let Err(e) = result {
+                    tracing::warn!("соединение прервано: {}", e);
+                }
+            });
+        }
+    });
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn serve(
+    _socket: &Path,
+    _base_dir: &Path,
+    _app_config: AppConfig,
+    _auto_exclude: bool,
+    _renderers: &[Box<dyn Renderer>],
+) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "daemon поддерживается только в системах с Unix-сокетами"))
+}
+
+// Один клиент - процесс, который запустил proj2tree; работа завершается с закрытием stdin
+pub fn serve_stdio(
+    base_dir: &Path,
+    app_config: AppConfig,
+    auto_exclude: bool,
+    renderers: &[Box<dyn Renderer>],
+) -> io::Result<()> {
+    let daemon = Mutex::new(Daemon { base_dir: base_dir.to_path_buf(), app_config, auto_exclude, snapshot: None, renderers });
+    handle_connection(&daemon, io::stdin().lock(), io::stdout().lock())
+}
+
+fn handle_connection<R: BufRead, W: Write>(daemon: &Mutex<Daemon>, reader: R, mut writer: W) -> io::Result<()> {
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        // Паника в запросе перехватывается в respond, но и отравленная блокировка
+        // не должна ронять всех следующих клиентов
+        let response = daemon.lock().unwrap_or_else(PoisonError::into_inner).respond(&line);
+        // На уведомления (запросы без id) JSON-RPC не отвечает
+        if let Some(response) = response {
+            writeln!(writer, "{}", response)?;
+            writer.flush()?;
+        }
+    }
+    Ok(())
+}
+
+impl Daemon<'_> {
+    fn respond(&mut self, line: &str) -> Option<String> {
+        let Ok(request) = serde_json::from_str::<Value>(line) else {
+            return Some(error_response(Value::Null, &RpcError::new(PARSE_ERROR, "некорректный JSON")));
+        };
+        // Идентификатор возвращается в том виде, в каком пришел
+        let id = match request.get("id") {
+            Some(id @ (Value::Number(_) | Value::String(_))) => id.clone(),
+            _ => Value::Null,
+        };
+        let Some(method) = request.get("method").and_then(Value::as_str) else {
+            return Some(error_response(id, &RpcError::new(INVALID_REQUEST, "не указан method")));
+        };
+        let notification = request.get("id").is_none();
+
+        let params = request.get("params").cloned().unwrap_or_else(|| json!({}));
+        let started = Instant::now();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| self.call(method, &params))).unwrap_or_else(|_| {
+            self.reset();
+            Err(RpcError::new(INTERNAL_ERROR, format!("внутренняя ошибка при выполнении '{}'", method)))
+        });
+        tracing::debug!("{}: {:?}", method, started.elapsed());
+
+        match result {
+            _ if notification => {
+                if let Err(error) = result {
+                    tracing::warn!("{}: {}", method, error.message);
+                }
+                None
+            }
+            Ok(result) => Some(json!({ "jsonrpc": "2.0", "id": id, "result": result }).to_string()),
+            Err(error) => Some(error_response(id, &error)),
+        }
+    }
+
+    fn call(&mut self, method: &str, params: &Value) -> Result<Value, RpcError> {
+        match method {
+            "scan" => self.scan(),
+            "get-tree" => self.get_tree(),
+            "get-file" => self.get_file(params),
+            "regenerate" => self.regenerate(params),
+            "get_tree" => self.get_tree_model(),
+            "render_subtree" => self.render_subtree(params),
+            "render_file" => self.render_file(params),
+            _ => Err(RpcError::new(METHOD_NOT_FOUND, format!("неизвестный метод '{}'", method))),
+        }
+    }
+
+    // После паники снимок и накопленные за запрос данные могли остаться недостроенными:
+    // снимок сбрасывается, и следующий запрос сканирует директорию заново
+    fn reset(&mut self) {
+        self.snapshot = None;
+        let app_config = &self.app_config;
+        app_config.quick_selection.clear_poison();
+        app_config.walk_time.clear_poison();
+        app_config.sample_cache.clear_poison();
+        app_config.walk_errors.clear_poison();
+        app_config.notices.clear_poison();
+        app_config.skipped.clear_poison();
+        app_config.excluded_sizes.clear_poison();
+        app_config.small_dirs.clear_poison();
+        app_config.empty_dirs.clear_poison();
+        app_config.token_counts.clear_poison();
+        app_config.slugs.clear_poison();
+    }
+
+    // Повторный обход с перечитыванием конфигурации и .gitignore
+    fn scan(&mut self) -> Result<Value, RpcError> {
+        self.rescan()?;
+        let snapshot = self.snapshot.as_ref().unwrap();
+        Ok(json!({ "files": count_files(&snapshot.nodes), "included": snapshot.files.len() }))
+    }
+
+    fn get_tree(&mut self) -> Result<Value, RpcError> {
+        self.ensure_scanned()?;
+        let snapshot = self.snapshot.as_ref().unwrap();
+        let mut tree = Vec::new();
+        tree::render_pretty(&snapshot.nodes, &mut tree, "", &self.app_config)?;
+        Ok(json!({ "tree": String::from_utf8_lossy(&tree) }))
+    }
+
+    // Модель дерева в том же виде, что поле "tree" формата json: расширение строит по ней
+    // свое представление, а не разбирает текст
+    fn get_tree_model(&mut self) -> Result<Value, RpcError> {
+        self.ensure_scanned()?;
+        let snapshot = self.snapshot.as_ref().unwrap();
+        Ok(json!({ "tree": scan::nodes_json(&self.base_dir, &snapshot.nodes) }))
+    }
+
+    // Документ для директории из кэша: ее дерево и содержимое ее файлов. Формат - любой
+    // из --format (markdown по умолчанию, html для webview)
+    fn render_subtree(&mut self, params: &Value) -> Result<Value, RpcError> {
+        let relative = params.get("path").and_then(Value::as_str).unwrap_or("");
+        let format = params.get("format").and_then(Value::as_str).unwrap_or("markdown");
+        let renderer = render::find(self.renderers, format).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))?;
+        self.ensure_scanned()?;
+        let snapshot = self.snapshot.as_ref().unwrap();
+
+        let target = Path::new(relative.trim_matches('/'));
+        let nodes = if target.as_os_str().is_empty() || target == Path::new(".") {
+            snapshot.nodes.clone()
+        } else {
+            match find_directory(&self.base_dir, &snapshot.nodes, target) {
+                Some(node) => vec![node.clone()],
+                None => {
+                    return Err(RpcError::new(INVALID_PARAMS, format!("директории '{}' нет в дереве", relative)));
+                }
+            }
+        };
+
+        let mut document = Vec::new();
+        let view = render::Snapshot::new(&self.base_dir, &nodes, &self.app_config, &snapshot.config);
+        renderer.render(&view, &mut document)?;
+        Ok(json!({ "path": relative, "format": format, "document": String::from_utf8_lossy(&document) }))
+    }
+
+    // Раздел одного файла так, как он выглядит в документе markdown: заголовок, пометки
+    // и блок кода с учетом маскирования секретов, выборки строк и нормализации
+    fn render_file(&mut self, params: &Value) -> Result<Value, RpcError> {
+        let Some(relative) = params.get("path").and_then(Value::as_str) else {
+            return Err(RpcError::new(INVALID_PARAMS, "не указан path"));
+        };
+        let path = self.base_dir.join(relative);
+        self.ensure_scanned()?;
+        let snapshot = self.snapshot.as_ref().unwrap();
+        if !snapshot.files.contains(&path) {
+            return Err(RpcError::new(INVALID_PARAMS, format!("файл '{}' не включен в документ", relative)));
+        }
+
+        let mut section = Vec::new();
+        let relative_path = relative_path(&self.base_dir, &path);
+        write_file_heading(&mut section, &relative_path, &mut PathBuf::new(), &self.app_config)?;
+        write_file_content(&mut section, &path, &self.app_config, &snapshot.config)?;
+        Ok(json!({ "path": relative, "markdown": String::from_utf8_lossy(&section).trim_start() }))
+    }
+
+    // Отдаются только файлы, включенные в документ по правилам проекта: демон
+    // не должен читать произвольные пути по запросу
+    fn get_file(&mut self, params: &Value) -> Result<Value, RpcError> {
+        let Some(relative) = params.get("path").and_then(Value::as_str) else {
+            return Err(RpcError::new(INVALID_PARAMS, "не указан path"));
+        };
+        let path = self.base_dir.join(relative);
+        self.ensure_scanned()?;
+        let snapshot = self.snapshot.as_ref().unwrap();
+        if !snapshot.files.contains(&path) {
+            return Err(RpcError::new(INVALID_PARAMS, format!("файл '{}' не включен в документ", relative)));
+        }
+
+        let content = self.app_config.fs.read_to_string(&path)?;
+        let content = if !self.app_config.unsafe_full_secrets && secrets::is_secret_file(&path, &snapshot.config) {
+            secrets::mask_secrets(&content)
+        } else {
+            content
+        };
+        Ok(json!({
+            "path": relative,
+            "language": content_language(&path, &content, &snapshot.config),
+            "content": content,
+        }))
+    }
+
+    // Полный документ markdown в файл (как при обычном запуске) или в ответ с "print": true
+    fn regenerate(&mut self, params: &Value) -> Result<Value, RpcError> {
+        self.rescan()?;
+        let snapshot = self.snapshot.as_ref().unwrap();
+
+        let mut document = Vec::new();
+        write_markdown_format(
+            &self.base_dir,
+            &mut document,
+            &self.app_config,
+            &snapshot.config,
+            &snapshot.gitignore_matcher,
+        )?;
+
+        if matches!(params.get("print"), Some(Value::Bool(true))) {
+            return Ok(json!({ "document": String::from_utf8_lossy(&document) }));
+        }
+
+        let output = match (params.get("output").and_then(Value::as_str), self.app_config.output_file.as_deref()) {
+            (Some(file), configured) if configured != Some(file) => self.client_output(file)?,
+            (_, Some(file)) => PathBuf::from(file),
+            (_, None) => self.base_dir.join("tree.md"),
+        };
+        atomic_file::write(&output, &document)?;
+        tracing::info!("Результат сохранен в файл: {}", output.display());
+        Ok(json!({ "output": output.to_string_lossy(), "bytes": document.len() }))
+    }
+
+    // Клиент может указать только файл внутри сканируемой директории: иначе любой, кто
+    // подключился к сокету, перезаписал бы произвольный файл от имени владельца демона
+    fn client_output(&self, file: &str) -> Result<PathBuf, RpcError> {
+        let output = self.base_dir.join(file);
+        let outside = || RpcError::new(INVALID_PARAMS, format!("output '{}' вне директории {}", file, self.base_dir.display()));
+        if output.components().any(|component| component == Component::ParentDir) {
+            return Err(outside());
+        }
+        // Родитель сравнивается после разрешения символических ссылок
+        let (Some(parent), Some(_)) = (output.parent(), output.file_name()) else {
+            return Err(outside());
+        };
+        let parent = fs::canonicalize(parent)
+            .map_err(|e| RpcError::new(INVALID_PARAMS, format!("output '{}': {}", file, e)))?;
+        if !parent.starts_with(fs::canonicalize(&self.base_dir)?) {
+            return Err(outside());
+        }
+        Ok(output)
+    }
+
+    // Первый запрос без scan сканирует директорию сам
+    fn ensure_scanned(&mut self) -> io::Result<()> {
+        if self.snapshot.is_none() {
+            self.rescan()?;
+        }
+        Ok(())
+    }
+
+    fn rescan(&mut self) -> io::Result<()> {
+        // Накопленное прошлыми запусками относится к старому состоянию директории
+        self.app_config.walk_errors.lock().unwrap().clear();
+        self.app_config.skipped.lock().unwrap().clear();
+        self.app_config.excluded_sizes.lock().unwrap().clear();
+        self.app_config.sample_cache.lock().unwrap().clear();
+        self.app_config.small_dirs.lock().unwrap().clear();
+        self.app_config.empty_dirs.lock().unwrap().clear();
+        *self.app_config.slugs.lock().unwrap() = anchors::SlugMap::new(self.app_config.ascii_paths);
+
+        let mut config = load_config(&self.base_dir);
+        if self.auto_exclude {
+            project::apply_auto_exclusions(&self.base_dir, &mut config);
+        }
+        let gitignore_matcher = create_ignore_matcher(&self.app_config)?;
+        let nodes = tree::build_tree(&self.base_dir, &self.app_config, &config, &gitignore_matcher)?;
+        let files = content_files(&nodes, &self.app_config, &config);
+
+        self.snapshot = Some(Snapshot { config, gitignore_matcher, nodes, files });
+        Ok(())
+    }
+}
+
+fn find_directory<'n>(base_dir: &Path, nodes: &'n [tree::TreeNode], target: &Path) -> Option<&'n tree::TreeNode> {
+    nodes.iter().find_map(|node| match &node.kind {
+        tree::NodeKind::Directory(_) if relative_path(base_dir, &node.path) == target => Some(node),
+        tree::NodeKind::Directory(children) if target.starts_with(relative_path(base_dir, &node.path)) => {
+            find_directory(base_dir, children, target)
+        }
+        _ => None,
+    })
+}
+
+fn count_files(nodes: &[tree::TreeNode]) -> usize {
+    let mut files = Vec::new();
+    tree::file_paths(nodes, &mut files);
+    files.len()
+}
+
+fn error_response(id: Value, error: &RpcError) -> String {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": error.code, "message": error.message } }).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{app_config_from_matches, cli};
+
+    struct Panicking;
+
+    impl Renderer for Panicking {
+        fn name(&self) -> &str {
+            "panic"
+        }
+
+        fn render(&self, _snapshot: &render::Snapshot, _writer: &mut dyn Write) -> io::Result<()> {
+            panic!("сбой рендерера");
+        }
+    }
+
+    fn daemon<'r>(name: &str, renderers: &'r [Box<dyn Renderer>]) -> (PathBuf, Mutex<Daemon<'r>>) {
+        let dir = std::env::temp_dir().join(format!("proj2tree-daemon-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("main.rs"), "fn main() {}\n").unwrap();
+        let matches = cli().get_matches_from(["proj2tree".as_ref(), dir.as_os_str()]);
+        let app_config = app_config_from_matches(&matches).unwrap();
+        let daemon = Daemon { base_dir: dir.clone(), app_config, auto_exclude: false, snapshot: None, renderers };
+        (dir, Mutex::new(daemon))
+    }
+
+    fn exchange(daemon: &Mutex<Daemon>, requests: &str) -> Vec<Value> {
+        let mut output = Vec::new();
+        handle_connection(daemon, requests.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output).unwrap().lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+    }
+
+    #[test]
+    fn panicking_request_returns_error_and_later_requests_work() {
+        let renderers: Vec<Box<dyn Renderer>> = vec![Box::new(Panicking)];
+        let (dir, daemon) = daemon("panic", &renderers);
+
+        let responses = exchange(
+            &daemon,
+            "{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"render_subtree\",\"params\":{\"format\":\"panic\"}}\n\
+             {\"jsonrpc\":\"2.0\",\"id\":2,\"method\":\"scan\"}\n",
+        );
+
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(responses[0]["error"]["code"], INTERNAL_ERROR);
+        assert_eq!(responses[1]["result"]["files"], 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn poisoned_lock_does_not_break_later_clients() {
+        let (dir, daemon) = daemon("poison", &[]);
+        let _ = std::thread::scope(|scope| {
+            scope.spawn(|| {
+                let _guard = daemon.lock().unwrap();
+                panic!("сбой при удержании блокировки");
+            })
+            .join()
+        });
+        assert!(daemon.is_poisoned());
+
+        let responses = exchange(&daemon, "{\"jsonrpc\":\"2.0\",\"id\":\"a\",\"method\":\"scan\"}\n");
+
+        assert_eq!(responses[0]["id"], "a");
+        assert_eq!(responses[0]["result"]["included"], 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
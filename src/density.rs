@@ -0,0 +1,80 @@
+// Оценка «плотности информации» файла (--density): отношение размера после сжатия к исходному.
+// Сгенерированный код, фикстуры и дампы сжимаются в разы лучше написанного руками и
+// выделяются в отчете как кандидаты на исключение.
+// Deflate не реализуется: проход LZ77 с окном deflate находит повторы, литералы оцениваются
+// энтропией Шеннона (как их закодировал бы Хаффман), совпадение - тремя байтами
+
+const WINDOW: usize = 32 * 1024;
+const MIN_MATCH: usize = 4;
+const MAX_MATCH: usize = 258;
+const HASH_BITS: u32 = 15;
+const MATCH_COST_BITS: f64 = 24.0;
+
+// Доля от исходного размера, от 0 до 1. Пустой файл считается несжимаемым
+pub fn ratio(data: &[u8]) -> f64 {
+    if data.is_empty() {
+        return 1.0;
+    }
+
+    let mut heads = vec![usize::MAX; 1 << HASH_BITS];
+    let mut literals = [0usize; 256];
+    let mut matches = 0usize;
+    let mut i = 0;
+
+    while i < data.len() {
+        if i + MIN_MATCH > data.len() {
+            literals[data[i] as usize] += 1;
+            i += 1;
+            continue;
+        }
+
+        let hash = hash(&data[i..i + MIN_MATCH]);
+        let candidate = heads[hash];
+        heads[hash] = i;
+
+        let length = if candidate != usize::MAX && i - candidate <= WINDOW {
+            data[i..]
+                .iter()
+                .zip(&data[candidate..])
+                .take(MAX_MATCH)
+                .take_while(|(a, b)| a == b)
+                .count()
+        } else {
+            0
+        };
+
+        if length >= MIN_MATCH {
+            matches += 1;
+            // Позиции внутри совпадения тоже становятся кандидатами для следующих повторов
+            for position in i + 1..(i + length).min(data.len() - MIN_MATCH + 1) {
+                heads[hash_at(data, position)] = position;
+            }
+            i += length;
+        } else {
+            literals[data[i] as usize] += 1;
+            i += 1;
+        }
+    }
+
+    let literal_count: usize = literals.iter().sum();
+    let entropy_bits: f64 = literals
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let probability = count as f64 / literal_count as f64;
+            -(count as f64) * probability.log2()
+        })
+        .sum();
+
+    let compressed_bytes = (entropy_bits + matches as f64 * MATCH_COST_BITS) / 8.0;
+    (compressed_bytes / data.len() as f64).clamp(0.0, 1.0)
+}
+
+fn hash_at(data: &[u8], position: usize) -> usize {
+    hash(&data[position..position + MIN_MATCH])
+}
+
+fn hash(bytes: &[u8]) -> usize {
+    let value = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    (value.wrapping_mul(0x9e37_79b1) >> (32 - HASH_BITS)) as usize
+}
@@ -0,0 +1,357 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::path::{Component, Path, PathBuf};
+
+const JS_EXTENSIONS: [&str; 8] = ["ts", "tsx", "js", "jsx", "mjs", "cjs", "vue", "svelte"];
+const C_EXTENSIONS: [&str; 8] = ["c", "h", "cc", "cpp", "cxx", "hh", "hpp", "hxx"];
+
+// Упорядочивает файлы так, чтобы зависимости шли раньше зависимых. Порядок по пути сохраняется
+// везде, где он не противоречит зависимостям; циклы разрываются в пользу файла, идущего раньше по пути
+pub fn order_by_dependencies(files: &[PathBuf], read_file: &dyn Fn(&Path) -> Option<String>) -> Vec<PathBuf> {
+    let index: HashMap<PathBuf, usize> = files
+        .iter()
+        .enumerate()
+        .map(|(i, path)| (normalize_path(path), i))
+        .collect();
+
+    let mut dependents = vec![Vec::new(); files.len()];
+    let mut pending = vec![0usize; files.len()];
+
+    for (i, path) in files.iter().enumerate() {
+        let Some(content) = read_file(path) else {
+            continue;
+        };
+
+        let mut dependencies: Vec<usize> = file_dependencies(path, &content)
+            .iter()
+            .filter_map(|candidate| index.get(&normalize_path(candidate)).copied())
+            .filter(|&dependency| dependency != i)
+            .collect();
+        dependencies.sort_unstable();
+        dependencies.dedup();
+
+        pending[i] = dependencies.len();
+        for dependency in dependencies {
+            dependents[dependency].push(i);
+        }
+    }
+
+    let mut ready: BinaryHeap<Reverse<usize>> = (0..files.len())
+        .filter(|&i| pending[i] == 0)
+        .map(Reverse)
+        .collect();
+    let mut placed = vec![false; files.len()];
+    let mut order = Vec::with_capacity(files.len());
+
+    while order.len() < files.len() {
+        let next = match ready.pop() {
+            Some(Reverse(i)) if placed[i] => continue,
+            Some(Reverse(i)) => i,
+            // Все оставшиеся файлы в циклах - берем первый по пути
+            None => (0..files.len()).find(|&i| !placed[i]).unwrap_or_default(),
+        };
+
+        placed[next] = true;
+        order.push(files[next].clone());
+
+        for &dependent in &dependents[next] {
+            pending[dependent] = pending[dependent].saturating_sub(1);
+            if pending[dependent] == 0 && !placed[dependent] {
+                ready.push(Reverse(dependent));
+            }
+        }
+    }
+
+    order
+}
+
+// Пути-кандидаты, на которые ссылается файл. Несуществующие кандидаты отбрасываются вызывающим кодом
+pub fn file_dependencies(path: &Path, content: &str) -> Vec<PathBuf> {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "rs" => rust_dependencies(path, content),
+        "py" => python_dependencies(path, content),
+        ext if JS_EXTENSIONS.contains(&ext) => js_dependencies(path, content),
+        ext if C_EXTENSIONS.contains(&ext) => c_dependencies(path, content),
+        _ => Vec::new(),
+    }
+}
+
+fn rust_dependencies(path: &Path, content: &str) -> Vec<PathBuf> {
+    let parent = path.parent().unwrap_or(Path::new(""));
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let module_dir = if matches!(stem.as_ref(), "main" | "lib" | "mod") {
+        parent.to_path_buf()
+    } else {
+        parent.join(stem.as_ref())
+    };
+    let crate_root = rust_crate_root(path);
+
+    let mut candidates = Vec::new();
+    let mut lines = content.lines();
+
+    while let Some(line) = lines.next() {
+        let mut line = strip_visibility(line.trim()).to_string();
+
+        // Многострочный `use crate::{...}` собирается до точки с запятой
+        if line.starts_with("use ") {
+            while !line.contains(';') {
+                let Some(next) = lines.next() else {
+                    break;
+                };
+                line.push(' ');
+                line.push_str(next.trim());
+            }
+        }
+
+        if let Some(name) = line.strip_prefix("mod ").and_then(|rest| rest.strip_suffix(';')) {
+            push_rust_module(&mut candidates, &module_dir, &[name.trim()]);
+        } else if let Some(rest) = line.strip_prefix("use ") {
+            let (base, rest) = if let Some(rest) = rest.strip_prefix("crate::") {
+                (crate_root.clone(), rest)
+            } else if let Some(rest) = rest.strip_prefix("super::") {
+                (module_dir.parent().unwrap_or(Path::new("")).to_path_buf(), rest)
+            } else if let Some(rest) = rest.strip_prefix("self::") {
+                (module_dir.clone(), rest)
+            } else {
+                continue;
+            };
+
+            for use_path in expand_use_tree(rest.trim_end_matches(';')) {
+                push_rust_module(&mut candidates, &base, &use_path);
+            }
+        }
+    }
+
+    candidates
+}
+
+fn strip_visibility(line: &str) -> &str {
+    if let Some(rest) = line.strip_prefix("pub ") {
+        return rest.trim_start();
+    }
+    if line.starts_with("pub(")
+        && let Some(end) = line.find(')')
+    {
+        return line[end + 1..].trim_start();
+    }
+    line
+}
+
+// Корень крейта - ближайшая директория src, иначе директория файла
+fn rust_crate_root(path: &Path) -> PathBuf {
+    path.ancestors()
+        .skip(1)
+        .find(|dir| dir.file_name().is_some_and(|name| name == "src"))
+        .or(path.parent())
+        .unwrap_or(Path::new(""))
+        .to_path_buf()
+}
+
+// Каждый префикс пути `a::b::c` может быть модулем: a.rs, a/mod.rs, a/b.rs, ...
+fn push_rust_module(candidates: &mut Vec<PathBuf>, base: &Path, segments: &[&str]) {
+    let mut dir = base.to_path_buf();
+    for segment in segments {
+        if segment.is_empty() || *segment == "self" || *segment == "*" {
+            break;
+        }
+        candidates.push(dir.join(format!("{}.rs", segment)));
+        candidates.push(dir.join(segment).join("mod.rs"));
+        dir = dir.join(segment);
+    }
+}
+
+// Раскрывает группы `a::{b, c::d}` в плоский список путей
+fn expand_use_tree(tree: &str) -> Vec<Vec<&str>> {
+    let tree = tree.trim();
+    let Some(open) = tree.find('{') else {
+        let path = tree.split(" as ").next().unwrap_or(tree);
+        return vec![path.split("::").map(str::trim).collect()];
+    };
+
+    let prefix: Vec<&str> = tree[..open]
+        .split("::")
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    let inner = tree[open + 1..].trim_end().strip_suffix('}').unwrap_or(&tree[open + 1..]);
+
+    let mut result = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in inner.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                push_expanded(&mut result, &prefix, &inner[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    push_expanded(&mut result, &prefix, &inner[start..]);
+
+    result
+}
+
+fn push_expanded<'a>(result: &mut Vec<Vec<&'a str>>, prefix: &[&'a str], item: &'a str) {
+    if item.trim().is_empty() {
+        return;
+    }
+    for path in expand_use_tree(item) {
+        let mut full = prefix.to_vec();
+        full.extend(path);
+        result.push(full);
+    }
+}
+
+fn python_dependencies(path: &Path, content: &str) -> Vec<PathBuf> {
+    let parent = path.parent().unwrap_or(Path::new(""));
+    let mut candidates = Vec::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+
+        if let Some(rest) = line.strip_prefix("import ") {
+            for module in rest.split(',') {
+                let module = module.split(" as ").next().unwrap_or("").trim();
+                push_python_module(&mut candidates, path, module);
+            }
+        } else if let Some(rest) = line.strip_prefix("from ") {
+            let Some((module, names)) = rest.split_once(" import ") else {
+                continue;
+            };
+            let module = module.trim();
+            let dots = module.chars().take_while(|&c| c == '.').count();
+
+            let names = names.trim_matches(|c: char| c == '(' || c == ')' || c.is_whitespace());
+            let names: Vec<&str> = names
+                .split(',')
+                .map(|name| name.split(" as ").next().unwrap_or("").trim())
+                .filter(|name| !name.is_empty() && *name != "*")
+                .collect();
+
+            if dots > 0 {
+                // Относительный импорт: каждая точка после первой поднимает на уровень выше
+                let mut dir = parent.to_path_buf();
+                for _ in 1..dots {
+                    dir = dir.parent().unwrap_or(Path::new("")).to_path_buf();
+                }
+                let module_dir = push_python_path(&mut candidates, &dir, &module[dots..]);
+                for name in names {
+                    push_python_path(&mut candidates, &module_dir, name);
+                }
+            } else {
+                push_python_module(&mut candidates, path, module);
+                for name in names {
+                    push_python_module(&mut candidates, path, &format!("{}.{}", module, name));
+                }
+            }
+        }
+    }
+
+    candidates
+}
+
+// Абсолютный импорт ищется от каждой родительской директории файла: корень пакета заранее неизвестен
+fn push_python_module(candidates: &mut Vec<PathBuf>, path: &Path, module: &str) {
+    for dir in path.ancestors().skip(1) {
+        push_python_path(candidates, dir, module);
+    }
+}
+
+fn push_python_path(candidates: &mut Vec<PathBuf>, dir: &Path, module: &str) -> PathBuf {
+    let mut module_path = dir.to_path_buf();
+    for part in module.split('.').filter(|part| !part.is_empty()) {
+        module_path = module_path.join(part);
+    }
+    if module_path != dir {
+        candidates.push(module_path.with_extension("py"));
+        candidates.push(module_path.join("__init__.py"));
+    }
+    module_path
+}
+
+fn js_dependencies(path: &Path, content: &str) -> Vec<PathBuf> {
+    let parent = path.parent().unwrap_or(Path::new(""));
+    let mut candidates = Vec::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        let is_import = line.starts_with("import ")
+            || line.starts_with("export ")
+            || line.contains("require(")
+            || line.contains("import(");
+        if !is_import {
+            continue;
+        }
+
+        for specifier in quoted_strings(line) {
+            if !specifier.starts_with('.') {
+                continue;
+            }
+            let target = parent.join(specifier);
+            candidates.push(target.clone());
+            for ext in JS_EXTENSIONS {
+                let mut with_extension = target.clone().into_os_string();
+                with_extension.push(".");
+                with_extension.push(ext);
+                candidates.push(PathBuf::from(with_extension));
+                candidates.push(target.join(format!("index.{}", ext)));
+            }
+        }
+    }
+
+    candidates
+}
+
+fn c_dependencies(path: &Path, content: &str) -> Vec<PathBuf> {
+    let parent = path.parent().unwrap_or(Path::new(""));
+
+    content
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("#include"))
+        .filter_map(|rest| quoted_strings(rest).into_iter().next())
+        .map(|header| parent.join(header))
+        .collect()
+}
+
+fn quoted_strings(line: &str) -> Vec<&str> {
+    let mut result = Vec::new();
+    let mut rest = line;
+
+    while let Some(start) = rest.find(['"', '\'']) {
+        let quote = rest[start..].chars().next().unwrap_or('"');
+        let after = &rest[start + 1..];
+        let Some(end) = after.find(quote) else {
+            break;
+        };
+        result.push(&after[..end]);
+        rest = &after[end + 1..];
+    }
+
+    result
+}
+
+// Лексическая нормализация: `a/./b/../c` -> `a/c`, без обращения к файловой системе
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match result.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    result.pop();
+                }
+                _ => result.push(".."),
+            },
+            other => result.push(other),
+        }
+    }
+    result
+}
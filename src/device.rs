@@ -0,0 +1,92 @@
+use std::path::Path;
+
+// Идентификаторы файловой системы для --one-file-system, --owner и поиска жестких ссылок,
+// точки повторной обработки Windows. На платформах без устройств, inode, владельцев
+// и reparse points в std функции ничего не сообщают
+
+#[cfg(unix)]
+pub fn device_id(path: &Path) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+
+    std::fs::metadata(path).ok().map(|metadata| metadata.dev())
+}
+
+#[cfg(not(unix))]
+pub fn device_id(_path: &Path) -> Option<u64> {
+    None
+}
+
+// (устройство, inode) для файлов, у которых больше одного имени. Берется из метаданных,
+// уже полученных при обходе
+#[cfg(unix)]
+pub fn hard_link_id(metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+
+    (metadata.nlink() > 1).then(|| (metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+pub fn hard_link_id(_metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+#[cfg(unix)]
+pub fn owner_id(path: &Path) -> Option<u32> {
+    use std::os::unix::fs::MetadataExt;
+
+    std::fs::metadata(path).ok().map(|metadata| metadata.uid())
+}
+
+#[cfg(not(unix))]
+pub fn owner_id(_path: &Path) -> Option<u32> {
+    None
+}
+
+// Точки повторной обработки NTFS (reparse points). Junction и ссылки на директории
+// std считает символическими ссылками; у файлов OneDrive и других облачных хранилищ
+// вместо этого стоят атрибуты отложенной загрузки, и чтение такого файла скачивает его
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(not(windows), allow(dead_code))]
+pub enum ReparsePoint {
+    Link,
+    CloudPlaceholder,
+}
+
+// symlink_metadata не открывает сам файл и загрузку не запускает
+#[cfg(windows)]
+pub fn reparse_point(path: &Path) -> Option<ReparsePoint> {
+    use std::os::windows::fs::MetadataExt;
+
+    const FILE_ATTRIBUTE_REPARSE_POINT: u32 = 0x400;
+    const FILE_ATTRIBUTE_OFFLINE: u32 = 0x1000;
+    const FILE_ATTRIBUTE_RECALL_ON_OPEN: u32 = 0x40000;
+    const FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS: u32 = 0x400000;
+
+    let metadata = std::fs::symlink_metadata(path).ok()?;
+    let attributes = metadata.file_attributes();
+    if attributes & (FILE_ATTRIBUTE_OFFLINE | FILE_ATTRIBUTE_RECALL_ON_OPEN | FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS) != 0 {
+        Some(ReparsePoint::CloudPlaceholder)
+    } else if attributes & FILE_ATTRIBUTE_REPARSE_POINT != 0 && metadata.file_type().is_symlink() {
+        Some(ReparsePoint::Link)
+    } else {
+        None
+    }
+}
+
+#[cfg(not(windows))]
+pub fn reparse_point(_path: &Path) -> Option<ReparsePoint> {
+    None
+}
+
+// UID пользователя для --owner: число или имя из /etc/passwd
+pub fn user_id(user: &str) -> Option<u32> {
+    if let Ok(uid) = user.parse() {
+        return Some(uid);
+    }
+    let passwd = std::fs::read_to_string("/etc/passwd").ok()?;
+    passwd.lines().find_map(|line| {
+        let mut fields = line.split(':');
+        (fields.next()? == user).then_some(())?;
+        fields.nth(1)?.parse().ok()
+    })
+}
@@ -0,0 +1,85 @@
+use std::path::Path;
+use serde::{Deserialize, Deserializer};
+
+use crate::vfs::FileSystem;
+
+// --skip-dir-over / skip_dir_over: директория, файлы которой в сумме больше порога,
+// сворачивается в `name/ ...`, как исключенная по имени. Подсчет ограничен: он прекращается,
+// как только сумма превысила порог, а после MAX_SCANNED элементов директория считается
+// небольшой - сотни тысяч мелких файлов не должны стоить полного обхода
+
+const MAX_SCANNED: usize = 100_000;
+
+pub enum Scan {
+    Over,
+    // Все файлы учтены, сумма не больше порога: поддиректории проверять не нужно
+    Under,
+    Incomplete,
+}
+
+// Размер в байтах или с суффиксом K, M, G, T (степени 1024): `200M`, `1.5G`, `512KiB`
+pub fn parse_size(text: &str) -> Result<u64, String> {
+    let text = text.trim();
+    let upper = text.to_ascii_uppercase();
+    let number = upper.trim_end_matches("IB").trim_end_matches('B');
+    let (number, multiplier) = match number.chars().last() {
+        Some('K') => (&number[..number.len() - 1], 1u64 << 10),
+        Some('M') => (&number[..number.len() - 1], 1 << 20),
+        Some('G') => (&number[..number.len() - 1], 1 << 30),
+        Some('T') => (&number[..number.len() - 1], 1 << 40),
+        _ => (number, 1),
+    };
+    match number.trim().parse::<f64>() {
+        Ok(value) if value.is_finite() && value > 0.0 => Ok((value * multiplier as f64) as u64),
+        _ => Err(format!("некорректный размер '{}' (ожидается, например, 200M или 1048576)", text)),
+    }
+}
+
+// В конфигурации порог задается числом байтов или строкой с суффиксом
+pub fn deserialize_size<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Size {
+        Bytes(u64),
+        Text(String),
+    }
+
+    match Option::<Size>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(Size::Bytes(bytes)) => Ok(Some(bytes)),
+        Some(Size::Text(text)) => parse_size(&text).map(Some).map_err(serde::de::Error::custom),
+    }
+}
+
+// Символические ссылки не разыменовываются, исключения не применяются: порог относится
+// к тому, что директория занимает на диске
+pub fn scan(filesystem: &dyn FileSystem, dir: &Path, limit: u64) -> Scan {
+    let mut total = 0u64;
+    let mut scanned = 0usize;
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = filesystem.read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.into_iter().flatten() {
+            scanned += 1;
+            if scanned > MAX_SCANNED {
+                return Scan::Incomplete;
+            }
+            if entry.is_symlink {
+                continue;
+            }
+            if entry.is_dir {
+                pending.push(entry.path);
+            } else if let Ok(metadata) = filesystem.metadata(&entry.path) {
+                total += metadata.len;
+                if total > limit {
+                    return Scan::Over;
+                }
+            }
+        }
+    }
+
+    Scan::Under
+}
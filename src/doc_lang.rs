@@ -0,0 +1,664 @@
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+// Строки генерируемого документа (--doc-lang). Язык документа не связан с языком
+// сообщений в консоли: англоязычный заказчик получает документ с английскими заголовками.
+// Шаблоны с `{}` заполняются функцией fill в порядке аргументов
+
+#[derive(Debug)]
+pub struct Strings {
+    pub project_structure: &'static str,
+    pub current_directory: &'static str,
+    pub stdin_archive: &'static str,
+    pub git_revision: &'static str,
+    pub file_tree: &'static str,
+    pub file_contents: &'static str,
+    pub quick_note: &'static str,
+    pub source_code: &'static str,
+    pub test_code: &'static str,
+    pub test_contents: &'static str,
+    pub build_and_ci: &'static str,
+    pub unreadable_file: &'static str,
+    pub deleted_file: &'static str,
+    pub cloud_placeholder: &'static str,
+    pub external_file: &'static str,
+    pub external_dir: &'static str,
+    pub nav_tree: &'static str,
+    pub hard_link: &'static str,
+    pub images: &'static str,
+
+    pub git_branch: &'static str,
+    pub git_detached: &'static str,
+    pub git_commit: &'static str,
+    pub git_state: &'static str,
+    pub git_clean: &'static str,
+    pub git_dirty: &'static str,
+    pub git_unknown: &'static str,
+    pub git_remote: &'static str,
+
+    pub tree_error: &'static str,
+    pub skipped_files: &'static str,
+    pub empty_dir: &'static str,
+    pub tree_tokens: &'static str,
+    pub sampled_out: &'static str,
+    pub walk_errors: &'static str,
+    pub merge_artifacts: &'static str,
+    pub merge_artifacts_note: &'static str,
+    pub merge_conflicts: &'static str,
+    pub merge_leftover: &'static str,
+    pub permission_denied: &'static str,
+    pub broken_link: &'static str,
+    pub deleted_during_run: &'static str,
+    pub name_too_long: &'static str,
+    pub link_loop: &'static str,
+
+    pub normalized: &'static str,
+    pub secrets_masked: &'static str,
+    pub sampled_lines: &'static str,
+    pub summarized: &'static str,
+    pub summary_failed: &'static str,
+    pub tabs_expanded: &'static str,
+    pub eol_converted: &'static str,
+    pub dedented: &'static str,
+    pub lines_wrapped: &'static str,
+    pub mixed_eol: &'static str,
+    pub lossy_replaced: &'static str,
+    pub bom_stripped: &'static str,
+    pub bom_transcoded: &'static str,
+    pub syntax_error: &'static str,
+    pub syntax_missing: &'static str,
+    pub syntax_unexpected: &'static str,
+    pub syntax_unfinished: &'static str,
+    pub syntax_invalid: &'static str,
+    pub syntax_invalid_at: &'static str,
+
+    pub lfs_object: &'static str,
+    pub lfs_too_large: &'static str,
+    pub lfs_binary: &'static str,
+    pub lfs_not_fetched: &'static str,
+
+    pub lockfile_summarized: &'static str,
+    pub total_packages: &'static str,
+    pub direct_dependencies: &'static str,
+
+    pub largest_files: &'static str,
+    pub included_files: &'static str,
+    pub excluded_files: &'static str,
+    pub size: &'static str,
+    pub file: &'static str,
+    pub reason: &'static str,
+    pub size_distribution: &'static str,
+    pub size_range: &'static str,
+    pub included_count: &'static str,
+    pub excluded_count: &'static str,
+    pub excluded_by_rules: &'static str,
+    pub interrupted_at: &'static str,
+    pub interrupted: &'static str,
+    pub binary_file: &'static str,
+    pub tree_only_file: &'static str,
+    pub dotfile_list_only: &'static str,
+    pub over_max_size: &'static str,
+    pub density: &'static str,
+    pub low_density_files: &'static str,
+
+    pub project_health: &'static str,
+    pub check: &'static str,
+    pub found: &'static str,
+    pub empty_dirs: &'static str,
+    pub empty_files: &'static str,
+    // Заголовки списков под таблицей; в таблице те же проверки названы empty_dirs и empty_files
+    pub empty_dir_list: &'static str,
+    pub empty_file_list: &'static str,
+    pub files_larger: &'static str,
+    pub stale_files: &'static str,
+    pub large_files: &'static str,
+    pub long_unchanged_files: &'static str,
+    pub days: &'static str,
+    pub and_more: &'static str,
+
+    pub import_graph: &'static str,
+    pub import_graph_empty: &'static str,
+    pub docker_context: &'static str,
+    pub docker_files: &'static str,
+    pub docker_no_ignore: &'static str,
+    pub docker_total: &'static str,
+    pub docker_copy: &'static str,
+    pub docker_not_copied: &'static str,
+    pub annotations: &'static str,
+    pub line: &'static str,
+    pub marker: &'static str,
+    pub text: &'static str,
+
+    pub dependencies: &'static str,
+    pub dependency: &'static str,
+    pub requirement: &'static str,
+    pub dependency_kind: &'static str,
+    pub package: &'static str,
+    pub no_dependencies: &'static str,
+
+    pub parent: &'static str,
+    pub directory: &'static str,
+    pub content_not_included: &'static str,
+
+    pub part: &'static str,
+
+    pub footer_generated: &'static str,
+    pub footer_options: &'static str,
+    pub footer_elapsed: &'static str,
+    pub footer_hash: &'static str,
+}
+
+pub static RU: Strings = Strings {
+    project_structure: "Структура проекта",
+    current_directory: "текущая директория",
+    stdin_archive: "архив из stdin",
+    git_revision: "ревизия {}",
+    file_tree: "Дерево файлов",
+    file_contents: "Содержимое файлов",
+    quick_note: "_Быстрый обзор (--quick): содержимое {} из {} файлов, остальные показаны только в дереве_",
+    source_code: "Код",
+    test_code: "Тесты",
+    test_contents: "Содержимое тестов",
+    build_and_ci: "Сборка и CI",
+    unreadable_file: "[Не удалось прочитать файл]",
+    deleted_file: "[Файл удален во время создания документа]",
+    cloud_placeholder: "[Облачный файл-заглушка: содержимое не загружено]",
+    external_file: "Внешний файл",
+    external_dir: "Внешняя директория",
+    nav_tree: "⬆ К дереву",
+    hard_link: "*Жесткая ссылка на {}, содержимое не повторяется*",
+    images: "Изображения",
+
+    git_branch: "Ветка",
+    git_detached: "отсоединенный HEAD",
+    git_commit: "Коммит",
+    git_state: "Состояние",
+    git_clean: "без изменений",
+    git_dirty: "есть незафиксированные изменения",
+    git_unknown: "неизвестно",
+    git_remote: "Репозиторий",
+
+    tree_error: "[ошибка: {}]",
+    skipped_files: "(файлов: {})",
+    empty_dir: "(пусто)",
+    tree_tokens: "(токенов: ~{})",
+    sampled_out: "… еще файлов: {} (--sample-dir)",
+    walk_errors: "Ошибки обхода",
+    merge_artifacts: "⚠️ Незавершенное слияние",
+    merge_artifacts_note: "**Проект содержит следы неразрешенного слияния: проверьте эти файлы, прежде чем передавать снимок.**",
+    merge_conflicts: "маркеры конфликта: {} (первый в строке {})",
+    merge_leftover: "файл, оставшийся после слияния или применения патча",
+    permission_denied: "нет доступа",
+    broken_link: "битая ссылка",
+    deleted_during_run: "удален во время создания документа",
+    name_too_long: "слишком длинное имя",
+    link_loop: "циклическая ссылка",
+
+    normalized: "_Нормализовано: {}_",
+    secrets_masked: "_Значения секретов скрыты (--unsafe-full-secrets выводит их как есть)_",
+    sampled_lines: "_Показано строк: {} из {} (sampling)_",
+    summarized: "_Сводка команды {} вместо содержимого (summarizers)_",
+    summary_failed: "_Сводка команды {} не создана: {}_",
+    tabs_expanded: "табуляция → {} пробел. ({})",
+    eol_converted: "переводы строк {} → {} ({} стр.)",
+    dedented: "общий отступ снят ({} симв.)",
+    lines_wrapped: "длинные строки перенесены по ширине {} ({})",
+    mixed_eol: "смешанные",
+    lossy_replaced: "_Некорректные байты UTF-8 заменены на U+FFFD: {}_",
+    bom_stripped: "_Файл начинается с BOM ({}), метка удалена из содержимого_",
+    bom_transcoded: "_Файл в кодировке {} с BOM, содержимое перекодировано в UTF-8_",
+    syntax_error: "_⚠️ Файл не разбирается: {}_",
+    syntax_missing: "не хватает {} в строке {}",
+    syntax_unexpected: "неожиданное {} в строке {}",
+    syntax_unfinished: "конструкция из строки {} обрывается в конце файла",
+    syntax_invalid: "некорректный {}",
+    syntax_invalid_at: "некорректный {} (строка {})",
+
+    lfs_object: "LFS-объект",
+    lfs_too_large: "больше max_file_size",
+    lfs_binary: "бинарный",
+    lfs_not_fetched: "не получен",
+
+    lockfile_summarized: "*Lock-файл заменен сводкой, полное содержимое выводится с `--full-lockfiles`*",
+    total_packages: "Всего пакетов: {}",
+    direct_dependencies: "Прямые зависимости ({}):",
+
+    largest_files: "Крупнейшие файлы",
+    included_files: "Включенные",
+    excluded_files: "Исключенные",
+    size: "Размер",
+    file: "Файл",
+    reason: "Причина",
+    size_distribution: "Распределение размеров",
+    size_range: "Диапазон",
+    included_count: "Включено",
+    excluded_count: "Исключено",
+    excluded_by_rules: "правила исключения",
+    interrupted_at: "**Генерация прервана на {}**: документ неполный",
+    interrupted: "**Генерация прервана**: документ неполный",
+    binary_file: "бинарный файл",
+    tree_only_file: "только в дереве (tree_only)",
+    dotfile_list_only: "скрытый файл (dotfiles = list-only)",
+    over_max_size: "превышает max_file_size",
+    density: "Плотность",
+    low_density_files: "Низкая плотность информации (возможно, сгенерированы)",
+
+    project_health: "Состояние проекта",
+    check: "Проверка",
+    found: "Найдено",
+    empty_dirs: "Пустые директории",
+    empty_files: "Пустые файлы",
+    empty_dir_list: "Пустые директории",
+    empty_file_list: "Пустые файлы",
+    files_larger: "Файлы больше {}",
+    stale_files: "Не изменялись больше года",
+    large_files: "Крупные файлы",
+    long_unchanged_files: "Давно не изменявшиеся файлы",
+    days: "{} дн.",
+    and_more: "… и еще {}",
+
+    import_graph: "Граф импортов",
+    import_graph_empty: "*Связей между файлами проекта не найдено*",
+    docker_context: "Контекст сборки Docker",
+    docker_files: "Dockerfile: {}, правила исключения: {}",
+    docker_no_ignore: "нет",
+    docker_total: "В контексте файлов: {}, объем: {}",
+    docker_copy: "{} (строка {}): файлов {}, {}",
+    docker_not_copied: "Не копируются в образ файлов: {}, объем: {}",
+    annotations: "Заметки в коде (TODO/FIXME/HACK/XXX)",
+    line: "Строка",
+    marker: "Метка",
+    text: "Текст",
+
+    dependencies: "Зависимости",
+    dependency: "Зависимость",
+    requirement: "Требование",
+    dependency_kind: "Раздел",
+    package: "пакет",
+    no_dependencies: "*Зависимостей нет*",
+
+    parent: "Родитель: {}",
+    directory: "Директория: {}",
+    content_not_included: "*Содержимое файла не включено*",
+
+    part: "Часть {}/{}",
+
+    footer_generated: "Сгенерировано: {}",
+    footer_options: "Параметры: {}",
+    footer_elapsed: "Время генерации: {} с",
+    footer_hash: "SHA-256 документа до разделителя: {}",
+};
+
+pub static EN: Strings = Strings {
+    project_structure: "Project structure",
+    current_directory: "current directory",
+    stdin_archive: "archive from stdin",
+    git_revision: "revision {}",
+    file_tree: "File tree",
+    file_contents: "File contents",
+    quick_note: "_Quick overview (--quick): contents of {} of {} files, the rest are only shown in the tree_",
+    source_code: "Source",
+    test_code: "Tests",
+    test_contents: "Test contents",
+    build_and_ci: "Build & CI",
+    unreadable_file: "[Could not read file]",
+    deleted_file: "[File was deleted while the document was generated]",
+    cloud_placeholder: "[cloud placeholder]",
+    external_file: "External file",
+    external_dir: "External directory",
+    nav_tree: "⬆ Back to tree",
+    hard_link: "*Hard link to {}, contents are not repeated*",
+    images: "Images",
+
+    git_branch: "Branch",
+    git_detached: "detached HEAD",
+    git_commit: "Commit",
+    git_state: "Status",
+    git_clean: "clean",
+    git_dirty: "uncommitted changes",
+    git_unknown: "unknown",
+    git_remote: "Remote",
+
+    tree_error: "[error: {}]",
+    skipped_files: "(files: {})",
+    empty_dir: "(empty)",
+    tree_tokens: "(tokens: ~{})",
+    sampled_out: "… {} more files (--sample-dir)",
+    walk_errors: "Walk errors",
+    merge_artifacts: "⚠️ Unresolved merge",
+    merge_artifacts_note: "**The project contains traces of an unresolved merge: check these files before sharing the snapshot.**",
+    merge_conflicts: "conflict markers: {} (first on line {})",
+    merge_leftover: "file left over from a merge or a patch",
+    permission_denied: "permission denied",
+    broken_link: "broken link",
+    deleted_during_run: "deleted while the document was generated",
+    name_too_long: "name too long",
+    link_loop: "symlink loop",
+
+    normalized: "_Normalized: {}_",
+    secrets_masked: "_Secret values are masked (--unsafe-full-secrets prints them as is)_",
+    sampled_lines: "_Showing the first {} of {} lines (sampling)_",
+    summarized: "_Summary from {} instead of the contents (summarizers)_",
+    summary_failed: "_Summary from {} failed: {}_",
+    tabs_expanded: "tabs → {} spaces ({})",
+    eol_converted: "line endings {} → {} ({} lines)",
+    dedented: "common indentation removed ({} chars)",
+    lines_wrapped: "long lines wrapped at {} columns ({})",
+    mixed_eol: "mixed",
+    lossy_replaced: "_Invalid UTF-8 bytes replaced with U+FFFD: {}_",
+    bom_stripped: "_File starts with a BOM ({}), removed from the contents_",
+    bom_transcoded: "_File is {} with a BOM, contents transcoded to UTF-8_",
+    syntax_error: "_⚠️ File does not parse: {}_",
+    syntax_missing: "missing {} on line {}",
+    syntax_unexpected: "unexpected {} on line {}",
+    syntax_unfinished: "construct from line {} is cut off at the end of the file",
+    syntax_invalid: "invalid {}",
+    syntax_invalid_at: "invalid {} (line {})",
+
+    lfs_object: "LFS object",
+    lfs_too_large: "larger than max_file_size",
+    lfs_binary: "binary",
+    lfs_not_fetched: "not fetched",
+
+    lockfile_summarized: "*Lock file replaced by a summary, use `--full-lockfiles` for full contents*",
+    total_packages: "Total packages: {}",
+    direct_dependencies: "Direct dependencies ({}):",
+
+    largest_files: "Largest files",
+    included_files: "Included",
+    excluded_files: "Excluded",
+    size: "Size",
+    file: "File",
+    reason: "Reason",
+    size_distribution: "Size distribution",
+    size_range: "Range",
+    included_count: "Included",
+    excluded_count: "Excluded",
+    excluded_by_rules: "exclusion rules",
+    interrupted_at: "**Generation interrupted at {}**: the document is incomplete",
+    interrupted: "**Generation interrupted**: the document is incomplete",
+    binary_file: "binary file",
+    tree_only_file: "tree only (tree_only)",
+    dotfile_list_only: "hidden file (dotfiles = list-only)",
+    over_max_size: "exceeds max_file_size",
+    density: "Density",
+    low_density_files: "Low information density (likely generated)",
+
+    project_health: "Project health",
+    check: "Check",
+    found: "Found",
+    empty_dirs: "Empty directories",
+    empty_files: "Empty files",
+    empty_dir_list: "Empty directories",
+    empty_file_list: "Empty files",
+    files_larger: "Files larger than {}",
+    stale_files: "Unchanged for over a year",
+    large_files: "Large files",
+    long_unchanged_files: "Long unchanged files",
+    days: "{} d",
+    and_more: "… and {} more",
+
+    import_graph: "Import graph",
+    import_graph_empty: "*No imports between project files found*",
+    docker_context: "Docker build context",
+    docker_files: "Dockerfile: {}, ignore rules: {}",
+    docker_no_ignore: "none",
+    docker_total: "Files in context: {}, size: {}",
+    docker_copy: "{} (line {}): {} files, {}",
+    docker_not_copied: "Files not copied into the image: {}, size: {}",
+    annotations: "Code annotations (TODO/FIXME/HACK/XXX)",
+    line: "Line",
+    marker: "Marker",
+    text: "Text",
+
+    dependencies: "Dependencies",
+    dependency: "Dependency",
+    requirement: "Requirement",
+    dependency_kind: "Section",
+    package: "package",
+    no_dependencies: "*No dependencies*",
+
+    parent: "Parent: {}",
+    directory: "Directory: {}",
+    content_not_included: "*File contents not included*",
+
+    part: "Part {}/{}",
+
+    footer_generated: "Generated by: {}",
+    footer_options: "Options: {}",
+    footer_elapsed: "Generation time: {} s",
+    footer_hash: "SHA-256 of the document before the separator: {}",
+};
+
+pub static DE: Strings = Strings {
+    project_structure: "Projektstruktur",
+    current_directory: "aktuelles Verzeichnis",
+    stdin_archive: "Archiv von stdin",
+    git_revision: "Revision {}",
+    file_tree: "Dateibaum",
+    file_contents: "Dateiinhalte",
+    quick_note: "_Schnellübersicht (--quick): Inhalt von {} von {} Dateien, die übrigen nur im Baum_",
+    source_code: "Quellcode",
+    test_code: "Tests",
+    test_contents: "Testinhalte",
+    build_and_ci: "Build & CI",
+    unreadable_file: "[Datei konnte nicht gelesen werden]",
+    deleted_file: "[Datei wurde während der Erstellung des Dokuments gelöscht]",
+    cloud_placeholder: "[Cloud-Platzhalter: Inhalt nicht heruntergeladen]",
+    external_file: "Externe Datei",
+    external_dir: "Externes Verzeichnis",
+    nav_tree: "⬆ Zum Baum",
+    hard_link: "*Harter Link auf {}, Inhalt wird nicht wiederholt*",
+    images: "Bilder",
+
+    git_branch: "Branch",
+    git_detached: "losgelöster HEAD",
+    git_commit: "Commit",
+    git_state: "Status",
+    git_clean: "sauber",
+    git_dirty: "nicht committete Änderungen",
+    git_unknown: "unbekannt",
+    git_remote: "Remote",
+
+    tree_error: "[Fehler: {}]",
+    skipped_files: "(Dateien: {})",
+    empty_dir: "(leer)",
+    tree_tokens: "(Tokens: ~{})",
+    sampled_out: "… {} weitere Dateien (--sample-dir)",
+    walk_errors: "Fehler beim Durchlaufen",
+    merge_artifacts: "⚠️ Unvollständiger Merge",
+    merge_artifacts_note: "**Das Projekt enthält Spuren eines ungelösten Merges: prüfen Sie diese Dateien, bevor Sie den Snapshot weitergeben.**",
+    merge_conflicts: "Konfliktmarker: {} (erster in Zeile {})",
+    merge_leftover: "Überbleibsel eines Merges oder Patches",
+    permission_denied: "kein Zugriff",
+    broken_link: "defekter Link",
+    deleted_during_run: "während der Erstellung des Dokuments gelöscht",
+    name_too_long: "Name zu lang",
+    link_loop: "zyklischer Link",
+
+    normalized: "_Normalisiert: {}_",
+    secrets_masked: "_Geheime Werte sind maskiert (--unsafe-full-secrets gibt sie unverändert aus)_",
+    sampled_lines: "_Gezeigt werden die ersten {} von {} Zeilen (sampling)_",
+    summarized: "_Zusammenfassung von {} statt des Inhalts (summarizers)_",
+    summary_failed: "_Zusammenfassung von {} fehlgeschlagen: {}_",
+    tabs_expanded: "Tabulatoren → {} Leerzeichen ({})",
+    eol_converted: "Zeilenenden {} → {} ({} Zeilen)",
+    dedented: "gemeinsame Einrückung entfernt ({} Zeichen)",
+    lines_wrapped: "lange Zeilen bei {} Spalten umbrochen ({})",
+    mixed_eol: "gemischt",
+    lossy_replaced: "_Ungültige UTF-8-Bytes durch U+FFFD ersetzt: {}_",
+    bom_stripped: "_Datei beginnt mit einem BOM ({}), aus dem Inhalt entfernt_",
+    bom_transcoded: "_Datei ist {} mit BOM, Inhalt nach UTF-8 umkodiert_",
+    syntax_error: "_⚠️ Datei ist nicht parsebar: {}_",
+    syntax_missing: "{} fehlt in Zeile {}",
+    syntax_unexpected: "unerwartetes {} in Zeile {}",
+    syntax_unfinished: "Konstrukt aus Zeile {} bricht am Dateiende ab",
+    syntax_invalid: "ungültiges {}",
+    syntax_invalid_at: "ungültiges {} (Zeile {})",
+
+    lfs_object: "LFS-Objekt",
+    lfs_too_large: "größer als max_file_size",
+    lfs_binary: "binär",
+    lfs_not_fetched: "nicht abgerufen",
+
+    lockfile_summarized: "*Lock-Datei durch eine Zusammenfassung ersetzt, vollständiger Inhalt mit `--full-lockfiles`*",
+    total_packages: "Pakete insgesamt: {}",
+    direct_dependencies: "Direkte Abhängigkeiten ({}):",
+
+    largest_files: "Größte Dateien",
+    included_files: "Eingeschlossen",
+    excluded_files: "Ausgeschlossen",
+    size: "Größe",
+    file: "Datei",
+    reason: "Grund",
+    size_distribution: "Größenverteilung",
+    size_range: "Bereich",
+    included_count: "Eingeschlossen",
+    excluded_count: "Ausgeschlossen",
+    excluded_by_rules: "Ausschlussregeln",
+    interrupted_at: "**Generierung abgebrochen bei {}**: das Dokument ist unvollständig",
+    interrupted: "**Generierung abgebrochen**: das Dokument ist unvollständig",
+    binary_file: "Binärdatei",
+    tree_only_file: "nur im Baum (tree_only)",
+    dotfile_list_only: "versteckte Datei (dotfiles = list-only)",
+    over_max_size: "überschreitet max_file_size",
+    density: "Dichte",
+    low_density_files: "Geringe Informationsdichte (vermutlich generiert)",
+
+    project_health: "Projektzustand",
+    check: "Prüfung",
+    found: "Gefunden",
+    empty_dirs: "Leere Verzeichnisse",
+    empty_files: "Leere Dateien",
+    empty_dir_list: "Leere Verzeichnisse",
+    empty_file_list: "Leere Dateien",
+    files_larger: "Dateien größer als {}",
+    stale_files: "Seit über einem Jahr unverändert",
+    large_files: "Große Dateien",
+    long_unchanged_files: "Lange unveränderte Dateien",
+    days: "{} T.",
+    and_more: "… und {} weitere",
+
+    import_graph: "Importgraph",
+    import_graph_empty: "*Keine Importe zwischen Projektdateien gefunden*",
+    docker_context: "Docker-Build-Kontext",
+    docker_files: "Dockerfile: {}, Ausschlussregeln: {}",
+    docker_no_ignore: "keine",
+    docker_total: "Dateien im Kontext: {}, Größe: {}",
+    docker_copy: "{} (Zeile {}): {} Dateien, {}",
+    docker_not_copied: "Nicht ins Image kopierte Dateien: {}, Größe: {}",
+    annotations: "Code-Anmerkungen (TODO/FIXME/HACK/XXX)",
+    line: "Zeile",
+    marker: "Markierung",
+    text: "Text",
+
+    dependencies: "Abhängigkeiten",
+    dependency: "Abhängigkeit",
+    requirement: "Anforderung",
+    dependency_kind: "Abschnitt",
+    package: "Paket",
+    no_dependencies: "*Keine Abhängigkeiten*",
+
+    parent: "Übergeordnet: {}",
+    directory: "Verzeichnis: {}",
+    content_not_included: "*Dateiinhalt nicht enthalten*",
+
+    part: "Teil {}/{}",
+
+    footer_generated: "Erzeugt mit: {}",
+    footer_options: "Optionen: {}",
+    footer_elapsed: "Erzeugungsdauer: {} s",
+    footer_hash: "SHA-256 des Dokuments vor dem Trenner: {}",
+};
+
+pub const LANGUAGES: [&str; 3] = ["ru", "en", "de"];
+
+// Один язык или два через `+` (ru+en): организациям, где документ читают на двух языках,
+// не нужно поддерживать два разных снимка
+pub fn parse_lang(value: &str) -> Result<String, String> {
+    let langs: Vec<&str> = value.split('+').collect();
+    if langs.len() > 2 || langs.iter().any(|lang| !LANGUAGES.contains(lang)) {
+        return Err(format!("ожидается один из языков {} или два через '+', например ru+en", LANGUAGES.join(", ")));
+    }
+    if langs.len() == 2 && langs[0] == langs[1] {
+        return Err(format!("язык {} указан дважды", langs[0]));
+    }
+    Ok(value.to_string())
+}
+
+pub fn strings(lang: &str) -> &'static Strings {
+    match lang.split_once('+') {
+        Some((first, second)) => bilingual(index(first), index(second)),
+        None => all()[index(lang)],
+    }
+}
+
+// Порядок совпадает с LANGUAGES и all()
+fn index(lang: &str) -> usize {
+    LANGUAGES.iter().position(|known| *known == lang).unwrap_or(0)
+}
+
+// Заголовки разделов выводятся на обоих языках через « / », а пометки, подписи таблиц
+// и строки дерева - на первом: в тексте двойные строки читаются хуже, а unpack и --update
+// распознают пометки на любом из языков. Набор строк для каждой пары собирается один раз
+// и живет до конца процесса, как и одноязычные наборы
+fn bilingual(first: usize, second: usize) -> &'static Strings {
+    static PAIRS: [OnceLock<Strings>; LANGUAGES.len() * LANGUAGES.len()] = [const { OnceLock::new() }; LANGUAGES.len() * LANGUAGES.len()];
+    PAIRS[first * LANGUAGES.len() + second].get_or_init(|| pair_strings(all()[first], all()[second]))
+}
+
+fn pair_strings(first: &'static Strings, second: &'static Strings) -> Strings {
+    let pair = |a: &str, b: &str| -> &'static str { String::leak(format!("{} / {}", a, b)) };
+    Strings {
+        project_structure: pair(first.project_structure, second.project_structure),
+        file_tree: pair(first.file_tree, second.file_tree),
+        file_contents: pair(first.file_contents, second.file_contents),
+        source_code: pair(first.source_code, second.source_code),
+        test_code: pair(first.test_code, second.test_code),
+        test_contents: pair(first.test_contents, second.test_contents),
+        build_and_ci: pair(first.build_and_ci, second.build_and_ci),
+        external_file: pair(first.external_file, second.external_file),
+        external_dir: pair(first.external_dir, second.external_dir),
+        images: pair(first.images, second.images),
+        walk_errors: pair(first.walk_errors, second.walk_errors),
+        merge_artifacts: pair(first.merge_artifacts, second.merge_artifacts.trim_start_matches("⚠️ ")),
+        largest_files: pair(first.largest_files, second.largest_files),
+        included_files: pair(first.included_files, second.included_files),
+        excluded_files: pair(first.excluded_files, second.excluded_files),
+        size_distribution: pair(first.size_distribution, second.size_distribution),
+        low_density_files: pair(first.low_density_files, second.low_density_files),
+        project_health: pair(first.project_health, second.project_health),
+        empty_dir_list: pair(first.empty_dir_list, second.empty_dir_list),
+        empty_file_list: pair(first.empty_file_list, second.empty_file_list),
+        large_files: pair(first.large_files, second.large_files),
+        long_unchanged_files: pair(first.long_unchanged_files, second.long_unchanged_files),
+        import_graph: pair(first.import_graph, second.import_graph),
+        docker_context: pair(first.docker_context, second.docker_context),
+        annotations: pair(first.annotations, second.annotations),
+        dependencies: pair(first.dependencies, second.dependencies),
+        package: pair(first.package, second.package),
+        ..*first
+    }
+}
+
+// Разбор чужого документа (unpack, --update) не знает, на каком языке он создан
+pub fn all() -> [&'static Strings; 3] {
+    [&RU, &EN, &DE]
+}
+
+pub fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut parts = template.split("{}");
+
+    if let Some(first) = parts.next() {
+        result.push_str(first);
+    }
+    for part in parts {
+        if let Some(arg) = args.next() {
+            result.push_str(&arg.to_string());
+        }
+        result.push_str(part);
+    }
+    result
+}
@@ -0,0 +1,244 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use globset::{GlobBuilder, GlobMatcher};
+use serde_json::Value;
+
+use crate::doc_lang::fill;
+use crate::escape::{code_span, display_path};
+use crate::report::format_size;
+use crate::{AppConfig, heading, relative_path, tree};
+
+// --docker-context [Dockerfile]: снимок того, что docker build отправит в контекст сборки.
+// Вместо .gitignore, скрытых файлов и exclude_* действуют правила .dockerignore (или
+// Dockerfile.dockerignore рядом с Dockerfile, как у BuildKit), а в конце документа - раздел
+// с инструкциями COPY/ADD: сколько файлов и байт каждая переносит в образ и что из контекста
+// в образ не попадает вовсе
+
+// Сколько самых больших файлов, не попадающих в образ, перечислять
+const LARGEST_NOT_COPIED: usize = 10;
+
+#[derive(Debug)]
+pub struct Context {
+    dockerfile: PathBuf,
+    ignore_file: Option<PathBuf>,
+    rules: Vec<Rule>,
+    instructions: Vec<Instruction>,
+}
+
+// Правило .dockerignore: шаблон от корня контекста, `!` - исключение из исключений
+#[derive(Debug)]
+struct Rule {
+    pattern: String,
+    glob: GlobMatcher,
+    exception: bool,
+}
+
+// COPY/ADD из контекста сборки; COPY --from и ADD по URL контекст не читают
+#[derive(Debug)]
+struct Instruction {
+    line: usize,
+    text: String,
+    sources: Vec<GlobMatcher>,
+}
+
+pub fn load(context_dir: &Path, dockerfile: &Path) -> io::Result<Context> {
+    let dockerfile = if dockerfile.is_absolute() { dockerfile.to_path_buf() } else { context_dir.join(dockerfile) };
+    let content = fs::read_to_string(&dockerfile).map_err(|e| {
+        io::Error::new(e.kind(), format!("не удалось прочитать {}: {}", dockerfile.display(), e))
+    })?;
+
+    let mut own_ignore = dockerfile.clone().into_os_string();
+    own_ignore.push(".dockerignore");
+    let ignore_file = [PathBuf::from(own_ignore), context_dir.join(".dockerignore")]
+        .into_iter()
+        .find(|path| path.is_file());
+    let rules = match &ignore_file {
+        Some(path) => parse_ignore(&fs::read_to_string(path)?),
+        None => Vec::new(),
+    };
+
+    Ok(Context { dockerfile, ignore_file, rules, instructions: parse_dockerfile(&content) })
+}
+
+// Последнее подходящее правило решает; правило для директории действует на все внутри
+pub fn is_ignored(context: &Context, relative: &Path) -> bool {
+    let path = slash_path(relative);
+    let mut ignored = false;
+    for rule in &context.rules {
+        if matches_or_parent(&rule.glob, &path) {
+            ignored = !rule.exception;
+        }
+    }
+    ignored
+}
+
+// Исключенную директорию нельзя свернуть, если исключение может вернуть что-то внутри нее
+pub fn has_exceptions_under(context: &Context, relative: &Path) -> bool {
+    let dir = format!("{}/", slash_path(relative));
+    context.rules.iter().filter(|rule| rule.exception).any(|rule| {
+        let literal = &rule.pattern[..rule.pattern.find(['*', '?', '[', '\\']).unwrap_or(rule.pattern.len())];
+        literal.starts_with(&dir) || dir.starts_with(literal)
+    })
+}
+
+pub fn write_section<W: Write>(
+    base_dir: &Path,
+    nodes: &[tree::TreeNode],
+    writer: &mut W,
+    context: &Context,
+    app_config: &AppConfig,
+) -> io::Result<()> {
+    let doc = app_config.doc;
+    let mut files = Vec::new();
+    tree::file_entries(nodes, &mut files);
+    let files: Vec<(String, u64)> =
+        files.iter().map(|(path, info)| (slash_path(&relative_path(base_dir, path)), info.len)).collect();
+
+    writeln!(writer, "\n{} {}\n", heading(app_config, 2), doc.docker_context)?;
+    let ignore_file = match &context.ignore_file {
+        Some(path) => code_span(&display_path(&relative_path(base_dir, path))),
+        None => doc.docker_no_ignore.to_string(),
+    };
+    let dockerfile = code_span(&display_path(&relative_path(base_dir, &context.dockerfile)));
+    writeln!(writer, "{}\n", fill(doc.docker_files, &[&dockerfile, &ignore_file]))?;
+    writeln!(writer, "{}\n", fill(doc.docker_total, &[&files.len(), &format_size(total_size(files.iter()))]))?;
+
+    let mut copied = vec![false; files.len()];
+    for instruction in &context.instructions {
+        let mut count = 0;
+        let mut size = 0;
+        for (i, (path, len)) in files.iter().enumerate() {
+            if instruction.sources.iter().any(|glob| matches_or_parent(glob, path)) {
+                copied[i] = true;
+                count += 1;
+                size += len;
+            }
+        }
+        let text = code_span(&instruction.text);
+        writeln!(writer, "- {}", fill(doc.docker_copy, &[&text, &instruction.line, &count, &format_size(size)]))?;
+    }
+
+    let rest: Vec<&(String, u64)> =
+        files.iter().zip(&copied).filter(|(_, copied)| !**copied).map(|(file, _)| file).collect();
+    if rest.is_empty() {
+        return Ok(());
+    }
+    let size = format_size(total_size(rest.iter().copied()));
+    writeln!(writer, "\n{}\n", fill(doc.docker_not_copied, &[&rest.len(), &size]))?;
+    let mut largest = rest;
+    largest.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    for (path, len) in largest.iter().take(LARGEST_NOT_COPIED) {
+        writeln!(writer, "- {} ({})", code_span(path), format_size(*len))?;
+    }
+    Ok(())
+}
+
+fn total_size<'a>(files: impl Iterator<Item = &'a (String, u64)>) -> u64 {
+    files.map(|(_, len)| len).sum()
+}
+
+fn parse_ignore(content: &str) -> Vec<Rule> {
+    let mut rules = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (exception, pattern) = match line.strip_prefix('!') {
+            Some(pattern) => (true, pattern.trim()),
+            None => (false, line),
+        };
+        let pattern = clean(pattern);
+        match glob(&pattern) {
+            Some(glob) => rules.push(Rule { pattern, glob, exception }),
+            None => tracing::warn!("шаблон .dockerignore '{}' пропущен", line),
+        }
+    }
+    rules
+}
+
+// Инструкции Dockerfile с учетом продолжения строки через `\` и комментариев внутри него
+fn parse_dockerfile(content: &str) -> Vec<Instruction> {
+    let mut instructions = Vec::new();
+    let mut current = String::new();
+    let mut start = 0;
+    for (number, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('#') || (current.is_empty() && trimmed.is_empty()) {
+            continue;
+        }
+        if current.is_empty() {
+            start = number + 1;
+        }
+        match trimmed.strip_suffix('\\') {
+            Some(head) => {
+                current.push_str(head);
+                current.push(' ');
+            }
+            None => {
+                current.push_str(trimmed);
+                instructions.extend(instruction(&current, start));
+                current.clear();
+            }
+        }
+    }
+    if !current.is_empty() {
+        instructions.extend(instruction(&current, start));
+    }
+    instructions
+}
+
+fn instruction(text: &str, line: usize) -> Option<Instruction> {
+    let (keyword, rest) = text.trim().split_once(char::is_whitespace)?;
+    let keyword = keyword.to_uppercase();
+    if keyword != "COPY" && keyword != "ADD" {
+        return None;
+    }
+
+    let mut args = rest.trim_start();
+    while args.starts_with("--") {
+        let (flag, tail) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+        if flag.starts_with("--from") {
+            return None;
+        }
+        args = tail.trim_start();
+    }
+
+    let args: Vec<String> = if args.starts_with('[') {
+        let value: Value = serde_json::from_str(args).ok()?;
+        value.as_array()?.iter().filter_map(|value| value.as_str().map(str::to_string)).collect()
+    } else {
+        args.split_whitespace().map(str::to_string).collect()
+    };
+    let (_, sources) = args.split_last()?;
+    let sources: Vec<GlobMatcher> = sources
+        .iter()
+        .filter(|source| !source.contains("://") && !source.starts_with("git@") && !source.starts_with("<<"))
+        .filter_map(|source| glob(&clean(source)))
+        .collect();
+    if sources.is_empty() {
+        return None;
+    }
+
+    let text = format!("{} {}", keyword, rest.split_whitespace().collect::<Vec<_>>().join(" "));
+    Some(Instruction { line, text, sources })
+}
+
+// Пути в шаблонах - от корня контекста, как после filepath.Clean у docker
+fn clean(pattern: &str) -> String {
+    let parts: Vec<&str> = pattern.split('/').filter(|part| !part.is_empty() && *part != ".").collect();
+    if parts.is_empty() { "**".to_string() } else { parts.join("/") }
+}
+
+fn glob(pattern: &str) -> Option<GlobMatcher> {
+    GlobBuilder::new(pattern).literal_separator(true).build().ok().map(|glob| glob.compile_matcher())
+}
+
+fn matches_or_parent(glob: &GlobMatcher, path: &str) -> bool {
+    glob.is_match(path) || path.match_indices('/').any(|(i, _)| glob.is_match(&path[..i]))
+}
+
+fn slash_path(path: &Path) -> String {
+    path.components().map(|part| part.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/")
+}
@@ -0,0 +1,77 @@
+use std::path::{Path, PathBuf};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use serde::Deserialize;
+
+use crate::{AppConfig, Config, relative_path};
+
+// dotfiles = "skip" | "include" | "list-only": что делать с файлами и директориями, имя которых
+// начинается с точки. keep_dotfiles - исключения, которые выводятся полностью при любой политике;
+// шаблоны сравниваются как в tree_only: без `/` - с именем, со `/` - с путем от целевой директории
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Policy {
+    // Пропускать, как и раньше
+    #[default]
+    Skip,
+    // Обрабатывать как обычные файлы
+    Include,
+    // Показывать в дереве без содержимого, вместе со всем внутри скрытых директорий
+    ListOnly,
+}
+
+pub fn name(policy: Policy) -> &'static str {
+    match policy {
+        Policy::Skip => "skip",
+        Policy::Include => "include",
+        Policy::ListOnly => "list-only",
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Globs {
+    names: GlobSet,
+    paths: GlobSet,
+}
+
+pub fn compile(config: &mut Config) {
+    let mut names = GlobSetBuilder::new();
+    let mut paths = GlobSetBuilder::new();
+    for pattern in &config.keep_dotfiles {
+        match GlobBuilder::new(pattern).literal_separator(true).build() {
+            Ok(glob) if pattern.contains('/') => {
+                paths.add(glob);
+            }
+            Ok(glob) => {
+                names.add(glob);
+            }
+            Err(e) => tracing::warn!("шаблон keep_dotfiles '{}' пропущен: {}", pattern, e),
+        }
+    }
+    config.keep_dotfiles_globs = Globs {
+        names: names.build().unwrap_or_default(),
+        paths: paths.build().unwrap_or_default(),
+    };
+}
+
+pub fn is_kept(path: &Path, app_config: &AppConfig, config: &Config) -> bool {
+    let globs = &config.keep_dotfiles_globs;
+    path.file_name().is_some_and(|name| globs.names.is_match(name))
+        || globs.paths.is_match(relative_path(Path::new(&app_config.target_dir), path))
+}
+
+// Скрытый файл или файл внутри скрытой директории, не попавшие в keep_dotfiles
+pub fn is_list_only(path: &Path, app_config: &AppConfig, config: &Config) -> bool {
+    if config.dotfiles != Policy::ListOnly || app_config.docker_context.is_some() {
+        return false;
+    }
+    let base_dir = Path::new(&app_config.target_dir);
+    let mut current = PathBuf::from(base_dir);
+    for component in relative_path(base_dir, path).components() {
+        current.push(component);
+        if component.as_os_str().to_string_lossy().starts_with('.') && !is_kept(&current, app_config, config) {
+            return true;
+        }
+    }
+    false
+}
@@ -0,0 +1,150 @@
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Component, Path, PathBuf};
+
+use crate::interrupt;
+use crate::tree::{self, FileInfo, NodeKind, TreeNode};
+use crate::unpack::{self, UnpackedFile};
+use crate::{AppConfig, Config, content_language, fence, relative_path, write_file_heading};
+
+// Встраивание готовых снимков (--embed other.md@services/auth): файлы снимка появляются
+// в дереве под виртуальной директорией и в разделе содержимого после файлов проекта.
+// Заголовки самого снимка (название, «Дерево файлов», «Содержимое файлов») не переносятся,
+// а файл, путь которого уже есть в документе, выводится один раз - из проекта или из
+// снимка, указанного раньше
+
+pub struct Embed {
+    pub prefix: PathBuf,
+    pub files: Vec<UnpackedFile>,
+}
+
+// SNAPSHOT@PREFIX; без префикса файлы ложатся в директорию с именем снимка без расширения
+pub fn parse_spec(value: &str) -> Result<(PathBuf, PathBuf), String> {
+    let (snapshot, prefix) = match value.rsplit_once('@') {
+        Some((snapshot, prefix)) => (snapshot, prefix.trim_matches('/').to_string()),
+        None => {
+            let stem = Path::new(value).file_stem().map(|stem| stem.to_string_lossy().into_owned());
+            (value, stem.unwrap_or_default())
+        }
+    };
+    if snapshot.is_empty() {
+        return Err("ожидается SNAPSHOT@PREFIX, например auth.md@services/auth".to_string());
+    }
+    let prefix = PathBuf::from(prefix);
+    if prefix.as_os_str().is_empty() || !prefix.components().all(|component| matches!(component, Component::Normal(_))) {
+        return Err(format!("'{}' не является относительным путем внутри проекта", prefix.display()));
+    }
+    Ok((PathBuf::from(snapshot), prefix))
+}
+
+// Снимки читаются один раз на документ. Пути, выходящие за пределы префикса, пропускаются,
+// как при unpack
+pub fn load(specs: &[(PathBuf, PathBuf)]) -> io::Result<Vec<Embed>> {
+    specs
+        .iter()
+        .map(|(snapshot, prefix)| {
+            let document = fs::read_to_string(snapshot).map_err(|e| {
+                io::Error::new(e.kind(), format!("не удалось прочитать снимок {}: {}", snapshot.display(), e))
+            })?;
+            let files = unpack::parse_snapshot(&document)
+                .into_iter()
+                .filter(|file| {
+                    let safe = file.path.components().all(|component| matches!(component, Component::Normal(_)));
+                    if !safe {
+                        tracing::warn!("{}: пропущен небезопасный путь '{}'", snapshot.display(), file.path.display());
+                    }
+                    safe
+                })
+                .collect();
+            Ok(Embed { prefix: prefix.clone(), files })
+        })
+        .collect()
+}
+
+// Дерево проекта с файлами снимков. Директория префикса, которая есть в проекте, дополняется,
+// а не дублируется; файл с уже занятым путем не добавляется
+pub fn splice_tree(base_dir: &Path, nodes: &[TreeNode], embeds: &[Embed]) -> Vec<TreeNode> {
+    let mut nodes = nodes.to_vec();
+    for embed in embeds {
+        for file in &embed.files {
+            let relative = embed.prefix.join(&file.path);
+            let info = FileInfo { len: file.content.len() as u64, hard_link: None, regular: true };
+            insert(&mut nodes, base_dir, &relative, info);
+        }
+    }
+    nodes
+}
+
+fn insert(nodes: &mut Vec<TreeNode>, dir: &Path, relative: &Path, info: FileInfo) {
+    let mut components = relative.components();
+    let Some(Component::Normal(name)) = components.next() else {
+        return;
+    };
+    let rest = components.as_path();
+    let path = dir.join(name);
+
+    let existing = nodes.iter().position(|node| {
+        node.name == name && !matches!(node.kind, NodeKind::Omitted(_) | NodeKind::Truncated(_))
+    });
+    let index = match existing {
+        Some(index) => index,
+        None => {
+            let kind = if rest.as_os_str().is_empty() { NodeKind::File(info) } else { NodeKind::Directory(Vec::new()) };
+            // Служебные строки (--sample-dir, --max-entries-per-dir) остаются последними
+            let index = nodes
+                .iter()
+                .position(|node| {
+                    matches!(node.kind, NodeKind::Omitted(_) | NodeKind::Truncated(_)) || node.name.as_os_str() > name
+                })
+                .unwrap_or(nodes.len());
+            nodes.insert(index, TreeNode { path: path.clone(), name: OsString::from(name), kind });
+            index
+        }
+    };
+
+    if let NodeKind::Directory(children) = &mut nodes[index].kind
+        && !rest.as_os_str().is_empty()
+    {
+        insert(children, &path, rest, info);
+    }
+}
+
+// Разделы файлов снимков в разделе содержимого текущего документа. Содержимое уже прошло
+// обработку при создании снимка (маскирование секретов, выборка строк) и выводится как есть
+pub fn write_sections<W: Write>(
+    base_dir: &Path,
+    nodes: &[TreeNode],
+    embeds: &[Embed],
+    writer: &mut W,
+    app_config: &AppConfig,
+    config: &Config,
+) -> io::Result<()> {
+    if interrupt::is_interrupted() {
+        return Ok(());
+    }
+
+    let mut written: HashSet<PathBuf> = HashSet::new();
+    let mut project_files = Vec::new();
+    tree::file_paths(nodes, &mut project_files);
+    written.extend(project_files.iter().map(|path| relative_path(base_dir, path)));
+
+    let mut current_dir = PathBuf::new();
+    for embed in embeds {
+        for file in &embed.files {
+            let relative = embed.prefix.join(&file.path);
+            if !written.insert(relative.clone()) {
+                tracing::warn!("{} уже есть в документе, раздел из встроенного снимка пропущен", relative.display());
+                continue;
+            }
+
+            write_file_heading(writer, &relative, &mut current_dir, app_config)?;
+            let fence = fence(&file.content, app_config);
+            writeln!(writer, "{}{}", fence, content_language(&relative, &file.content, config))?;
+            write!(writer, "{}", file.content)?;
+            writeln!(writer, "{}", fence)?;
+        }
+    }
+    Ok(())
+}
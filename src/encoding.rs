@@ -0,0 +1,66 @@
+use std::io;
+
+// Метка порядка байтов в начале текстового файла. UTF-8 BOM отбрасывается, иначе первая
+// строка блока кода начинается с невидимого U+FEFF; UTF-16 с BOM перекодируется в UTF-8,
+// без этого такие файлы (часто из Windows-редакторов) не читаются вовсе
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Bom {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+}
+
+impl Bom {
+    pub fn name(self) -> &'static str {
+        match self {
+            Bom::Utf8 => "UTF-8",
+            Bom::Utf16Le => "UTF-16LE",
+            Bom::Utf16Be => "UTF-16BE",
+        }
+    }
+}
+
+pub fn detect(bytes: &[u8]) -> Option<Bom> {
+    if bytes.starts_with(UTF8_BOM) {
+        Some(Bom::Utf8)
+    } else if bytes.starts_with(b"\xFF\xFE") {
+        Some(Bom::Utf16Le)
+    } else if bytes.starts_with(b"\xFE\xFF") {
+        Some(Bom::Utf16Be)
+    } else {
+        None
+    }
+}
+
+// Содержимое без BOM и сама метка, если она была
+pub fn decode(bytes: Vec<u8>) -> io::Result<(String, Option<Bom>)> {
+    let bom = detect(&bytes);
+    let content = match bom {
+        None => String::from_utf8(bytes).map_err(|_| invalid("UTF-8"))?,
+        Some(Bom::Utf8) => String::from_utf8(bytes[UTF8_BOM.len()..].to_vec()).map_err(|_| invalid("UTF-8"))?,
+        Some(bom) => {
+            let data = &bytes[2..];
+            if !data.len().is_multiple_of(2) {
+                return Err(invalid(bom.name()));
+            }
+            let units = data.chunks_exact(2).map(|pair| match bom {
+                Bom::Utf16Le => u16::from_le_bytes([pair[0], pair[1]]),
+                _ => u16::from_be_bytes([pair[0], pair[1]]),
+            });
+            char::decode_utf16(units).collect::<Result<String, _>>().map_err(|_| invalid(bom.name()))?
+        }
+    };
+    Ok((content, bom))
+}
+
+// Байты для замены битых последовательностей (--lossy-read): UTF-8 BOM в вывод не попадает
+pub fn strip_utf8_bom(bytes: &[u8]) -> &[u8] {
+    bytes.strip_prefix(UTF8_BOM).unwrap_or(bytes)
+}
+
+fn invalid(encoding: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("stream did not contain valid {}", encoding))
+}
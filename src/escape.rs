@@ -0,0 +1,197 @@
+use std::ffi::{OsStr, OsString};
+use std::fmt::Write as _;
+use std::path::{Component, Path, PathBuf};
+
+// Имена файлов выводятся без потерь: некорректные байты и управляющие символы экранируются,
+// а не заменяются на U+FFFD, чтобы разные файлы не сливались в одно имя
+pub fn display_os_str(name: &OsStr) -> String {
+    let mut result = String::new();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+
+        for chunk in name.as_bytes().utf8_chunks() {
+            push_escaped_str(&mut result, chunk.valid());
+            for byte in chunk.invalid() {
+                let _ = write!(result, "\\x{:02X}", byte);
+            }
+        }
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::ffi::OsStrExt;
+
+        for unit in char::decode_utf16(name.encode_wide()) {
+            match unit {
+                Ok(c) => push_escaped_char(&mut result, c),
+                Err(e) => {
+                    let _ = write!(result, "\\u{{{:04X}}}", e.unpaired_surrogate());
+                }
+            }
+        }
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    push_escaped_str(&mut result, &name.to_string_lossy());
+
+    result
+}
+
+// Компоненты пути разделяются '/' на всех платформах: обратная косая черта внутри имени
+// экранируется, и путь однозначно разбирается обратно (parse_display_path)
+pub fn display_path(path: &Path) -> String {
+    let mut result = String::new();
+    let mut needs_separator = false;
+
+    for component in path.components() {
+        match component {
+            Component::Prefix(prefix) => result.push_str(&prefix.as_os_str().to_string_lossy()),
+            Component::RootDir => result.push('/'),
+            other => {
+                if needs_separator {
+                    result.push('/');
+                }
+                result.push_str(&display_os_str(other.as_os_str()));
+                needs_separator = true;
+                continue;
+            }
+        }
+        needs_separator = false;
+    }
+
+    result
+}
+
+// Обратная операция к display_path. Незнакомые последовательности после '\\' остаются как есть,
+// чтобы пути, написанные вручную (`src\main.rs`), не искажались
+pub fn parse_display_path(text: &str) -> PathBuf {
+    let mut bytes = Vec::new();
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        if c == '\\'
+            && let Some((decoded, length)) = parse_escape(&rest[1..])
+        {
+            bytes.extend_from_slice(&decoded);
+            rest = &rest[1 + length..];
+            continue;
+        }
+        push_char_bytes(&mut bytes, c);
+        rest = &rest[c.len_utf8()..];
+    }
+
+    PathBuf::from(os_string_from_bytes(bytes))
+}
+
+// Последовательность после '\\': декодированные байты и длина в исходном тексте
+fn parse_escape(text: &str) -> Option<(Vec<u8>, usize)> {
+    let is_hex = |hex: &str| !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit());
+
+    match text.chars().next()? {
+        '\\' => Some((vec![b'\\'], 1)),
+        'n' => Some((vec![b'\n'], 1)),
+        'r' => Some((vec![b'\r'], 1)),
+        't' => Some((vec![b'\t'], 1)),
+        'x' => {
+            let hex = text.get(1..3).filter(|hex| is_hex(hex))?;
+            Some((vec![u8::from_str_radix(hex, 16).ok()?], 3))
+        }
+        'u' => {
+            let inner = text.strip_prefix("u{")?;
+            let end = inner.find('}')?;
+            let hex = Some(&inner[..end]).filter(|hex| is_hex(hex) && hex.len() <= 6)?;
+            let decoded = char::from_u32(u32::from_str_radix(hex, 16).ok()?)?;
+            let mut bytes = Vec::new();
+            push_char_bytes(&mut bytes, decoded);
+            Some((bytes, end + 3))
+        }
+        _ => None,
+    }
+}
+
+fn push_char_bytes(bytes: &mut Vec<u8>, c: char) {
+    let mut buffer = [0u8; 4];
+    bytes.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+}
+
+#[cfg(unix)]
+fn os_string_from_bytes(bytes: Vec<u8>) -> OsString {
+    use std::os::unix::ffi::OsStringExt;
+
+    OsString::from_vec(bytes)
+}
+
+// Вне unix имя из некорректных байтов восстановить нельзя
+#[cfg(not(unix))]
+fn os_string_from_bytes(bytes: Vec<u8>) -> OsString {
+    OsString::from(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn push_escaped_str(result: &mut String, text: &str) {
+    for c in text.chars() {
+        push_escaped_char(result, c);
+    }
+}
+
+// Кроме управляющих символов экранируются символы управления направлением текста (bidi):
+// иначе имя `evil\u{202E}txt.exe` отображается как `evilexe.txt`
+fn push_escaped_char(result: &mut String, c: char) {
+    match c {
+        '\\' => result.push_str("\\\\"),
+        '\n' => result.push_str("\\n"),
+        '\r' => result.push_str("\\r"),
+        '\t' => result.push_str("\\t"),
+        c if c.is_control() || is_bidi_control(c) => {
+            let _ = write!(result, "\\u{{{:04X}}}", c as u32);
+        }
+        c => result.push(c),
+    }
+}
+
+fn is_bidi_control(c: char) -> bool {
+    matches!(c, '\u{061C}' | '\u{200E}' | '\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}')
+}
+
+// Inline-код по CommonMark: разделитель длиннее любой серии бэктиков внутри текста
+pub fn code_span(text: &str) -> String {
+    let mut max_run = 0;
+    let mut current_run = 0;
+    for c in text.chars() {
+        if c == '`' {
+            current_run += 1;
+            max_run = max_run.max(current_run);
+        } else {
+            current_run = 0;
+        }
+    }
+
+    let fence = "`".repeat(max_run + 1);
+    // Один пробел с каждой стороны рендерер отбрасывает, поэтому он безопасно отделяет
+    // бэктики на краях текста от разделителя
+    let needs_padding = text.starts_with('`')
+        || text.ends_with('`')
+        || (text.starts_with(' ') && text.ends_with(' ') && !text.trim().is_empty());
+
+    if needs_padding {
+        format!("{} {} {}", fence, text, fence)
+    } else {
+        format!("{}{}{}", fence, text, fence)
+    }
+}
+
+// Текст внутри HTML (дерево со ссылками выводится блоком <pre>)
+pub fn html(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            c => result.push(c),
+        }
+    }
+    result
+}
@@ -0,0 +1,50 @@
+use std::ffi::{CStr, CString, c_char};
+use std::panic;
+use std::ptr;
+use serde_json::json;
+
+use crate::scan;
+
+// C ABI для встраивания сканера из C и других языков; модуль Python - в python.rs.
+// Строки передаются в UTF-8 с завершающим нулем. Результат - JSON, при ошибке - {"error": "..."};
+// его нужно освободить через proj2tree_free_string
+
+// # Safety
+// path и options - корректные C-строки или NULL (options может быть NULL)
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn proj2tree_scan_project(path: *const c_char, options: *const c_char) -> *mut c_char {
+    // Паника не должна пересекать границу FFI
+    let result = panic::catch_unwind(|| {
+        // SAFETY: вызывающая сторона гарантирует корректность указателей
+        let path = unsafe { c_str(path) }.ok_or("path не является строкой UTF-8")?;
+        let options = unsafe { c_str(options) }.unwrap_or_default();
+        scan::scan_project(&path, &options).map_err(|e| e.to_string())
+    });
+
+    let json = match result {
+        Ok(Ok(json)) => json,
+        Ok(Err(message)) => json!({ "error": message }).to_string(),
+        Err(_) => "{\"error\":\"внутренняя ошибка сканера\"}".to_string(),
+    };
+
+    // JSON не содержит нулевых байтов: они экранируются как \u0000
+    CString::new(json).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+// # Safety
+// value - результат proj2tree_scan_project или NULL, освобождается один раз
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn proj2tree_free_string(value: *mut c_char) {
+    if !value.is_null() {
+        // SAFETY: указатель получен из CString::into_raw в proj2tree_scan_project
+        drop(unsafe { CString::from_raw(value) });
+    }
+}
+
+unsafe fn c_str(value: *const c_char) -> Option<String> {
+    if value.is_null() {
+        return None;
+    }
+    // SAFETY: непустой указатель на строку с завершающим нулем
+    unsafe { CStr::from_ptr(value) }.to_str().ok().map(str::to_string)
+}
@@ -0,0 +1,74 @@
+use std::env;
+use std::io::{self, Write};
+use std::time::Duration;
+
+use sha2::{Digest, Sha256};
+
+use crate::AppConfig;
+use crate::doc_lang::fill;
+use crate::escape::code_span;
+use crate::sha256;
+
+// --footer: сведения о том, как получен документ, - версия, параметры запуска, время
+// и SHA-256 всего, что выведено до подвала. Подвал начинается строкой SEPARATOR:
+// контрольную сумму можно пересчитать по байтам документа перед ней
+
+pub const SEPARATOR: &str = "\n---\n";
+
+// Пропускает вывод дальше, попутно считая его хеш
+pub struct HashingWriter<'a, W: Write> {
+    inner: &'a mut W,
+    hasher: Sha256,
+}
+
+impl<'a, W: Write> HashingWriter<'a, W> {
+    pub fn new(inner: &'a mut W) -> HashingWriter<'a, W> {
+        HashingWriter { inner, hasher: Sha256::default() }
+    }
+
+    pub fn finish(self) -> String {
+        sha256::hex(self.hasher)
+    }
+}
+
+impl<W: Write> Write for HashingWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+pub fn write<W: Write>(writer: &mut W, hash: &str, elapsed: Duration, app_config: &AppConfig) -> io::Result<()> {
+    let doc = app_config.doc;
+    write!(writer, "{}", SEPARATOR)?;
+    writeln!(writer)?;
+    writeln!(writer, "- {}", fill(doc.footer_generated, &[&concat!("proj2tree ", env!("CARGO_PKG_VERSION"))]))?;
+    writeln!(writer, "- {}", fill(doc.footer_options, &[&code_span(&effective_options())]))?;
+    writeln!(writer, "- {}", fill(doc.footer_elapsed, &[&format!("{:.2}", elapsed.as_secs_f64())]))?;
+    writeln!(writer, "- {}", fill(doc.footer_hash, &[&code_span(hash)]))
+}
+
+// Аргументы командной строки и переменные PROJ2TREE_*, которые тоже задают параметры
+pub fn effective_options() -> String {
+    let mut options: Vec<String> = env::vars()
+        .filter(|(name, _)| name.starts_with("PROJ2TREE_"))
+        .map(|(name, value)| format!("{}={}", name, quote(&value)))
+        .collect();
+    options.sort();
+    options.push("proj2tree".to_string());
+    options.extend(env::args_os().skip(1).map(|arg| quote(&arg.to_string_lossy())));
+    options.join(" ")
+}
+
+fn quote(value: &str) -> String {
+    if !value.is_empty() && !value.contains(|c: char| c.is_whitespace() || "'\"$`\\|&;<>()*?".contains(c)) {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
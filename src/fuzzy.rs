@@ -0,0 +1,161 @@
+// Нечеткий поиск в духе fzf: каждое слово запроса должно встречаться в пути как
+// подпоследовательность символов. В отличие от интерактивного fzf здесь нет ранжирования,
+// поэтому случайные совпадения отсекаются порогом по очкам
+
+const SCORE_MATCH: i32 = 16;
+const BONUS_BOUNDARY: i32 = 8;
+const BONUS_CONSECUTIVE: i32 = 4;
+const PENALTY_GAP_START: i32 = 3;
+const PENALTY_GAP_EXTENSION: i32 = 1;
+
+pub fn matches_query(query: &str, text: &str) -> bool {
+    query
+        .split_whitespace()
+        .all(|term| term_score(term, text).is_some_and(|score| score >= min_score(term)))
+}
+
+// Разрывы должны окупаться бонусами: в среднем каждый символ после первого обязан
+// набрать хотя бы половину бонуса за непрерывность
+fn min_score(term: &str) -> i32 {
+    let length = term.chars().count() as i32;
+    length * SCORE_MATCH + (length - 1) * BONUS_CONSECUTIVE / 2
+}
+
+// Жадный поиск слева направо, затем сужение совпадения справа налево, как в fzf v1.
+// Поиск повторяется от каждого вхождения первого символа, чтобы ранний случайный символ
+// (например, `r` в `src/`) не помешал найти плотное совпадение дальше по пути
+fn term_score(term: &str, text: &str) -> Option<i32> {
+    // Smart case: заглавные буквы в слове запроса включают учет регистра
+    let case_sensitive = term.chars().any(char::is_uppercase);
+    let normalize = |c: char| if case_sensitive { c } else { c.to_lowercase().next().unwrap_or(c) };
+
+    let pattern: Vec<char> = term.chars().map(normalize).collect();
+    let original: Vec<char> = text.chars().collect();
+    let chars: Vec<char> = original.iter().copied().map(normalize).collect();
+
+    (0..chars.len())
+        .filter(|&i| chars[i] == pattern[0])
+        .filter_map(|from| match_range(&chars, &pattern, from))
+        .map(|(start, end)| range_score(&original, &chars, &pattern, start, end))
+        .max()
+}
+
+fn match_range(chars: &[char], pattern: &[char], from: usize) -> Option<(usize, usize)> {
+    let mut pattern_index = 0;
+    let mut end = None;
+    for (i, &c) in chars.iter().enumerate().skip(from) {
+        if c == pattern[pattern_index] {
+            pattern_index += 1;
+            if pattern_index == pattern.len() {
+                end = Some(i);
+                break;
+            }
+        }
+    }
+    let end = end?;
+
+    let mut start = end;
+    let mut pattern_index = pattern.len();
+    for i in (from..=end).rev() {
+        if chars[i] == pattern[pattern_index - 1] {
+            pattern_index -= 1;
+            if pattern_index == 0 {
+                start = i;
+                break;
+            }
+        }
+    }
+
+    Some((start, end))
+}
+
+fn range_score(original: &[char], chars: &[char], pattern: &[char], start: usize, end: usize) -> i32 {
+    let mut score = 0;
+    let mut pattern_index = 0;
+    let mut previous_match: Option<usize> = None;
+    for (i, &c) in chars.iter().enumerate().take(end + 1).skip(start) {
+        if pattern_index < pattern.len() && c == pattern[pattern_index] {
+            score += SCORE_MATCH;
+            if is_boundary(original, i) {
+                score += BONUS_BOUNDARY;
+            }
+            match previous_match {
+                Some(previous) if previous + 1 == i => score += BONUS_CONSECUTIVE,
+                Some(previous) => {
+                    let gap = (i - previous - 1) as i32;
+                    score -= PENALTY_GAP_START + (gap - 1) * PENALTY_GAP_EXTENSION;
+                }
+                None => {}
+            }
+            previous_match = Some(i);
+            pattern_index += 1;
+        }
+    }
+    score
+}
+
+// Начало слова: после разделителя пути или имени, либо переход в camelCase
+fn is_boundary(chars: &[char], i: usize) -> bool {
+    if i == 0 {
+        return true;
+    }
+    let previous = chars[i - 1];
+    matches!(previous, '/' | '\\' | '_' | '-' | '.' | ' ')
+        || (previous.is_lowercase() && chars[i].is_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(term: &str, text: &str) -> i32 {
+        term_score(term, text).unwrap_or(i32::MIN)
+    }
+
+    #[test]
+    fn dense_and_boundary_matches_rank_higher() {
+        // Подряд идущие символы лучше разбросанных, начало слова лучше середины
+        assert!(score("main", "src/main.rs") > score("main", "src/domain.rs"));
+        assert!(score("main", "src/my_app/init.rs") > score("main", "src/mxaxixn.rs"));
+        assert!(score("rh", "src/request_handler.rs") > score("rh", "src/other.rs"));
+        assert!(score("ph", "src/parseHeader.rs") > score("ph", "src/graphics.rs"));
+    }
+
+    #[test]
+    fn best_occurrence_wins_over_the_first_one() {
+        // Первое `m` в `mod/` не мешает найти плотное `main` дальше по пути
+        assert_eq!(score("main", "mod/x/main.rs"), score("main", "main.rs"));
+    }
+
+    #[test]
+    fn scattered_matches_fall_below_threshold() {
+        assert!(matches_query("main", "src/main.rs"));
+        assert!(matches_query("auth handler", "src/auth/request_handler.rs"));
+        // Начала слов окупают разрывы, а символы в случайных местах - нет
+        assert!(matches_query("main", "src/my_app/init.rs"));
+        assert!(!matches_query("main", "src/mxaxixn.rs"));
+        assert!(!matches_query("auth handler", "src/auth/mod.rs"));
+        assert!(!matches_query("xyz", "src/main.rs"));
+    }
+
+    #[test]
+    fn smart_case() {
+        assert!(matches_query("readme", "README.md"));
+        assert!(matches_query("README", "README.md"));
+        assert!(!matches_query("Readme", "readme.md"));
+    }
+
+    #[test]
+    fn non_ascii_paths() {
+        assert!(matches_query("отчет", "Документы/Отчеты/2024.md"));
+        assert!(matches_query("Отчет", "Документы/Отчеты/2024.md"));
+        assert!(!matches_query("Отчет", "документы/отчеты/2024.md"));
+        assert!(matches_query("über", "docs/Über uns.md"));
+        // Переход в верхний регистр внутри слова - граница и для кириллицы
+        assert!(score("гп", "src/годовойПлан.rs") > score("гп", "src/гиппопотам.rs"));
+        // Буквы, у которых строчная форма длиннее одного символа, и эмодзи не ломают поиск
+        assert!(matches_query("istanbul", "İstanbul/notes.md"));
+        assert!(matches_query("🚀", "launch/🚀.md"));
+        assert!(!matches_query("🚀", "launch/plan.md"));
+    }
+}
@@ -0,0 +1,119 @@
+use std::path::Path;
+
+use globset::{GlobBuilder, GlobMatcher};
+
+// Файлы с атрибутами linguist-generated и linguist-vendored из .gitattributes исключаются так же,
+// как GitHub скрывает их в diff и статистике языков: сгенерированный код и чужие библиотеки
+// только раздувают снимок. Читается .gitattributes в корне проекта. Шаблоны сравниваются как
+// в git: без `/` - с именем файла на любой глубине, с `/` - с путем от корня; для каждого атрибута
+// действует последняя подходящая строка, поэтому `-linguist-generated` ниже снимает исключение.
+// Атрибуты относятся к файлам, директории не исключаются целиком (`dist/** linguist-generated`)
+
+const GENERATED: &str = "linguist-generated";
+const VENDORED: &str = "linguist-vendored";
+
+#[derive(Debug, Default)]
+pub struct Rules {
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug)]
+struct Rule {
+    pattern: String,
+    glob: GlobMatcher,
+    by_path: bool,
+    generated: Option<bool>,
+    vendored: Option<bool>,
+}
+
+pub fn load(target_dir: &Path, read_file: &dyn Fn(&Path) -> Option<String>) -> Rules {
+    let path = target_dir.join(".gitattributes");
+    let Some(text) = read_file(&path) else {
+        return Rules::default();
+    };
+    let rules = parse(&text);
+    if !rules.rules.is_empty() {
+        tracing::info!("Учтены атрибуты linguist из .gitattributes");
+    }
+    rules
+}
+
+pub fn parse(text: &str) -> Rules {
+    let mut rules = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        // Макросы `[attr]name` задают наборы атрибутов, а не правила для файлов
+        if line.is_empty() || line.starts_with('#') || line.starts_with("[attr]") {
+            continue;
+        }
+        let mut parts = line.split_whitespace();
+        let Some(pattern) = parts.next() else {
+            continue;
+        };
+
+        let (mut generated, mut vendored) = (None, None);
+        for attribute in parts {
+            if let Some(value) = attribute_value(attribute, GENERATED) {
+                generated = Some(value);
+            } else if let Some(value) = attribute_value(attribute, VENDORED) {
+                vendored = Some(value);
+            }
+        }
+        // Шаблон с `/` на конце в .gitattributes не подходит ни к одному файлу
+        if (generated.is_none() && vendored.is_none()) || pattern.ends_with('/') {
+            continue;
+        }
+
+        let by_path = pattern.contains('/');
+        match GlobBuilder::new(pattern.trim_start_matches('/')).literal_separator(true).build() {
+            Ok(glob) => rules.push(Rule {
+                pattern: pattern.to_string(),
+                glob: glob.compile_matcher(),
+                by_path,
+                generated,
+                vendored,
+            }),
+            Err(e) => tracing::warn!(".gitattributes: шаблон '{}' пропущен: {}", pattern, e),
+        }
+    }
+    Rules { rules }
+}
+
+// `attr` и `attr=true` включают атрибут; `-attr`, `!attr` и другие значения выключают
+fn attribute_value(attribute: &str, name: &str) -> Option<bool> {
+    if attribute == name {
+        return Some(true);
+    }
+    if let Some(value) = attribute.strip_prefix(name).and_then(|rest| rest.strip_prefix('=')) {
+        return Some(value == "true");
+    }
+    let unset = attribute.strip_prefix('-').or_else(|| attribute.strip_prefix('!'));
+    (unset == Some(name)).then_some(false)
+}
+
+impl Rules {
+    // Атрибут, из-за которого файл исключается, и шаблон строки, которая его задала
+    pub fn excluded(&self, relative_path: &Path) -> Option<(&'static str, &str)> {
+        let name = relative_path.file_name()?;
+        let mut generated: Option<(bool, &str)> = None;
+        let mut vendored: Option<(bool, &str)> = None;
+        for rule in &self.rules {
+            let matched = if rule.by_path { rule.glob.is_match(relative_path) } else { rule.glob.is_match(name) };
+            if !matched {
+                continue;
+            }
+            if let Some(value) = rule.generated {
+                generated = Some((value, &rule.pattern));
+            }
+            if let Some(value) = rule.vendored {
+                vendored = Some((value, &rule.pattern));
+            }
+        }
+
+        match (generated, vendored) {
+            (Some((true, pattern)), _) => Some((GENERATED, pattern)),
+            (_, Some((true, pattern))) => Some((VENDORED, pattern)),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::doc_lang::fill;
+use crate::escape::{code_span, display_path};
+use crate::report::format_size;
+use crate::tree::{NodeKind, TreeNode};
+use crate::{AppConfig, Config, heading, is_binary_file, relative_path};
+
+// Раздел --health: подозрительные находки среди включенных файлов, чтобы снимок
+// заодно служил быстрой проверкой состояния проекта
+
+const STALE_AGE: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+// Длинные списки обрезаются: раздел не должен вытеснять содержимое документа
+const MAX_LISTED: usize = 20;
+const MARKERS: [&str; 2] = ["TODO", "FIXME"];
+
+#[derive(Default)]
+struct Findings {
+    empty_dirs: Vec<PathBuf>,
+    empty_files: Vec<PathBuf>,
+    large_files: Vec<(PathBuf, u64)>,
+    markers: Vec<(PathBuf, usize)>,
+    stale_files: Vec<(PathBuf, u64)>,
+}
+
+pub fn write_health_report<W: Write>(
+    base_dir: &Path,
+    nodes: &[TreeNode],
+    writer: &mut W,
+    large_size: u64,
+    app_config: &AppConfig,
+    config: &Config,
+) -> io::Result<()> {
+    let mut findings = Findings::default();
+    scan(nodes, &mut findings, large_size, app_config, config);
+    findings.large_files.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    findings.markers.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    findings.stale_files.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let doc = app_config.doc;
+    writeln!(writer, "\n{} {}\n", heading(app_config, 2), doc.project_health)?;
+
+    let total_markers: usize = findings.markers.iter().map(|(_, count)| count).sum();
+    let files_larger = fill(doc.files_larger, &[&format_size(large_size)]);
+    writeln!(writer, "| {} | {} |", doc.check, doc.found)?;
+    writeln!(writer, "|---|---:|")?;
+    writeln!(writer, "| {} | {} |", doc.empty_dirs, findings.empty_dirs.len())?;
+    writeln!(writer, "| {} | {} |", doc.empty_files, findings.empty_files.len())?;
+    writeln!(writer, "| {} | {} |", files_larger, findings.large_files.len())?;
+    writeln!(writer, "| TODO/FIXME | {} |", total_markers)?;
+    writeln!(writer, "| {} | {} |", doc.stale_files, findings.stale_files.len())?;
+
+    write_list(writer, app_config, doc.empty_dir_list, &findings.empty_dirs, |path| {
+        code_span(&format!("{}/", display_path(&relative_path(base_dir, path))))
+    })?;
+    write_list(writer, app_config, doc.empty_file_list, &findings.empty_files, |path| path_item(base_dir, path))?;
+    write_list(writer, app_config, doc.large_files, &findings.large_files, |(path, size)| {
+        format!("{} - {}", path_item(base_dir, path), format_size(*size))
+    })?;
+    write_list(writer, app_config, "TODO/FIXME", &findings.markers, |(path, count)| {
+        format!("{} - {}", path_item(base_dir, path), count)
+    })?;
+    write_list(writer, app_config, doc.long_unchanged_files, &findings.stale_files, |(path, days)| {
+        format!("{} - {}", path_item(base_dir, path), fill(doc.days, &[days]))
+    })?;
+
+    Ok(())
+}
+
+// Находки собираются по модели обхода: размеры уже известны, отдельно читается только
+// время изменения и содержимое файлов для подсчета меток
+fn scan(nodes: &[TreeNode], findings: &mut Findings, large_size: u64, app_config: &AppConfig, config: &Config) {
+    let now = SystemTime::now();
+
+    for node in nodes {
+        let info = match &node.kind {
+            NodeKind::Directory(children) => {
+                // Свернутые (`...`) и пропущенные элементы тоже означают, что директория не пуста
+                if children.is_empty() {
+                    findings.empty_dirs.push(node.path.clone());
+                }
+                scan(children, findings, large_size, app_config, config);
+                continue;
+            }
+            NodeKind::File(info) => info,
+            // Недоступные элементы уже попадают в раздел ошибок обхода
+            NodeKind::Collapsed(_)
+            | NodeKind::Skipped
+            | NodeKind::Omitted(_)
+            | NodeKind::Truncated(_)
+            | NodeKind::Failed { .. } => continue,
+        };
+        let path = &node.path;
+        let size = info.len;
+
+        if size == 0 {
+            findings.empty_files.push(path.clone());
+        } else if size > large_size {
+            findings.large_files.push((path.clone(), size));
+        }
+
+        if let Some(modified) = app_config.fs.metadata(path).ok().and_then(|metadata| metadata.modified)
+            && let Ok(age) = now.duration_since(modified)
+            && age > STALE_AGE
+        {
+            findings.stale_files.push((path.clone(), age.as_secs() / (24 * 60 * 60)));
+        }
+
+        if size > 0
+            && size <= large_size
+            && !is_binary_file(path, config)
+            && let Ok(content) = app_config.fs.read_to_string(path)
+        {
+            let count: usize = MARKERS.iter().map(|marker| content.matches(marker).count()).sum();
+            if count > 0 {
+                findings.markers.push((path.clone(), count));
+            }
+        }
+    }
+}
+
+fn write_list<W: Write, T>(
+    writer: &mut W,
+    app_config: &AppConfig,
+    title: &str,
+    items: &[T],
+    format_item: impl Fn(&T) -> String,
+) -> io::Result<()> {
+    if items.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(writer, "\n{} {}\n", heading(app_config, 3), title)?;
+    for item in items.iter().take(MAX_LISTED) {
+        writeln!(writer, "- {}", format_item(item))?;
+    }
+    if items.len() > MAX_LISTED {
+        writeln!(writer, "- {}", fill(app_config.doc.and_more, &[&(items.len() - MAX_LISTED)]))?;
+    }
+
+    Ok(())
+}
+
+fn path_item(base_dir: &Path, path: &Path) -> String {
+    code_span(&display_path(&relative_path(base_dir, path)))
+}
@@ -0,0 +1,152 @@
+use crate::escape;
+
+// Подсветка кода для HTML с --theme/--css: комментарии, строки, числа и ключевые слова
+// оборачиваются в <span class="hl-..."> по простым правилам языка, без полного разбора.
+// Цвета задает тема (html_theme.rs) или свой CSS. Для незнакомых языков код только экранируется
+
+struct Rules {
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static [char],
+    keywords: &'static [&'static str],
+}
+
+const RUST: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "false", "fn",
+    "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self", "Self",
+    "static", "struct", "super", "trait", "true", "type", "unsafe", "use", "where", "while",
+];
+const C: &[&str] = &[
+    "auto", "break", "case", "char", "class", "const", "continue", "default", "delete", "do", "double", "else", "enum",
+    "extern", "false", "float", "for", "goto", "if", "inline", "int", "long", "namespace", "new", "nullptr", "private",
+    "protected", "public", "return", "short", "signed", "sizeof", "static", "struct", "switch", "template", "this",
+    "true", "typedef", "union", "unsigned", "using", "virtual", "void", "volatile", "while",
+];
+const JAVA: &[&str] = &[
+    "abstract", "boolean", "break", "case", "catch", "class", "const", "continue", "default", "do", "else", "enum",
+    "extends", "false", "final", "finally", "for", "fun", "if", "implements", "import", "interface", "new", "null",
+    "override", "package", "private", "protected", "public", "return", "static", "super", "switch", "this", "throw",
+    "throws", "true", "try", "val", "var", "void", "when", "while",
+];
+const GO: &[&str] = &[
+    "break", "case", "chan", "const", "continue", "default", "defer", "else", "false", "for", "func", "go", "goto",
+    "if", "import", "interface", "map", "nil", "package", "range", "return", "select", "struct", "switch", "true",
+    "type", "var",
+];
+const JAVASCRIPT: &[&str] = &[
+    "async", "await", "break", "case", "catch", "class", "const", "continue", "default", "delete", "do", "else",
+    "export", "extends", "false", "finally", "for", "from", "function", "if", "import", "in", "instanceof",
+    "interface", "let", "new", "null", "return", "super", "switch", "this", "throw", "true", "try", "type", "typeof",
+    "undefined", "var", "void", "while", "yield",
+];
+const PYTHON: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del",
+    "elif", "else", "except", "finally", "for", "from", "global", "if", "import", "in", "is", "lambda", "nonlocal",
+    "not", "or", "pass", "raise", "return", "try", "while", "with", "yield",
+];
+const SHELL: &[&str] = &[
+    "case", "do", "done", "elif", "else", "esac", "export", "fi", "for", "function", "if", "in", "local", "return",
+    "then", "until", "while",
+];
+const DATA: &[&str] = &["false", "null", "true"];
+
+fn rules(language: &str) -> Option<Rules> {
+    let c_like = |keywords| Rules {
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        quotes: &['"', '\''],
+        keywords,
+    };
+    let rules = match language {
+        "rust" | "rs" => Rules { quotes: &['"'], ..c_like(RUST) },
+        "c" | "h" | "cpp" | "hpp" | "cc" | "csharp" | "cs" | "objc" => c_like(C),
+        "java" | "kotlin" | "kt" | "scala" | "swift" | "dart" | "groovy" => c_like(JAVA),
+        "go" => Rules { quotes: &['"', '\'', '`'], ..c_like(GO) },
+        "javascript" | "js" | "jsx" | "mjs" | "cjs" | "typescript" | "ts" | "tsx" => {
+            Rules { quotes: &['"', '\'', '`'], ..c_like(JAVASCRIPT) }
+        }
+        "python" | "py" => Rules { line_comments: &["#"], block_comment: None, quotes: &['"', '\''], keywords: PYTHON },
+        "bash" | "sh" | "shell" | "zsh" => {
+            Rules { line_comments: &["#"], block_comment: None, quotes: &['"', '\''], keywords: SHELL }
+        }
+        "toml" | "yaml" | "yml" => {
+            Rules { line_comments: &["#"], block_comment: None, quotes: &['"', '\''], keywords: DATA }
+        }
+        "json" => Rules { line_comments: &[], block_comment: None, quotes: &['"'], keywords: DATA },
+        _ => return None,
+    };
+    Some(rules)
+}
+
+// Экранированный код с разметкой подсветки
+pub fn html(language: &str, content: &str) -> String {
+    let Some(rules) = rules(language) else {
+        return escape::html(content);
+    };
+
+    let mut result = String::with_capacity(content.len() * 5 / 4);
+    let mut plain = 0;
+    let mut pos = 0;
+    while pos < content.len() {
+        let rest = &content[pos..];
+        let c = rest.chars().next().unwrap();
+        let token = if let Some(marker) = rules.line_comments.iter().find(|marker| rest.starts_with(**marker)) {
+            Some(("hl-comment", rest.find('\n').unwrap_or(rest.len()).max(marker.len())))
+        } else if let Some((open, close)) = rules.block_comment
+            && rest.starts_with(open)
+        {
+            Some(("hl-comment", rest[open.len()..].find(close).map_or(rest.len(), |end| open.len() + end + close.len())))
+        } else if rules.quotes.contains(&c) {
+            Some(("hl-string", string_end(rest, c)))
+        } else if c.is_ascii_digit() && !previous_is_word(content, pos) {
+            Some(("hl-number", word_end(rest, true)))
+        } else if is_word_char(c) && !previous_is_word(content, pos) {
+            let end = word_end(rest, false);
+            rules.keywords.contains(&&rest[..end]).then_some(("hl-keyword", end))
+        } else {
+            None
+        };
+
+        match token {
+            Some((class, length)) => {
+                result.push_str(&escape::html(&content[plain..pos]));
+                result.push_str(&format!("<span class=\"{}\">{}</span>", class, escape::html(&rest[..length])));
+                pos += length;
+                plain = pos;
+            }
+            None => pos += c.len_utf8(),
+        }
+    }
+    result.push_str(&escape::html(&content[plain..]));
+    result
+}
+
+// Строка до закрывающей кавычки с учетом `\`; незакрытая - до конца строки файла
+fn string_end(rest: &str, quote: char) -> usize {
+    let mut escaped = false;
+    for (i, c) in rest.char_indices().skip(1) {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == quote {
+            return i + c.len_utf8();
+        } else if c == '\n' && quote != '`' {
+            return i;
+        }
+    }
+    rest.len()
+}
+
+// В числе допускается точка (1.5, 0.1e3), в слове - нет
+fn word_end(rest: &str, number: bool) -> usize {
+    rest.find(|c: char| !(is_word_char(c) || (number && c == '.'))).unwrap_or(rest.len()).max(1)
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn previous_is_word(content: &str, pos: usize) -> bool {
+    content[..pos].chars().next_back().is_some_and(is_word_char)
+}
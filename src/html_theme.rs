@@ -0,0 +1,53 @@
+// --theme light|dark|auto и --css FILE для формата html: стили встраиваются в документ,
+// чтобы он оставался одним файлом. Тема задает оформление страницы и цвета подсветки
+// (highlight.rs); свой CSS выводится после темы и может переопределить любое правило, а без
+// --theme заменяет встроенное оформление целиком
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    Light,
+    Dark,
+    // Светлая или темная по настройке системы (prefers-color-scheme)
+    Auto,
+}
+
+impl Theme {
+    pub fn parse(name: &str) -> Option<Theme> {
+        match name {
+            "light" => Some(Theme::Light),
+            "dark" => Some(Theme::Dark),
+            "auto" => Some(Theme::Auto),
+            _ => None,
+        }
+    }
+}
+
+// Оформление без темы и своего CSS, как до появления --theme
+const BASE: &str = "body{font-family:sans-serif;max-width:60em;margin:2em auto;padding:0 1em}\
+pre{background:#f6f8fa;padding:1em;overflow-x:auto}nav{font-size:.9em;margin:.5em 0}";
+
+const LAYOUT: &str = "body{font-family:sans-serif;max-width:60em;margin:2em auto;padding:0 1em}\
+pre{padding:1em;overflow-x:auto;border-radius:4px}nav{font-size:.9em;margin:.5em 0}";
+
+const LIGHT: &str = "body{background:#fff;color:#24292f}a{color:#0969da}pre{background:#f6f8fa}\
+.hl-comment{color:#6e7781;font-style:italic}.hl-string{color:#0a3069}.hl-number{color:#0550ae}\
+.hl-keyword{color:#cf222e;font-weight:bold}";
+
+const DARK: &str = "body{background:#0d1117;color:#c9d1d9}a{color:#58a6ff}pre{background:#161b22}\
+.hl-comment{color:#8b949e;font-style:italic}.hl-string{color:#a5d6ff}.hl-number{color:#79c0ff}\
+.hl-keyword{color:#ff7b72;font-weight:bold}";
+
+pub fn stylesheet(theme: Option<Theme>, custom_css: Option<&str>) -> String {
+    let mut style = match theme {
+        None if custom_css.is_some() => String::new(),
+        None => BASE.to_string(),
+        Some(Theme::Light) => format!("{}{}", LAYOUT, LIGHT),
+        Some(Theme::Dark) => format!("{}{}", LAYOUT, DARK),
+        Some(Theme::Auto) => format!("{}{}@media (prefers-color-scheme:dark){{{}}}", LAYOUT, LIGHT, DARK),
+    };
+    if let Some(css) = custom_css {
+        // Закрывающий тег внутри CSS оборвал бы <style> раньше времени
+        style.push_str(&css.replace("</style", "<\\/style"));
+    }
+    style
+}
@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::deps::{file_dependencies, normalize_path};
+use crate::escape::{code_span, display_path};
+use crate::{AppConfig, Config, content_files, heading, relative_path, tree};
+
+// Раздел --import-graph: связи между файлами проекта по import/use/require/#include
+// (Rust, Python, JS/TS, C/C++ - те же правила, что и у --order deps). Внешние пакеты
+// не показываются: учитываются только ссылки на файлы раздела содержимого
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    // Список смежности: файл и файлы, которые он импортирует
+    List,
+    Mermaid,
+}
+
+pub fn write_import_graph_section<W: Write>(
+    base_dir: &Path,
+    nodes: &[tree::TreeNode],
+    writer: &mut W,
+    format: GraphFormat,
+    app_config: &AppConfig,
+    config: &Config,
+) -> io::Result<()> {
+    let mut files = content_files(nodes, app_config, config);
+    files.sort();
+    let edges = edges(&files, app_config);
+
+    writeln!(writer, "\n{} {}\n", heading(app_config, 2), app_config.doc.import_graph)?;
+    if edges.is_empty() {
+        return writeln!(writer, "{}", app_config.doc.import_graph_empty);
+    }
+
+    let name = |index: usize| display_path(&relative_path(base_dir, &files[index]));
+    match format {
+        GraphFormat::List => {
+            for (from, targets) in &edges {
+                let targets: Vec<String> = targets.iter().map(|&to| code_span(&name(to))).collect();
+                writeln!(writer, "- {} → {}", code_span(&name(*from)), targets.join(", "))?;
+            }
+        }
+        // Идентификаторы узлов - номера файлов: пути с точками и дефисами Mermaid не принимает
+        GraphFormat::Mermaid => {
+            writeln!(writer, "```mermaid\ngraph LR")?;
+            for (from, targets) in &edges {
+                for to in targets {
+                    writeln!(
+                        writer,
+                        "    n{}[\"{}\"] --> n{}[\"{}\"]",
+                        from,
+                        mermaid_label(&name(*from)),
+                        to,
+                        mermaid_label(&name(*to))
+                    )?;
+                }
+            }
+            writeln!(writer, "```")?;
+        }
+    }
+    Ok(())
+}
+
+// Для каждого файла с импортами - номера импортируемых файлов по порядку путей
+fn edges(files: &[PathBuf], app_config: &AppConfig) -> Vec<(usize, Vec<usize>)> {
+    let index: HashMap<PathBuf, usize> = files
+        .iter()
+        .enumerate()
+        .map(|(i, path)| (normalize_path(path), i))
+        .collect();
+
+    let mut edges = Vec::new();
+    for (i, path) in files.iter().enumerate() {
+        let Ok(content) = app_config.fs.read_to_string(path) else {
+            continue;
+        };
+        let mut targets: Vec<usize> = file_dependencies(path, &content)
+            .iter()
+            .filter_map(|candidate| index.get(&normalize_path(candidate)).copied())
+            .filter(|&target| target != i)
+            .collect();
+        targets.sort_unstable();
+        targets.dedup();
+        if !targets.is_empty() {
+            edges.push((i, targets));
+        }
+    }
+    edges
+}
+
+fn mermaid_label(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
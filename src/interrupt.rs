@@ -0,0 +1,93 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Ctrl-C (и SIGTERM) во время генерации: обработчик только поднимает флаг, а обход
+// и вывод содержимого проверяют его перед каждым элементом и останавливаются. Документ
+// дописывается до конца - блоки кода закрыты, в конце пометка, на каком файле генерация
+// прервана, - и запуск завершается с кодом 130. Повторный Ctrl-C завершает процесс сразу
+
+// Код завершения прерванного запуска, как у shell для SIGINT (128 + 2)
+pub const EXIT_CODE: i32 = 130;
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+static STOPPED_AT: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+pub fn install() {
+    sys::install();
+}
+
+pub fn is_interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+// Проверка перед очередным элементом: при прерывании запоминается первый
+// необработанный путь
+pub fn stop_at(path: &Path) -> bool {
+    if !is_interrupted() {
+        return false;
+    }
+    let mut stopped_at = STOPPED_AT.lock().unwrap();
+    if stopped_at.is_none() {
+        *stopped_at = Some(path.to_path_buf());
+    }
+    true
+}
+
+pub fn stopped_at() -> Option<PathBuf> {
+    STOPPED_AT.lock().unwrap().clone()
+}
+
+#[cfg(unix)]
+mod sys {
+    use std::ffi::c_int;
+    use std::sync::atomic::Ordering;
+
+    const SIGINT: c_int = 2;
+    const SIGTERM: c_int = 15;
+
+    unsafe extern "C" {
+        fn signal(signum: c_int, handler: extern "C" fn(c_int)) -> usize;
+        fn _exit(status: c_int) -> !;
+    }
+
+    // В обработчике допустимы только async-signal-safe операции: атомарный флаг и _exit
+    extern "C" fn handle(_: c_int) {
+        if super::INTERRUPTED.swap(true, Ordering::SeqCst) {
+            unsafe { _exit(super::EXIT_CODE) }
+        }
+    }
+
+    pub fn install() {
+        unsafe {
+            signal(SIGINT, handle);
+            signal(SIGTERM, handle);
+        }
+    }
+}
+
+#[cfg(windows)]
+mod sys {
+    use std::sync::atomic::Ordering;
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn SetConsoleCtrlHandler(handler: Option<unsafe extern "system" fn(u32) -> i32>, add: i32) -> i32;
+    }
+
+    // FALSE для повторного нажатия передает событие обработчику по умолчанию, и процесс завершается
+    unsafe extern "system" fn handle(_: u32) -> i32 {
+        if super::INTERRUPTED.swap(true, Ordering::SeqCst) { 0 } else { 1 }
+    }
+
+    pub fn install() {
+        unsafe {
+            SetConsoleCtrlHandler(Some(handle), 1);
+        }
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod sys {
+    pub fn install() {}
+}
@@ -0,0 +1,185 @@
+use std::io::{self, Write};
+use std::path::Path;
+use ignore::gitignore::Gitignore;
+use serde_json::{Value, json};
+
+use crate::escape::display_path;
+use crate::logging;
+use crate::schema;
+use crate::tree_only;
+use crate::{
+    AppConfig, Config, SkipReason, classify_entry, content_language, entry_is_dir, is_binary_file,
+    is_extension_allowed, relative_path, secrets, walk_errors,
+};
+
+// --format jsonl: события обхода по одному JSON-объекту в строке, по мере обхода, а не
+// после построения модели. Потребитель обрабатывает большой репозиторий потоком:
+//   {"event":"dir-enter","path":"src"}
+//   {"event":"file","path":"src/main.rs","size":120,"language":"rust","content":"..."}
+//   {"event":"skip","path":"target","kind":"dir","reason":"exclude_dirs","collapsed":true}
+//   {"event":"dir-exit","path":"src"}
+//   {"event":"end","dirs":3,"files":12,"skipped":4}
+// Директории обходятся последовательно в порядке имен; каждая строка сбрасывается сразу
+
+#[derive(Default)]
+struct Counts {
+    dirs: usize,
+    files: usize,
+    skipped: usize,
+}
+
+pub fn write_events<W: Write>(
+    base_dir: &Path,
+    writer: &mut W,
+    app_config: &AppConfig,
+    config: &Config,
+    gitignore_matcher: &Option<Gitignore>,
+) -> io::Result<()> {
+    emit(
+        writer,
+        json!({
+            "event": "start",
+            "schema_version": schema::JSONL.version,
+            "version": schema::JSONL.version,
+            "root": display_path(base_dir),
+        }),
+    )?;
+
+    let mut counts = Counts::default();
+    walk(base_dir, base_dir, writer, &mut counts, app_config, config, gitignore_matcher)?;
+
+    emit(writer, json!({ "event": "end", "dirs": counts.dirs, "files": counts.files, "skipped": counts.skipped }))?;
+    tracing::info!("Событий file: {}, skip: {}", counts.files, counts.skipped);
+    Ok(())
+}
+
+fn walk<W: Write>(
+    base_dir: &Path,
+    current_dir: &Path,
+    writer: &mut W,
+    counts: &mut Counts,
+    app_config: &AppConfig,
+    config: &Config,
+    gitignore_matcher: &Option<Gitignore>,
+) -> io::Result<()> {
+    let _span = logging::directory_span(current_dir).entered();
+    let dir_path = event_path(base_dir, current_dir);
+    counts.dirs += 1;
+    emit(writer, json!({ "event": "dir-enter", "path": dir_path }))?;
+
+    let mut entries = Vec::new();
+    match app_config.fs.read_dir(current_dir) {
+        Ok(read) => {
+            for entry in read {
+                match entry {
+                    Ok(entry) => entries.push(entry),
+                    Err(e) => write_error(writer, base_dir, current_dir, &e, app_config)?,
+                }
+            }
+        }
+        Err(e) => write_error(writer, base_dir, current_dir, &e, app_config)?,
+    }
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+
+    for entry in entries {
+        let path = &entry.path;
+        let is_dir = entry_is_dir(&entry, app_config);
+        let (reason, cause) =
+            classify_entry(path, &entry.name.to_string_lossy(), is_dir, app_config, config, gitignore_matcher);
+
+        match reason {
+            SkipReason::Skip | SkipReason::SkipWithEllipsis => {
+                counts.skipped += 1;
+                emit(
+                    writer,
+                    json!({
+                        "event": "skip",
+                        "path": event_path(base_dir, path),
+                        "kind": if is_dir { "dir" } else { "file" },
+                        "reason": cause,
+                        "collapsed": reason == SkipReason::SkipWithEllipsis,
+                    }),
+                )?;
+            }
+            SkipReason::NoSkip if is_dir => {
+                walk(base_dir, path, writer, counts, app_config, config, gitignore_matcher)?;
+            }
+            SkipReason::NoSkip => {
+                counts.files += 1;
+                write_file(writer, base_dir, path, app_config, config)?;
+            }
+        }
+    }
+
+    emit(writer, json!({ "event": "dir-exit", "path": dir_path }))
+}
+
+// Содержимое есть только у файлов, которые попали бы в раздел содержимого документа
+fn write_file<W: Write>(
+    writer: &mut W,
+    base_dir: &Path,
+    path: &Path,
+    app_config: &AppConfig,
+    config: &Config,
+) -> io::Result<()> {
+    let mut event = json!({ "event": "file", "path": event_path(base_dir, path) });
+
+    let size = match app_config.fs.metadata(path) {
+        Ok(metadata) => metadata.len,
+        Err(e) => {
+            walk_errors::record(app_config, path, &walk_errors::describe(&e, app_config.doc));
+            event["error"] = json!(e.to_string());
+            return emit(writer, event);
+        }
+    };
+    event["size"] = json!(size);
+
+    let with_content = app_config.include_contents
+        && is_extension_allowed(path, app_config)
+        && !is_binary_file(path, config)
+        && !tree_only::is_tree_only(path, app_config, config)
+        && config.max_file_size.is_none_or(|max_size| size <= max_size);
+    if with_content {
+        match app_config.fs.read_text(path) {
+            Ok((content, _)) => {
+                let content = if !app_config.unsafe_full_secrets && secrets::is_secret_file(path, config) {
+                    secrets::mask_secrets(&content)
+                } else {
+                    content
+                };
+                event["language"] = json!(content_language(path, &content, config));
+                event["content"] = json!(content);
+            }
+            Err(e) => {
+                walk_errors::record(app_config, path, &walk_errors::describe(&e, app_config.doc));
+                event["error"] = json!(e.to_string());
+            }
+        }
+    }
+
+    emit(writer, event)
+}
+
+fn write_error<W: Write>(
+    writer: &mut W,
+    base_dir: &Path,
+    path: &Path,
+    error: &io::Error,
+    app_config: &AppConfig,
+) -> io::Result<()> {
+    walk_errors::record(app_config, path, &walk_errors::describe(error, app_config.doc));
+    emit(writer, json!({ "event": "error", "path": event_path(base_dir, path), "message": error.to_string() }))
+}
+
+fn event_path(base_dir: &Path, path: &Path) -> String {
+    if path == base_dir {
+        return ".".to_string();
+    }
+    display_path(&relative_path(base_dir, path))
+}
+
+// Строка уходит потребителю сразу, а не при заполнении буфера
+fn emit<W: Write>(writer: &mut W, event: Value) -> io::Result<()> {
+    writeln!(writer, "{}", event)?;
+    writer.flush()
+}
@@ -0,0 +1,124 @@
+use std::path::Path;
+use serde_json::Value;
+
+// Определение языка для файлов, по расширению которых язык неизвестен (скрипты без
+// расширения, Dockerfile и т.п.): по известному имени, shebang или началу содержимого
+
+// Содержимое длиннее целиком как JSON не разбирается: определение языка выполняется для каждого
+// файла без расширения и не должно стоить полного разбора больших данных
+const JSON_SNIFF_LIMIT: usize = 64 * 1024;
+
+const NAMED_FILES: [(&str, &str); 10] = [
+    ("Dockerfile", "dockerfile"),
+    ("Containerfile", "dockerfile"),
+    ("Makefile", "makefile"),
+    ("GNUmakefile", "makefile"),
+    ("Gemfile", "ruby"),
+    ("Rakefile", "ruby"),
+    ("Vagrantfile", "ruby"),
+    ("Podfile", "ruby"),
+    ("Jenkinsfile", "groovy"),
+    ("Justfile", "just"),
+];
+
+const INTERPRETERS: [(&str, &str); 20] = [
+    ("python", "python"),
+    ("pypy", "python"),
+    ("bash", "bash"),
+    ("sh", "bash"),
+    ("dash", "bash"),
+    ("ksh", "bash"),
+    ("zsh", "zsh"),
+    ("fish", "fish"),
+    ("node", "javascript"),
+    ("nodejs", "javascript"),
+    ("deno", "typescript"),
+    ("bun", "javascript"),
+    ("ruby", "ruby"),
+    ("perl", "perl"),
+    ("php", "php"),
+    ("lua", "lua"),
+    ("Rscript", "r"),
+    ("pwsh", "powershell"),
+    ("awk", "awk"),
+    ("tclsh", "tcl"),
+];
+
+pub fn detect(path: &Path, content: &str) -> Option<&'static str> {
+    let name = path.file_name()?.to_string_lossy();
+    if let Some((_, language)) = NAMED_FILES
+        .iter()
+        .find(|(file, _)| name.eq_ignore_ascii_case(file) || name.starts_with(&format!("{}.", file)))
+    {
+        return Some(language);
+    }
+
+    let content = content.trim_start_matches('\u{feff}');
+    if let Some(shebang) = content.lines().next().and_then(|line| line.strip_prefix("#!")) {
+        return shebang_language(shebang);
+    }
+
+    let start = content.trim_start();
+    if start.starts_with("<?xml") {
+        return Some("xml");
+    }
+    let doctype = start.get(..14).is_some_and(|prefix| prefix.eq_ignore_ascii_case("<!doctype html"));
+    if doctype || start.starts_with("<html") {
+        return Some("html");
+    }
+    if start.starts_with("<?php") {
+        return Some("php");
+    }
+    if (start.starts_with('{') || start.starts_with('['))
+        && content.len() <= JSON_SNIFF_LIMIT
+        && serde_json::from_str::<Value>(content).is_ok()
+    {
+        return Some("json");
+    }
+
+    None
+}
+
+// `#!/usr/bin/env -S python3 -u`, `#!/bin/bash -e`, `#!/usr/local/bin/python3.11`
+fn shebang_language(shebang: &str) -> Option<&'static str> {
+    let mut words = shebang.split_whitespace();
+    let mut program = words.next()?.rsplit('/').next()?;
+
+    if program == "env" {
+        program = words.find(|word| !word.starts_with('-') && !word.contains('='))?;
+    }
+
+    // Версия интерпретатора в имени не важна: python3.11 -> python
+    let program = program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.' || c == '-');
+    let program = program.strip_prefix('g').filter(|p| *p == "awk").unwrap_or(program);
+
+    INTERPRETERS
+        .iter()
+        .find(|(interpreter, _)| *interpreter == program)
+        .map(|(_, language)| *language)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_is_detected_by_content() {
+        assert_eq!(detect(Path::new("data"), "{\"a\": [1, 2]}\n"), Some("json"));
+        assert_eq!(detect(Path::new("notes"), "[draft] not json\n"), None);
+    }
+
+    #[test]
+    fn hostile_content_is_not_parsed() {
+        let nested = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+        assert_eq!(detect(Path::new("payload"), &nested), None);
+        let large = format!("[{}1]", "1, ".repeat(JSON_SNIFF_LIMIT));
+        assert_eq!(detect(Path::new("large"), &large), None);
+    }
+
+    #[test]
+    fn shebang_selects_interpreter() {
+        assert_eq!(detect(Path::new("run"), "#!/usr/bin/env -S python3.11 -u\n"), Some("python"));
+        assert_eq!(detect(Path::new("Dockerfile.dev"), "FROM alpine\n"), Some("dockerfile"));
+    }
+}
@@ -0,0 +1,98 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::doc_lang::Strings;
+use crate::git;
+use crate::report::format_size;
+
+// Файлы под Git LFS хранятся в репозитории как короткие указатели:
+//   version https://git-lfs.github.com/spec/v1
+//   oid sha256:4d7a21...
+//   size 48213
+// Вместо указателя выводится пометка, а с --lfs-fetch - сам объект
+
+const POINTER_VERSION: &str = "version https://git-lfs.github.com/spec/v1";
+// Указатель по спецификации не длиннее 1024 байт
+const MAX_POINTER_SIZE: usize = 1024;
+// oid - 64 шестнадцатеричные цифры SHA-256 в нижнем регистре
+const OID_LEN: usize = 64;
+
+pub struct Pointer {
+    pub oid: String,
+    pub size: u64,
+}
+
+pub fn parse_pointer(content: &str) -> Option<Pointer> {
+    if content.len() > MAX_POINTER_SIZE || !content.starts_with(POINTER_VERSION) {
+        return None;
+    }
+
+    let mut oid = None;
+    let mut size = None;
+    for line in content.lines().skip(1) {
+        match line.split_once(' ') {
+            Some(("oid", value)) => oid = value.strip_prefix("sha256:").filter(|oid| is_valid_oid(oid)).map(str::to_string),
+            Some(("size", value)) => size = value.parse().ok(),
+            _ => {}
+        }
+    }
+
+    Some(Pointer { oid: oid?, size: size? })
+}
+
+// Из oid собирается путь в .git/lfs/objects и текст для git lfs smudge: все, кроме
+// шестнадцатеричного хеша, указателем не считается
+fn is_valid_oid(oid: &str) -> bool {
+    oid.len() == OID_LEN && oid.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+pub fn write_annotation<W: Write>(
+    writer: &mut W,
+    pointer: &Pointer,
+    fence: &str,
+    doc: &Strings,
+    note: Option<&str>,
+) -> io::Result<()> {
+    let note = note.map(|note| format!(", {}", note)).unwrap_or_default();
+    writeln!(writer, "{}", fence)?;
+    writeln!(writer, "[{}, {}, sha256:{}{}]", doc.lfs_object, format_size(pointer.size), pointer.oid, note)?;
+    writeln!(writer, "{}", fence)
+}
+
+// Объект берется из локального хранилища .git/lfs/objects, а если его там нет -
+// через `git lfs smudge`, который скачивает объект с сервера
+pub fn fetch_object(path: &Path, pointer: &Pointer) -> Option<Vec<u8>> {
+    let dir = path.parent()?;
+
+    if let Some(git_dir) = git::git_dir(dir) {
+        let local = git_dir
+            .join("lfs/objects")
+            .join(&pointer.oid[..2])
+            .join(&pointer.oid[2..4])
+            .join(&pointer.oid);
+        if let Ok(data) = fs::read(local) {
+            return Some(data);
+        }
+    }
+
+    let pointer_text = format!("{}\noid sha256:{}\nsize {}\n", POINTER_VERSION, pointer.oid, pointer.size);
+    let mut child = Command::new("git")
+        .args(["lfs", "smudge"])
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| tracing::warn!("не удалось запустить git lfs: {}", e))
+        .ok()?;
+
+    child.stdin.take()?.write_all(pointer_text.as_bytes()).ok()?;
+    let output = child.wait_with_output().ok()?;
+    if !output.status.success() {
+        tracing::warn!("git lfs не смог получить объект для {}", path.display());
+        return None;
+    }
+    Some(output.stdout)
+}
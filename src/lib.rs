@@ -0,0 +1,2364 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use serde::Deserialize;
+use clap::ArgMatches;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+use args::cli;
+
+mod anchors;
+mod archive;
+mod args;
+mod atomic_file;
+mod batch;
+mod bench;
+mod build_files;
+mod bundle;
+mod chunk;
+mod config_diff;
+mod content_order;
+mod density;
+mod daemon;
+mod deps;
+mod device;
+mod dir_size;
+mod doc_lang;
+mod embed;
+mod docker;
+mod dotfiles;
+mod encoding;
+mod escape;
+mod ffi;
+mod footer;
+mod fuzzy;
+mod git;
+mod gitattributes;
+mod health;
+mod highlight;
+mod html_theme;
+mod import_graph;
+mod interrupt;
+mod jsonl;
+mod language;
+mod lfs;
+mod lockfile;
+mod logging;
+mod manifests;
+mod md_dir;
+mod merge_artifacts;
+mod notices;
+mod obsidian;
+mod output;
+mod pager;
+mod project;
+#[cfg(feature = "python")]
+mod python;
+mod quick;
+pub mod render;
+mod report;
+mod sampling;
+mod scan;
+mod schema;
+mod search_index;
+mod secrets;
+mod sha256;
+mod sidecar;
+mod skip_stats;
+mod summarize;
+mod syntax;
+mod tar;
+mod test_paths;
+mod todos;
+mod tokens;
+mod translit;
+mod tree;
+mod tree_only;
+mod unpack;
+mod update;
+mod vfs;
+mod walk_errors;
+mod whitespace;
+mod zip;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Config {
+    exclude_dirs: Vec<String>,
+    exclude_files: Vec<String>,
+    exclude_extensions: Vec<String>,
+    secret_files: Vec<String>,
+    include_external: Vec<String>,
+    max_file_size: Option<u64>,
+    extension_mapping: Option<HashMap<String, String>>,
+    // Порядок файлов в разделе содержимого по шаблонам путей
+    content_order: Vec<String>,
+    // Порог суммарного размера директории, выше которого она сворачивается
+    #[serde(deserialize_with = "dir_size::deserialize_size")]
+    skip_dir_over: Option<u64>,
+    // Файлы, которые показываются в дереве, но не в содержимом
+    tree_only: Vec<String>,
+    #[serde(skip)]
+    tree_only_globs: tree_only::Globs,
+    // Политика для скрытых файлов и исключения из нее
+    dotfiles: dotfiles::Policy,
+    keep_dotfiles: Vec<String>,
+    #[serde(skip)]
+    keep_dotfiles_globs: dotfiles::Globs,
+    // Файлы данных, от которых выводятся только первые N строк
+    sampling: HashMap<String, usize>,
+    #[serde(skip)]
+    sampling_rules: sampling::Rules,
+    // Шаблоны [sampling] из конфигурации проекта, которые сильнее встроенного exclude_files
+    #[serde(skip)]
+    explicit_sampling: Vec<String>,
+    // Внешние команды, вывод которых заменяет содержимое подходящих файлов
+    summarizers: Vec<summarize::Summarizer>,
+    #[serde(skip)]
+    summarizer_globs: summarize::Globs,
+}
+
+#[derive(Debug)]
+struct AppConfig {
+    target_dir: String,
+    output_file: Option<String>,
+    // Выходной файл внутри целевой директории в том виде, в каком его путь встретится при обходе
+    output_path: Option<PathBuf>,
+    include_tree: bool,
+    include_contents: bool,
+    print_to_console: bool,
+    use_pager: bool,
+    no_gitignore: bool,
+    ignore_files: Vec<PathBuf>,
+    // Сравнение имен без учета регистра: по умолчанию в Windows и macOS (--case-insensitive)
+    case_insensitive: bool,
+    // Файлы из позиционных аргументов: выводятся всегда, даже если их исключают фильтры
+    explicit_files: Vec<PathBuf>,
+    flat_tree: bool,
+    // Цепочки директорий с единственной поддиректорией одной строкой (--compact-tree)
+    compact_tree: bool,
+    // Отдельные дерево и содержимое для тестов (--split-tests)
+    split_tests: bool,
+    // Отдельный раздел содержимого для файлов сборки и CI (--build-section)
+    build_section: bool,
+    // Быстрый обзор (--quick): файлы с содержимым, выбранные после обхода, в порядке вывода
+    quick: bool,
+    quick_selection: Mutex<Option<Vec<PathBuf>>>,
+    unsafe_full_secrets: bool,
+    // Запускать команды [[summarizers]] из конфигурации (--run-summarizers)
+    run_summarizers: bool,
+    full_lockfiles: bool,
+    lfs_fetch: bool,
+    normalize_eol: whitespace::Eol,
+    expand_tabs: Option<usize>,
+    // Снимать общий отступ и переносить строки длиннее N символов (--dedent, --max-width)
+    dedent: bool,
+    max_width: Option<usize>,
+    // Шаблон строки информации блока кода (--fence-info)
+    fence_info: Option<String>,
+    // Минимальная длина разделителя блоков кода (--min-fence)
+    min_fence: usize,
+    // Префикс путей в заголовках и дереве (--paths): пустой для путей от целевой директории,
+    // абсолютный путь директории или ее путь от корня репозитория
+    path_prefix: PathBuf,
+    // Читать файлы с некорректным UTF-8 с заменой байтов на U+FFFD (--lossy-read)
+    lossy_read: bool,
+    // Отмечать файлы с незакрытыми скобками, строками и комментариями (--check-syntax)
+    check_syntax: bool,
+    // Раздел о маркерах конфликта и файлах .orig/.rej (отключается --no-merge-check)
+    merge_check: bool,
+    // Максимальная длина части документа в символах (--chunk)
+    chunk: Option<usize>,
+    content_order: ContentOrder,
+    top_files: Option<usize>,
+    // Колонка сжимаемости в отчете --top-files
+    density: bool,
+    // Порог крупного файла для раздела --health
+    health: Option<u64>,
+    // Раздел заметок TODO/FIXME/HACK/XXX и файл CSV с ними (--todos, --todos-csv)
+    todos: bool,
+    todos_csv: Option<String>,
+    // Раздел зависимостей из манифестов (--deps)
+    deps: bool,
+    // Раздел связей между файлами по импортам (--import-graph)
+    import_graph: Option<import_graph::GraphFormat>,
+    // Контекст сборки Docker (--docker-context): правила .dockerignore и инструкции COPY/ADD
+    docker_context: Option<docker::Context>,
+    // Правила linguist-generated и linguist-vendored из .gitattributes; None с --no-gitattributes
+    gitattributes: Option<gitattributes::Rules>,
+    // Подвал с версией, параметрами, временем и хешем документа (--footer)
+    footer: bool,
+    // Метаданные снимка в FILE.meta.json рядом с документом (--sidecar) и время обхода для них
+    sidecar: bool,
+    walk_time: Mutex<Option<Duration>>,
+    only_extensions: Option<Vec<String>>,
+    full_tree: bool,
+    filter: Option<String>,
+    sample_dirs: Vec<(String, usize)>,
+    // Готовые снимки, встраиваемые в документ под своими префиксами (--embed)
+    embeds: Vec<(PathBuf, PathBuf)>,
+    // Сколько элементов директории показывать в дереве (--max-entries-per-dir)
+    max_entries_per_dir: Option<usize>,
+    // Число потоков обхода директорий (--threads)
+    threads: usize,
+    // Выборки --sample-dir по директориям: выбранные файлы и общее число кандидатов
+    sample_cache: Mutex<HashMap<PathBuf, (Vec<PathBuf>, usize)>>,
+    update_prefix: Option<PathBuf>,
+    // Элементы, которые не удалось прочитать при обходе, с описанием ошибки
+    walk_errors: Mutex<BTreeMap<PathBuf, String>>,
+    // Замечания для сводки в конце запуска, ее формат (--report) и --fail-on-warning
+    notices: Mutex<Vec<notices::Notice>>,
+    // Причина и правило исключения каждого пропущенного элемента для той же сводки
+    skipped: Mutex<HashMap<PathBuf, (&'static str, String)>>,
+    // Размеры файлов, исключенных правилами, для отчета --top-files; заполняется при обходе
+    excluded_sizes: Mutex<BTreeMap<PathBuf, u64>>,
+    report_json: bool,
+    fail_on_warning: bool,
+    root_device: Option<u64>,
+    // Обходить junction и ссылки на директории в Windows (--follow-reparse-points)
+    follow_reparse_points: bool,
+    // Глубина, начиная с которой директории сворачиваются (--max-path-depth), и владелец
+    // файлов, которые остаются в дереве (--owner)
+    max_path_depth: Option<usize>,
+    owner: Option<u32>,
+    // Порог --skip-dir-over (приоритетнее skip_dir_over из конфигурации) и директории,
+    // уже подсчитанные целиком и оказавшиеся меньше порога
+    skip_dir_over: Option<u64>,
+    small_dirs: Mutex<HashSet<PathBuf>>,
+    // Пустые директории (--mark-empty, --prune-empty), заполняется при обходе
+    mark_empty: bool,
+    prune_empty: bool,
+    empty_dirs: Mutex<HashSet<PathBuf>>,
+    count_skipped: Option<usize>,
+    // Оценка токенов у файлов и директорий в дереве (--tree-tokens), считается после обхода
+    tree_tokens: bool,
+    token_counts: Mutex<HashMap<PathBuf, usize>>,
+    // Пометка свернутых директорий и пропущенных файлов (--skip-marker); с --show-skipped-files
+    // пропущенные файлы остаются в дереве с этой пометкой
+    skip_marker: String,
+    show_skipped_files: bool,
+    title: Option<String>,
+    header: Option<String>,
+    // Оформление документа html (--theme, --css); с любым из них код подсвечивается
+    theme: Option<html_theme::Theme>,
+    custom_css: Option<String>,
+    // Формат документа внутри архива bundle (--bundle-format), по умолчанию markdown
+    bundle_format: Option<String>,
+    heading_offset: usize,
+    nest_headings: bool,
+    // Транслитерация не-ASCII путей в заголовках и якорях (--ascii-paths)
+    ascii_paths: bool,
+    anchors: bool,
+    // Карта якорей для внешних ссылок (--anchor-map) и ее путь при обходе, чтобы не попасть в снимок
+    anchor_map: Option<PathBuf>,
+    anchor_map_path: Option<PathBuf>,
+    // Ссылки «к дереву», на предыдущий и следующий файл у каждого раздела файла
+    nav_links: bool,
+    link_base: Option<String>,
+    git_context: Option<git::Context>,
+    slugs: Mutex<anchors::SlugMap>,
+    // Строки документа на выбранном языке (--doc-lang)
+    doc: &'static doc_lang::Strings,
+    // Источник файлов проекта: локальный диск или архив в памяти (--stdin-tar)
+    fs: Box<dyn vfs::FileSystem>,
+    // Подпись источника в заголовке вместо пути директории: архив из stdin или ревизия --at
+    source: Option<String>,
+    format: OutputFormat,
+}
+
+#[derive(Debug, PartialEq)]
+enum ContentOrder {
+    Path,
+    Dependencies,
+}
+
+#[derive(Debug, PartialEq)]
+enum OutputFormat {
+    Markdown,
+    Obsidian,
+    Bundle,
+    Index,
+    MdDir,
+    Jsonl,
+    // html, json и сторонние рендереры (render.rs) по имени
+    Renderer(String),
+}
+
+#[derive(PartialEq)]
+enum SkipReason {
+    NoSkip,
+    Skip,
+    SkipWithEllipsis,
+}
+
+pub use tree::{FileInfo, NodeKind, TreeNode};
+
+// Точка входа CLI. Библиотека используется и из src/main.rs, и через C ABI (ffi.rs)
+pub fn run() -> std::io::Result<()> {
+    run_with_renderers(Vec::new())
+}
+
+// Точка входа для бинарных файлов со своими форматами вывода (render::Renderer):
+// они доступны через --format наравне со встроенными
+pub fn run_with_renderers(renderers: Vec<Box<dyn render::Renderer>>) -> std::io::Result<()> {
+    let mut renderers = renderers;
+    renderers.extend(render::builtin());
+    let matches = cli().get_matches();
+
+    logging::init(match matches.get_one::<String>("log-format").map(String::as_str) {
+        Some("json") => logging::LogFormat::Json,
+        _ => logging::LogFormat::Pretty,
+    });
+
+    if let Some(("unpack", unpack_matches)) = matches.subcommand() {
+        let snapshot = unpack_matches.get_one::<String>("snapshot").unwrap();
+        let out_dir = unpack_matches.get_one::<String>("directory").unwrap();
+        
+        let count = unpack::unpack(Path::new(snapshot), Path::new(out_dir))?;
+        tracing::info!("Восстановлено файлов: {} в директории {}", count, out_dir);
+        return Ok(());
+    }
+
+    // Параметры снимков задаются в манифесте для каждого проекта
+    if let Some(("batch", batch_matches)) = matches.subcommand() {
+        return batch::run(Path::new(batch_matches.get_one::<String>("manifest").unwrap()));
+    }
+
+    // Схема печатается как есть, без обхода директории
+    if let Some(schema) = matches.get_one::<String>("print-schema").and_then(|name| schema::find(name)) {
+        print!("{}", schema.text);
+        return Ok(());
+    }
+
+    let app_config = app_config_from_matches(&matches)?;
+    let renderer = render::find(&renderers, match &app_config.format {
+        OutputFormat::Renderer(name) => name,
+        OutputFormat::Bundle => app_config.bundle_format.as_deref().unwrap_or("markdown"),
+        _ => "markdown",
+    })?;
+    let bench_matches = matches.subcommand_matches("bench");
+    let daemon_matches = matches.subcommand_matches("daemon");
+
+    output::warn_ignored_options(&matches, &app_config);
+    
+    let anchor_map = output::anchor_map(&matches, &app_config);
+    if let Some(map) = anchor_map {
+        app_config.slugs.lock().unwrap().reuse(anchors::load_map(map));
+    }
+    
+    if matches.get_flag("stdin-tar") {
+        return archive::run_stdin_tar(app_config);
+    }
+    
+    if let Some(reference) = matches.get_one::<String>("at") {
+        if bench_matches.is_some() || daemon_matches.is_some() {
+            tracing::warn!("--at не применяется к подкомандам bench и daemon");
+        } else {
+            return archive::run_git_revision(app_config, reference);
+        }
+    }
+    
+    if !Path::new(&app_config.target_dir).exists() || !Path::new(&app_config.target_dir).is_dir() {
+        tracing::error!("'{}' не является существующей директорией", app_config.target_dir);
+        std::process::exit(1);
+    }
+
+    let mut config = load_config(Path::new(&app_config.target_dir));
+    if !matches.get_flag("no-auto-exclude") {
+        project::apply_auto_exclusions(Path::new(&app_config.target_dir), &mut config);
+    }
+    if !config.summarizers.is_empty() && !app_config.run_summarizers {
+        tracing::warn!("команды [[summarizers]] из конфигурации не запускаются без --run-summarizers");
+    }
+    
+    let gitignore_matcher = create_ignore_matcher(&app_config)?;
+    warn_if_output_excluded(&app_config, &config, &gitignore_matcher);
+    
+    let base_dir = Path::new(&app_config.target_dir);
+    
+    if bench_matches.is_some() {
+        return bench::run(base_dir, &app_config, &config, &gitignore_matcher);
+    }
+    
+    if let Some(diff_matches) = config_diff_matches(&matches) {
+        let changes: Vec<String> = diff_matches.get_many::<String>("set").unwrap().cloned().collect();
+        let mut stdout = io::stdout().lock();
+        let auto_exclude = !matches.get_flag("no-auto-exclude");
+        return config_diff::run(base_dir, &mut stdout, &changes, auto_exclude, &app_config, &gitignore_matcher);
+    }
+    
+    if let Some(daemon_matches) = matches.subcommand_matches("daemon") {
+        let socket = PathBuf::from(daemon_matches.get_one::<String>("socket").unwrap());
+        let base_dir = base_dir.to_path_buf();
+        return daemon::serve(&socket, &base_dir, app_config, !matches.get_flag("no-auto-exclude"), &renderers);
+    }
+    
+    if matches.get_flag("stdio-api") {
+        let base_dir = base_dir.to_path_buf();
+        return daemon::serve_stdio(&base_dir, app_config, !matches.get_flag("no-auto-exclude"), &renderers);
+    }
+    
+    interrupt::install();
+    
+    output::write(renderer, base_dir, &app_config, &config, &gitignore_matcher)?;
+    
+    if let Some(map) = anchor_map
+        && !interrupt::is_interrupted()
+    {
+        let document = (!app_config.print_to_console)
+            .then(|| app_config.output_file.as_ref().map_or_else(|| base_dir.join("tree.md"), PathBuf::from));
+        anchors::write_map(map, document.as_deref(), &app_config.slugs.lock().unwrap())?;
+        tracing::info!("Карта якорей сохранена в файл: {}", map.display());
+    }
+    
+    if interrupt::is_interrupted() {
+        notices::finish(&app_config)?;
+        match interrupt::stopped_at() {
+            Some(path) => tracing::error!("генерация прервана на {}, документ неполный", path.display()),
+            None => tracing::error!("генерация прервана, документ неполный"),
+        }
+        std::process::exit(interrupt::EXIT_CODE);
+    }
+    
+    tracing::info!("Проанализирована директория: {}", app_config.target_dir);
+    notices::finish(&app_config)
+}
+
+fn config_diff_matches(matches: &ArgMatches) -> Option<&ArgMatches> {
+    matches.subcommand_matches("config")?.subcommand_matches("diff")
+}
+
+// Выходной файл исключается из дерева по пути, а не только по имени: `-o build/../tree.md`,
+// абсолютный путь и ссылка на директорию приводятся к пути от целевой директории.
+// Самого файла может еще не быть, поэтому канонизируется его директория
+fn output_path_in_target(target_dir: &Path, output: &Path) -> Option<PathBuf> {
+    let target = fs::canonicalize(target_dir).ok()?;
+    let parent = match output.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let output = fs::canonicalize(parent).ok()?.join(output.file_name()?);
+    let relative = output.strip_prefix(&target).ok()?;
+    Some(target_dir.join(relative))
+}
+
+// Среди позиционных аргументов может быть одна директория, остальные - файлы
+// (`proj2tree src/ ../ops/deploy.sh Cargo.toml`). Без директории анализируется текущая
+fn split_positional_paths<'a>(paths: impl Iterator<Item = &'a String>) -> io::Result<(String, Vec<PathBuf>)> {
+    let mut target_dir = None;
+    let mut files = Vec::new();
+    
+    for path in paths {
+        if Path::new(path).is_file() {
+            files.push(PathBuf::from(path));
+        } else if let Some(previous) = target_dir.replace(path.clone()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("можно указать только одну директорию, а указаны '{}' и '{}'", previous, path),
+            ));
+        }
+    }
+    
+    Ok((target_dir.unwrap_or_else(|| ".".to_string()), files))
+}
+
+fn app_config_from_matches(matches: &ArgMatches) -> std::io::Result<AppConfig> {
+    // Второе значение --format задает место назначения и имеет приоритет над -o
+    let mut format_values = matches.get_many::<String>("format").into_iter().flatten();
+    let format = match format_values.next().map(String::as_str) {
+        None | Some("markdown") => OutputFormat::Markdown,
+        Some("obsidian") => OutputFormat::Obsidian,
+        Some("bundle") => OutputFormat::Bundle,
+        Some("index") => OutputFormat::Index,
+        Some("md-dir") => OutputFormat::MdDir,
+        Some("jsonl") => OutputFormat::Jsonl,
+        // Наличие рендерера с таким именем проверяется при выводе
+        Some(other) => OutputFormat::Renderer(other.to_string()),
+    };
+    // Остальные параметры (исключения, фильтры) для bench задаются до имени подкоманды
+    let (target_dir, explicit_files) = split_positional_paths(
+        matches
+            .subcommand_matches("bench")
+            .or_else(|| matches.subcommand_matches("daemon"))
+            .or_else(|| config_diff_matches(matches))
+            .unwrap_or(matches)
+            .get_many::<String>("directory")
+            .unwrap(),
+    )?;
+    let output_file = format_values
+        .next()
+        .or_else(|| matches.get_one::<String>("output"))
+        .map(|s| s.to_string())
+        .or_else(|| {
+            // Архив, индекс и поток событий по умолчанию кладутся рядом с tree.md и должны исключаться так же, как он
+            let default_name = match &format {
+                OutputFormat::Bundle => "tree.zip".to_string(),
+                OutputFormat::Index => "tree.index.json".to_string(),
+                OutputFormat::Jsonl => "tree.jsonl".to_string(),
+                OutputFormat::Renderer(name) => format!("tree.{}", name),
+                _ => return None,
+            };
+            Some(Path::new(&target_dir).join(default_name).to_string_lossy().to_string())
+        });
+
+    let output_path = if matches.get_flag("print") {
+        None
+    } else {
+        let output = output_file.clone().unwrap_or_else(|| Path::new(&target_dir).join("tree.md").to_string_lossy().to_string());
+        output_path_in_target(Path::new(&target_dir), Path::new(&output))
+    };
+    let anchor_map_path = matches
+        .get_one::<String>("anchor-map")
+        .and_then(|map| output_path_in_target(Path::new(&target_dir), Path::new(map)));
+    let docker_context = matches
+        .get_one::<String>("docker-context")
+        .map(|dockerfile| docker::load(Path::new(&target_dir), Path::new(dockerfile)))
+        .transpose()?;
+    // Контекст сборки определяет только .dockerignore, как и для .gitignore
+    let gitattributes = (!matches.get_flag("no-gitattributes") && docker_context.is_none())
+        .then(|| gitattributes::load(Path::new(&target_dir), &|path| fs::read_to_string(path).ok()));
+
+    // Файл вступления читается заранее, чтобы ошибка не оборвала документ на середине
+    let header = match matches.get_one::<String>("header-file") {
+        Some(file) => Some(fs::read_to_string(file).map_err(|e| {
+            io::Error::new(e.kind(), format!("не удалось прочитать {}: {}", file, e))
+        })?),
+        None => None,
+    };
+    let custom_css = match matches.get_one::<String>("css") {
+        Some(file) => Some(fs::read_to_string(file).map_err(|e| {
+            io::Error::new(e.kind(), format!("не удалось прочитать {}: {}", file, e))
+        })?),
+        None => None,
+    };
+    
+    let root_device = if matches.get_flag("one-file-system") {
+        let device = device::device_id(Path::new(&target_dir));
+        if device.is_none() {
+            tracing::warn!("--one-file-system не поддерживается на этой платформе");
+        }
+        device
+    } else {
+        None
+    };
+    
+    let owner = match matches.get_one::<String>("owner") {
+        Some(user) => {
+            let uid = device::user_id(user).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, format!("неизвестный пользователь '{}' в --owner", user))
+            })?;
+            if device::owner_id(Path::new(&target_dir)).is_none() {
+                tracing::warn!("--owner не поддерживается на этой платформе");
+            }
+            Some(uid)
+        }
+        None => None,
+    };
+    
+    let link_base = matches
+        .get_one::<String>("link-base")
+        .map(|base| anchors::resolve_link_base(base, Path::new(&target_dir)));
+    
+    let path_prefix = path_prefix(Path::new(&target_dir), matches.get_one::<String>("paths").unwrap());
+    
+    let git_context = if matches.get_flag("git-context") {
+        let context = git::context(Path::new(&target_dir));
+        if context.is_none() {
+            tracing::warn!("'{}' не находится в git-репозитории, --git-context пропущен", target_dir);
+        }
+        context
+    } else {
+        None
+    };
+    
+    Ok(AppConfig {
+        target_dir,
+        output_file,
+        output_path,
+        include_tree: !matches.get_flag("no-tree"),
+        include_contents: !matches.get_flag("no-contents"),
+        print_to_console: matches.get_flag("print"),
+        use_pager: !matches.get_flag("no-pager"),
+        // docker build не читает .gitignore
+        no_gitignore: matches.get_flag("no-gitignore") || docker_context.is_some(),
+        ignore_files: matches
+            .get_many::<String>("ignore-file")
+            .map(|files| files.map(PathBuf::from).collect())
+            .unwrap_or_default(),
+        explicit_files,
+        case_insensitive: matches.get_flag("case-insensitive") || cfg!(any(windows, target_os = "macos")),
+        flat_tree: matches.get_flag("flat"),
+        compact_tree: matches.get_flag("compact-tree"),
+        split_tests: matches.get_flag("split-tests"),
+        build_section: matches.get_flag("build-section"),
+        quick: matches.get_flag("quick"),
+        quick_selection: Mutex::new(None),
+        run_summarizers: matches.get_flag("run-summarizers"),
+        unsafe_full_secrets: matches.get_flag("unsafe-full-secrets"),
+        full_lockfiles: matches.get_flag("full-lockfiles"),
+        lfs_fetch: matches.get_flag("lfs-fetch"),
+        normalize_eol: whitespace::Eol::parse(matches.get_one::<String>("normalize-eol").unwrap()),
+        expand_tabs: matches.get_one::<usize>("expand-tabs").copied(),
+        dedent: matches.get_flag("dedent"),
+        max_width: matches.get_one::<u64>("max-width").map(|&width| width as usize),
+        fence_info: matches.get_one::<String>("fence-info").cloned(),
+        min_fence: matches.get_one::<u64>("min-fence").map_or(3, |&length| length as usize),
+        chunk: matches.get_one::<usize>("chunk").copied(),
+        lossy_read: matches.get_flag("lossy-read"),
+        check_syntax: matches.get_flag("check-syntax"),
+        merge_check: !matches.get_flag("no-merge-check"),
+        path_prefix,
+        content_order: match matches.get_one::<String>("order").map(String::as_str) {
+            Some("deps") => ContentOrder::Dependencies,
+            _ => ContentOrder::Path,
+        },
+        top_files: matches.get_one::<usize>("top-files").copied(),
+        density: matches.get_flag("density"),
+        health: matches
+            .get_flag("health")
+            .then(|| matches.get_one::<u64>("health-large-size").copied().unwrap_or(1 << 20)),
+        todos: matches.get_flag("todos"),
+        todos_csv: matches.get_one::<String>("todos-csv").cloned(),
+        deps: matches.get_flag("deps"),
+        docker_context,
+        gitattributes,
+        import_graph: match matches.get_one::<String>("import-graph").map(String::as_str) {
+            Some("mermaid") => Some(import_graph::GraphFormat::Mermaid),
+            Some(_) => Some(import_graph::GraphFormat::List),
+            None => None,
+        },
+        footer: matches.get_flag("footer"),
+        sidecar: matches.get_flag("sidecar"),
+        walk_time: Mutex::new(None),
+        only_extensions: matches.get_many::<String>("only-ext").map(|values| {
+            values
+                .map(|ext| ext.trim().trim_start_matches('.').to_lowercase())
+                .filter(|ext| !ext.is_empty())
+                .collect()
+        }),
+        full_tree: matches.get_flag("full-tree"),
+        filter: matches
+            .get_one::<String>("filter")
+            .filter(|query| !query.trim().is_empty())
+            .cloned(),
+        update_prefix: matches.get_one::<String>("update").map(|prefix| {
+            Path::new(prefix.trim_start_matches("./")).components().collect()
+        }),
+        walk_errors: Mutex::new(BTreeMap::new()),
+        notices: Mutex::new(Vec::new()),
+        skipped: Mutex::new(HashMap::new()),
+        excluded_sizes: Mutex::new(BTreeMap::new()),
+        report_json: matches.get_one::<String>("report").is_some_and(|format| format == "json"),
+        fail_on_warning: matches.get_flag("fail-on-warning"),
+        root_device,
+        follow_reparse_points: matches.get_flag("follow-reparse-points"),
+        max_path_depth: matches
+            .get_one::<u64>("max-path-depth")
+            .map(|&depth| depth as usize)
+            .or(matches.get_flag("quick").then_some(quick::DEPTH)),
+        owner,
+        skip_dir_over: matches.get_one::<u64>("skip-dir-over").copied(),
+        small_dirs: Mutex::new(HashSet::new()),
+        mark_empty: matches.get_flag("mark-empty"),
+        prune_empty: matches.get_flag("prune-empty"),
+        empty_dirs: Mutex::new(HashSet::new()),
+        tree_tokens: matches.get_flag("tree-tokens"),
+        token_counts: Mutex::new(HashMap::new()),
+        count_skipped: matches
+            .get_flag("count-skipped")
+            .then(|| matches.get_one::<u64>("count-limit").map_or(10000, |&limit| limit as usize)),
+        skip_marker: matches.get_one::<String>("skip-marker").cloned().unwrap_or_default(),
+        show_skipped_files: matches.get_flag("show-skipped-files"),
+        title: matches.get_one::<String>("title").cloned(),
+        header,
+        theme: matches.get_one::<String>("theme").and_then(|name| html_theme::Theme::parse(name)),
+        custom_css,
+        bundle_format: matches.get_one::<String>("bundle-format").cloned(),
+        max_entries_per_dir: matches.get_one::<u64>("max-entries-per-dir").map(|&limit| limit as usize),
+        embeds: matches
+            .get_many::<(PathBuf, PathBuf)>("embed")
+            .map(|specs| specs.cloned().collect())
+            .unwrap_or_default(),
+        sample_dirs: matches
+            .get_many::<(String, usize)>("sample-dir")
+            .map(|specs| specs.cloned().collect())
+            .unwrap_or_default(),
+        threads: matches.get_one::<usize>("threads").copied().unwrap_or_else(default_threads),
+        sample_cache: Mutex::new(HashMap::new()),
+        heading_offset: matches.get_one::<usize>("heading-offset").copied().unwrap_or(0),
+        nest_headings: matches.get_flag("nest-headings"),
+        ascii_paths: matches.get_flag("ascii-paths"),
+        anchors: matches.get_flag("anchors")
+            || link_base.is_some()
+            || matches.get_flag("nav-links")
+            || matches.contains_id("anchor-map"),
+        anchor_map_path,
+        anchor_map: matches.get_one::<String>("anchor-map").map(PathBuf::from),
+        nav_links: matches.get_flag("nav-links"),
+        link_base,
+        git_context,
+        slugs: Mutex::new(anchors::SlugMap::new(matches.get_flag("ascii-paths"))),
+        doc: doc_lang::strings(matches.get_one::<String>("doc-lang").unwrap()),
+        fs: Box::new(vfs::RealFs),
+        source: None,
+        format,
+    })
+}
+
+fn default_threads() -> usize {
+    std::thread::available_parallelism().map_or(1, |threads| threads.get())
+}
+
+// Правила корневого .gitignore и файлов --ignore-file. Шаблоны из --ignore-file
+// отсчитываются от целевой директории, где бы ни лежал сам файл
+fn create_ignore_matcher(app_config: &AppConfig) -> std::io::Result<Option<Gitignore>> {
+    let dir_path = Path::new(&app_config.target_dir);
+    let mut builder = GitignoreBuilder::new(ignore_root(dir_path));
+    builder.case_insensitive(app_config.case_insensitive).map_err(|e| io::Error::other(e.to_string()))?;
+    let mut has_rules = false;
+    
+    if app_config.no_gitignore {
+        tracing::info!("Игнорирование .gitignore отключено");
+    } else if dir_path.join(".gitignore").exists() {
+        builder.add(dir_path.join(".gitignore"));
+        has_rules = true;
+        tracing::info!("Учтены правила из .gitignore");
+    } else {
+        notices::add(app_config, notices::Kind::NoGitignore, None, "файл .gitignore не найден");
+    }
+    
+    for file in &app_config.ignore_files {
+        if !file.is_file() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("файл правил {} не найден", file.display()),
+            ));
+        }
+        if let Some(e) = builder.add(file) {
+            tracing::warn!("{}: {}", file.display(), e);
+        }
+        has_rules = true;
+        tracing::info!("Учтены правила из {}", file.display());
+    }
+    
+    if !has_rules {
+        return Ok(None);
+    }
+    builder.build().map(Some).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+// Корень матчера без `.` и лишних `/`: Gitignore отрезает его от путей побайтно и сам
+// снимает только ведущий `./`, поэтому из `.//` получался бы корень `/`
+fn ignore_root(dir: &Path) -> PathBuf {
+    let root = components_without_cur_dir(dir);
+    if root.as_os_str().is_empty() { PathBuf::from(".") } else { root }
+}
+
+fn components_without_cur_dir(path: &Path) -> PathBuf {
+    path.components().filter(|component| *component != Component::CurDir).collect()
+}
+
+// Путь сравнивается с правилами относительно корня матчера. Относительный путь вычисляется
+// по компонентам и присоединяется к корню в его собственной записи: путь, записанный иначе
+// (`./p/x` или `p//x` при корне `p`), не сравнивается целиком, а `srcgen/x` при корне `src` -
+// как `gen/x`. Пути вне корня правилам не подлежат
+fn is_ignored_by(matcher: &Gitignore, path: &Path, is_dir: bool) -> bool {
+    ignore_match(matcher, path, is_dir).is_ignore()
+}
+
+fn ignore_match<'m>(matcher: &'m Gitignore, path: &Path, is_dir: bool) -> ignore::Match<&'m ignore::gitignore::Glob> {
+    match components_without_cur_dir(path).strip_prefix(components_without_cur_dir(matcher.path())) {
+        Ok(relative) if !relative.as_os_str().is_empty() => matcher.matched(matcher.path().join(relative), is_dir),
+        _ => ignore::Match::None,
+    }
+}
+
+fn create_gitignore_matcher(dir: &str, case_insensitive: bool) -> Result<Gitignore, Box<dyn std::error::Error>> {
+    let dir_path = Path::new(dir);
+    
+    let mut builder = GitignoreBuilder::new(ignore_root(dir_path));
+    builder.case_insensitive(case_insensitive)?;
+    
+    let gitignore_path = dir_path.join(".gitignore");
+    if gitignore_path.exists() {
+        builder.add(&gitignore_path);
+        Ok(builder.build()?)
+    } else {
+        Err("Файл .gitignore не найден".into())
+    }
+}
+
+fn load_config(target_dir: &Path) -> Config {
+    load_config_layers(load_project_config_values(target_dir, &|path| fs::read_to_string(path).ok()))
+}
+
+fn load_config_layers(project_values: Vec<(PathBuf, toml::Value)>) -> Config {
+    // Слои применяются по возрастанию приоритета: встроенная конфигурация, файлы проекта,
+    // переменные окружения и, наконец, флаги командной строки
+    let explicit_sampling = sampling::explicit_patterns(&project_values);
+    let config_value = merge_config_values(load_builtin_config_value(), project_values);
+    
+    let mut config = match config_value.try_into() {
+        Ok(config) => config,
+        Err(e) => {
+            tracing::error!("не удалось разобрать встроенную конфигурацию: {}", e);
+            Config::default()
+        }
+    };
+    
+    config.explicit_sampling = explicit_sampling;
+    apply_env_overrides(&mut config);
+    tree_only::compile(&mut config);
+    dotfiles::compile(&mut config);
+    sampling::compile(&mut config);
+    summarize::compile(&mut config);
+    config
+}
+
+fn load_builtin_config_value() -> Option<toml::Value> {
+    let cargo_toml_content = include_str!("../Cargo.toml");
+    
+    match toml::from_str::<toml::Value>(cargo_toml_content) {
+        Ok(cargo_toml) => cargo_toml
+            .get("package")
+            .and_then(|p| p.get("metadata"))
+            .and_then(|m| m.get("proj2tree"))
+            .cloned(),
+        Err(e) => {
+            tracing::error!("не удалось разобрать встроенный Cargo.toml: {}", e);
+            None
+        }
+    }
+}
+
+fn load_project_config_values(
+    target_dir: &Path,
+    read_file: &dyn Fn(&Path) -> Option<String>,
+) -> Vec<(PathBuf, toml::Value)> {
+    let mut values = Vec::new();
+    
+    let cargo_toml_path = target_dir.join("Cargo.toml");
+    if let Some(cargo_toml) = read_toml_file(&cargo_toml_path, read_file)
+        && let Some(proj2tree_config) = cargo_toml
+            .get("package")
+            .and_then(|p| p.get("metadata"))
+            .and_then(|m| m.get("proj2tree"))
+    {
+        values.push((cargo_toml_path, proj2tree_config.clone()));
+    }
+    
+    let config_path = target_dir.join(".proj2tree.toml");
+    if let Some(config_value) = read_toml_file(&config_path, read_file) {
+        values.push((config_path, config_value));
+    }
+    
+    values
+}
+
+fn read_toml_file(path: &Path, read_file: &dyn Fn(&Path) -> Option<String>) -> Option<toml::Value> {
+    let content = read_file(path)?;
+    match toml::from_str(&content) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            tracing::warn!("не удалось разобрать {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+// Встроенная конфигурация и файлы проекта одним значением, до переменных окружения
+fn merge_config_values(builtin: Option<toml::Value>, project_values: Vec<(PathBuf, toml::Value)>) -> toml::Value {
+    let mut config_value = builtin.unwrap_or_else(|| {
+        tracing::info!("Встроенная конфигурация не найдена, используются пустые исключения");
+        toml::Value::Table(toml::Table::new())
+    });
+    
+    for (source, project_value) in project_values {
+        if let Err(e) = project_value.clone().try_into::<Config>() {
+            tracing::warn!("конфигурация {} пропущена: {}", source.display(), e);
+            continue;
+        }
+        merge_toml_values(&mut config_value, project_value);
+        tracing::info!("Учтена конфигурация из {}", source.display());
+    }
+    
+    config_value
+}
+
+// Таблицы объединяются рекурсивно, остальные значения (включая списки) заменяются целиком
+fn merge_toml_values(base: &mut toml::Value, overlay: toml::Value) {
+    match (base, overlay) {
+        (toml::Value::Table(base_table), toml::Value::Table(overlay_table)) => {
+            for (key, value) in overlay_table {
+                match base_table.get_mut(&key) {
+                    Some(existing) => merge_toml_values(existing, value),
+                    None => {
+                        base_table.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+fn apply_env_overrides(config: &mut Config) {
+    if let Ok(value) = env::var("PROJ2TREE_MAX_FILE_SIZE") {
+        match value.trim().parse() {
+            Ok(size) => config.max_file_size = Some(size),
+            Err(_) => tracing::warn!("некорректное значение PROJ2TREE_MAX_FILE_SIZE: '{}'", value),
+        }
+    }
+    
+    // Шаблоны из окружения дополняют конфигурацию, а не заменяют ее
+    if let Ok(value) = env::var("PROJ2TREE_EXCLUDE") {
+        for pattern in split_env_list(&value) {
+            config.exclude_dirs.push(pattern.clone());
+            config.exclude_files.push(pattern);
+        }
+    }
+    
+    if let Ok(value) = env::var("PROJ2TREE_EXCLUDE_EXTENSIONS") {
+        config.exclude_extensions.extend(split_env_list(&value));
+    }
+}
+
+fn split_env_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn write_markdown_format<W: Write>(
+    base_dir: &Path,
+    writer: &mut W, 
+    app_config: &AppConfig, 
+    config: &Config,
+    gitignore_matcher: &Option<Gitignore>,
+) -> std::io::Result<()> {
+    let nodes = build_model(base_dir, app_config, config, gitignore_matcher)?;
+    write_markdown_document(base_dir, &nodes, writer, app_config, config)
+}
+
+// Документ в формате, выбранном --format, через зарегистрированный рендерер
+fn render_document<W: Write>(
+    renderer: &dyn render::Renderer,
+    base_dir: &Path,
+    mut writer: &mut W,
+    app_config: &AppConfig,
+    config: &Config,
+    gitignore_matcher: &Option<Gitignore>,
+) -> std::io::Result<Vec<tree::TreeNode>> {
+    let nodes = build_model(base_dir, app_config, config, gitignore_matcher)?;
+    let snapshot = render::Snapshot::new(base_dir, &nodes, app_config, config);
+    renderer.render(&snapshot, &mut writer)?;
+    Ok(nodes)
+}
+
+// Проект обходится один раз: дерево, содержимое, --health, --todos и --deps берут файлы
+// и их размеры из одной модели, и каждый файл читается с диска только при выводе
+fn build_model(
+    base_dir: &Path,
+    app_config: &AppConfig,
+    config: &Config,
+    gitignore_matcher: &Option<Gitignore>,
+) -> std::io::Result<Vec<tree::TreeNode>> {
+    let needs_model = app_config.include_tree
+        || app_config.include_contents
+        || app_config.top_files.is_some()
+        || app_config.health.is_some()
+        || app_config.todos
+        || app_config.deps;
+    let started = Instant::now();
+    let nodes = if needs_model {
+        tree::build_tree(base_dir, app_config, config, gitignore_matcher)?
+    } else {
+        Vec::new()
+    };
+    *app_config.walk_time.lock().unwrap() = Some(started.elapsed());
+    
+    if app_config.quick {
+        let candidates = candidate_entries(&nodes, app_config, config);
+        *app_config.quick_selection.lock().unwrap() = Some(quick::select(base_dir, &candidates));
+    }
+    
+    if app_config.tree_tokens && app_config.include_tree {
+        *app_config.token_counts.lock().unwrap() = tokens::count(&nodes, app_config, config);
+    }
+    Ok(nodes)
+}
+
+fn write_markdown_document<W: Write>(
+    base_dir: &Path,
+    nodes: &[tree::TreeNode],
+    writer: &mut W, 
+    app_config: &AppConfig, 
+    config: &Config,
+) -> std::io::Result<()> {
+    if !app_config.footer {
+        return write_markdown_body(base_dir, nodes, writer, app_config, config);
+    }
+    
+    let started = Instant::now();
+    let mut hashing = footer::HashingWriter::new(writer);
+    write_markdown_body(base_dir, nodes, &mut hashing, app_config, config)?;
+    let hash = hashing.finish();
+    footer::write(writer, &hash, started.elapsed(), app_config)
+}
+
+fn write_markdown_body<W: Write>(
+    base_dir: &Path,
+    nodes: &[tree::TreeNode],
+    writer: &mut W, 
+    app_config: &AppConfig, 
+    config: &Config,
+) -> std::io::Result<()> {
+    write_document_header(writer, &source_name(base_dir, app_config), app_config)?;
+    
+    let embeds = embed::load(&app_config.embeds)?;
+    
+    if app_config.include_tree || app_config.include_contents {
+        if app_config.merge_check {
+            merge_artifacts::write_warnings(base_dir, nodes, writer, app_config, config)?;
+        }
+        
+        if app_config.anchors {
+            let mut slugs = app_config.slugs.lock().unwrap();
+            if app_config.nav_links {
+                slugs.reserve(anchors::TREE_ID);
+            }
+            slugs.assign_tree(base_dir, nodes);
+        }
+        
+        let (source, tests) = if app_config.split_tests {
+            tree::split_tests(base_dir, nodes)
+        } else {
+            (Vec::new(), Vec::new())
+        };
+        let split = !tests.is_empty();
+        
+        if app_config.include_tree {
+            write_tree_heading(writer, app_config)?;
+            if split {
+                let source = embed::splice_tree(base_dir, &source, &embeds);
+                writeln!(writer, "{} {}\n", heading(app_config, 3), app_config.doc.source_code)?;
+                write_tree_block(base_dir, base_dir, &source, writer, app_config, config)?;
+                writeln!(writer, "{} {}\n", heading(app_config, 3), app_config.doc.test_code)?;
+                write_tree_block(base_dir, base_dir, &tests, writer, app_config, config)?;
+            } else {
+                let nodes = embed::splice_tree(base_dir, nodes, &embeds);
+                write_tree_block(base_dir, base_dir, &nodes, writer, app_config, config)?;
+            }
+        }
+        
+        if app_config.include_contents {
+            // Файлы сборки уходят из основного содержимого в свой раздел, в дереве остаются
+            let main = if split { &source[..] } else { nodes };
+            let (rest, build) = if app_config.build_section {
+                tree::split(base_dir, main, &build_files::is_build_path)
+            } else {
+                (Vec::new(), Vec::new())
+            };
+            let main = if app_config.build_section { &rest } else { main };
+            
+            writeln!(writer, "{} {}\n", heading(app_config, 2), app_config.doc.file_contents)?;
+            if let Some(selection) = app_config.quick_selection.lock().unwrap().as_ref() {
+                let total = candidate_entries(nodes, app_config, config).len();
+                writeln!(writer, "{}\n", doc_lang::fill(app_config.doc.quick_note, &[&selection.len(), &total]))?;
+            }
+            print_file_contents(base_dir, main, writer, app_config, config)?;
+            print_explicit_files(base_dir, nodes, writer, app_config, config)?;
+            embed::write_sections(base_dir, nodes, &embeds, writer, app_config, config)?;
+            if split {
+                writeln!(writer, "\n{} {}\n", heading(app_config, 2), app_config.doc.test_contents)?;
+                print_file_contents(base_dir, &tests, writer, app_config, config)?;
+            }
+            if !build.is_empty() {
+                writeln!(writer, "\n{} {}\n", heading(app_config, 2), app_config.doc.build_and_ci)?;
+                print_file_contents(base_dir, &build, writer, app_config, config)?;
+            }
+        }
+    }
+    
+    // Отчеты после прерывания не строятся: пометка закрывает документ
+    if interrupt::is_interrupted() {
+        return write_interrupted_note(writer, base_dir, app_config);
+    }
+    
+    for external in &config.include_external {
+        write_external_section(base_dir, external, writer, app_config, config)?;
+    }
+    
+    if let Some(limit) = app_config.top_files {
+        report::write_size_report(base_dir, nodes, writer, limit, app_config, config)?;
+    }
+    
+    if let Some(large_size) = app_config.health {
+        health::write_health_report(base_dir, nodes, writer, large_size, app_config, config)?;
+    }
+    
+    if app_config.todos {
+        todos::write_todos_section(base_dir, nodes, writer, app_config, config)?;
+    }
+    
+    if app_config.deps {
+        manifests::write_dependencies_section(base_dir, nodes, writer, app_config)?;
+    }
+    
+    if let Some(format) = app_config.import_graph {
+        import_graph::write_import_graph_section(base_dir, nodes, writer, format, app_config, config)?;
+    }
+    
+    if let Some(docker_context) = &app_config.docker_context {
+        docker::write_section(base_dir, nodes, writer, docker_context, app_config)?;
+    }
+    
+    walk_errors::write_summary(base_dir, writer, app_config)?;
+    
+    Ok(())
+}
+
+fn write_interrupted_note<W: Write>(writer: &mut W, base_dir: &Path, app_config: &AppConfig) -> std::io::Result<()> {
+    match interrupt::stopped_at() {
+        Some(path) => {
+            let path = escape::code_span(&escape::display_path(&relative_path(base_dir, &path)));
+            writeln!(writer, "\n{}", doc_lang::fill(app_config.doc.interrupted_at, &[&path]))
+        }
+        None => writeln!(writer, "\n{}", app_config.doc.interrupted),
+    }
+}
+
+fn source_name(base_dir: &Path, app_config: &AppConfig) -> String {
+    if let Some(source) = &app_config.source {
+        source.clone()
+    } else if base_dir == Path::new(".") {
+        app_config.doc.current_directory.to_string()
+    } else {
+        escape::display_path(base_dir)
+    }
+}
+
+fn document_title(source: &str, app_config: &AppConfig) -> String {
+    match &app_config.title {
+        Some(title) => title.clone(),
+        None => format!("{}: {}", app_config.doc.project_structure, source),
+    }
+}
+
+fn heading_title(base_dir: &Path, app_config: &AppConfig) -> String {
+    document_title(&source_name(base_dir, app_config), app_config)
+}
+
+fn write_document_header<W: Write>(writer: &mut W, source: &str, app_config: &AppConfig) -> std::io::Result<()> {
+    let title = document_title(source, app_config);
+    writeln!(writer, "{} {}\n", heading(app_config, 1), title)?;
+    
+    if let Some(context) = &app_config.git_context {
+        write_git_context(writer, context, app_config.doc)?;
+    }
+    
+    // Во вступлении подставляются {title}, {source} и {date}; прочие фигурные скобки остаются как есть
+    if let Some(header) = &app_config.header {
+        let header = header
+            .replace("{title}", &title)
+            .replace("{source}", source)
+            .replace("{date}", &logging::format_date(SystemTime::now()));
+        writeln!(writer, "{}\n", header.trim_end())?;
+    }
+    
+    Ok(())
+}
+
+fn write_git_context<W: Write>(writer: &mut W, context: &git::Context, doc: &doc_lang::Strings) -> std::io::Result<()> {
+    let branch = context.branch.as_deref().map_or(doc.git_detached.to_string(), escape::code_span);
+    writeln!(writer, "- {}: {}", doc.git_branch, branch)?;
+    if let Some(commit) = &context.commit {
+        writeln!(writer, "- {}: {}", doc.git_commit, escape::code_span(commit))?;
+    }
+    let state = match context.dirty {
+        Some(true) => doc.git_dirty,
+        Some(false) => doc.git_clean,
+        None => doc.git_unknown,
+    };
+    writeln!(writer, "- {}: {}", doc.git_state, state)?;
+    if let Some(url) = &context.remote_url {
+        writeln!(writer, "- {}: {}", doc.git_remote, escape::code_span(url))?;
+    }
+    writeln!(writer)
+}
+
+// Дерево собирается целиком заранее: имена файлов могут содержать бэктики,
+// и длину fence нужно подобрать по всему содержимому блока
+fn write_tree_block<W: Write>(
+    base_dir: &Path,
+    root: &Path,
+    nodes: &[tree::TreeNode],
+    writer: &mut W,
+    app_config: &AppConfig,
+    config: &Config,
+) -> std::io::Result<()> {
+    if app_config.anchors {
+        let sections: HashSet<PathBuf> = content_files(nodes, app_config, config).into_iter().collect();
+        let has_section = |path: &Path| app_config.include_contents && sections.contains(path);
+        writeln!(writer, "<pre>")?;
+        if let Some(root) = tree_root_line(base_dir, root, app_config) {
+            writeln!(writer, "{}", escape::html(&root))?;
+        }
+        tree::render_linked(base_dir, nodes, writer, "", app_config, &has_section)?;
+        return writeln!(writer, "</pre>\n");
+    }
+    
+    let mut tree = Vec::new();
+    if app_config.flat_tree {
+        let prefix = prefixed_path(app_config, root.strip_prefix(base_dir).unwrap_or(Path::new("")));
+        tree::render_flat(root, &prefix, nodes, &mut tree, app_config)?;
+    } else {
+        if let Some(root) = tree_root_line(base_dir, root, app_config) {
+            writeln!(tree, "{}", root)?;
+        }
+        tree::render_pretty(nodes, &mut tree, "", app_config)?;
+    }
+    let tree = String::from_utf8_lossy(&tree);
+    let fence = fence(&tree, app_config);
+    
+    writeln!(writer, "{}", fence)?;
+    write!(writer, "{}", tree)?;
+    writeln!(writer, "{}\n", fence)?;
+    
+    Ok(())
+}
+
+// С --paths дерево начинается строкой корня, как у утилиты `tree`: иначе имена верхнего
+// уровня не к чему привязать
+fn tree_root_line(base_dir: &Path, root: &Path, app_config: &AppConfig) -> Option<String> {
+    if app_config.path_prefix.as_os_str().is_empty() || app_config.flat_tree {
+        return None;
+    }
+    let root = prefixed_path(app_config, root.strip_prefix(base_dir).unwrap_or(Path::new("")));
+    Some(format!("{}/", escape::display_path(&root)).replace("//", "/"))
+}
+
+// Внешние пути (например, соседняя общая библиотека) добавляются отдельными разделами,
+// а заголовки файлов в них указываются относительно целевой директории
+fn write_external_section<W: Write>(
+    base_dir: &Path,
+    external: &str,
+    writer: &mut W,
+    app_config: &AppConfig,
+    config: &Config,
+) -> std::io::Result<()> {
+    let external_path = base_dir.join(external);
+    
+    if app_config.fs.metadata(&external_path).is_ok_and(|metadata| metadata.is_file) {
+        writeln!(
+            writer,
+            "\n{} {}: {}\n",
+            heading(app_config, 2),
+            app_config.doc.external_file,
+            escape::code_span(external)
+        )?;
+        if app_config.include_contents {
+            write_file_content(writer, &external_path, app_config, config)?;
+        }
+        return Ok(());
+    }
+    
+    if !app_config.fs.is_dir(&external_path) {
+        tracing::warn!("внешний путь '{}' не найден", external);
+        return Ok(());
+    }
+    
+    let gitignore_matcher = if app_config.no_gitignore {
+        None
+    } else {
+        create_gitignore_matcher(&external_path.to_string_lossy(), app_config.case_insensitive).ok()
+    };
+    
+    writeln!(
+        writer,
+        "\n{} {}: {}\n",
+        heading(app_config, 2),
+        app_config.doc.external_dir,
+        escape::code_span(external)
+    )?;
+    
+    if !app_config.include_tree && !app_config.include_contents {
+        return Ok(());
+    }
+    
+    let nodes = tree::build_tree(&external_path, app_config, config, &gitignore_matcher)?;
+    
+    if app_config.include_tree {
+        write_tree_block(base_dir, &external_path, &nodes, writer, app_config, config)?;
+    }
+    
+    if app_config.include_contents {
+        print_file_contents(base_dir, &nodes, writer, app_config, config)?;
+    }
+    
+    Ok(())
+}
+
+fn print_file_contents<W: Write>(
+    base_dir: &Path,
+    nodes: &[tree::TreeNode],
+    writer: &mut W,
+    app_config: &AppConfig,
+    config: &Config,
+) -> std::io::Result<()> {
+    let entries = content_entries(nodes, app_config, config);
+    let hard_link_ids: HashMap<PathBuf, (u64, u64)> =
+        entries.iter().filter_map(|(path, info)| Some((path.clone(), info.hard_link?))).collect();
+    let files = order_content_files(base_dir, entries.into_iter().map(|(path, _)| path).collect(), app_config, config);
+    
+    let relative_paths: Vec<PathBuf> = files.iter().map(|path| relative_path(base_dir, path)).collect();
+    
+    let mut current_dir = PathBuf::new();
+    let mut hard_links: HashMap<(u64, u64), PathBuf> = HashMap::new();
+    
+    for (index, path) in files.iter().enumerate() {
+        if interrupt::stop_at(path) {
+            break;
+        }
+        let relative_path = &relative_paths[index];
+        write_file_heading(writer, relative_path, &mut current_dir, app_config)?;
+        write_nav_line(writer, &relative_paths, index, false, app_config)?;
+        
+        // Жесткие ссылки на один и тот же файл выводятся один раз
+        if let Some(&id) = hard_link_ids.get(path) {
+            if let Some(original) = hard_links.get(&id) {
+                let original = escape::code_span(&escape::display_path(original));
+                writeln!(writer, "{}", doc_lang::fill(app_config.doc.hard_link, &[&original]))?;
+                write_nav_line(writer, &relative_paths, index, true, app_config)?;
+                continue;
+            }
+            hard_links.insert(id, relative_path.clone());
+        }
+        
+        write_file_content(writer, path, app_config, config)?;
+        write_nav_line(writer, &relative_paths, index, true, app_config)?;
+    }
+    
+    Ok(())
+}
+
+fn write_tree_heading<W: Write>(writer: &mut W, app_config: &AppConfig) -> std::io::Result<()> {
+    writeln!(writer, "{} {}", heading(app_config, 2), app_config.doc.file_tree)?;
+    if app_config.nav_links {
+        writeln!(writer, "<a id=\"{}\"></a>", anchors::TREE_ID)?;
+    }
+    writeln!(writer)
+}
+
+// --nav-links: строка ссылок под заголовком файла и после его содержимого, чтобы
+// в длинном документе не листать до дерева или соседнего файла. Соседи берутся
+// в пределах раздела (основной код, тесты, сборка)
+fn nav_line(relative_paths: &[PathBuf], index: usize, app_config: &AppConfig) -> String {
+    let mut slugs = app_config.slugs.lock().unwrap();
+    let mut links = Vec::new();
+    if app_config.include_tree {
+        links.push(format!("[{}](#{})", app_config.doc.nav_tree, anchors::TREE_ID));
+    }
+    let name = |path: &Path| {
+        escape::code_span(&path.file_name().map_or_else(|| escape::display_path(path), escape::display_os_str))
+    };
+    if let Some(previous) = index.checked_sub(1).map(|index| &relative_paths[index]) {
+        links.push(format!("[← {}](#{})", name(previous), slugs.slug(previous)));
+    }
+    if let Some(next) = relative_paths.get(index + 1) {
+        links.push(format!("[{} →](#{})", name(next), slugs.slug(next)));
+    }
+    links.join(" · ")
+}
+
+// Под заголовком строка отделяется от блока кода пустой строкой, после содержимого - перед ней
+fn write_nav_line<W: Write>(
+    writer: &mut W,
+    relative_paths: &[PathBuf],
+    index: usize,
+    footer: bool,
+    app_config: &AppConfig,
+) -> std::io::Result<()> {
+    if !app_config.nav_links {
+        return Ok(());
+    }
+    match nav_line(relative_paths, index, app_config) {
+        line if line.is_empty() => Ok(()),
+        line if footer => writeln!(writer, "\n{}", line),
+        line => writeln!(writer, "{}\n", line),
+    }
+}
+
+fn order_content_files(base_dir: &Path, mut files: Vec<PathBuf>, app_config: &AppConfig, config: &Config) -> Vec<PathBuf> {
+    if app_config.content_order == ContentOrder::Dependencies {
+        files = deps::order_by_dependencies(&files, &|path| app_config.fs.read_to_string(path).ok());
+    } else if let Some(selection) = app_config.quick_selection.lock().unwrap().as_ref() {
+        files.sort_by_key(|path| selection.iter().position(|selected| selected == path));
+    } else {
+        content_order::sort(base_dir, &mut files, &config.content_order, app_config.case_insensitive);
+    }
+    files
+}
+
+// Файлы раздела содержимого в порядке вывода
+fn ordered_content_files(base_dir: &Path, nodes: &[tree::TreeNode], app_config: &AppConfig, config: &Config) -> Vec<PathBuf> {
+    order_content_files(base_dir, content_files(nodes, app_config, config), app_config, config)
+}
+
+fn content_files(nodes: &[tree::TreeNode], app_config: &AppConfig, config: &Config) -> Vec<PathBuf> {
+    content_entries(nodes, app_config, config).into_iter().map(|(path, _)| path).collect()
+}
+
+// С --quick остаются только выбранные файлы
+fn content_entries(
+    nodes: &[tree::TreeNode],
+    app_config: &AppConfig,
+    config: &Config,
+) -> Vec<(PathBuf, tree::FileInfo)> {
+    let mut files = candidate_entries(nodes, app_config, config);
+    if let Some(selection) = app_config.quick_selection.lock().unwrap().as_ref() {
+        files.retain(|(path, _)| selection.contains(path));
+    }
+    files
+}
+
+// Размер для max_file_size берется из обхода, повторный stat не нужен
+fn candidate_entries(
+    nodes: &[tree::TreeNode],
+    app_config: &AppConfig,
+    config: &Config,
+) -> Vec<(PathBuf, tree::FileInfo)> {
+    let mut files = Vec::new();
+    tree::file_entries(nodes, &mut files);
+    files.retain(|(path, info)| {
+        info.regular
+            && is_extension_allowed(path, app_config)
+            && !tree_only::is_tree_only(path, app_config, config)
+            && (summarize::find(path, app_config, config).is_some()
+                || (!is_binary_file(path, config) && config.max_file_size.is_none_or(|max_size| info.len <= max_size)))
+    });
+    files
+}
+
+// Файлы из командной строки выводятся после содержимого директории. Файл внутри нее,
+// уже выведенный по общим правилам, не повторяется; файл снаружи озаглавлен путем,
+// как он указан в командной строке
+fn print_explicit_files<W: Write>(
+    base_dir: &Path,
+    nodes: &[tree::TreeNode],
+    writer: &mut W,
+    app_config: &AppConfig,
+    config: &Config,
+) -> std::io::Result<()> {
+    if app_config.explicit_files.is_empty() {
+        return Ok(());
+    }
+    
+    let canonical_base = fs::canonicalize(base_dir)?;
+    let mut written: HashSet<PathBuf> = content_files(nodes, app_config, config)
+        .iter()
+        .filter_map(|path| fs::canonicalize(path).ok())
+        .collect();
+    let mut current_dir = PathBuf::new();
+    
+    for file in &app_config.explicit_files {
+        // Файл мог исчезнуть после разбора аргументов: пометку выведет write_file_content
+        let canonical = fs::canonicalize(file).unwrap_or_else(|_| file.clone());
+        if !written.insert(canonical.clone()) {
+            continue;
+        }
+        
+        // Снаружи директории путь из командной строки отсчитывается от текущей директории,
+        // а не от целевой, поэтому с --paths выводится абсолютный путь
+        let heading_path = match canonical.strip_prefix(&canonical_base) {
+            Ok(inside) => inside.to_path_buf(),
+            Err(_) if !app_config.path_prefix.as_os_str().is_empty() => canonical.clone(),
+            Err(_) => file.clone(),
+        };
+        write_file_heading(writer, &heading_path, &mut current_dir, app_config)?;
+        write_file_content(writer, file, app_config, config)?;
+    }
+    
+    Ok(())
+}
+
+// Заголовок раздела файла. С --nest-headings перед ним выводятся заголовки директорий,
+// которых не было в пути предыдущего файла (current_dir)
+fn write_file_heading<W: Write>(
+    writer: &mut W,
+    relative_path: &Path,
+    current_dir: &mut PathBuf,
+    app_config: &AppConfig,
+) -> std::io::Result<()> {
+    let parent = relative_path.parent().unwrap_or(Path::new(""));
+    let mut level = 3;
+    
+    if app_config.nest_headings {
+        let common = current_dir
+            .components()
+            .zip(parent.components())
+            .take_while(|(a, b)| a == b)
+            .count();
+        let mut dir = PathBuf::new();
+        for (i, component) in parent.components().enumerate() {
+            dir.push(component);
+            if i >= common {
+                writeln!(writer, "\n{} {}", heading(app_config, 3 + i), heading_path(app_config, &dir, "/"))?;
+                if app_config.anchors {
+                    writeln!(writer, "{}", app_config.slugs.lock().unwrap().anchor_line(&dir))?;
+                }
+            }
+        }
+        *current_dir = parent.to_path_buf();
+        level += parent.components().count();
+    }
+    
+    writeln!(
+        writer,
+        "\n{} {}",
+        heading(app_config, level),
+        heading_path(app_config, relative_path, "")
+    )?;
+    // Якорь идет после заголовка, а не перед ним: так он остается в разделе своего файла (--update)
+    if app_config.anchors {
+        writeln!(writer, "{}", app_config.slugs.lock().unwrap().anchor_line(relative_path))?;
+    }
+    writeln!(writer)
+}
+
+// Путь в заголовке. С --ascii-paths путь с не-ASCII символами выводится транслитерированным,
+// а исходный - в скобках: `otchet.md` (`отчет.md`)
+fn heading_path(app_config: &AppConfig, relative_path: &Path, suffix: &str) -> String {
+    let path = prefixed_path(app_config, relative_path);
+    let original = escape::code_span(&format!("{}{}", escape::display_path(&path), suffix));
+    if !app_config.ascii_paths || translit::is_ascii_path(&path) {
+        return original;
+    }
+    let ascii = format!("{}{}", escape::display_path(&translit::ascii_path(&path)), suffix);
+    format!("{} ({})", escape::code_span(&ascii), original)
+}
+
+// Markdown поддерживает только шесть уровней заголовков: более глубокие прижимаются к шестому
+fn heading(app_config: &AppConfig, level: usize) -> String {
+    "#".repeat((level + app_config.heading_offset).min(6))
+}
+
+fn relative_path(base_dir: &Path, path: &Path) -> PathBuf {
+    if let Ok(rel_path) = path.strip_prefix(base_dir) {
+        if rel_path.as_os_str().is_empty() {
+            Path::new(".").join(path.file_name().unwrap_or_default())
+        } else {
+            rel_path.to_path_buf()
+        }
+    } else {
+        path.to_path_buf()
+    }
+}
+
+// Путь для вывода с префиксом --paths. `..` сворачиваются без обращения к диску:
+// внешние разделы указываются относительно целевой директории
+fn prefixed_path(app_config: &AppConfig, path: &Path) -> PathBuf {
+    if app_config.path_prefix.as_os_str().is_empty() {
+        return path.to_path_buf();
+    }
+    
+    let mut result = PathBuf::new();
+    for component in app_config.path_prefix.join(path).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if matches!(result.components().next_back(), Some(Component::Normal(_))) => {
+                result.pop();
+            }
+            component => result.push(component),
+        }
+    }
+    result
+}
+
+// Префикс для --paths: абсолютный путь целевой директории или ее путь от корня рабочего дерева git
+fn path_prefix(target_dir: &Path, mode: &str) -> PathBuf {
+    if mode == "relative" {
+        return PathBuf::new();
+    }
+    
+    let absolute = fs::canonicalize(target_dir).unwrap_or_else(|_| target_dir.to_path_buf());
+    if mode == "absolute" {
+        return absolute;
+    }
+    
+    match git::work_tree_root(&absolute) {
+        Some(root) => absolute.strip_prefix(&root).map(Path::to_path_buf).unwrap_or_default(),
+        None => {
+            tracing::warn!("'{}' не находится в репозитории git, пути выводятся от целевой директории", target_dir.display());
+            PathBuf::new()
+        }
+    }
+}
+
+fn write_file_content<W: Write>(
+    writer: &mut W,
+    path: &Path,
+    app_config: &AppConfig,
+    config: &Config,
+) -> std::io::Result<()> {
+    if let Some(summarizer) = summarize::find(path, app_config, config) {
+        return write_summary(writer, path, summarizer, app_config);
+    }
+    
+    let error = match app_config.fs.read_text(path) {
+        Ok((content, bom)) => {
+            if app_config.lfs_fetch
+                && let Some(pointer) = lfs::parse_pointer(&content)
+            {
+                return write_lfs_object(writer, path, &pointer, app_config, config);
+            }
+            write_bom_note(writer, bom, app_config)?;
+            if app_config.check_syntax {
+                write_syntax_note(writer, path, &content, app_config, config)?;
+            }
+            return write_content_block(writer, path, &content, app_config, config);
+        }
+        Err(e) if e.kind() == io::ErrorKind::InvalidData && app_config.lossy_read => match app_config.fs.read(path) {
+            Ok(bytes) => {
+                return write_lossy_content(writer, path, &bytes, app_config, config);
+            }
+            Err(e) => e,
+        },
+        Err(e) => e,
+    };
+    
+    // Список файлов снят при обходе один раз: файл, исчезнувший после этого (сборка, временные
+    // файлы редактора), остается в дереве и получает пометку вместо содержимого
+    if vfs::is_cloud_placeholder(&error) {
+        notices::add(app_config, notices::Kind::CloudPlaceholder, Some(path), &error.to_string());
+        let fence = fence("", app_config);
+        writeln!(writer, "{}", fence)?;
+        writeln!(writer, "{}", app_config.doc.cloud_placeholder)?;
+        return writeln!(writer, "{}", fence);
+    }
+    if error.kind() == io::ErrorKind::NotFound {
+        walk_errors::record(app_config, path, app_config.doc.deleted_during_run);
+        let fence = fence("", app_config);
+        writeln!(writer, "{}", fence)?;
+        writeln!(writer, "{}", app_config.doc.deleted_file)?;
+        return writeln!(writer, "{}", fence);
+    }
+    walk_errors::record(app_config, path, &walk_errors::describe(&error, app_config.doc));
+    write_unreadable_block(writer, app_config)
+}
+
+// Пометка перед блоком: при unpack сводка не принимается за содержимое файла
+fn write_summary<W: Write>(
+    writer: &mut W,
+    path: &Path,
+    summarizer: &summarize::Summarizer,
+    app_config: &AppConfig,
+) -> std::io::Result<()> {
+    let command = escape::code_span(&summarizer.command);
+    match summarize::run(summarizer, path, app_config) {
+        Ok(summary) => {
+            writeln!(writer, "{}\n", doc_lang::fill(app_config.doc.summarized, &[&command]))?;
+            let summary = if summary.ends_with('\n') || summary.is_empty() { summary } else { format!("{}\n", summary) };
+            let fence = fence(&summary, app_config);
+            writeln!(writer, "{}text", fence)?;
+            write!(writer, "{}", summary)?;
+            writeln!(writer, "{}", fence)
+        }
+        Err(message) => {
+            notices::add(app_config, notices::Kind::Summarizer, Some(path), &message);
+            writeln!(writer, "{}", doc_lang::fill(app_config.doc.summary_failed, &[&command, &message]))
+        }
+    }
+}
+
+// Метка в начале файла не выводится, но упоминается перед блоком: при восстановлении
+// файла из документа ее можно вернуть
+fn write_bom_note<W: Write>(writer: &mut W, bom: Option<encoding::Bom>, app_config: &AppConfig) -> std::io::Result<()> {
+    match bom {
+        None => Ok(()),
+        Some(encoding::Bom::Utf8) => writeln!(writer, "{}\n", doc_lang::fill(app_config.doc.bom_stripped, &[&"UTF-8"])),
+        Some(bom) => writeln!(writer, "{}\n", doc_lang::fill(app_config.doc.bom_transcoded, &[&bom.name()])),
+    }
+}
+
+// Незакрытые скобки и строки (--check-syntax) отмечаются перед блоком и в логе: обрезанный
+// или недописанный файл иначе легко принять за целый
+fn write_syntax_note<W: Write>(
+    writer: &mut W,
+    path: &Path,
+    content: &str,
+    app_config: &AppConfig,
+    config: &Config,
+) -> std::io::Result<()> {
+    let language = content_language(path, content, config);
+    let Some(problem) = syntax::check(&language, content) else {
+        return Ok(());
+    };
+    let message = syntax::describe(&problem, &language, &doc_lang::RU);
+    notices::add(app_config, notices::Kind::Syntax, Some(path), &format!("файл не разбирается: {}", message));
+    let description = syntax::describe(&problem, &language, app_config.doc);
+    writeln!(writer, "{}\n", doc_lang::fill(app_config.doc.syntax_error, &[&description]))
+}
+
+// Почти текстовый файл с несколькими битыми байтами выводится целиком, а не теряется
+fn write_lossy_content<W: Write>(
+    writer: &mut W,
+    path: &Path,
+    bytes: &[u8],
+    app_config: &AppConfig,
+    config: &Config,
+) -> std::io::Result<()> {
+    let bytes = encoding::strip_utf8_bom(bytes);
+    let replaced: usize = bytes.utf8_chunks().map(|chunk| chunk.invalid().len()).sum();
+    let message = format!("некорректный UTF-8, заменено байтов: {}", replaced);
+    notices::add(app_config, notices::Kind::LossyUtf8, Some(path), &message);
+    
+    write_content_block(writer, path, &String::from_utf8_lossy(bytes), app_config, config)?;
+    writeln!(writer, "\n{}", doc_lang::fill(app_config.doc.lossy_replaced, &[&replaced]))
+}
+
+fn write_lfs_object<W: Write>(
+    writer: &mut W,
+    path: &Path,
+    pointer: &lfs::Pointer,
+    app_config: &AppConfig,
+    config: &Config,
+) -> std::io::Result<()> {
+    let fence = fence("", app_config);
+    // Размер в указателе может не совпадать с объектом: сравнивается настоящий
+    let Some(data) = lfs::fetch_object(path, pointer) else {
+        return lfs::write_annotation(writer, pointer, &fence, app_config.doc, Some(app_config.doc.lfs_not_fetched));
+    };
+    if config.max_file_size.is_some_and(|max| data.len() as u64 > max) {
+        return lfs::write_annotation(writer, pointer, &fence, app_config.doc, Some(app_config.doc.lfs_too_large));
+    }
+    
+    match String::from_utf8(data) {
+        Ok(content) => write_content_block(writer, path, &content, app_config, config),
+        Err(_) => lfs::write_annotation(writer, pointer, &fence, app_config.doc, Some(app_config.doc.lfs_binary)),
+    }
+}
+
+// Блок кода с содержимым файла. Соседние файлы (манифест рядом с lock-файлом) читаются
+// через app_config.fs: при чтении из архива они берутся не с диска
+fn write_content_block<W: Write>(
+    writer: &mut W,
+    path: &Path,
+    content: &str,
+    app_config: &AppConfig,
+    config: &Config,
+) -> std::io::Result<()> {
+    if let Some(pointer) = lfs::parse_pointer(content) {
+        return lfs::write_annotation(writer, &pointer, &fence("", app_config), app_config.doc, None);
+    }
+    
+    let language = content_language(path, content, config);
+    
+    if !app_config.full_lockfiles
+        && lockfile::is_lockfile(path)
+        && let Some(summary) = lockfile::summarize(path, content, app_config.fs.as_ref())
+    {
+        return lockfile::write_summary(writer, &summary, app_config.doc);
+    }
+    
+    let sample = sampling::line_limit(path, app_config, config).and_then(|limit| sampling::head(content, limit));
+    let content = match sample {
+        Some((head, _)) => head,
+        None => content,
+    };
+    let masked = !app_config.unsafe_full_secrets && secrets::is_secret_file(path, config);
+    let content = if masked { secrets::mask_secrets(content) } else { content.to_string() };
+    let (content, normalized) = whitespace::normalize(
+        path,
+        &content,
+        app_config.normalize_eol,
+        app_config.expand_tabs,
+        app_config.dedent,
+        app_config.max_width,
+        app_config.doc,
+    );
+    
+    let fence = fence(&content, app_config);
+    
+    writeln!(writer, "{}{}", fence, fence_info(path, &language, app_config))?;
+    
+    // Убедимся, что контент заканчивается переводом строки
+    let content = if content.ends_with('\n') {
+        content
+    } else {
+        format!("{}\n", content)
+    };
+    write!(writer, "{}", content)?;
+    
+    writeln!(writer, "{}", fence)?;
+    
+    // Пометки идут после блока, чтобы не мешать разбору заголовка и блока. По ним unpack
+    // узнает, что содержимое в снимке отличается от файла, и не восстанавливает его
+    if masked {
+        writeln!(writer, "\n{}", app_config.doc.secrets_masked)?;
+    }
+    if let Some((head, total)) = sample {
+        writeln!(writer, "\n{}", doc_lang::fill(app_config.doc.sampled_lines, &[&head.lines().count(), &total]))?;
+    }
+    if !normalized.is_empty() {
+        writeln!(writer, "\n{}", doc_lang::fill(app_config.doc.normalized, &[&normalized.join(", ")]))?;
+    }
+    Ok(())
+}
+
+// Расширенная строка информации для рендереров вроде Docusaurus и Shiki: ```rust title="src/main.rs".
+// Первым словом должен остаться язык, иначе рендерер примет за язык атрибут
+fn fence_info(path: &Path, language: &str, app_config: &AppConfig) -> String {
+    let Some(template) = &app_config.fence_info else {
+        return language.to_string();
+    };
+    
+    let relative = relative_path(Path::new(&app_config.target_dir), path);
+    let quote = |text: String| text.replace('\\', "\\\\").replace('"', "\\\"");
+    let info = template
+        .replace("{lang}", language)
+        .replace("{path}", &quote(escape::display_path(&relative)))
+        .replace("{name}", &quote(escape::display_os_str(path.file_name().unwrap_or_default())));
+    
+    // В строке информации блока из бэктиков бэктик недопустим (CommonMark), как и перевод строки
+    if info.contains(['`', '\n', '\r']) {
+        tracing::debug!("шаблон --fence-info для {} дает недопустимую строку, выводится только язык", path.display());
+        return language.to_string();
+    }
+    
+    let info = info.trim();
+    if info.starts_with(language) {
+        info.to_string()
+    } else {
+        format!("{} {}", language, info)
+    }
+}
+
+// Для файлов, которые не удалось прочитать, используем стандартные 3 бактика (или --min-fence)
+fn write_unreadable_block<W: Write>(writer: &mut W, app_config: &AppConfig) -> std::io::Result<()> {
+    let fence = fence("", app_config);
+    writeln!(writer, "{}", fence)?;
+    writeln!(writer, "{}", app_config.doc.unreadable_file)?;
+    writeln!(writer, "{}", fence)
+}
+
+// Разделитель блока: длиннее любой серии бэктиков в содержимом и не короче --min-fence
+fn fence(content: &str, app_config: &AppConfig) -> String {
+    "`".repeat(calculate_fence_length(content).max(app_config.min_fence))
+}
+
+fn calculate_fence_length(content: &str) -> usize {
+    let mut max_backticks = 0;
+    let mut current_backticks = 0;
+    
+    // Проходим по всем символам контента
+    for c in content.chars() {
+        if c == '`' {
+            current_backticks += 1;
+        } else {
+            if current_backticks > max_backticks {
+                max_backticks = current_backticks;
+            }
+            current_backticks = 0;
+        }
+    }
+    
+    // Проверяем последовательность в конце строки
+    if current_backticks > max_backticks {
+        max_backticks = current_backticks;
+    }
+    
+    // Используем минимум 3 бактика, но если в файле есть последовательность из 3 или более, то на 1 больше
+    // Для особых случаев (Markdown, JavaScript) увеличиваем базовый минимум
+    let base_minimum = if content.contains("```") || content.contains("`${") {
+        4  // Для файлов, где вероятно есть блоки кода или template literals
+    } else {
+        3  // Для обычных файлов
+    };
+    
+    std::cmp::max(base_minimum, max_backticks + 1)
+}
+
+// is_dir передается вызывающим: при обходе тип элемента уже известен из чтения директории,
+// и повторный stat на каждый элемент не нужен
+fn should_skip_entry(
+    path: &Path, 
+    name: &str, 
+    is_dir: bool,
+    app_config: &AppConfig, 
+    config: &Config,
+    gitignore_matcher: &Option<Gitignore>,
+) -> SkipReason {
+    let (reason, cause) = classify_entry(path, name, is_dir, app_config, config, gitignore_matcher);
+    if reason != SkipReason::NoSkip {
+        tracing::debug!(target: "proj2tree::skip", "{} пропущен: {}", escape::display_path(path), cause);
+        let rule = skip_rule(path, name, is_dir, cause, app_config, config, gitignore_matcher);
+        skip_stats::record(app_config, path, cause, rule);
+    }
+    reason
+}
+
+// Конкретное правило, исключившее элемент, для сводки: шаблон .gitignore, имя из exclude_dirs,
+// шаблон exclude_files или .gitattributes. У остальных причин правило совпадает с самой причиной
+fn skip_rule(
+    path: &Path,
+    name: &str,
+    is_dir: bool,
+    cause: &str,
+    app_config: &AppConfig,
+    config: &Config,
+    gitignore_matcher: &Option<Gitignore>,
+) -> String {
+    let rule = match cause {
+        ".gitignore" => gitignore_matcher
+            .as_ref()
+            .and_then(|matcher| match ignore_match(matcher, path, is_dir) {
+                ignore::Match::Ignore(glob) => Some(glob.original()),
+                _ => None,
+            })
+            .map(str::to_string),
+        "exclude_dirs" => config.exclude_dirs.iter().find(|dir| names_equal(name, dir, app_config.case_insensitive)).cloned(),
+        "exclude_files" => config
+            .exclude_files
+            .iter()
+            .find(|pattern| matches_name_pattern(name, pattern, app_config.case_insensitive))
+            .cloned(),
+        "linguist-generated" | "linguist-vendored" => app_config
+            .gitattributes
+            .as_ref()
+            .and_then(|attributes| attributes.excluded(&relative_path(Path::new(&app_config.target_dir), path)))
+            .map(|(_, pattern)| pattern.to_string()),
+        _ => None,
+    };
+    rule.unwrap_or_else(|| cause.to_string())
+}
+
+fn classify_entry(
+    path: &Path,
+    name: &str,
+    is_dir: bool,
+    app_config: &AppConfig,
+    config: &Config,
+    gitignore_matcher: &Option<Gitignore>,
+) -> (SkipReason, &'static str) {
+    let result = classify_unsampled(path, name, is_dir, app_config, config, gitignore_matcher);
+    
+    if result.0 == SkipReason::NoSkip
+        && !app_config.sample_dirs.is_empty()
+        && !is_dir
+        && let Some(parent) = path.parent()
+        && let Some((sample, _)) = directory_sample(parent, app_config, config, gitignore_matcher)
+        && !sample.contains(&path.to_path_buf())
+    {
+        return (SkipReason::Skip, "--sample-dir");
+    }
+    
+    result
+}
+
+fn classify_unsampled(
+    path: &Path,
+    name: &str,
+    is_dir: bool,
+    app_config: &AppConfig,
+    config: &Config,
+    gitignore_matcher: &Option<Gitignore>,
+) -> (SkipReason, &'static str) {
+    if is_output_path(path, app_config) {
+        return (SkipReason::Skip, "выходной файл");
+    }
+    
+    if let Some(matcher) = gitignore_matcher
+        && is_ignored_by(matcher, path, is_dir)
+    {
+        return if is_dir {
+            (SkipReason::SkipWithEllipsis, ".gitignore")
+        } else {
+            (SkipReason::Skip, ".gitignore")
+        };
+    }
+    
+    // Контекст сборки определяет только .dockerignore: скрытые файлы и exclude_* в него попадают
+    if let Some(docker_context) = &app_config.docker_context {
+        let relative = relative_path(Path::new(&app_config.target_dir), path);
+        if docker::is_ignored(docker_context, &relative)
+            && !(is_dir && docker::has_exceptions_under(docker_context, &relative))
+        {
+            return if is_dir {
+                (SkipReason::SkipWithEllipsis, ".dockerignore")
+            } else {
+                (SkipReason::Skip, ".dockerignore")
+            };
+        }
+    }
+    
+    if !is_dir
+        && let Some(attributes) = &app_config.gitattributes
+        && let Some((cause, _)) = attributes.excluded(&relative_path(Path::new(&app_config.target_dir), path))
+    {
+        return (SkipReason::Skip, cause);
+    }
+    
+    if name.starts_with('.')
+        && app_config.docker_context.is_none()
+        && config.dotfiles == dotfiles::Policy::Skip
+        && !dotfiles::is_kept(path, app_config, config)
+        && !(app_config.build_section && build_files::is_hidden_build_name(name))
+    {
+        return (SkipReason::Skip, "скрытый файл");
+    }
+    
+    if app_config.build_section
+        && build_files::is_outside_workflows(&relative_path(Path::new(&app_config.target_dir), path))
+    {
+        return (SkipReason::Skip, ".github вне workflows");
+    }
+    
+    if is_dir
+        && app_config.docker_context.is_none()
+        && config.exclude_dirs.iter().any(|dir| names_equal(name, dir, app_config.case_insensitive)) {
+        return (SkipReason::SkipWithEllipsis, "exclude_dirs");
+    }
+    
+    if let Some(root_device) = app_config.root_device
+        && is_dir
+        && device::device_id(path).is_some_and(|device| device != root_device)
+    {
+        return (SkipReason::SkipWithEllipsis, "--one-file-system");
+    }
+    
+    // Junction и ссылки на директории в Windows по умолчанию не обходятся: junction
+    // на родительскую директорию зацикливает обход, а облачная директория без локальной
+    // копии скачивается при чтении (--follow-reparse-points)
+    if is_dir && !app_config.follow_reparse_points && device::reparse_point(path).is_some() {
+        return (SkipReason::SkipWithEllipsis, "reparse point");
+    }
+    
+    // Глубина считается от целевой директории: элементы верхнего уровня - глубина 1.
+    // Директория на предельной глубине выводится как `name/ ...`
+    if let Some(max_depth) = app_config.max_path_depth {
+        let depth = relative_path(Path::new(&app_config.target_dir), path).components().count();
+        if is_dir && depth >= max_depth {
+            return (SkipReason::SkipWithEllipsis, "--max-path-depth");
+        }
+        if depth > max_depth {
+            return (SkipReason::Skip, "--max-path-depth");
+        }
+    }
+    
+    if let Some(owner) = app_config.owner
+        && device::owner_id(path).is_some_and(|uid| uid != owner)
+    {
+        return if is_dir {
+            (SkipReason::SkipWithEllipsis, "--owner")
+        } else {
+            (SkipReason::Skip, "--owner")
+        };
+    }
+    
+    if is_dir
+        && let Some(limit) = app_config.skip_dir_over.or(config.skip_dir_over)
+        && is_directory_over(path, limit, app_config)
+    {
+        return (SkipReason::SkipWithEllipsis, "--skip-dir-over");
+    }
+    
+    if !is_dir
+        && app_config.docker_context.is_none()
+        && config
+            .exclude_files
+            .iter()
+            .any(|pattern| matches_name_pattern(name, pattern, app_config.case_insensitive))
+        && !sampling::overrides_exclusion(path, app_config, config)
+    {
+        return (SkipReason::Skip, "exclude_files");
+    }
+    
+    if !app_config.full_tree && !is_dir && !is_extension_allowed(path, app_config) {
+        return (SkipReason::Skip, "--only-ext");
+    }
+    
+    if let Some(query) = &app_config.filter
+        && !matches_filter(path, is_dir, query, app_config, config, gitignore_matcher)
+    {
+        return (SkipReason::Skip, "--filter");
+    }
+    
+    if let Some(output_file) = &app_config.output_file {
+        if let Some(output_name) = Path::new(output_file).file_name()
+            && names_equal(name, &output_name.to_string_lossy(), app_config.case_insensitive)
+        {
+            return (SkipReason::Skip, "выходной файл");
+        }
+    } else if names_equal(name, "tree.md", app_config.case_insensitive) {
+        return (SkipReason::Skip, "выходной файл");
+    }
+    
+    if let Some(csv_path) = &app_config.todos_csv
+        && let Some(csv_name) = Path::new(csv_path).file_name()
+        && names_equal(name, &csv_name.to_string_lossy(), app_config.case_insensitive)
+    {
+        return (SkipReason::Skip, "выходной файл");
+    }
+    
+    // Части прошлого запуска с --chunk
+    if app_config.chunk.is_some() {
+        let output_file = app_config.output_file.as_deref().unwrap_or("tree.md");
+        if chunk::is_part_of(name, Path::new(output_file)) {
+            return (SkipReason::Skip, "выходной файл");
+        }
+    }
+    
+    (SkipReason::NoSkip, "")
+}
+
+// Директория внутри уже подсчитанной целиком и небольшой не может быть больше порога
+fn is_directory_over(path: &Path, limit: u64, app_config: &AppConfig) -> bool {
+    {
+        let small_dirs = app_config.small_dirs.lock().unwrap();
+        if path.ancestors().skip(1).any(|dir| small_dirs.contains(dir)) {
+            return false;
+        }
+    }
+    match dir_size::scan(app_config.fs.as_ref(), path, limit) {
+        dir_size::Scan::Over => true,
+        dir_size::Scan::Under => {
+            app_config.small_dirs.lock().unwrap().insert(path.to_path_buf());
+            false
+        }
+        dir_size::Scan::Incomplete => false,
+    }
+}
+
+// Выборка --sample-dir для директории: N самых маленьких файлов (при равенстве - по имени)
+// и общее число файлов, прошедших остальные правила. None, если директория не отбирается
+fn directory_sample(
+    dir: &Path,
+    app_config: &AppConfig,
+    config: &Config,
+    gitignore_matcher: &Option<Gitignore>,
+) -> Option<(Vec<PathBuf>, usize)> {
+    let relative = relative_path(Path::new(&app_config.target_dir), dir);
+    let dir_name = dir.file_name().map(|name| name.to_string_lossy());
+    let (_, limit) = app_config.sample_dirs.iter().find(|(spec, _)| {
+        let spec = spec.trim_end_matches('/');
+        if spec.contains('/') {
+            relative == Path::new(spec)
+        } else {
+            dir_name.as_deref() == Some(spec)
+        }
+    })?;
+    
+    if let Some(cached) = app_config.sample_cache.lock().unwrap().get(dir) {
+        return Some(cached.clone());
+    }
+    
+    let mut candidates: Vec<(u64, PathBuf)> = app_config
+        .fs
+        .read_dir(dir)
+        .ok()?
+        .into_iter()
+        .flatten()
+        .filter(|entry| !entry_is_dir(entry, app_config))
+        .filter(|entry| {
+            classify_unsampled(&entry.path, &entry.name.to_string_lossy(), false, app_config, config, gitignore_matcher).0
+                == SkipReason::NoSkip
+        })
+        .map(|entry| (app_config.fs.metadata(&entry.path).map(|metadata| metadata.len).unwrap_or(0), entry.path))
+        .collect();
+    candidates.sort();
+    
+    let total = candidates.len();
+    let sample: Vec<PathBuf> = candidates.into_iter().take(*limit).map(|(_, path)| path).collect();
+    app_config.sample_cache.lock().unwrap().insert(dir.to_path_buf(), (sample.clone(), total));
+    
+    Some((sample, total))
+}
+
+// Число файлов директории, не попавших в выборку --sample-dir
+fn sampled_out_count(
+    dir: &Path,
+    app_config: &AppConfig,
+    config: &Config,
+    gitignore_matcher: &Option<Gitignore>,
+) -> usize {
+    if app_config.sample_dirs.is_empty() {
+        return 0;
+    }
+    directory_sample(dir, app_config, config, gitignore_matcher)
+        .map_or(0, |(sample, total)| total - sample.len())
+}
+
+fn parse_sample_spec(value: &str) -> Result<(String, usize), String> {
+    let (dir, count) = value
+        .rsplit_once(':')
+        .ok_or_else(|| "ожидается DIR:N, например data/:3".to_string())?;
+    let count = count
+        .parse::<usize>()
+        .ok()
+        .filter(|&count| count > 0)
+        .ok_or_else(|| format!("'{}' не является положительным числом", count))?;
+    if dir.trim_matches('/').is_empty() {
+        return Err("не указана директория".to_string());
+    }
+    Ok((dir.trim_start_matches("./").to_string(), count))
+}
+
+// Режим белого списка (--only-ext): без него разрешены все расширения
+fn is_extension_allowed(path: &Path, app_config: &AppConfig) -> bool {
+    let Some(allowed) = &app_config.only_extensions else {
+        return true;
+    };
+    
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| allowed.contains(&ext))
+}
+
+// Файл проходит --filter, если с запросом совпадает его путь относительно целевой директории.
+// Директория остается в дереве, только если внутри есть хотя бы один такой файл
+fn matches_filter(
+    path: &Path,
+    is_dir: bool,
+    query: &str,
+    app_config: &AppConfig,
+    config: &Config,
+    gitignore_matcher: &Option<Gitignore>,
+) -> bool {
+    if !is_dir {
+        let rel_path = relative_path(Path::new(&app_config.target_dir), path);
+        return fuzzy::matches_query(query, &rel_path.to_string_lossy());
+    }
+    
+    let Ok(entries) = app_config.fs.read_dir(path) else {
+        return false;
+    };
+    
+    entries.into_iter().flatten().any(|entry| {
+        let is_dir = entry_is_dir(&entry, app_config);
+        classify_entry(&entry.path, &entry.name.to_string_lossy(), is_dir, app_config, config, gitignore_matcher).0
+            == SkipReason::NoSkip
+    })
+}
+
+// Тип элемента из чтения директории; stat нужен только символическим ссылкам,
+// которые разыменовываются
+fn entry_is_dir(entry: &vfs::DirEntry, app_config: &AppConfig) -> bool {
+    if entry.is_symlink {
+        app_config.fs.is_dir(&entry.path)
+    } else {
+        entry.is_dir
+    }
+}
+
+// Шаблоны имен: `*.ext` - по расширению, `name.*` - по префиксу, иначе точное совпадение
+fn matches_name_pattern(name: &str, pattern: &str, case_insensitive: bool) -> bool {
+    if case_insensitive {
+        return matches_name_pattern(&name.to_lowercase(), &pattern.to_lowercase(), false);
+    }
+    
+    if let Some(ext) = pattern.strip_prefix("*.") {
+        name.ends_with(ext) || name.contains(&format!(".{}", ext))
+    } else if let Some(stem) = pattern.strip_suffix(".*") {
+        name.starts_with(&format!("{}.", stem))
+    } else {
+        name == pattern
+    }
+}
+
+// Файл метаданных прошлого запуска с --sidecar и карта якорей тоже не попадают в снимок
+fn is_output_path(path: &Path, app_config: &AppConfig) -> bool {
+    let is = |candidate: &Path| {
+        names_equal(&path.to_string_lossy(), &candidate.to_string_lossy(), app_config.case_insensitive)
+    };
+    app_config.output_path.as_deref().is_some_and(|output| {
+        [output.to_path_buf(), sidecar::path(output), atomic_file::temp_path(output)].iter().any(|candidate| is(candidate))
+    }) || app_config.anchor_map_path.as_deref().is_some_and(|map| is(map) || is(&atomic_file::temp_path(map)))
+}
+
+// Документ, записанный в исключенную директорию, на следующих запусках меняет ее вид
+// (`build/ ...` появляется только после первого запуска), поэтому об этом предупреждаем
+fn warn_if_output_excluded(app_config: &AppConfig, config: &Config, gitignore_matcher: &Option<Gitignore>) {
+    let Some(output_path) = &app_config.output_path else {
+        return;
+    };
+    let base_dir = Path::new(&app_config.target_dir);
+    let mut dirs: Vec<&Path> = output_path.ancestors().skip(1).take_while(|dir| *dir != base_dir).collect();
+    dirs.reverse();
+    for dir in dirs {
+        let name = dir.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+        let (reason, cause) = classify_unsampled(dir, &name, true, app_config, config, gitignore_matcher);
+        if reason != SkipReason::NoSkip {
+            tracing::warn!(
+                "выходной файл {} записывается в исключенную директорию {} ({}): в дереве она будет свернута",
+                escape::display_path(output_path),
+                escape::display_path(dir),
+                cause
+            );
+            return;
+        }
+    }
+}
+
+fn names_equal(name: &str, other: &str, case_insensitive: bool) -> bool {
+    if case_insensitive {
+        name.to_lowercase() == other.to_lowercase()
+    } else {
+        name == other
+    }
+}
+
+// Расширения сравниваются без учета регистра на любой платформе: `*.PNG` тоже картинка
+fn is_binary_file(path: &Path, config: &Config) -> bool {
+    if let Some(ext) = path.extension() {
+        let ext_str = ext.to_string_lossy().to_lowercase();
+        config.exclude_extensions.iter().any(|e| e.to_lowercase() == ext_str)
+    } else {
+        false
+    }
+}
+
+fn is_file_too_large(path: &Path, app_config: &AppConfig, config: &Config) -> bool {
+    if let Some(max_size) = config.max_file_size
+        && let Ok(metadata) = app_config.fs.metadata(path)
+    {
+        return metadata.len > max_size;
+    }
+    false
+}
+
+// Язык по расширению, а для неизвестных расширений - по имени и содержимому
+fn content_language(path: &Path, content: &str, config: &Config) -> String {
+    let language = get_file_extension(path, config);
+    if language == "text"
+        && let Some(detected) = language::detect(path, content)
+    {
+        return detected.to_string();
+    }
+    language
+}
+
+fn get_file_extension(path: &Path, config: &Config) -> String {
+    const DEFAULT_LANGUAGE: &str = "text";
+    
+    let Some(ext) = path.extension() else {
+        return DEFAULT_LANGUAGE.to_string();
+    };
+    
+    let ext_str = ext.to_string_lossy().to_lowercase();
+    
+    if let Some(mapping) = &config.extension_mapping
+        && let Some(language) = mapping.get(ext_str.as_str())
+    {
+        return language.clone();
+    }
+    
+    DEFAULT_LANGUAGE.to_string()
+}
\ No newline at end of file
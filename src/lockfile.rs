@@ -0,0 +1,457 @@
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use globset::Glob;
+use serde_json::Value;
+
+use crate::doc_lang::{Strings, fill};
+use crate::escape::code_span;
+use crate::vfs::FileSystem;
+
+// Lock-файлы огромны, а их построчное содержимое почти никогда не нужно читателю снимка:
+// вместо него выводится сводка - число пакетов и прямые зависимости с версиями
+
+const LOCKFILES: [&str; 10] = [
+    "Cargo.lock",
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "poetry.lock",
+    "uv.lock",
+    "Pipfile.lock",
+    "composer.lock",
+    "Gemfile.lock",
+];
+
+// Пакеты (имя, версия) и имена прямых зависимостей
+type Packages = Vec<(String, String)>;
+type Parsed = (Packages, Option<Vec<String>>);
+
+pub struct LockSummary {
+    pub total: usize,
+    // None - прямые зависимости определить не удалось (нет манифеста рядом)
+    pub direct: Option<Vec<(String, Option<String>)>>,
+}
+
+pub fn is_lockfile(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| LOCKFILES.iter().any(|lockfile| name == *lockfile))
+}
+
+pub fn summarize(path: &Path, content: &str, fs: &dyn FileSystem) -> Option<LockSummary> {
+    let name = path.file_name()?.to_string_lossy();
+    let dir = path.parent().unwrap_or(Path::new(""));
+
+    let (packages, direct_names) = match name.as_ref() {
+        "Cargo.lock" => cargo_lock(content, workspace_members(dir, fs))?,
+        "package-lock.json" | "npm-shrinkwrap.json" => (npm_lock(content)?, package_json_dependencies(dir, fs)),
+        "yarn.lock" => (yarn_lock(content), package_json_dependencies(dir, fs)),
+        "pnpm-lock.yaml" => (pnpm_lock(content), package_json_dependencies(dir, fs)),
+        "poetry.lock" | "uv.lock" => (toml_packages(content)?, pyproject_dependencies(dir, fs)),
+        "Pipfile.lock" => (pipfile_lock(content)?, pipfile_dependencies(dir, fs)),
+        "composer.lock" => (composer_lock(content)?, composer_json_dependencies(dir, fs)),
+        "Gemfile.lock" => gemfile_lock(content),
+        _ => return None,
+    };
+
+    let python = matches!(name.as_ref(), "poetry.lock" | "uv.lock" | "Pipfile.lock");
+    let normalize = |name: &str| {
+        if python {
+            normalize_python_name(name)
+        } else {
+            name.to_string()
+        }
+    };
+
+    let direct = direct_names.map(|names| {
+        names
+            .into_iter()
+            .map(|name| {
+                let version = packages
+                    .iter()
+                    .find(|(package, _)| normalize(package) == normalize(&name))
+                    .map(|(_, version)| version.clone());
+                (name, version)
+            })
+            .collect()
+    });
+
+    Some(LockSummary {
+        total: packages.len(),
+        direct,
+    })
+}
+
+pub fn write_summary<W: Write>(writer: &mut W, summary: &LockSummary, doc: &Strings) -> io::Result<()> {
+    writeln!(writer, "{}\n", doc.lockfile_summarized)?;
+    writeln!(writer, "{}", fill(doc.total_packages, &[&summary.total]))?;
+
+    if let Some(direct) = &summary.direct {
+        writeln!(writer, "\n{}\n", fill(doc.direct_dependencies, &[&direct.len()]))?;
+        for (name, version) in direct {
+            match version {
+                Some(version) => writeln!(writer, "- {} {}", code_span(name), version)?,
+                None => writeln!(writer, "- {}", code_span(name))?,
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Cargo.lock: прямые зависимости - зависимости членов рабочего пространства. Члены берутся
+// из Cargo.toml рядом: у path-зависимостей вне рабочего пространства тоже нет `source`,
+// и по нему члена от такой зависимости не отличить. Без манифеста членами считаются
+// пакеты без `source`
+fn cargo_lock(content: &str, members: Option<Vec<String>>) -> Option<Parsed> {
+    let value: toml::Value = toml::from_str(content).ok()?;
+    let entries = value.get("package")?.as_array()?;
+    let is_member = |entry: &toml::Value, name: &String| match &members {
+        Some(members) => members.contains(name),
+        None => entry.get("source").is_none(),
+    };
+
+    let mut packages = Vec::new();
+    let mut member_names = Vec::new();
+    let mut direct = Vec::new();
+
+    for entry in entries {
+        let name = entry.get("name").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+        let version = entry.get("version").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+
+        if !is_member(entry, &name) {
+            packages.push((name, version));
+            continue;
+        }
+
+        for dependency in entry.get("dependencies").and_then(|v| v.as_array()).into_iter().flatten() {
+            // Формат элемента: "name", "name version" или "name version (source)"
+            if let Some(dependency) = dependency.as_str().and_then(|d| d.split_whitespace().next())
+                && !direct.iter().any(|d| d == dependency)
+            {
+                direct.push(dependency.to_string());
+            }
+        }
+        member_names.push(name);
+    }
+
+    direct.retain(|name| !member_names.contains(name));
+    direct.sort();
+
+    Some((packages, Some(direct)))
+}
+
+// Корневой пакет и пакеты из [workspace] members за вычетом exclude
+fn workspace_members(dir: &Path, fs: &dyn FileSystem) -> Option<Vec<String>> {
+    let manifest: toml::Value = toml::from_str(&fs.read_to_string(&dir.join("Cargo.toml")).ok()?).ok()?;
+    let workspace = manifest.get("workspace");
+    let list = |key: &str| {
+        workspace
+            .and_then(|w| w.get(key))
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_str())
+            .collect::<Vec<_>>()
+    };
+    let excluded: Vec<PathBuf> = list("exclude").iter().map(|path| dir.join(path)).collect();
+
+    let mut names: Vec<String> = package_name(&manifest).into_iter().collect();
+    for pattern in list("members") {
+        for member in expand_members(dir, pattern, fs) {
+            if excluded.contains(&member) {
+                continue;
+            }
+            let manifest = fs.read_to_string(&member.join("Cargo.toml")).ok();
+            if let Some(name) = manifest.and_then(|m| toml::from_str(&m).ok()).and_then(|m| package_name(&m)) {
+                names.push(name);
+            }
+        }
+    }
+    Some(names)
+}
+
+fn package_name(manifest: &toml::Value) -> Option<String> {
+    manifest.get("package")?.get("name")?.as_str().map(str::to_string)
+}
+
+// Шаблоны членов вроде `crates/*` раскрываются по одному компоненту пути
+fn expand_members(dir: &Path, pattern: &str, fs: &dyn FileSystem) -> Vec<PathBuf> {
+    let mut paths = vec![dir.to_path_buf()];
+    for component in pattern.split('/').filter(|c| !c.is_empty() && *c != ".") {
+        if !component.contains(['*', '?', '[']) {
+            paths.iter_mut().for_each(|path| path.push(component));
+            continue;
+        }
+        let Ok(glob) = Glob::new(component) else {
+            return Vec::new();
+        };
+        let matcher = glob.compile_matcher();
+        paths = paths
+            .iter()
+            .flat_map(|path| fs.read_dir(path).into_iter().flatten().flatten())
+            .filter(|entry| entry.is_dir && matcher.is_match(&entry.name))
+            .map(|entry| entry.path)
+            .collect();
+    }
+    paths
+}
+
+// package-lock.json v2/v3 перечисляет пакеты в `packages`, v1 - во вложенных `dependencies`
+fn npm_lock(content: &str) -> Option<Packages> {
+    let value = serde_json::from_str::<Value>(content).ok()?;
+    let mut packages = Vec::new();
+
+    if let Some(entries) = value.get("packages").and_then(Value::as_object) {
+        for (key, entry) in entries {
+            let Some(index) = key.rfind("node_modules/") else {
+                continue;
+            };
+            let version = entry.get("version").and_then(Value::as_str).unwrap_or_default();
+            packages.push((key[index + "node_modules/".len()..].to_string(), version.to_string()));
+        }
+    } else if let Some(dependencies) = value.get("dependencies") {
+        collect_npm_v1(dependencies, &mut packages);
+    }
+
+    Some(packages)
+}
+
+fn collect_npm_v1(dependencies: &Value, packages: &mut Packages) {
+    for (name, entry) in dependencies.as_object().into_iter().flatten() {
+        let version = entry.get("version").and_then(Value::as_str).unwrap_or_default();
+        packages.push((name.clone(), version.to_string()));
+        if let Some(nested) = entry.get("dependencies") {
+            collect_npm_v1(nested, packages);
+        }
+    }
+}
+
+// yarn.lock (classic и berry): заголовок записи `"name@^1.0", name@^1.1:`, далее `version "1.2.3"`
+fn yarn_lock(content: &str) -> Packages {
+    let mut packages = Vec::new();
+    let mut current: Option<String> = None;
+
+    for line in content.lines() {
+        if line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+
+        if !line.starts_with(' ') {
+            let spec = line.trim_end_matches(':').split(',').next().unwrap_or_default();
+            let spec = spec.trim().trim_matches('"');
+            current = package_name_from_spec(spec)
+                .filter(|name| *name != "__metadata" && !spec.contains("@workspace:"))
+                .map(str::to_string);
+            continue;
+        }
+
+        if let Some(name) = &current
+            && let Some(version) = line.trim().strip_prefix("version")
+        {
+            let version = version.trim_start_matches(':').trim().trim_matches('"');
+            packages.push((name.clone(), version.to_string()));
+            current = None;
+        }
+    }
+
+    packages
+}
+
+// pnpm-lock.yaml: ключи раздела `packages:` - `/name@1.0.0` (v6), `name@1.0.0` (v9) или `/name/1.0.0` (v5)
+fn pnpm_lock(content: &str) -> Packages {
+    let mut packages = Vec::new();
+    let mut in_packages = false;
+
+    for line in content.lines() {
+        if !line.starts_with(' ') && !line.trim().is_empty() {
+            in_packages = line.trim_end() == "packages:";
+            continue;
+        }
+        if !in_packages || !line.starts_with("  ") || line.starts_with("   ") {
+            continue;
+        }
+
+        let key = line.trim().trim_end_matches(':').trim_matches(|c| c == '\'' || c == '"');
+        let key = key.strip_prefix('/').unwrap_or(key);
+        // Суффикс с peer-зависимостями: `name@1.0.0(react@18.0.0)` или `name/1.0.0_react@18.0.0`
+        let key = key.split('(').next().unwrap_or(key);
+
+        let split = match key[1..].rfind('@') {
+            Some(index) => Some(index + 1),
+            None => key.rfind('/'),
+        };
+        if let Some(index) = split {
+            let version = key[index + 1..].split('_').next().unwrap_or_default();
+            packages.push((key[..index].to_string(), version.to_string()));
+        }
+    }
+
+    packages
+}
+
+// poetry.lock и uv.lock: массив таблиц [[package]] с полями name и version
+fn toml_packages(content: &str) -> Option<Packages> {
+    let value: toml::Value = toml::from_str(content).ok()?;
+    let entries = value.get("package")?.as_array()?;
+
+    Some(
+        entries
+            .iter()
+            .map(|entry| {
+                let field = |key| entry.get(key).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+                (field("name"), field("version"))
+            })
+            .collect(),
+    )
+}
+
+fn pipfile_lock(content: &str) -> Option<Packages> {
+    let value = serde_json::from_str::<Value>(content).ok()?;
+    let mut packages = Vec::new();
+
+    for section in ["default", "develop"] {
+        for (name, entry) in value.get(section).and_then(Value::as_object).into_iter().flatten() {
+            let version = entry.get("version").and_then(Value::as_str).unwrap_or_default();
+            packages.push((name.clone(), version.trim_start_matches("==").to_string()));
+        }
+    }
+
+    Some(packages)
+}
+
+fn composer_lock(content: &str) -> Option<Packages> {
+    let value = serde_json::from_str::<Value>(content).ok()?;
+    let mut packages = Vec::new();
+
+    for section in ["packages", "packages-dev"] {
+        for entry in value.get(section).and_then(Value::as_array).into_iter().flatten() {
+            let field = |key| entry.get(key).and_then(Value::as_str).unwrap_or_default().to_string();
+            packages.push((field("name"), field("version")));
+        }
+    }
+
+    Some(packages)
+}
+
+// Gemfile.lock сам хранит прямые зависимости в разделе DEPENDENCIES
+fn gemfile_lock(content: &str) -> Parsed {
+    let mut packages = Vec::new();
+    let mut direct = Vec::new();
+    let mut section = "";
+
+    for line in content.lines() {
+        if !line.starts_with(' ') {
+            section = line.trim();
+            continue;
+        }
+
+        let indent = line.len() - line.trim_start().len();
+        let entry = line.trim();
+
+        if section == "DEPENDENCIES" && indent == 2 {
+            let name = entry.split([' ', '!']).next().unwrap_or_default();
+            direct.push(name.to_string());
+        } else if indent == 4
+            && let Some((name, version)) = entry.split_once(" (")
+        {
+            packages.push((name.to_string(), version.trim_end_matches(')').to_string()));
+        }
+    }
+
+    (packages, Some(direct))
+}
+
+// Имя пакета из спецификации `name@range`; у scoped-пакетов `@scope/name@range` первый '@' - часть имени
+fn package_name_from_spec(spec: &str) -> Option<&str> {
+    let index = spec.get(1..)?.find('@')? + 1;
+    Some(&spec[..index])
+}
+
+fn package_json_dependencies(dir: &Path, fs: &dyn FileSystem) -> Option<Vec<String>> {
+    let value = serde_json::from_str::<Value>(&fs.read_to_string(&dir.join("package.json")).ok()?).ok()?;
+    let mut names = Vec::new();
+
+    for section in ["dependencies", "devDependencies", "optionalDependencies"] {
+        for (name, _) in value.get(section).and_then(Value::as_object).into_iter().flatten() {
+            names.push(name.clone());
+        }
+    }
+
+    Some(names)
+}
+
+fn composer_json_dependencies(dir: &Path, fs: &dyn FileSystem) -> Option<Vec<String>> {
+    let value = serde_json::from_str::<Value>(&fs.read_to_string(&dir.join("composer.json")).ok()?).ok()?;
+    let mut names = Vec::new();
+
+    for section in ["require", "require-dev"] {
+        for (name, _) in value.get(section).and_then(Value::as_object).into_iter().flatten() {
+            // Платформенные требования (php, ext-*) не являются пакетами
+            if name.contains('/') {
+                names.push(name.clone());
+            }
+        }
+    }
+
+    Some(names)
+}
+
+fn pyproject_dependencies(dir: &Path, fs: &dyn FileSystem) -> Option<Vec<String>> {
+    let value: toml::Value = toml::from_str(&fs.read_to_string(&dir.join("pyproject.toml")).ok()?).ok()?;
+    let mut names = Vec::new();
+
+    // PEP 621: строки вида `requests>=2.0; python_version > "3.8"`
+    let project = value.get("project");
+    let optional = project
+        .and_then(|p| p.get("optional-dependencies"))
+        .and_then(|v| v.as_table())
+        .into_iter()
+        .flat_map(|table| table.values());
+    for list in project.and_then(|p| p.get("dependencies")).into_iter().chain(optional) {
+        for requirement in list.as_array().into_iter().flatten().filter_map(|v| v.as_str()) {
+            let name = requirement
+                .split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_' || c == '.'))
+                .next()
+                .unwrap_or_default();
+            names.push(name.to_string());
+        }
+    }
+
+    // Poetry: таблицы [tool.poetry.dependencies] и [tool.poetry.group.<name>.dependencies]
+    if let Some(poetry) = value.get("tool").and_then(|t| t.get("poetry")) {
+        let groups = poetry
+            .get("group")
+            .and_then(|v| v.as_table())
+            .into_iter()
+            .flat_map(|table| table.values())
+            .filter_map(|group| group.get("dependencies"));
+        for table in poetry.get("dependencies").into_iter().chain(groups) {
+            for name in table.as_table().into_iter().flat_map(|table| table.keys()) {
+                if name != "python" {
+                    names.push(name.clone());
+                }
+            }
+        }
+    }
+
+    Some(names)
+}
+
+fn pipfile_dependencies(dir: &Path, fs: &dyn FileSystem) -> Option<Vec<String>> {
+    let value: toml::Value = toml::from_str(&fs.read_to_string(&dir.join("Pipfile")).ok()?).ok()?;
+
+    Some(
+        ["packages", "dev-packages"]
+            .iter()
+            .filter_map(|section| value.get(section).and_then(|v| v.as_table()))
+            .flat_map(|table| table.keys().cloned())
+            .collect(),
+    )
+}
+
+// PEP 503: регистр и разделители `-`, `_`, `.` в именах пакетов не различаются
+fn normalize_python_name(name: &str) -> String {
+    name.to_lowercase().replace(['_', '.'], "-")
+}
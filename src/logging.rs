@@ -0,0 +1,174 @@
+use std::env;
+use std::fmt;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde_json::json;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Level, Span, Subscriber};
+use tracing_subscriber::filter::{EnvFilter, FilterExt, LevelFilter, filter_fn};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{Layer, Registry};
+
+use crate::escape::display_path;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogFormat {
+    Pretty,
+    Json,
+}
+
+// Span на каждую обходимую директорию. Потоки параллельного обхода входят в span
+// родительской директории (Span::in_scope), поэтому цепочка родителей не теряется
+pub fn directory_span(dir: &Path) -> Span {
+    let span = tracing::trace_span!(target: "proj2tree::walk", "directory", directory = %display_path(dir));
+    span.in_scope(|| tracing::trace!(target: "proj2tree::walk", "Обход директории"));
+    span
+}
+
+// Значение поля directory, сохраненное при создании span
+struct Directory(String);
+
+#[derive(Default)]
+struct Fields {
+    message: String,
+    directory: Option<String>,
+}
+
+impl Visit for Fields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "directory" => self.directory = Some(value.to_string()),
+            "message" => self.message = value.to_string(),
+            _ => {}
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        match field.name() {
+            "directory" => self.directory = Some(format!("{:?}", value)),
+            "message" => self.message = format!("{:?}", value),
+            _ => {}
+        }
+    }
+}
+
+// Вывод событий в stderr в формате --log-format
+struct Output {
+    format: LogFormat,
+}
+
+impl<S> Layer<S> for Output
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut fields = Fields::default();
+        attrs.record(&mut fields);
+        if let (Some(directory), Some(span)) = (fields.directory, ctx.span(id)) {
+            span.extensions_mut().insert(Directory(directory));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        // Директории от корня обхода к самой вложенной
+        let spans: Vec<String> = ctx
+            .event_scope(event)
+            .map(|scope| {
+                scope
+                    .from_root()
+                    .filter_map(|span| span.extensions().get::<Directory>().map(|directory| directory.0.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let metadata = event.metadata();
+        let line = match self.format {
+            LogFormat::Pretty => format_pretty(metadata.level(), metadata.target(), &fields.message, spans.last()),
+            LogFormat::Json => format_json(metadata.level(), metadata.target(), &fields.message, &spans),
+        };
+        let _ = writeln!(io::stderr().lock(), "{}", line);
+    }
+}
+
+// Логи пишутся в stderr, чтобы не смешиваться с документом при выводе в консоль.
+// RUST_LOG фильтрует события (`warn,proj2tree::skip=debug`); span директорий создаются
+// всегда, чтобы отладочные события из пропущенного фильтром модуля обхода сохраняли контекст
+pub fn init(format: LogFormat) {
+    let filter = EnvFilter::builder()
+        .with_default_directive(LevelFilter::INFO.into())
+        .parse_lossy(env::var("RUST_LOG").unwrap_or_default());
+    let layer = Output { format }.with_filter(filter.or(filter_fn(|metadata| metadata.is_span())));
+    let _ = Registry::default().with(layer).try_init();
+}
+
+fn format_pretty(level: &Level, target: &str, message: &str, span: Option<&String>) -> String {
+    match *level {
+        Level::ERROR => format!("Ошибка: {}", message),
+        Level::WARN => format!("Предупреждение: {}", message),
+        Level::INFO => message.to_string(),
+        level => match span {
+            Some(span) => format!("[{} {}] {} (в {})", level, target, message, span),
+            None => format!("[{} {}] {}", level, target, message),
+        },
+    }
+}
+
+fn format_json(level: &Level, target: &str, message: &str, spans: &[String]) -> String {
+    let mut line = json!({
+        "timestamp": format_timestamp(SystemTime::now()),
+        "level": level.as_str(),
+        "target": target,
+    });
+    if let Some(span) = spans.last() {
+        line["span"] = json!({ "directory": span });
+        line["spans"] = spans.iter().map(|span| json!({ "directory": span })).collect();
+    }
+    line["message"] = json!(message);
+    line.to_string()
+}
+
+// RFC 3339 в UTC, например 2024-05-01T12:30:00.123Z
+pub fn format_timestamp(time: SystemTime) -> String {
+    let duration = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = duration.as_secs();
+    let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+    let seconds_of_day = seconds % 86_400;
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        seconds_of_day % 3600 / 60,
+        seconds_of_day % 60,
+        duration.subsec_millis()
+    )
+}
+
+// Дата в UTC, например 2024-05-01
+pub fn format_date(time: SystemTime) -> String {
+    let seconds = time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// Алгоритм Говарда Хиннанта: число дней от 1970-01-01 -> (год, месяц, день)
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
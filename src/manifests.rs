@@ -0,0 +1,262 @@
+use std::io::{self, Write};
+use std::path::Path;
+use serde_json::Value;
+
+use crate::escape::{code_span, display_path};
+use crate::notices;
+use crate::{AppConfig, heading, relative_path, tree};
+
+// Раздел --deps: зависимости из манифестов проекта (Cargo.toml, package.json, pyproject.toml,
+// go.mod) с требованиями к версиям - короткий ответ на вопрос «от чего зависит проект»
+// вместо чтения самих манифестов. Манифесты берутся из модели обхода, поэтому
+// node_modules, vendor и прочие исключенные директории не попадают в раздел
+
+const MANIFESTS: [&str; 4] = ["Cargo.toml", "package.json", "pyproject.toml", "go.mod"];
+
+struct Dependency {
+    name: String,
+    requirement: String,
+    kind: String,
+}
+
+struct Manifest {
+    package: Option<String>,
+    dependencies: Vec<Dependency>,
+}
+
+pub fn write_dependencies_section<W: Write>(
+    base_dir: &Path,
+    nodes: &[tree::TreeNode],
+    writer: &mut W,
+    app_config: &AppConfig,
+) -> io::Result<()> {
+    let mut files = Vec::new();
+    tree::file_paths(nodes, &mut files);
+
+    let doc = app_config.doc;
+    writeln!(writer, "\n{} {}", heading(app_config, 2), doc.dependencies)?;
+
+    for path in files {
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if !MANIFESTS.contains(&name) {
+            continue;
+        }
+        let Ok(content) = app_config.fs.read_to_string(&path) else {
+            continue;
+        };
+        let parsed = match name {
+            "Cargo.toml" => cargo_toml(&content),
+            "package.json" => package_json(&content),
+            "pyproject.toml" => pyproject_toml(&content),
+            _ => Some(go_mod(&content)),
+        };
+        let Some(manifest) = parsed else {
+            notices::add(app_config, notices::Kind::Manifest, Some(&path), "не удалось разобрать манифест");
+            continue;
+        };
+
+        let title = code_span(&display_path(&relative_path(base_dir, &path)));
+        match &manifest.package {
+            Some(package) => writeln!(writer, "\n{} {} - {} {}\n", heading(app_config, 3), title, doc.package, package)?,
+            None => writeln!(writer, "\n{} {}\n", heading(app_config, 3), title)?,
+        }
+
+        if manifest.dependencies.is_empty() {
+            writeln!(writer, "{}", doc.no_dependencies)?;
+            continue;
+        }
+        writeln!(writer, "| {} | {} | {} |", doc.dependency, doc.requirement, doc.dependency_kind)?;
+        writeln!(writer, "|---|---|---|")?;
+        for dependency in &manifest.dependencies {
+            writeln!(
+                writer,
+                "| {} | {} | {} |",
+                code_span(&dependency.name),
+                table_cell(&dependency.requirement),
+                dependency.kind
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn cargo_toml(content: &str) -> Option<Manifest> {
+    let value: toml::Value = toml::from_str(content).ok()?;
+    let package = value.get("package").and_then(|p| p.get("name")).and_then(|v| v.as_str()).map(str::to_string);
+
+    let mut dependencies = Vec::new();
+    for kind in ["dependencies", "dev-dependencies", "build-dependencies"] {
+        cargo_table(value.get(kind), kind, &mut dependencies);
+    }
+    // [target.'cfg(unix)'.dependencies] и аналогичные
+    for (target, table) in value.get("target").and_then(|v| v.as_table()).into_iter().flatten() {
+        for kind in ["dependencies", "dev-dependencies", "build-dependencies"] {
+            cargo_table(table.get(kind), &format!("{} ({})", kind, target), &mut dependencies);
+        }
+    }
+    cargo_table(value.get("workspace").and_then(|w| w.get("dependencies")), "workspace", &mut dependencies);
+
+    Some(Manifest { package, dependencies })
+}
+
+fn cargo_table(table: Option<&toml::Value>, kind: &str, dependencies: &mut Vec<Dependency>) {
+    for (name, spec) in table.and_then(|v| v.as_table()).into_iter().flatten() {
+        dependencies.push(Dependency { name: name.clone(), requirement: cargo_requirement(spec), kind: kind.to_string() });
+    }
+}
+
+// `serde = "1"`, `serde = { version = "1" }`, а без версии - источник: путь, git или workspace
+fn cargo_requirement(spec: &toml::Value) -> String {
+    if let Some(version) = spec.as_str() {
+        return version.to_string();
+    }
+    let field = |key| spec.get(key).and_then(|v: &toml::Value| v.as_str());
+    if let Some(version) = field("version") {
+        return version.to_string();
+    }
+    if let Some(path) = field("path") {
+        return format!("path = {}", path);
+    }
+    if let Some(git) = field("git") {
+        return match field("tag").or(field("branch")).or(field("rev")) {
+            Some(reference) => format!("git = {} ({})", git, reference),
+            None => format!("git = {}", git),
+        };
+    }
+    if spec.get("workspace").and_then(|v| v.as_bool()) == Some(true) {
+        return "workspace".to_string();
+    }
+    "*".to_string()
+}
+
+fn package_json(content: &str) -> Option<Manifest> {
+    let value = serde_json::from_str::<Value>(content).ok()?;
+    let package = value.get("name").and_then(Value::as_str).map(str::to_string);
+
+    let mut dependencies = Vec::new();
+    for kind in ["dependencies", "devDependencies", "peerDependencies", "optionalDependencies"] {
+        for (name, requirement) in value.get(kind).and_then(Value::as_object).into_iter().flatten() {
+            dependencies.push(Dependency {
+                name: name.clone(),
+                requirement: requirement.as_str().unwrap_or("*").to_string(),
+                kind: kind.to_string(),
+            });
+        }
+    }
+
+    Some(Manifest { package, dependencies })
+}
+
+fn pyproject_toml(content: &str) -> Option<Manifest> {
+    let value: toml::Value = toml::from_str(content).ok()?;
+    let project = value.get("project");
+    let poetry = value.get("tool").and_then(|t| t.get("poetry"));
+    let package = project
+        .or(poetry)
+        .and_then(|p| p.get("name"))
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+
+    let mut dependencies = Vec::new();
+
+    // PEP 621: строки вида `requests>=2.0; python_version > "3.8"`
+    let requirements = |list: Option<&toml::Value>, kind: &str, dependencies: &mut Vec<Dependency>| {
+        for requirement in list.and_then(|v| v.as_array()).into_iter().flatten().filter_map(|v| v.as_str()) {
+            let end = requirement
+                .find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_' || c == '.'))
+                .unwrap_or(requirement.len());
+            let (name, rest) = requirement.split_at(end);
+            let rest = rest.trim();
+            dependencies.push(Dependency {
+                name: name.to_string(),
+                requirement: if rest.is_empty() { "*".to_string() } else { rest.to_string() },
+                kind: kind.to_string(),
+            });
+        }
+    };
+    requirements(project.and_then(|p| p.get("dependencies")), "dependencies", &mut dependencies);
+    for (group, list) in project
+        .and_then(|p| p.get("optional-dependencies"))
+        .and_then(|v| v.as_table())
+        .into_iter()
+        .flatten()
+    {
+        requirements(Some(list), &format!("optional ({})", group), &mut dependencies);
+    }
+
+    // Poetry: таблицы [tool.poetry.dependencies] и [tool.poetry.group.<name>.dependencies]
+    if let Some(poetry) = poetry {
+        poetry_table(poetry.get("dependencies"), "tool.poetry", &mut dependencies);
+        for (group, table) in poetry.get("group").and_then(|v| v.as_table()).into_iter().flatten() {
+            poetry_table(table.get("dependencies"), &format!("tool.poetry ({})", group), &mut dependencies);
+        }
+    }
+
+    Some(Manifest { package, dependencies })
+}
+
+fn poetry_table(table: Option<&toml::Value>, kind: &str, dependencies: &mut Vec<Dependency>) {
+    for (name, spec) in table.and_then(|v| v.as_table()).into_iter().flatten() {
+        if name == "python" {
+            continue;
+        }
+        // Табличная форма Poetry совпадает с Cargo по полям version, path и git
+        dependencies.push(Dependency { name: name.clone(), requirement: cargo_requirement(spec), kind: kind.to_string() });
+    }
+}
+
+// go.mod: одиночные `require path v1.2.3` и блоки `require ( ... )`; косвенные зависимости
+// помечены комментарием `// indirect`
+fn go_mod(content: &str) -> Manifest {
+    let mut package = None;
+    let mut dependencies = Vec::new();
+    let mut in_block = false;
+
+    for line in content.lines() {
+        let line = line.trim();
+        let (code, comment) = match line.split_once("//") {
+            Some((code, comment)) => (code.trim(), comment.trim()),
+            None => (line, ""),
+        };
+
+        let requirement = if in_block {
+            if code == ")" {
+                in_block = false;
+                continue;
+            }
+            code
+        } else if let Some(module) = code.strip_prefix("module ") {
+            package = Some(module.trim().trim_matches('"').to_string());
+            continue;
+        } else if let Some(rest) = code.strip_prefix("require") {
+            let rest = rest.trim();
+            if rest == "(" {
+                in_block = true;
+                continue;
+            }
+            rest
+        } else {
+            continue;
+        };
+
+        let mut parts = requirement.split_whitespace();
+        let (Some(name), Some(version)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        let kind = if comment == "indirect" { "require (indirect)" } else { "require" };
+        dependencies.push(Dependency {
+            name: name.trim_matches('"').to_string(),
+            requirement: version.to_string(),
+            kind: kind.to_string(),
+        });
+    }
+
+    Manifest { package, dependencies }
+}
+
+fn table_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
@@ -0,0 +1,69 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use ignore::gitignore::Gitignore;
+
+use crate::atomic_file::AtomicFile;
+use crate::bundle::{archive_entry_name, percent_encode};
+use crate::doc_lang::fill;
+use crate::escape::{code_span, display_path};
+use crate::{
+    AppConfig, Config, build_model, content_files, heading, relative_path, walk_errors, write_document_header,
+    write_file_content, write_tree_block,
+};
+
+const INDEX_NAME: &str = "index.md";
+
+// `--format md-dir OUT_DIR`: по странице markdown на каждый включенный файл с той же
+// структурой директорий (`src/main.rs` -> `OUT_DIR/src/main.rs.md`) и индекс со списком
+// страниц - для вики с ограничением размера страницы
+pub fn write_pages(
+    base_dir: &Path,
+    out_dir: &Path,
+    app_config: &AppConfig,
+    config: &Config,
+    gitignore_matcher: &Option<Gitignore>,
+) -> io::Result<usize> {
+    let nodes = build_model(base_dir, app_config, config, gitignore_matcher)?;
+    let files = if app_config.include_contents { content_files(&nodes, app_config, config) } else { Vec::new() };
+
+    fs::create_dir_all(out_dir)?;
+    let mut index = AtomicFile::create(&out_dir.join(INDEX_NAME))?;
+    let source = if base_dir == Path::new(".") {
+        app_config.doc.current_directory.to_string()
+    } else {
+        display_path(base_dir)
+    };
+    write_document_header(&mut index, &source, app_config)?;
+
+    if app_config.include_tree {
+        writeln!(index, "{} {}\n", heading(app_config, 2), app_config.doc.file_tree)?;
+        write_tree_block(base_dir, base_dir, &nodes, &mut index, app_config, config)?;
+    }
+
+    if app_config.include_contents {
+        writeln!(index, "{} {}\n", heading(app_config, 2), app_config.doc.file_contents)?;
+    }
+    for path in &files {
+        let relative_path = relative_path(base_dir, path);
+        let page_name = format!("{}.md", archive_entry_name(&relative_path));
+        writeln!(index, "- [{}](<{}>)", code_span(&display_path(&relative_path)), percent_encode(&page_name))?;
+
+        let page_path = out_dir.join(&page_name);
+        if let Some(parent) = page_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut page = AtomicFile::create(&page_path)?;
+        // Ссылка на индекс отсчитывается от директории страницы
+        let index_link = format!("{}{}", "../".repeat(page_name.matches('/').count()), INDEX_NAME);
+        writeln!(page, "{} {}\n", heading(app_config, 1), code_span(&display_path(&relative_path)))?;
+        writeln!(page, "{}\n", fill(app_config.doc.parent, &[&format!("[{}]({})", INDEX_NAME, index_link)]))?;
+        write_file_content(&mut page, path, app_config, config)?;
+        page.commit()?;
+    }
+
+    walk_errors::write_summary(base_dir, &mut index, app_config)?;
+    index.commit()?;
+
+    Ok(files.len())
+}
@@ -0,0 +1,111 @@
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::doc_lang::fill;
+use crate::escape::{code_span, display_path};
+use crate::notices;
+use crate::tree::{self, TreeNode};
+use crate::{AppConfig, Config, content_files, heading, relative_path};
+
+// Следы незавершенного слияния: маркеры конфликта в содержимом файлов и файлы, которые
+// оставляют `git merge`, `git mergetool` и `patch`. Раздел выводится сразу под заголовком
+// документа, чтобы его нельзя было пропустить (отключается --no-merge-check)
+
+const LEFTOVER_EXTENSIONS: [&str; 2] = ["orig", "rej"];
+// git mergetool: `main_BACKUP_12345.rs`, `main_BASE_12345.rs`, ...
+const MERGETOOL_PARTS: [&str; 4] = ["_BACKUP_", "_BASE_", "_LOCAL_", "_REMOTE_"];
+
+enum Finding {
+    // Число конфликтов и строка первого из них
+    Conflicts(usize, usize),
+    Leftover,
+}
+
+pub fn write_warnings<W: Write>(
+    base_dir: &Path,
+    nodes: &[TreeNode],
+    writer: &mut W,
+    app_config: &AppConfig,
+    config: &Config,
+) -> io::Result<()> {
+    let mut findings: Vec<(PathBuf, Finding)> = Vec::new();
+
+    for path in content_files(nodes, app_config, config) {
+        if let Ok(content) = app_config.fs.read_to_string(&path)
+            && let Some((count, line)) = find_conflicts(&content)
+        {
+            findings.push((path, Finding::Conflicts(count, line)));
+        }
+    }
+
+    let mut files = Vec::new();
+    tree::file_paths(nodes, &mut files);
+    for path in files {
+        if is_leftover(&path) && !findings.iter().any(|(found, _)| *found == path) {
+            findings.push((path, Finding::Leftover));
+        }
+    }
+
+    if findings.is_empty() {
+        return Ok(());
+    }
+    findings.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let doc = app_config.doc;
+    writeln!(writer, "{} {}\n", heading(app_config, 2), doc.merge_artifacts)?;
+    writeln!(writer, "{}\n", doc.merge_artifacts_note)?;
+    for (path, finding) in &findings {
+        let item = code_span(&display_path(&relative_path(base_dir, path)));
+        let description = match finding {
+            Finding::Conflicts(count, line) => fill(doc.merge_conflicts, &[count, line]),
+            Finding::Leftover => doc.merge_leftover.to_string(),
+        };
+        notices::add(app_config, notices::Kind::MergeArtifact, Some(path), &description);
+        writeln!(writer, "- {} - {}", item, description)?;
+    }
+    writeln!(writer)
+}
+
+// Конфликт - строка `<<<<<<<` с парной `>>>>>>>` после нее. Одиночные `=======`
+// не учитываются: так подчеркиваются заголовки Markdown и reStructuredText
+fn find_conflicts(content: &str) -> Option<(usize, usize)> {
+    let mut count = 0;
+    let mut first_line = 0;
+    let mut open_line = None;
+
+    for (number, line) in content.lines().enumerate() {
+        if is_marker(line, '<') {
+            open_line = Some(number + 1);
+        } else if is_marker(line, '>')
+            && let Some(line) = open_line.take()
+        {
+            count += 1;
+            if first_line == 0 {
+                first_line = line;
+            }
+        }
+    }
+
+    (count > 0).then_some((count, first_line))
+}
+
+// Ровно семь символов, за которыми конец строки или пробел и имя ветки
+fn is_marker(line: &str, c: char) -> bool {
+    let line = line.trim_end_matches('\r');
+    line.len() >= 7
+        && line.chars().take(7).all(|x| x == c)
+        && line[7..].chars().next().is_none_or(|next| next == ' ')
+}
+
+fn is_leftover(path: &Path) -> bool {
+    let extension = path.extension().map(|extension| extension.to_string_lossy().to_lowercase());
+    if extension.is_some_and(|extension| LEFTOVER_EXTENSIONS.contains(&extension.as_str())) {
+        return true;
+    }
+
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    MERGETOOL_PARTS.iter().any(|part| {
+        stem.rsplit_once(part)
+            .is_some_and(|(name, pid)| !name.is_empty() && !pid.is_empty() && pid.bytes().all(|b| b.is_ascii_digit()))
+    })
+}
@@ -0,0 +1,151 @@
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use serde_json::{Value, json};
+
+use crate::escape::display_path;
+use crate::schema;
+use crate::skip_stats;
+use crate::{AppConfig, relative_path};
+
+// Замечания о проекте (нет .gitignore, файл не разбирается, битый UTF-8 и т.п.) копятся
+// за время запуска и выводятся одной сводкой в конце, а не строкой на каждое событие:
+// на большом проекте сотни одинаковых предупреждений заслоняют настоящие проблемы.
+// Отдельные события видны на уровне debug (RUST_LOG=proj2tree::notices=debug).
+// --report json выводит сводку в stderr JSON-объектом, --fail-on-warning завершает
+// запуск с ошибкой, если замечания есть. Туда же попадает статистика исключений (skip_stats.rs)
+
+// Сколько путей перечислять в строке сводки
+const LISTED_PATHS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Kind {
+    NoGitignore,
+    WalkError,
+    Deleted,
+    MergeArtifact,
+    Syntax,
+    LossyUtf8,
+    Manifest,
+    CloudPlaceholder,
+    Summarizer,
+}
+
+impl Kind {
+    fn code(self) -> &'static str {
+        match self {
+            Kind::NoGitignore => "no-gitignore",
+            Kind::WalkError => "walk-error",
+            Kind::Deleted => "deleted",
+            Kind::MergeArtifact => "merge-artifact",
+            Kind::Syntax => "syntax",
+            Kind::LossyUtf8 => "lossy-utf8",
+            Kind::Manifest => "manifest",
+            Kind::CloudPlaceholder => "cloud-placeholder",
+            Kind::Summarizer => "summarizer",
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            Kind::NoGitignore => "файл .gitignore не найден",
+            Kind::WalkError => "элементы пропущены из-за ошибок",
+            Kind::Deleted => "файлы удалены во время запуска",
+            Kind::MergeArtifact => "следы незавершенного слияния",
+            Kind::Syntax => "файлы не разбираются",
+            Kind::LossyUtf8 => "некорректный UTF-8 заменен",
+            Kind::Manifest => "манифесты не разобраны",
+            Kind::CloudPlaceholder => "облачные файлы-заглушки не загружались",
+            Kind::Summarizer => "команды summarizers завершились с ошибкой",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Notice {
+    kind: Kind,
+    path: Option<PathBuf>,
+    message: String,
+}
+
+// Одно и то же событие может встретиться несколько раз (дерево и содержимое, --split-tests):
+// в сводке оно учитывается один раз
+pub fn add(app_config: &AppConfig, kind: Kind, path: Option<&Path>, message: &str) {
+    match path {
+        Some(path) => tracing::debug!("{}: {}", path.display(), message),
+        None => tracing::debug!("{}", message),
+    }
+
+    let mut notices = app_config.notices.lock().unwrap();
+    if notices.iter().any(|notice| notice.kind == kind && notice.path.as_deref() == path) {
+        return;
+    }
+    notices.push(Notice { kind, path: path.map(Path::to_path_buf), message: message.to_string() });
+}
+
+// Сводка в конце запуска. Ошибки обхода хранятся отдельно (они попадают в документ)
+// и добавляются к замечаниям только здесь
+pub fn finish(app_config: &AppConfig) -> io::Result<()> {
+    let mut notices: Vec<Notice> = std::mem::take(&mut *app_config.notices.lock().unwrap());
+    for (path, message) in app_config.walk_errors.lock().unwrap().iter() {
+        notices.push(Notice { kind: Kind::WalkError, path: Some(path.clone()), message: message.clone() });
+    }
+    notices.sort_by(|a, b| a.kind.cmp(&b.kind).then_with(|| a.path.cmp(&b.path)));
+
+    let base_dir = Path::new(&app_config.target_dir);
+    if app_config.report_json {
+        write_json(&mut io::stderr().lock(), base_dir, &notices, skip_stats::to_json(app_config))?;
+    } else {
+        skip_stats::write_text(app_config);
+        write_text(base_dir, &notices);
+    }
+
+    if app_config.fail_on_warning && !notices.is_empty() {
+        return Err(io::Error::other(format!("предупреждений: {} (--fail-on-warning)", notices.len())));
+    }
+    Ok(())
+}
+
+fn write_text(base_dir: &Path, notices: &[Notice]) {
+    for group in notices.chunk_by(|a, b| a.kind == b.kind) {
+        let kind = group[0].kind;
+        let paths: Vec<String> = group
+            .iter()
+            .filter_map(|notice| notice.path.as_deref())
+            .take(LISTED_PATHS)
+            .map(|path| display_path(&relative_path(base_dir, path)))
+            .collect();
+
+        if paths.is_empty() {
+            tracing::warn!("{}", kind.title());
+        } else if group.len() > paths.len() {
+            tracing::warn!("{} ({}): {} и еще {}", kind.title(), group.len(), paths.join(", "), group.len() - paths.len());
+        } else {
+            tracing::warn!("{} ({}): {}", kind.title(), group.len(), paths.join(", "));
+        }
+    }
+}
+
+fn write_json<W: Write>(writer: &mut W, base_dir: &Path, notices: &[Notice], skipped: Value) -> io::Result<()> {
+    let report = json!({
+        "schema_version": schema::REPORT.version,
+        "notices": json_list(base_dir, notices),
+        "count": notices.len(),
+        "skipped": skipped,
+    });
+    writeln!(writer, "{}", report)
+}
+
+// Замечания массивом JSON для сканирования (scan.rs), без сводки в stderr
+pub fn to_json(base_dir: &Path, app_config: &AppConfig) -> Value {
+    json_list(base_dir, &app_config.notices.lock().unwrap())
+}
+
+fn json_list(base_dir: &Path, notices: &[Notice]) -> Value {
+    notices
+        .iter()
+        .map(|notice| {
+            let path = notice.path.as_deref().map(|path| display_path(&relative_path(base_dir, path)));
+            json!({ "kind": notice.kind.code(), "path": path, "message": notice.message })
+        })
+        .collect()
+}
@@ -0,0 +1,138 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Component, Path, PathBuf};
+use ignore::gitignore::Gitignore;
+
+use crate::atomic_file::AtomicFile;
+use crate::doc_lang::fill;
+use crate::escape::display_os_str;
+use crate::tree::{self, NodeKind, TreeNode};
+use crate::{AppConfig, Config, build_model, content_files, get_file_extension, relative_path, walk_errors, write_file_content};
+
+// `--format obsidian VAULT_DIR`: заметка на каждый файл и директорию из общей модели дерева,
+// связанные wiki-ссылками по структуре директорий, и индексная MOC-заметка
+
+pub fn write_vault(
+    base_dir: &Path,
+    vault_dir: &Path,
+    app_config: &AppConfig,
+    config: &Config,
+    gitignore_matcher: &Option<Gitignore>,
+) -> io::Result<()> {
+    let nodes = build_model(base_dir, app_config, config, gitignore_matcher)?;
+    // Содержимое получают те же файлы, что и в разделе содержимого документа
+    let included: HashSet<PathBuf> =
+        if app_config.include_contents { content_files(&nodes, app_config, config).into_iter().collect() } else { HashSet::new() };
+
+    fs::create_dir_all(vault_dir)?;
+
+    let project_name = project_name(base_dir);
+    let moc_name = format!("{} MOC.md", project_name);
+    let moc_link = format!("[[{}|{}]]", moc_name, link_alias(&project_name));
+
+    let children = write_directory_notes(base_dir, &nodes, vault_dir, &moc_link, &included, app_config, config)?;
+
+    // Индексная заметка (Map of Content) - точка входа в хранилище
+    let mut moc = AtomicFile::create(&vault_dir.join(&moc_name))?;
+    writeln!(moc, "---\ntags: [proj2tree/moc]\n---\n")?;
+    writeln!(moc, "# {}: {}\n", app_config.doc.project_structure, project_name)?;
+    for child in children {
+        writeln!(moc, "- {}", child)?;
+    }
+    walk_errors::write_summary(base_dir, &mut moc, app_config)?;
+    moc.commit()
+}
+
+// Создает заметки для узлов директории и возвращает список ссылок на них
+fn write_directory_notes(
+    base_dir: &Path,
+    nodes: &[TreeNode],
+    vault_dir: &Path,
+    parent_link: &str,
+    included: &HashSet<PathBuf>,
+    app_config: &AppConfig,
+    config: &Config,
+) -> io::Result<Vec<String>> {
+    let mut links = Vec::new();
+
+    for node in nodes {
+        let path = &node.path;
+        let name = display_os_str(&node.name);
+        let note_path = vault_note_path(&relative_path(base_dir, path));
+
+        match &node.kind {
+            NodeKind::Directory(children) => {
+                let dir_link = format!("[[{}.md|{}/]]", note_path, link_alias(&name));
+
+                fs::create_dir_all(vault_dir.join(&note_path))?;
+                let children =
+                    write_directory_notes(base_dir, children, vault_dir, &dir_link, included, app_config, config)?;
+
+                let mut note = AtomicFile::create(&vault_dir.join(format!("{}.md", note_path)))?;
+                writeln!(note, "---\ntags: [proj2tree/directory]\n---\n")?;
+                writeln!(note, "# {}/\n", name)?;
+                writeln!(note, "{}\n", fill(app_config.doc.parent, &[&parent_link]))?;
+                for child in children {
+                    writeln!(note, "- {}", child)?;
+                }
+                note.commit()?;
+
+                links.push(dir_link);
+            }
+            NodeKind::File(_) => {
+                let file_link = format!("[[{}.md|{}]]", note_path, link_alias(&name));
+
+                let mut note = AtomicFile::create(&vault_dir.join(format!("{}.md", note_path)))?;
+                writeln!(note, "---\ntags: [proj2tree/file, lang/{}]\n---\n", get_file_extension(path, config))?;
+                writeln!(note, "# {}\n", name)?;
+                writeln!(note, "{}\n", fill(app_config.doc.directory, &[&parent_link]))?;
+
+                if !app_config.include_contents {
+                    // Заметка нужна только для графа связей
+                } else if included.contains(path) {
+                    write_file_content(&mut note, path, app_config, config)?;
+                } else {
+                    writeln!(note, "{}", app_config.doc.content_not_included)?;
+                }
+                note.commit()?;
+
+                links.push(file_link);
+            }
+            _ => links.extend(tree::label(node, app_config)),
+        }
+    }
+
+    Ok(links)
+}
+
+// Путь заметки внутри хранилища всегда записывается через '/', как того ожидают wiki-ссылки.
+// Символы, которые Obsidian не допускает в цели ссылки, заменяются на '_'
+fn vault_note_path(relative_path: &Path) -> String {
+    relative_path
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(
+                display_os_str(part)
+                    .chars()
+                    .map(|c| if "[]|#^\\:".contains(c) { '_' } else { c })
+                    .collect::<String>(),
+            ),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+// Псевдоним ссылки не может содержать '|' и ']]'
+fn link_alias(name: &str) -> String {
+    name.replace('|', "\\|").replace("]]", "] ]")
+}
+
+fn project_name(base_dir: &Path) -> String {
+    base_dir
+        .canonicalize()
+        .ok()
+        .and_then(|path| path.file_name().map(|name| name.to_string_lossy().to_string()))
+        .unwrap_or_else(|| "project".to_string())
+}
@@ -0,0 +1,259 @@
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use clap::ArgMatches;
+use ignore::gitignore::Gitignore;
+
+use crate::{
+    AppConfig, Config, OutputFormat, atomic_file, bundle, chunk, interrupt, jsonl, md_dir, obsidian, pager, prefixed_path,
+    render, render_document, search_index, sidecar, update, write_markdown_format,
+};
+
+// Вывод снимка в выбранном формате: хранилище obsidian, страницы md-dir, архив bundle, поисковый
+// индекс, события jsonl, обновление (--update) и части (--chunk) markdown, иначе документ рендерера
+
+// Параметры, которые выбранный формат или режим не поддерживает, пропускаются с предупреждением
+pub fn warn_ignored_options(matches: &ArgMatches, app_config: &AppConfig) {
+    let bench_matches = matches.subcommand_matches("bench");
+    let daemon_matches = matches.subcommand_matches("daemon");
+
+    if !app_config.explicit_files.is_empty()
+        && (app_config.format != OutputFormat::Markdown
+            || matches.get_flag("stdin-tar")
+            || matches.contains_id("at")
+            || bench_matches.is_some())
+    {
+        tracing::warn!("отдельные файлы из командной строки выводятся только в документ markdown и будут пропущены");
+    }
+    
+    if app_config.chunk.is_some()
+        && (app_config.format != OutputFormat::Markdown
+            || app_config.update_prefix.is_some()
+            || matches.get_flag("stdin-tar")
+            || matches.contains_id("at")
+            || bench_matches.is_some()
+            || daemon_matches.is_some())
+    {
+        tracing::warn!("--chunk применяется только к документу markdown и будет проигнорирован");
+    }
+    
+    if app_config.sidecar
+        && (app_config.print_to_console
+            || !matches!(app_config.format, OutputFormat::Markdown | OutputFormat::Renderer(_))
+            || app_config.update_prefix.is_some()
+            || app_config.chunk.is_some()
+            || matches.get_flag("stdin-tar")
+            || matches.contains_id("at")
+            || bench_matches.is_some()
+            || daemon_matches.is_some())
+    {
+        tracing::warn!("--sidecar записывается только рядом с документом в файле и будет проигнорирован");
+    }
+    
+    let html_bundle = app_config.format == OutputFormat::Bundle && app_config.bundle_format.as_deref() == Some("html");
+    if (app_config.theme.is_some() || app_config.custom_css.is_some())
+        && app_config.format != OutputFormat::Renderer("html".to_string())
+        && !html_bundle
+    {
+        tracing::warn!("--theme и --css применяются только к формату html и будут проигнорированы");
+    }
+    
+    if !app_config.embeds.is_empty()
+        && (app_config.format != OutputFormat::Markdown || matches.get_flag("stdin-tar") || matches.contains_id("at"))
+    {
+        tracing::warn!("--embed применяется только к документу markdown и будет проигнорирован");
+    }
+    
+    if app_config.bundle_format.is_some() && app_config.format != OutputFormat::Bundle {
+        tracing::warn!("--bundle-format применяется только к формату bundle и будет проигнорирован");
+    }
+    
+    if app_config.update_prefix.is_some() && matches!(app_config.format, OutputFormat::Renderer(_)) {
+        tracing::warn!("--update применяется только к документу markdown и будет проигнорирован");
+    }
+}
+
+// Якоря выводят только markdown и html; в частях --chunk и разделах --update карта разошлась бы с файлом
+pub fn anchor_map<'a>(matches: &ArgMatches, app_config: &'a AppConfig) -> Option<&'a Path> {
+    let bench_matches = matches.subcommand_matches("bench");
+    let daemon_matches = matches.subcommand_matches("daemon");
+
+    app_config.anchor_map.as_deref().filter(|_| {
+        let ignored = !matches!(&app_config.format, OutputFormat::Markdown)
+            && app_config.format != OutputFormat::Renderer("html".to_string())
+            || app_config.update_prefix.is_some()
+            || app_config.chunk.is_some()
+            || matches.get_flag("stdin-tar")
+            || matches.contains_id("at")
+            || bench_matches.is_some()
+            || daemon_matches.is_some();
+        if ignored {
+            tracing::warn!("--anchor-map записывается только для документа markdown или html и будет проигнорирован");
+        }
+        !ignored
+    })
+}
+
+pub fn write(
+    renderer: &dyn render::Renderer,
+    base_dir: &Path,
+    app_config: &AppConfig,
+    config: &Config,
+    gitignore_matcher: &Option<Gitignore>,
+) -> io::Result<()> {
+    if app_config.format == OutputFormat::Obsidian {
+        let Some(vault_dir) = &app_config.output_file else {
+            tracing::error!("для формата obsidian укажите директорию хранилища");
+            std::process::exit(1);
+        };
+        if app_config.print_to_console {
+            tracing::error!("формат obsidian не поддерживает вывод в консоль");
+            std::process::exit(1);
+        }
+        
+        obsidian::write_vault(base_dir, Path::new(vault_dir), app_config, config, gitignore_matcher)?;
+        tracing::info!("Хранилище Obsidian сохранено в: {}", vault_dir);
+    } else if app_config.format == OutputFormat::MdDir {
+        let Some(out_dir) = &app_config.output_file else {
+            tracing::error!("для формата md-dir укажите выходную директорию");
+            std::process::exit(1);
+        };
+        if app_config.print_to_console {
+            tracing::error!("формат md-dir не поддерживает вывод в консоль");
+            std::process::exit(1);
+        }
+        
+        let pages = md_dir::write_pages(base_dir, Path::new(out_dir), app_config, config, gitignore_matcher)?;
+        tracing::info!("Страниц: {}, индекс сохранен в: {}", pages, Path::new(out_dir).join("index.md").display());
+    } else if app_config.format == OutputFormat::Bundle {
+        if app_config.print_to_console {
+            tracing::error!("формат bundle не поддерживает вывод в консоль");
+            std::process::exit(1);
+        }
+        
+        let archive = app_config.output_file.as_deref().unwrap_or("tree.zip");
+        bundle::write_bundle(renderer, base_dir, Path::new(archive), app_config, config, gitignore_matcher)?;
+        tracing::info!("Архив сохранен в файл: {}", archive);
+    } else if app_config.format == OutputFormat::Index {
+        if app_config.print_to_console {
+            search_index::write_index(base_dir, &mut io::stdout().lock(), app_config, config, gitignore_matcher)?;
+        } else {
+            let index = app_config.output_file.as_deref().unwrap_or("tree.index.json");
+            let mut writer = atomic_file::AtomicFile::create(Path::new(index))?;
+            search_index::write_index(base_dir, &mut writer, app_config, config, gitignore_matcher)?;
+            writer.commit()?;
+            tracing::info!("Поисковый индекс сохранен в файл: {}", index);
+        }
+    } else if app_config.format == OutputFormat::Jsonl {
+        if app_config.print_to_console {
+            jsonl::write_events(base_dir, &mut io::stdout().lock(), app_config, config, gitignore_matcher)?;
+        } else {
+            let events = app_config.output_file.as_deref().unwrap_or("tree.jsonl");
+            let mut writer = atomic_file::AtomicFile::create(Path::new(events))?;
+            jsonl::write_events(base_dir, &mut writer, app_config, config, gitignore_matcher)?;
+            writer.commit()?;
+            tracing::info!("События обхода сохранены в файл: {}", events);
+        }
+    } else if let Some(prefix) = &app_config.update_prefix
+        && app_config.format == OutputFormat::Markdown
+    {
+        if app_config.print_to_console {
+            tracing::error!("--update изменяет выходной файл и не поддерживает вывод в консоль");
+            std::process::exit(1);
+        }
+        
+        let output_file = match &app_config.output_file {
+            Some(file) => PathBuf::from(file),
+            None => base_dir.join("tree.md"),
+        };
+        let existing = fs::read_to_string(&output_file).map_err(|e| {
+            io::Error::new(e.kind(), format!("не удалось прочитать {}: {}", output_file.display(), e))
+        })?;
+        
+        let mut fresh = Vec::new();
+        write_markdown_format(base_dir, &mut fresh, app_config, config, gitignore_matcher)?;
+        // Неполный документ удалил бы из файла разделы, до которых генерация не дошла
+        if interrupt::is_interrupted() {
+            tracing::error!("генерация прервана, {} не изменен", output_file.display());
+            std::process::exit(interrupt::EXIT_CODE);
+        }
+        
+        // Заголовки сравниваются в том виде, в каком выведены (--paths)
+        let prefix = prefixed_path(app_config, prefix);
+        let result = update::update_document(&existing, &String::from_utf8_lossy(&fresh), &prefix);
+        atomic_file::write(&output_file, result.document)?;
+        tracing::info!(
+            "Обновлен файл {}: изменено разделов {}, добавлено {}, удалено {}",
+            output_file.display(),
+            result.updated,
+            result.added,
+            result.removed
+        );
+    } else if let Some(limit) = app_config.chunk
+        && app_config.format == OutputFormat::Markdown
+    {
+        let mut document = Vec::new();
+        write_markdown_format(base_dir, &mut document, app_config, config, gitignore_matcher)?;
+        let chunks = chunk::split(&String::from_utf8_lossy(&document), limit, app_config.doc);
+        
+        if app_config.print_to_console {
+            chunk::print(&mut io::stdout().lock(), &chunks)?;
+        } else {
+            let output_file = match &app_config.output_file {
+                Some(file) => PathBuf::from(file),
+                None => base_dir.join("tree.md"),
+            };
+            chunk::write_files(&output_file, &chunks)?;
+            tracing::info!(
+                "Результат сохранен в файлы {} и далее (частей: {})",
+                chunk::part_path(&output_file, 1).display(),
+                chunks.len()
+            );
+        }
+    } else if app_config.print_to_console {
+        let pager = if app_config.use_pager { pager::start() } else { None };
+        
+        if let Some(mut pager) = pager {
+            let result = match pager.stdin.take() {
+                Some(stdin) => {
+                    let mut writer = BufWriter::new(stdin);
+                    render_document(renderer, base_dir, &mut writer, app_config, config, gitignore_matcher)
+                        .and_then(|_| writer.flush())
+                }
+                None => Ok(()),
+            };
+            pager.wait()?;
+            
+            // Пользователь мог закрыть пейджер, не дочитав вывод
+            match result {
+                Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {}
+                other => other?,
+            }
+        } else {
+            let mut stdout = io::stdout();
+            render_document(renderer, base_dir, &mut stdout, app_config, config, gitignore_matcher)?;
+        }
+    } else {
+        let output_file = if let Some(file) = &app_config.output_file {
+            file.clone()
+        } else {
+            let path = base_dir.join("tree.md");
+            path.to_string_lossy().to_string()
+        };
+        
+        let started = Instant::now();
+        let mut file = atomic_file::AtomicFile::create(Path::new(&output_file))?;
+        let nodes = render_document(renderer, base_dir, &mut file, app_config, config, gitignore_matcher)?;
+        file.commit()?;
+        tracing::info!("Результат сохранен в файл: {}", output_file);
+        
+        // Метаданные неполного документа описывали бы файлы, которых в нем нет
+        if app_config.sidecar && !interrupt::is_interrupted() {
+            let sidecar = sidecar::write(Path::new(&output_file), base_dir, &nodes, started.elapsed(), app_config, config)?;
+            tracing::info!("Метаданные снимка сохранены в файл: {}", sidecar.display());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use std::env;
+use std::io::{self, IsTerminal};
+use std::process::{Child, Command, Stdio};
+
+// Как и git, запускаем пейджер только для интерактивного терминала. Переменная LESS=FRX
+// заставляет less сразу завершиться, если вывод помещается на один экран
+pub fn start() -> Option<Child> {
+    if !io::stdout().is_terminal() {
+        return None;
+    }
+
+    let command = env::var("PROJ2TREE_PAGER")
+        .or_else(|_| env::var("PAGER"))
+        .unwrap_or_else(|_| "less".to_string());
+    let mut parts = command.split_whitespace();
+    let program = parts.next()?;
+    if program == "cat" {
+        return None;
+    }
+
+    let mut pager = Command::new(program);
+    pager.args(parts).stdin(Stdio::piped());
+    if env::var_os("LESS").is_none() {
+        pager.env("LESS", "FRX");
+    }
+
+    // Если пейджер не найден (например, less на Windows), выводим напрямую
+    pager.spawn().ok()
+}
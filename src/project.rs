@@ -0,0 +1,66 @@
+use std::fs;
+use std::path::Path;
+
+use crate::Config;
+
+// Экосистемы определяются по манифестам в корне и в директориях первого уровня (монорепозитории).
+// Для каждой найденной экосистемы к exclude_dirs добавляются ее служебные директории
+const ECOSYSTEMS: [(&str, &[&str], &[&str]); 7] = [
+    ("Rust", &["Cargo.toml"], &["target"]),
+    ("Node.js", &["package.json"], &["node_modules", "dist", "coverage", ".next", ".nuxt", ".turbo"]),
+    ("Go", &["go.mod"], &["vendor", "bin"]),
+    ("Maven", &["pom.xml"], &["target"]),
+    ("Gradle", &["build.gradle", "build.gradle.kts", "settings.gradle"], &["build", ".gradle"]),
+    (
+        "Python",
+        &["pyproject.toml", "setup.py", "requirements.txt", "Pipfile"],
+        &[".venv", "venv", "__pycache__", ".pytest_cache", ".mypy_cache", ".tox", "build", "dist"],
+    ),
+    ("PHP", &["composer.json"], &["vendor"]),
+];
+
+pub fn apply_auto_exclusions(base_dir: &Path, config: &mut Config) {
+    let mut dirs = vec![base_dir.to_path_buf()];
+    if let Ok(entries) = fs::read_dir(base_dir) {
+        dirs.extend(
+            entries
+                .flatten()
+                .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+                .map(|entry| entry.path()),
+        );
+    }
+
+    let mut detected = Vec::new();
+    let mut added = Vec::new();
+
+    for (name, manifests, exclusions) in ECOSYSTEMS {
+        let found = dirs
+            .iter()
+            .any(|dir| manifests.iter().any(|manifest| dir.join(manifest).is_file()));
+        if !found {
+            continue;
+        }
+
+        detected.push(name);
+        for exclusion in exclusions {
+            if !config.exclude_dirs.iter().any(|dir| dir == exclusion) {
+                config.exclude_dirs.push(exclusion.to_string());
+                added.push(*exclusion);
+            }
+        }
+    }
+
+    if detected.is_empty() {
+        return;
+    }
+
+    if added.is_empty() {
+        tracing::info!("Тип проекта: {}", detected.join(", "));
+    } else {
+        tracing::info!(
+            "Тип проекта: {}; автоматически исключены: {} (отключается флагом --no-auto-exclude)",
+            detected.join(", "),
+            added.join(", ")
+        );
+    }
+}
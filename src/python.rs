@@ -0,0 +1,34 @@
+use pyo3::create_exception;
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::scan;
+
+// Модуль Python поверх того же сканера, что и C ABI (ffi.rs). Собирается maturin
+// из bindings/python: import proj2tree; proj2tree.scan_project("repo", only_ext="rs")
+// Параметры - именованные аргументы с ключами длинных флагов CLI, результат - dict
+
+create_exception!(proj2tree, Proj2TreeError, PyRuntimeError);
+
+#[pyfunction]
+#[pyo3(signature = (path, **options))]
+fn scan_project<'py>(py: Python<'py>, path: &str, options: Option<&Bound<'py, PyDict>>) -> PyResult<Bound<'py, PyAny>> {
+    let json = py.import("json")?;
+    let options: String = match options {
+        Some(options) => json.call_method1("dumps", (options,))?.extract()?,
+        None => String::new(),
+    };
+    // Обход может занять секунды: остальные потоки Python в это время не ждут
+    let result = py
+        .allow_threads(|| scan::scan_project(path, &options))
+        .map_err(|e| Proj2TreeError::new_err(e.to_string()))?;
+    json.call_method1("loads", (result,))
+}
+
+#[pymodule]
+fn proj2tree(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(scan_project, module)?)?;
+    module.add("Proj2TreeError", module.py().get_type::<Proj2TreeError>())?;
+    Ok(())
+}
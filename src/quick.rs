@@ -0,0 +1,119 @@
+use std::path::{Component, Path, PathBuf};
+
+use crate::tree::FileInfo;
+use crate::relative_path;
+
+// --quick: обзор репозитория без настройки. Содержимое выводится только для важных файлов -
+// README и манифестов, точек входа, конфигурации в корне и кода в src-подобных директориях, -
+// пока не исчерпан общий бюджет. Остальные файлы видны только в дереве
+
+pub const DEPTH: usize = 3;
+const BUDGET: u64 = 64 * 1024;
+// Один большой файл не должен съедать весь бюджет: README и манифестам допускается больше
+const MAX_FILE: u64 = 16 * 1024;
+const MAX_MANIFEST: u64 = 32 * 1024;
+
+const MANIFESTS: [&str; 15] = [
+    "cargo.toml",
+    "package.json",
+    "pyproject.toml",
+    "setup.py",
+    "setup.cfg",
+    "requirements.txt",
+    "go.mod",
+    "pom.xml",
+    "build.gradle",
+    "build.gradle.kts",
+    "composer.json",
+    "gemfile",
+    "makefile",
+    "dockerfile",
+    "cmakelists.txt",
+];
+
+const ENTRY_POINTS: [&str; 18] = [
+    "main.rs",
+    "lib.rs",
+    "main.go",
+    "main.py",
+    "__main__.py",
+    "app.py",
+    "manage.py",
+    "index.js",
+    "index.ts",
+    "index.tsx",
+    "main.js",
+    "main.ts",
+    "server.js",
+    "app.js",
+    "program.cs",
+    "main.java",
+    "main.c",
+    "main.cpp",
+];
+
+const CONFIG_EXTENSIONS: [&str; 6] = ["toml", "yaml", "yml", "json", "ini", "cfg"];
+const CONFIG_DIRS: [&str; 3] = ["config", "conf", "settings"];
+const SOURCE_ROOTS: [&str; 7] = ["src", "lib", "app", "cmd", "pkg", "internal", "source"];
+
+// Классы важности: 0 - README и манифесты, 1 - точки входа, 2 - конфигурация, 3 - исходный код
+fn rank(relative: &Path) -> Option<u8> {
+    let names: Vec<String> = relative
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy().to_lowercase()),
+            _ => None,
+        })
+        .collect();
+    let (name, dirs) = names.split_last()?;
+    let extension = Path::new(name).extension().map(|extension| extension.to_string_lossy().into_owned());
+
+    // Манифесты пакетов монорепозитория лежат на уровень ниже корня
+    let manifest = dirs.len() <= 1 && MANIFESTS.contains(&name.as_str());
+    if manifest || (dirs.is_empty() && name.starts_with("readme")) {
+        Some(0)
+    } else if ENTRY_POINTS.contains(&name.as_str()) {
+        Some(1)
+    } else if extension.is_some_and(|extension| CONFIG_EXTENSIONS.contains(&extension.as_str()))
+        && (dirs.is_empty() || dirs.iter().any(|dir| CONFIG_DIRS.contains(&dir.as_str())))
+    {
+        Some(2)
+    } else if dirs.first().is_some_and(|root| SOURCE_ROOTS.contains(&root.as_str())) {
+        Some(3)
+    } else {
+        None
+    }
+}
+
+// Выбранные файлы в порядке вывода: по классу, затем ближе к корню, затем меньшие
+pub fn select(base_dir: &Path, candidates: &[(PathBuf, FileInfo)]) -> Vec<PathBuf> {
+    let mut ranked: Vec<_> = candidates
+        .iter()
+        .filter_map(|(path, info)| {
+            let relative = relative_path(base_dir, path);
+            let class = rank(&relative)?;
+            if info.len > if class == 0 { MAX_MANIFEST } else { MAX_FILE } {
+                return None;
+            }
+            Some(((class, relative.components().count(), info.len, relative), path, info.len))
+        })
+        .collect();
+    ranked.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut total = 0;
+    let mut selected = Vec::new();
+    for (_, path, len) in ranked {
+        if total + len <= BUDGET {
+            total += len;
+            selected.push(path.clone());
+        }
+    }
+
+    tracing::info!(
+        "--quick: содержимое {} из {} файлов ({} КиБ)",
+        selected.len(),
+        candidates.len(),
+        total.div_ceil(1024)
+    );
+    selected
+}
@@ -0,0 +1,260 @@
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use serde_json::json;
+
+use crate::anchors;
+use crate::escape::{self, display_path};
+use crate::{highlight, html_theme, schema};
+use crate::interrupt;
+use crate::tree::{self, TreeNode};
+use crate::{AppConfig, Config, content_language, heading_title, ordered_content_files, relative_path, scan, secrets};
+
+// Форматы документа подключаются через трейт Renderer: рендерер получает построенную
+// модель дерева и поток содержимого файлов, уже отобранных по общим правилам (фильтры,
+// исключения, маскирование секретов). Встроенные markdown, html и json регистрируются
+// так же, как сторонние: свой формат подключается через run_with_renderers без правки
+// обхода. Формат выбирается по имени (--format NAME), файл по умолчанию - tree.NAME
+
+pub trait Renderer: Send + Sync {
+    // Имя для --format
+    fn name(&self) -> &str;
+
+    fn render(&self, snapshot: &Snapshot, writer: &mut dyn Write) -> io::Result<()>;
+}
+
+// Снимок проекта для рендерера: модель дерева и доступ к содержимому файлов
+pub struct Snapshot<'a> {
+    base_dir: &'a Path,
+    nodes: &'a [TreeNode],
+    app_config: &'a AppConfig,
+    config: &'a Config,
+}
+
+// Содержимое файла раздела содержимого. Путь - от корня проекта
+pub struct FileContent {
+    pub path: PathBuf,
+    pub language: String,
+    pub content: io::Result<String>,
+}
+
+impl<'a> Snapshot<'a> {
+    pub(crate) fn new(
+        base_dir: &'a Path,
+        nodes: &'a [TreeNode],
+        app_config: &'a AppConfig,
+        config: &'a Config,
+    ) -> Self {
+        Snapshot { base_dir, nodes, app_config, config }
+    }
+
+    pub fn root(&self) -> &Path {
+        self.base_dir
+    }
+
+    // Заголовок документа: --title или «Структура проекта: <директория>» на языке документа
+    pub fn title(&self) -> String {
+        heading_title(self.base_dir, self.app_config)
+    }
+
+    pub fn nodes(&self) -> &[TreeNode] {
+        self.nodes
+    }
+
+    pub fn include_tree(&self) -> bool {
+        self.app_config.include_tree
+    }
+
+    pub fn include_contents(&self) -> bool {
+        self.app_config.include_contents
+    }
+
+    // Дерево в текстовом виде, как в блоке кода markdown (с учетом --flat и --skip-marker)
+    pub fn tree_text(&self) -> io::Result<String> {
+        let mut text = Vec::new();
+        if self.app_config.flat_tree {
+            tree::render_flat(self.base_dir, Path::new(""), self.nodes, &mut text, self.app_config)?;
+        } else {
+            tree::render_pretty(self.nodes, &mut text, "", self.app_config)?;
+        }
+        Ok(String::from_utf8_lossy(&text).into_owned())
+    }
+
+    // Файлы читаются по одному по мере перебора, в порядке раздела содержимого
+    pub fn files(&self) -> impl Iterator<Item = FileContent> + '_ {
+        let files = if self.app_config.include_contents {
+            ordered_content_files(self.base_dir, self.nodes, self.app_config, self.config)
+        } else {
+            Vec::new()
+        };
+        files.into_iter().take_while(|path| !interrupt::stop_at(path)).map(|path| {
+            let content = self.app_config.fs.read_to_string(&path).map(|content| {
+                if !self.app_config.unsafe_full_secrets && secrets::is_secret_file(&path, self.config) {
+                    secrets::mask_secrets(&content)
+                } else {
+                    content
+                }
+            });
+            let language = match &content {
+                Ok(content) => content_language(&path, content, self.config),
+                Err(_) => content_language(&path, "", self.config),
+            };
+            FileContent { path: relative_path(self.base_dir, &path), language, content }
+        })
+    }
+}
+
+// Встроенные рендереры. Сторонние проверяются раньше и могут заменить встроенный с тем же именем
+pub fn builtin() -> Vec<Box<dyn Renderer>> {
+    vec![Box::new(Markdown), Box::new(Html), Box::new(Json)]
+}
+
+pub fn find<'a>(renderers: &'a [Box<dyn Renderer>], name: &str) -> io::Result<&'a dyn Renderer> {
+    renderers
+        .iter()
+        .find(|renderer| renderer.name() == name)
+        .map(|renderer| renderer.as_ref())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("неизвестный формат вывода '{}'", name)))
+}
+
+// Markdown использует все возможности документа (--anchors, --split-tests, отчеты),
+// поэтому выводится общим кодом из lib.rs
+struct Markdown;
+
+impl Renderer for Markdown {
+    fn name(&self) -> &str {
+        "markdown"
+    }
+
+    fn render(&self, snapshot: &Snapshot, mut writer: &mut dyn Write) -> io::Result<()> {
+        crate::write_markdown_document(
+            snapshot.base_dir,
+            snapshot.nodes,
+            &mut writer,
+            snapshot.app_config,
+            snapshot.config,
+        )
+    }
+}
+
+// Один HTML-файл без внешних ресурсов: дерево и файлы в <pre>
+struct Html;
+
+impl Renderer for Html {
+    fn name(&self) -> &str {
+        "html"
+    }
+
+    fn render(&self, snapshot: &Snapshot, writer: &mut dyn Write) -> io::Result<()> {
+        let doc = snapshot.app_config.doc;
+        let title = escape::html(&snapshot.title());
+        writeln!(writer, "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">")?;
+        writeln!(writer, "<title>{}</title>", title)?;
+        let app_config = snapshot.app_config;
+        writeln!(writer, "<style>{}</style>", html_theme::stylesheet(app_config.theme, app_config.custom_css.as_deref()))?;
+        writeln!(writer, "</head>\n<body>\n<h1>{}</h1>", title)?;
+
+        let nav_links = snapshot.app_config.nav_links;
+        let paths: Vec<PathBuf> = if nav_links && snapshot.include_contents() {
+            ordered_content_files(snapshot.base_dir, snapshot.nodes, snapshot.app_config, snapshot.config)
+                .iter()
+                .map(|path| relative_path(snapshot.base_dir, path))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        if nav_links {
+            snapshot.app_config.slugs.lock().unwrap().reserve(anchors::TREE_ID);
+        }
+
+        if snapshot.include_tree() {
+            if nav_links {
+                writeln!(writer, "<h2 id=\"{}\">{}</h2>", anchors::TREE_ID, escape::html(doc.file_tree))?;
+            } else {
+                writeln!(writer, "<h2>{}</h2>", escape::html(doc.file_tree))?;
+            }
+            writeln!(writer, "<pre class=\"tree\">{}</pre>", escape::html(&snapshot.tree_text()?))?;
+        }
+
+        if snapshot.include_contents() {
+            writeln!(writer, "<h2>{}</h2>", escape::html(doc.file_contents))?;
+            for (index, file) in snapshot.files().enumerate() {
+                let path = display_path(&file.path);
+                let slug = snapshot.app_config.slugs.lock().unwrap().place(&file.path);
+                writeln!(writer, "<h3 id=\"{}\"><code>{}</code></h3>", slug, escape::html(&path))?;
+                let nav = if nav_links { nav_html(snapshot, &paths, index) } else { String::new() };
+                if !nav.is_empty() {
+                    writeln!(writer, "{}", nav)?;
+                }
+                match &file.content {
+                    Ok(content) => {
+                        let code = if app_config.theme.is_some() || app_config.custom_css.is_some() {
+                            highlight::html(&file.language, content)
+                        } else {
+                            escape::html(content)
+                        };
+                        writeln!(
+                            writer,
+                            "<pre><code class=\"language-{}\">{}</code></pre>",
+                            escape::html(&file.language),
+                            code
+                        )?
+                    }
+                    Err(_) => writeln!(writer, "<pre>{}</pre>", escape::html(doc.unreadable_file))?,
+                }
+                if !nav.is_empty() {
+                    writeln!(writer, "{}", nav)?;
+                }
+            }
+        }
+
+        writeln!(writer, "</body>\n</html>")
+    }
+}
+
+// Ссылки --nav-links: к дереву, на предыдущий и следующий файл
+fn nav_html(snapshot: &Snapshot, paths: &[PathBuf], index: usize) -> String {
+    let mut slugs = snapshot.app_config.slugs.lock().unwrap();
+    let mut links = Vec::new();
+    if snapshot.include_tree() {
+        links.push(format!("<a href=\"#{}\">{}</a>", anchors::TREE_ID, escape::html(snapshot.app_config.doc.nav_tree)));
+    }
+    let name = |path: &Path| escape::html(&path.file_name().map_or_else(|| display_path(path), escape::display_os_str));
+    if let Some(previous) = index.checked_sub(1).and_then(|index| paths.get(index)) {
+        links.push(format!("<a href=\"#{}\">&larr; {}</a>", slugs.slug(previous), name(previous)));
+    }
+    if let Some(next) = paths.get(index + 1) {
+        links.push(format!("<a href=\"#{}\">{} &rarr;</a>", slugs.slug(next), name(next)));
+    }
+    if links.is_empty() { String::new() } else { format!("<nav>{}</nav>", links.join(" &middot; ")) }
+}
+
+// Дерево в том же виде, что и у сканирования (scan.rs), и файлы с содержимым
+struct Json;
+
+impl Renderer for Json {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    fn render(&self, snapshot: &Snapshot, writer: &mut dyn Write) -> io::Result<()> {
+        // Файлы выводятся по одному в строке, не собирая весь документ в памяти
+        let header = json!({
+            "schema_version": schema::JSON.version,
+            "root": display_path(snapshot.base_dir),
+            "title": snapshot.title(),
+            "tree": scan::nodes_json(snapshot.base_dir, if snapshot.include_tree() { snapshot.nodes } else { &[] }),
+        });
+        let header = header.to_string();
+        writeln!(writer, "{},\"files\":[", header.strip_suffix('}').unwrap_or(&header))?;
+
+        for (i, file) in snapshot.files().enumerate() {
+            let mut value = json!({ "path": display_path(&file.path), "language": file.language });
+            match &file.content {
+                Ok(content) => value["content"] = json!(content),
+                Err(e) => value["error"] = json!(e.to_string()),
+            }
+            writeln!(writer, "{}{}", if i > 0 { "," } else { "" }, value)?;
+        }
+        writeln!(writer, "]}}")
+    }
+}
@@ -0,0 +1,207 @@
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::density;
+use crate::escape::{code_span, display_path};
+use crate::tree::{self, TreeNode};
+use crate::{dotfiles, tree_only};
+use crate::{AppConfig, Config, heading, is_binary_file, relative_path};
+
+const SIZE_BUCKETS: [(u64, &str); 6] = [
+    (1 << 10, "< 1 KB"),
+    (10 << 10, "1–10 KB"),
+    (100 << 10, "10–100 KB"),
+    (1 << 20, "100 KB–1 MB"),
+    (10 << 20, "1–10 MB"),
+    (u64::MAX, "≥ 10 MB"),
+];
+const HISTOGRAM_WIDTH: usize = 30;
+// Обычный исходный код сжимается примерно до трети, сгенерированный и табличный - намного сильнее
+const LOW_DENSITY: f64 = 0.15;
+
+struct SizedFile {
+    path: PathBuf,
+    size: u64,
+    exclusion: Option<&'static str>,
+    // Сжимаемость включенного файла (--density)
+    density: Option<f64>,
+}
+
+pub fn write_size_report<W: Write>(
+    base_dir: &Path,
+    nodes: &[TreeNode],
+    writer: &mut W,
+    limit: usize,
+    app_config: &AppConfig,
+    config: &Config,
+) -> io::Result<()> {
+    let mut files = sized_files(base_dir, nodes, app_config, config);
+    files.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+
+    let doc = app_config.doc;
+    writeln!(writer, "\n{} {}\n", heading(app_config, 2), doc.largest_files)?;
+
+    writeln!(writer, "{} {}\n", heading(app_config, 3), doc.included_files)?;
+    if app_config.density {
+        writeln!(writer, "| {} | {} | {} |", doc.size, doc.density, doc.file)?;
+        writeln!(writer, "|---:|---:|---|")?;
+    } else {
+        writeln!(writer, "| {} | {} |", doc.size, doc.file)?;
+        writeln!(writer, "|---:|---|")?;
+    }
+    for file in files.iter().filter(|file| file.exclusion.is_none()).take(limit) {
+        match file.density {
+            Some(density) => writeln!(
+                writer,
+                "| {} | {} | {} |",
+                format_size(file.size),
+                format_density(density),
+                path_cell(base_dir, &file.path)
+            )?,
+            None => writeln!(writer, "| {} | {} |", format_size(file.size), path_cell(base_dir, &file.path))?,
+        }
+    }
+
+    if app_config.density {
+        write_low_density(base_dir, writer, &files, limit, app_config)?;
+    }
+
+    writeln!(writer, "\n{} {}\n", heading(app_config, 3), doc.excluded_files)?;
+    writeln!(writer, "| {} | {} | {} |", doc.size, doc.file, doc.reason)?;
+    writeln!(writer, "|---:|---|---|")?;
+    for file in files.iter().filter(|file| file.exclusion.is_some()).take(limit) {
+        writeln!(
+            writer,
+            "| {} | {} | {} |",
+            format_size(file.size),
+            path_cell(base_dir, &file.path),
+            file.exclusion.unwrap_or_default()
+        )?;
+    }
+
+    writeln!(writer, "\n{} {}\n", heading(app_config, 3), doc.size_distribution)?;
+    writeln!(writer, "| {} | {} | {} | |", doc.size_range, doc.included_count, doc.excluded_count)?;
+    writeln!(writer, "|---|---:|---:|---|")?;
+
+    let mut counts = [(0usize, 0usize); SIZE_BUCKETS.len()];
+    for file in &files {
+        let bucket = SIZE_BUCKETS
+            .iter()
+            .position(|(upper, _)| file.size < *upper)
+            .unwrap_or(SIZE_BUCKETS.len() - 1);
+        if file.exclusion.is_none() {
+            counts[bucket].0 += 1;
+        } else {
+            counts[bucket].1 += 1;
+        }
+    }
+
+    let max_count = counts.iter().map(|(i, e)| i + e).max().unwrap_or(0).max(1);
+    for ((_, label), (included, excluded)) in SIZE_BUCKETS.iter().zip(counts) {
+        let bar = "█".repeat((included + excluded) * HISTOGRAM_WIDTH / max_count);
+        writeln!(writer, "| {} | {} | {} | {} |", label, included, excluded, bar)?;
+    }
+
+    Ok(())
+}
+
+// Самые сжимаемые из включенных файлов: кандидаты в exclude_files. Мелкие файлы не учитываются,
+// у них оценка сжатия неустойчива
+fn write_low_density<W: Write>(
+    base_dir: &Path,
+    writer: &mut W,
+    files: &[SizedFile],
+    limit: usize,
+    app_config: &AppConfig,
+) -> io::Result<()> {
+    let mut low: Vec<(&SizedFile, f64)> = files
+        .iter()
+        .filter(|file| file.size >= 1024)
+        .filter_map(|file| file.density.map(|density| (file, density)))
+        .filter(|(_, density)| *density < LOW_DENSITY)
+        .collect();
+    if low.is_empty() {
+        return Ok(());
+    }
+    low.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.path.cmp(&b.0.path)));
+
+    let doc = app_config.doc;
+    writeln!(writer, "\n{} {}\n", heading(app_config, 3), doc.low_density_files)?;
+    writeln!(writer, "| {} | {} | {} |", doc.density, doc.size, doc.file)?;
+    writeln!(writer, "|---:|---:|---|")?;
+    for (file, density) in low.into_iter().take(limit) {
+        writeln!(
+            writer,
+            "| {} | {} | {} |",
+            format_density(density),
+            format_size(file.size),
+            path_cell(base_dir, &file.path)
+        )?;
+    }
+    Ok(())
+}
+
+fn format_density(density: f64) -> String {
+    format!("{:.0}%", density * 100.0)
+}
+
+// Размеры берутся из модели дерева, файлы внутри свернутых директорий (`...`) не учитываются.
+// Файлы, исключенные правилами, в дереве не остаются: их размеры обход собирает отдельно
+fn sized_files(base_dir: &Path, nodes: &[TreeNode], app_config: &AppConfig, config: &Config) -> Vec<SizedFile> {
+    let mut entries = Vec::new();
+    tree::file_entries(nodes, &mut entries);
+
+    let mut files: Vec<SizedFile> = entries
+        .into_iter()
+        .map(|(path, info)| {
+            let exclusion = if is_binary_file(&path, config) {
+                Some(app_config.doc.binary_file)
+            } else if dotfiles::is_list_only(&path, app_config, config) {
+                Some(app_config.doc.dotfile_list_only)
+            } else if tree_only::is_tree_only(&path, app_config, config) {
+                Some(app_config.doc.tree_only_file)
+            } else if config.max_file_size.is_some_and(|max_size| info.len > max_size) {
+                Some(app_config.doc.over_max_size)
+            } else {
+                None
+            };
+            let density = if app_config.density && exclusion.is_none() {
+                app_config.fs.read(&path).ok().map(|data| density::ratio(&data))
+            } else {
+                None
+            };
+            SizedFile { path, size: info.len, exclusion, density }
+        })
+        .collect();
+
+    // Обходы внешних директорий (include_external) в отчет о проекте не входят
+    let excluded = app_config.excluded_sizes.lock().unwrap();
+    files.extend(excluded.iter().filter(|(path, _)| path.starts_with(base_dir)).map(|(path, &size)| SizedFile {
+        path: path.clone(),
+        size,
+        exclusion: Some(app_config.doc.excluded_by_rules),
+        density: None,
+    }));
+    files
+}
+
+// В ячейках таблицы GFM символ '|' разделяет колонки даже внутри inline-кода
+fn path_cell(base_dir: &Path, path: &Path) -> String {
+    code_span(&display_path(&relative_path(base_dir, path))).replace('|', "\\|")
+}
+
+pub fn format_size(size: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+
+    if size < 1024 {
+        return format!("{} B", size);
+    }
+
+    let mut value = size as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
@@ -0,0 +1,131 @@
+use std::path::{Path, PathBuf};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+
+use crate::{AppConfig, Config, relative_path};
+
+// [sampling] "*.log" = 50: у файлов данных (логи, CSV, NDJSON) в документ попадают только
+// первые N строк и пометка с их общим числом - видна структура, но не весь объем. Шаблоны
+// сравниваются как в tree_only; 0 отключает выборку для подходящих файлов, а из нескольких
+// подходящих ненулевых значений действует меньшее. Шаблон из [sampling] проекта сильнее
+// встроенного exclude_files: без этого "*.log" = 50 никогда бы не сработал, ведь *.log
+// исключен по умолчанию. Собственный exclude_files проекта по-прежнему имеет приоритет
+
+#[derive(Debug, Default)]
+pub struct Rules {
+    names: GlobSet,
+    paths: GlobSet,
+    // Число строк для шаблона с тем же номером в names или paths
+    name_limits: Vec<usize>,
+    path_limits: Vec<usize>,
+    // Ненулевые шаблоны проекта, снимающие встроенное исключение по exclude_files
+    explicit_names: GlobSet,
+    explicit_paths: GlobSet,
+}
+
+// Ненулевые шаблоны [sampling] из файлов проекта, если проект не задает свой exclude_files
+pub fn explicit_patterns(project_values: &[(PathBuf, toml::Value)]) -> Vec<String> {
+    let mut patterns = Vec::new();
+    let mut own_excludes: Option<Vec<&str>> = None;
+    for (_, value) in project_values {
+        if let Some(excludes) = value.get("exclude_files").and_then(|v| v.as_array()) {
+            own_excludes = Some(excludes.iter().filter_map(|v| v.as_str()).collect());
+        }
+        if let Some(table) = value.get("sampling").and_then(|v| v.as_table()) {
+            for (pattern, lines) in table {
+                patterns.retain(|p| p != pattern);
+                if lines.as_integer().is_some_and(|n| n > 0) {
+                    patterns.push(pattern.clone());
+                }
+            }
+        }
+    }
+    let Some(own_excludes) = own_excludes else {
+        return patterns;
+    };
+    for pattern in patterns.iter().filter(|p| own_excludes.contains(&p.as_str())) {
+        tracing::warn!("шаблон sampling '{}' не сработает: он же указан в exclude_files", pattern);
+    }
+    Vec::new()
+}
+
+pub fn compile(config: &mut Config) {
+    let mut patterns: Vec<(&String, &usize)> = config.sampling.iter().collect();
+    patterns.sort();
+
+    let mut names = GlobSetBuilder::new();
+    let mut paths = GlobSetBuilder::new();
+    let mut name_limits = Vec::new();
+    let mut path_limits = Vec::new();
+    for (pattern, &lines) in patterns {
+        match GlobBuilder::new(pattern).literal_separator(true).build() {
+            Ok(glob) if pattern.contains('/') => {
+                paths.add(glob);
+                path_limits.push(lines);
+            }
+            Ok(glob) => {
+                names.add(glob);
+                name_limits.push(lines);
+            }
+            Err(e) => tracing::warn!("шаблон sampling '{}' пропущен: {}", pattern, e),
+        }
+    }
+    let mut explicit_names = GlobSetBuilder::new();
+    let mut explicit_paths = GlobSetBuilder::new();
+    for pattern in &config.explicit_sampling {
+        if let Ok(glob) = GlobBuilder::new(pattern).literal_separator(true).build() {
+            if pattern.contains('/') {
+                explicit_paths.add(glob);
+            } else {
+                explicit_names.add(glob);
+            }
+        }
+    }
+    config.sampling_rules = Rules {
+        names: names.build().unwrap_or_default(),
+        paths: paths.build().unwrap_or_default(),
+        name_limits,
+        path_limits,
+        explicit_names: explicit_names.build().unwrap_or_default(),
+        explicit_paths: explicit_paths.build().unwrap_or_default(),
+    };
+}
+
+// Снимает ли явная выборка проекта исключение файла по exclude_files
+pub fn overrides_exclusion(path: &Path, app_config: &AppConfig, config: &Config) -> bool {
+    let rules = &config.sampling_rules;
+    if rules.explicit_names.is_empty() && rules.explicit_paths.is_empty() {
+        return false;
+    }
+    if path.file_name().is_some_and(|name| rules.explicit_names.is_match(name)) {
+        return true;
+    }
+    rules.explicit_paths.is_match(relative_path(Path::new(&app_config.target_dir), path))
+}
+
+// Сколько строк файла выводить, если для него задана выборка
+pub fn line_limit(path: &Path, app_config: &AppConfig, config: &Config) -> Option<usize> {
+    let rules = &config.sampling_rules;
+    if rules.names.is_empty() && rules.paths.is_empty() {
+        return None;
+    }
+    let mut limits: Vec<usize> = Vec::new();
+    if let Some(name) = path.file_name() {
+        limits.extend(rules.names.matches(name).into_iter().map(|i| rules.name_limits[i]));
+    }
+    let relative = relative_path(Path::new(&app_config.target_dir), path);
+    limits.extend(rules.paths.matches(relative).into_iter().map(|i| rules.path_limits[i]));
+    if limits.contains(&0) {
+        return None;
+    }
+    limits.into_iter().min()
+}
+
+// Первые limit строк и общее число строк, если файл длиннее
+pub fn head(content: &str, limit: usize) -> Option<(&str, usize)> {
+    let total = content.lines().count();
+    if total <= limit {
+        return None;
+    }
+    let end = content.match_indices('\n').nth(limit - 1).map_or(content.len(), |(i, _)| i + 1);
+    Some((&content[..end], total))
+}
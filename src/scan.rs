@@ -0,0 +1,178 @@
+use std::io;
+use std::path::Path;
+use ignore::gitignore::Gitignore;
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+use crate::escape::display_path;
+use crate::notices;
+use crate::schema;
+use crate::sha256;
+use crate::tree::{self, NodeKind, TreeNode};
+use crate::tree_only;
+use crate::{
+    AppConfig, Config, app_config_from_matches, cli, content_language, create_ignore_matcher,
+    is_binary_file, is_extension_allowed, load_config, project, relative_path, secrets,
+};
+
+// Сканирование проекта в JSON для встраивания (ffi.rs): те же правила, что и у CLI,
+// но результат - структура, а не markdown. Параметры передаются JSON-объектом, ключи
+// которого повторяют длинные флаги CLI: {"only_ext": "rs,toml", "no_gitignore": true}
+//
+// У каждого файла два идентификатора: content_id - хеш содержимого, совпадает у файла
+// до и после переименования; id - хеш пути и содержимого, различает копии одного файла.
+// Для файлов с содержимым в выводе хешируется именно оно (с замаскированными секретами)
+
+pub fn scan_project(path: &str, options: &str) -> io::Result<String> {
+    let args = options_to_args(options)?;
+    let matches = cli()
+        .try_get_matches_from(["proj2tree".to_string(), path.to_string()].into_iter().chain(args))
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))?;
+    let app_config = app_config_from_matches(&matches)?;
+
+    let base_dir = Path::new(&app_config.target_dir);
+    if !base_dir.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("'{}' не является существующей директорией", app_config.target_dir),
+        ));
+    }
+
+    let mut config = load_config(base_dir);
+    if !matches.get_flag("no-auto-exclude") {
+        project::apply_auto_exclusions(base_dir, &mut config);
+    }
+    let gitignore_matcher = create_ignore_matcher(&app_config)?;
+
+    write_json(base_dir, &app_config, &config, &gitignore_matcher)
+}
+
+fn options_to_args(options: &str) -> io::Result<Vec<String>> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidInput, "параметры должны быть JSON-объектом");
+    if options.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let options: Value = serde_json::from_str(options).map_err(|_| invalid())?;
+    let members = options.as_object().ok_or_else(invalid)?;
+
+    let mut args = Vec::new();
+    for (key, value) in members {
+        let flag = format!("--{}", key.replace('_', "-"));
+        let values = match value {
+            Value::Array(items) => items.iter().collect(),
+            value => vec![value],
+        };
+        for value in values {
+            match value {
+                Value::Bool(true) => args.push(flag.clone()),
+                Value::Bool(false) | Value::Null => {}
+                Value::String(text) => args.extend([flag.clone(), text.clone()]),
+                Value::Number(number) => args.extend([flag.clone(), number.to_string()]),
+                _ => return Err(invalid()),
+            }
+        }
+    }
+    Ok(args)
+}
+
+fn write_json(
+    base_dir: &Path,
+    app_config: &AppConfig,
+    config: &Config,
+    gitignore_matcher: &Option<Gitignore>,
+) -> io::Result<String> {
+    let nodes = tree::build_tree(base_dir, app_config, config, gitignore_matcher)?;
+    let mut files = Vec::new();
+    tree::file_entries(&nodes, &mut files);
+    files.retain(|(path, _)| is_extension_allowed(path, app_config));
+
+    let mut file_values = Vec::new();
+    for (path, info) in &files {
+        // Размер из обхода. Бинарные, слишком большие и нечитаемые файлы перечисляются без содержимого
+        let size = info.len;
+        let content = if is_binary_file(path, config)
+            || tree_only::is_tree_only(path, app_config, config)
+            || config.max_file_size.is_some_and(|max_size| size > max_size)
+        {
+            None
+        } else {
+            app_config.fs.read_to_string(path).ok()
+        };
+        let language = content.as_deref().map(|content| content_language(path, content, config));
+        let content = content.map(|content| {
+            if !app_config.unsafe_full_secrets && secrets::is_secret_file(path, config) {
+                secrets::mask_secrets(&content)
+            } else {
+                content
+            }
+        });
+
+        let display = display_path(&relative_path(base_dir, path));
+        let (id, content_id) = file_ids(path, &display, content.as_deref()).unzip();
+
+        file_values.push(json!({
+            "path": display,
+            "id": id,
+            "content_id": content_id,
+            "size": size,
+            "language": language,
+            "content": content,
+        }));
+    }
+
+    let errors: Vec<Value> = app_config
+        .walk_errors
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(path, message)| json!({ "path": display_path(&relative_path(base_dir, path)), "message": message }))
+        .collect();
+
+    let scan = json!({
+        "schema_version": schema::SCAN.version,
+        "root": display_path(base_dir),
+        "tree": nodes_json(base_dir, &nodes),
+        "files": file_values,
+        "errors": errors,
+        "notices": notices::to_json(base_dir, app_config),
+    });
+    Ok(scan.to_string())
+}
+
+fn file_ids(path: &Path, display: &str, content: Option<&str>) -> Option<(String, String)> {
+    let mut hasher = Sha256::default();
+    match content {
+        Some(content) => hasher.update(content.as_bytes()),
+        None => sha256::hash_file(path, &mut hasher).ok()?,
+    }
+    let content_id = sha256::hex(hasher);
+
+    let mut hasher = Sha256::default();
+    hasher.update(display.as_bytes());
+    hasher.update([0]);
+    hasher.update(content_id.as_bytes());
+
+    Some((format!("sha256:{}", sha256::hex(hasher)), format!("sha256:{}", content_id)))
+}
+
+pub fn nodes_json(base_dir: &Path, nodes: &[TreeNode]) -> Value {
+    nodes
+        .iter()
+        .map(|node| {
+            let path = display_path(&relative_path(base_dir, &node.path));
+            match &node.kind {
+                NodeKind::File(_) => json!({ "type": "file", "path": path }),
+                NodeKind::Directory(children) => {
+                    json!({ "type": "directory", "path": path, "children": nodes_json(base_dir, children) })
+                }
+                NodeKind::Collapsed(count) => {
+                    json!({ "type": "collapsed", "path": path, "files": count.map(|(count, _)| count) })
+                }
+                NodeKind::Skipped => json!({ "type": "skipped", "path": path }),
+                NodeKind::Omitted(count) => json!({ "type": "omitted", "path": path, "files": count }),
+                NodeKind::Truncated(count) => json!({ "type": "truncated", "path": path, "entries": count }),
+                NodeKind::Failed { message, .. } => json!({ "type": "error", "path": path, "message": message }),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,27 @@
+// Схемы JSON структурированных выводов (schemas/*.schema.json) и их версии. Каждый документ
+// начинается с "schema_version"; в пределах версии формат меняется только добавлением полей
+// и видов узлов/событий, удаление или смена смысла поля - новая версия. Схемы встроены в
+// бинарный файл и печатаются через --print-schema NAME
+
+pub struct Schema {
+    pub name: &'static str,
+    pub version: u32,
+    pub text: &'static str,
+}
+
+pub const JSON: Schema = Schema { name: "json", version: 1, text: include_str!("../schemas/json.schema.json") };
+pub const JSONL: Schema = Schema { name: "jsonl", version: 1, text: include_str!("../schemas/jsonl.schema.json") };
+pub const INDEX: Schema = Schema { name: "index", version: 1, text: include_str!("../schemas/index.schema.json") };
+pub const SIDECAR: Schema =
+    Schema { name: "sidecar", version: 1, text: include_str!("../schemas/sidecar.schema.json") };
+pub const REPORT: Schema = Schema { name: "report", version: 1, text: include_str!("../schemas/report.schema.json") };
+pub const SCAN: Schema = Schema { name: "scan", version: 1, text: include_str!("../schemas/scan.schema.json") };
+
+pub const ANCHORS: Schema =
+    Schema { name: "anchors", version: 1, text: include_str!("../schemas/anchors.schema.json") };
+
+pub const ALL: [&Schema; 7] = [&JSON, &JSONL, &INDEX, &SIDECAR, &REPORT, &SCAN, &ANCHORS];
+
+pub fn find(name: &str) -> Option<&'static Schema> {
+    ALL.into_iter().find(|schema| schema.name == name)
+}
@@ -0,0 +1,144 @@
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::Path;
+use ignore::gitignore::Gitignore;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::escape::display_path;
+use crate::schema;
+use crate::sha256;
+use crate::{AppConfig, Config, build_model, content_files, relative_path, secrets};
+
+// Обратный индекс по содержимому включенных файлов (--format index): терм -> файлы и номера
+// строк. Внешний инструмент ищет по снимку, не перечитывая репозиторий.
+// Термы - слова в нижнем регистре, составные идентификаторы дополнительно делятся на части:
+// parseConfig и parse_config находятся и по parse, и по config
+
+const MIN_TERM_CHARS: usize = 2;
+const MAX_TERM_CHARS: usize = 64;
+
+#[derive(Serialize)]
+struct IndexedFile {
+    path: String,
+    content_id: String,
+    lines: usize,
+}
+
+// Вхождение терма: [номер файла в files, [строки...]]
+#[derive(Serialize)]
+struct Index<'a> {
+    schema_version: u32,
+    version: u32,
+    files: &'a [IndexedFile],
+    #[serde(serialize_with = "serialize_terms")]
+    terms: &'a BTreeMap<String, BTreeMap<usize, Vec<usize>>>,
+}
+
+fn serialize_terms<S: serde::Serializer>(
+    terms: &&BTreeMap<String, BTreeMap<usize, Vec<usize>>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(terms.iter().map(|(term, occurrences)| (term, occurrences.iter().collect::<Vec<_>>())))
+}
+
+pub fn write_index<W: Write>(
+    base_dir: &Path,
+    writer: &mut W,
+    app_config: &AppConfig,
+    config: &Config,
+    gitignore_matcher: &Option<Gitignore>,
+) -> io::Result<()> {
+    let nodes = build_model(base_dir, app_config, config, gitignore_matcher)?;
+
+    let mut files = Vec::new();
+    // терм -> номер файла -> номера строк (с 1)
+    let mut postings: BTreeMap<String, BTreeMap<usize, Vec<usize>>> = BTreeMap::new();
+
+    for path in content_files(&nodes, app_config, config) {
+        let Ok(content) = app_config.fs.read_to_string(&path) else {
+            continue;
+        };
+        let content = if !app_config.unsafe_full_secrets && secrets::is_secret_file(&path, config) {
+            secrets::mask_secrets(&content)
+        } else {
+            content
+        };
+
+        let file_index = files.len();
+        for (number, line) in content.lines().enumerate() {
+            for term in terms(line) {
+                let lines = postings.entry(term).or_default().entry(file_index).or_default();
+                if lines.last() != Some(&(number + 1)) {
+                    lines.push(number + 1);
+                }
+            }
+        }
+
+        let mut hasher = Sha256::default();
+        hasher.update(content.as_bytes());
+        files.push(IndexedFile {
+            path: display_path(&relative_path(base_dir, &path)),
+            content_id: format!("sha256:{}", sha256::hex(hasher)),
+            lines: content.lines().count(),
+        });
+    }
+
+    let index = Index {
+        schema_version: schema::INDEX.version,
+        version: schema::INDEX.version,
+        files: &files,
+        terms: &postings,
+    };
+    serde_json::to_writer(&mut *writer, &index)?;
+    writeln!(writer)?;
+    tracing::info!("Проиндексировано файлов: {}, термов: {}", files.len(), postings.len());
+    Ok(())
+}
+
+fn terms(line: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    for word in line.split(|c: char| !c.is_alphanumeric() && c != '_') {
+        let word = word.trim_matches('_');
+        push_term(&mut terms, word);
+
+        let parts = identifier_parts(word);
+        if parts.len() > 1 {
+            for part in parts {
+                push_term(&mut terms, &part);
+            }
+        }
+    }
+    terms
+}
+
+fn push_term(terms: &mut Vec<String>, word: &str) {
+    let length = word.chars().count();
+    if (MIN_TERM_CHARS..=MAX_TERM_CHARS).contains(&length) {
+        terms.push(word.to_lowercase());
+    }
+}
+
+// snake_case и camelCase: HTTPServer -> http, server
+fn identifier_parts(word: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    for piece in word.split('_') {
+        let chars: Vec<char> = piece.chars().collect();
+        let mut current = String::new();
+        for (i, &c) in chars.iter().enumerate() {
+            let boundary = i > 0
+                && c.is_uppercase()
+                && (chars[i - 1].is_lowercase()
+                    || chars[i - 1].is_numeric()
+                    || chars.get(i + 1).is_some_and(|next| next.is_lowercase()));
+            if boundary && !current.is_empty() {
+                parts.push(std::mem::take(&mut current));
+            }
+            current.push(c);
+        }
+        if !current.is_empty() {
+            parts.push(current);
+        }
+    }
+    parts
+}
@@ -0,0 +1,108 @@
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use serde_json::{Value, json};
+use sha2::Sha256;
+
+use crate::atomic_file;
+use crate::escape::display_path;
+use crate::schema;
+use crate::sha256;
+use crate::{AppConfig, Config, dotfiles, footer, git, ordered_content_files, relative_path, tree};
+
+// --sidecar: рядом с документом записывается FILE.meta.json - все, что нужно инструментам
+// для проверки происхождения снимка: параметры запуска, конфигурация после всех слоев,
+// состояние git, файлы раздела содержимого в порядке вывода с размерами и SHA-256
+// и время генерации. Сам документ остается текстом для человека
+
+pub fn path(output: &Path) -> PathBuf {
+    let mut name = OsString::from(output.as_os_str());
+    name.push(".meta.json");
+    PathBuf::from(name)
+}
+
+pub fn write(
+    output: &Path,
+    base_dir: &Path,
+    nodes: &[tree::TreeNode],
+    elapsed: Duration,
+    app_config: &AppConfig,
+    config: &Config,
+) -> io::Result<PathBuf> {
+    // Файл мог исчезнуть после вывода: размер и хеш тогда null
+    let files: Vec<Value> = ordered_content_files(base_dir, nodes, app_config, config)
+        .iter()
+        .map(|path| {
+            let (size, hash) = file_hash(path).map(|(size, hash)| (size, format!("sha256:{}", hash))).unzip();
+            json!({ "path": display_path(&relative_path(base_dir, path)), "size": size, "sha256": hash })
+        })
+        .collect();
+
+    let walk = app_config.walk_time.lock().unwrap().unwrap_or_default();
+    let meta = json!({
+        "schema_version": schema::SIDECAR.version,
+        "version": schema::SIDECAR.version,
+        "generator": concat!("proj2tree ", env!("CARGO_PKG_VERSION")),
+        "output": output.to_string_lossy(),
+        "output_sha256": file_hash(output).map(|(_, hash)| format!("sha256:{}", hash)),
+        "root": display_path(&std::path::absolute(base_dir)?),
+        "options": footer::effective_options(),
+        "config": config_json(config),
+        "git": git_json(base_dir),
+        "files": files,
+        "timings": { "walk_ms": walk.as_millis() as u64, "total_ms": elapsed.as_millis() as u64 },
+    });
+
+    let sidecar = path(output);
+    atomic_file::write(&sidecar, format!("{}\n", meta))?;
+    Ok(sidecar)
+}
+
+fn config_json(config: &Config) -> Value {
+    let mapping: BTreeMap<&String, &String> = config.extension_mapping.iter().flatten().collect();
+    let sampling: BTreeMap<&String, &usize> = config.sampling.iter().collect();
+    let summarizers: Vec<Value> = config
+        .summarizers
+        .iter()
+        .map(|summarizer| json!({ "glob": summarizer.glob, "command": summarizer.command }))
+        .collect();
+
+    json!({
+        "exclude_dirs": config.exclude_dirs,
+        "exclude_files": config.exclude_files,
+        "exclude_extensions": config.exclude_extensions,
+        "secret_files": config.secret_files,
+        "include_external": config.include_external,
+        "max_file_size": config.max_file_size,
+        "extension_mapping": mapping,
+        "content_order": config.content_order,
+        "skip_dir_over": config.skip_dir_over,
+        "tree_only": config.tree_only,
+        "dotfiles": dotfiles::name(config.dotfiles),
+        "keep_dotfiles": config.keep_dotfiles,
+        "sampling": sampling,
+        "summarizers": summarizers,
+    })
+}
+
+fn git_json(base_dir: &Path) -> Value {
+    let Some(context) = git::context(base_dir) else {
+        return Value::Null;
+    };
+    json!({
+        "branch": context.branch,
+        "commit": context.commit,
+        "dirty": context.dirty,
+        "remote_url": context.remote_url,
+    })
+}
+
+fn file_hash(path: &Path) -> Option<(u64, String)> {
+    let size = fs::metadata(path).ok()?.len();
+    let mut hasher = Sha256::default();
+    sha256::hash_file(path, &mut hasher).ok()?;
+    Some((size, sha256::hex(hasher)))
+}
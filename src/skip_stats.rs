@@ -0,0 +1,79 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use serde_json::{Map, Value, json};
+
+use crate::AppConfig;
+
+// Статистика исключений для сводки в конце запуска: сколько элементов пропущено по каждой
+// причине (.gitignore, exclude_dirs, скрытые файлы, ...) и какие правила исключили больше
+// всего. Исключенная директория не обходится и считается одним элементом. Элемент учитывается
+// один раз, даже если правила проверялись для него несколько раз
+
+// Сколько правил показывать в сводке
+const TOP_RULES: usize = 5;
+
+// Выходной файл пропускается на каждом запуске и ничего не говорит о настройке фильтров
+const OUTPUT_CAUSE: &str = "выходной файл";
+
+pub fn record(app_config: &AppConfig, path: &Path, cause: &'static str, rule: String) {
+    if cause == OUTPUT_CAUSE {
+        return;
+    }
+    app_config.skipped.lock().unwrap().insert(path.to_path_buf(), (cause, rule));
+}
+
+struct Summary {
+    total: usize,
+    by_cause: BTreeMap<&'static str, usize>,
+    // Правило, его причина и число элементов: по убыванию числа, при равенстве - по правилу
+    top_rules: Vec<(String, &'static str, usize)>,
+}
+
+fn summarize(app_config: &AppConfig) -> Summary {
+    let skipped = app_config.skipped.lock().unwrap();
+    let mut by_cause = BTreeMap::new();
+    let mut by_rule: HashMap<(&'static str, &str), usize> = HashMap::new();
+    for (cause, rule) in skipped.values() {
+        *by_cause.entry(*cause).or_insert(0) += 1;
+        *by_rule.entry((*cause, rule.as_str())).or_insert(0) += 1;
+    }
+
+    let mut top_rules: Vec<(String, &'static str, usize)> =
+        by_rule.into_iter().map(|((cause, rule), count)| (rule.to_string(), cause, count)).collect();
+    top_rules.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)).then_with(|| a.1.cmp(b.1)));
+    top_rules.truncate(TOP_RULES);
+
+    Summary { total: skipped.len(), by_cause, top_rules }
+}
+
+pub fn write_text(app_config: &AppConfig) {
+    let summary = summarize(app_config);
+    if summary.total == 0 {
+        return;
+    }
+
+    let causes: Vec<String> = summary.by_cause.iter().map(|(cause, count)| format!("{}: {}", cause, count)).collect();
+    tracing::info!("Пропущено элементов: {} ({})", summary.total, causes.join(", "));
+
+    let rules: Vec<String> = summary
+        .top_rules
+        .iter()
+        .map(|(rule, cause, count)| {
+            if rule == cause { format!("{}: {}", rule, count) } else { format!("{} ({}): {}", rule, cause, count) }
+        })
+        .collect();
+    tracing::info!("Больше всего исключили правила: {}", rules.join(", "));
+}
+
+// Поле "skipped" объекта --report json
+pub fn to_json(app_config: &AppConfig) -> Value {
+    let summary = summarize(app_config);
+    let causes: Map<String, Value> =
+        summary.by_cause.iter().map(|(cause, count)| (cause.to_string(), json!(count))).collect();
+    let rules: Vec<Value> = summary
+        .top_rules
+        .iter()
+        .map(|(rule, cause, count)| json!({ "rule": rule, "cause": cause, "count": count }))
+        .collect();
+    json!({ "total": summary.total, "by_cause": causes, "top_rules": rules })
+}
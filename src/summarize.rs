@@ -0,0 +1,91 @@
+use std::path::Path;
+use std::process::{Command, Stdio};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use serde::Deserialize;
+
+use crate::{AppConfig, Config, relative_path};
+
+// [[summarizers]] glob = "*.proto", command = "protoc --descriptor": вместо содержимого
+// подходящего файла в документ попадает вывод внешней команды. Так большие или особые
+// файлы (схемы, дампы, сгенерированный код) остаются в документе, даже если они больше
+// max_file_size или исключены как бинарные по расширению. Шаблоны сравниваются как
+// в tree_only, действует первый подходящий. Команда разбивается по пробелам, путь файла
+// от целевой директории добавляется последним аргументом, запуск идет из целевой директории.
+// Конфигурация проекта может прийти из чужого репозитория, поэтому команды запускаются
+// только с --run-summarizers
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Summarizer {
+    pub glob: String,
+    pub command: String,
+}
+
+#[derive(Debug, Default)]
+pub struct Globs {
+    names: GlobSet,
+    paths: GlobSet,
+    // Номер summarizer для шаблона с тем же номером в names или paths
+    name_indices: Vec<usize>,
+    path_indices: Vec<usize>,
+}
+
+pub fn compile(config: &mut Config) {
+    let mut names = GlobSetBuilder::new();
+    let mut paths = GlobSetBuilder::new();
+    let mut name_indices = Vec::new();
+    let mut path_indices = Vec::new();
+    for (index, summarizer) in config.summarizers.iter().enumerate() {
+        match GlobBuilder::new(&summarizer.glob).literal_separator(true).build() {
+            Ok(glob) if summarizer.glob.contains('/') => {
+                paths.add(glob);
+                path_indices.push(index);
+            }
+            Ok(glob) => {
+                names.add(glob);
+                name_indices.push(index);
+            }
+            Err(e) => tracing::warn!("шаблон summarizers '{}' пропущен: {}", summarizer.glob, e),
+        }
+    }
+    config.summarizer_globs = Globs {
+        names: names.build().unwrap_or_default(),
+        paths: paths.build().unwrap_or_default(),
+        name_indices,
+        path_indices,
+    };
+}
+
+pub fn find<'c>(path: &Path, app_config: &AppConfig, config: &'c Config) -> Option<&'c Summarizer> {
+    let globs = &config.summarizer_globs;
+    if !app_config.run_summarizers || (globs.names.is_empty() && globs.paths.is_empty()) {
+        return None;
+    }
+    let mut indices: Vec<usize> = Vec::new();
+    if let Some(name) = path.file_name() {
+        indices.extend(globs.names.matches(name).into_iter().map(|i| globs.name_indices[i]));
+    }
+    let relative = relative_path(Path::new(&app_config.target_dir), path);
+    indices.extend(globs.paths.matches(relative).into_iter().map(|i| globs.path_indices[i]));
+    indices.into_iter().min().map(|index| &config.summarizers[index])
+}
+
+// Вывод команды или описание ошибки для пометки в документе
+pub fn run(summarizer: &Summarizer, path: &Path, app_config: &AppConfig) -> Result<String, String> {
+    let target_dir = Path::new(&app_config.target_dir);
+    let mut parts = summarizer.command.split_whitespace();
+    let program = parts.next().ok_or_else(|| "пустая команда".to_string())?;
+    let output = Command::new(program)
+        .args(parts)
+        .arg(relative_path(target_dir, path))
+        .current_dir(target_dir)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("{}: {}", program, e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr.lines().rev().find(|line| !line.trim().is_empty()).unwrap_or("").trim();
+        return Err(if reason.is_empty() { output.status.to_string() } else { reason.to_string() });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -0,0 +1,146 @@
+use tree_sitter::{Language, Node, Parser};
+
+use crate::doc_lang::{Strings, fill};
+use crate::escape::code_span;
+
+// --check-syntax: файл разбирается грамматикой tree-sitter его языка, и первая ошибка
+// разбора (узел ERROR или недостающий токен MISSING) попадает в пометку перед блоком.
+// Так находятся обрезанные, недописанные генератором и испорченные слиянием файлы.
+// TOML разбирается библиотекой toml. Для языков без грамматики проверки нет
+
+#[derive(Debug, PartialEq)]
+pub enum Problem {
+    // Парсер дописал бы токен, чтобы продолжить разбор: `}` в конце обрезанного файла
+    Missing { token: String, line: usize },
+    // Фрагмент, который не укладывается в грамматику
+    Unexpected { text: String, line: usize },
+    // Конструкция, которую парсер так и не смог закрыть до конца файла: файл обрезан
+    Unfinished { line: usize },
+    Invalid { line: Option<usize> },
+}
+
+// Фрагмент в пометке обрезается: узел ERROR может занимать пол-файла
+const MAX_SNIPPET: usize = 40;
+
+fn grammar(language: &str) -> Option<Language> {
+    let grammar = match language {
+        "rust" | "rs" => tree_sitter_rust::LANGUAGE,
+        "python" | "py" => tree_sitter_python::LANGUAGE,
+        "javascript" | "js" | "jsx" | "mjs" | "cjs" => tree_sitter_javascript::LANGUAGE,
+        "typescript" | "ts" => tree_sitter_typescript::LANGUAGE_TYPESCRIPT,
+        "tsx" => tree_sitter_typescript::LANGUAGE_TSX,
+        "go" => tree_sitter_go::LANGUAGE,
+        "c" | "h" => tree_sitter_c::LANGUAGE,
+        "cpp" | "hpp" | "cc" => tree_sitter_cpp::LANGUAGE,
+        "csharp" | "cs" => tree_sitter_c_sharp::LANGUAGE,
+        "java" => tree_sitter_java::LANGUAGE,
+        "php" => tree_sitter_php::LANGUAGE_PHP,
+        "css" => tree_sitter_css::LANGUAGE,
+        "bash" | "sh" => tree_sitter_bash::LANGUAGE,
+        "json" => tree_sitter_json::LANGUAGE,
+        _ => return None,
+    };
+    Some(grammar.into())
+}
+
+pub fn check(language: &str, content: &str) -> Option<Problem> {
+    if language == "toml" {
+        return toml::from_str::<toml::Table>(content)
+            .err()
+            .map(|e| Problem::Invalid { line: e.span().map(|span| line_of(content, span.start)) });
+    }
+
+    let mut parser = Parser::new();
+    parser.set_language(&grammar(language)?).ok()?;
+    let tree = parser.parse(content, None)?;
+    let node = first_error(tree.root_node())?;
+    let line = node.start_position().row + 1;
+    if node.is_missing() {
+        return Some(Problem::Missing { token: node.kind().to_string(), line });
+    }
+    if node.child_count() > 1 && node.end_byte() >= content.trim_end().len() {
+        return Some(Problem::Unfinished { line });
+    }
+    Some(Problem::Unexpected { text: snippet(&content[node.byte_range()]), line })
+}
+
+pub fn describe(problem: &Problem, language: &str, doc: &Strings) -> String {
+    match problem {
+        Problem::Missing { token, line } => fill(doc.syntax_missing, &[&code_span(token), line]),
+        Problem::Unexpected { text, line } => fill(doc.syntax_unexpected, &[&code_span(text), line]),
+        Problem::Unfinished { line } => fill(doc.syntax_unfinished, &[line]),
+        Problem::Invalid { line: Some(line) } => fill(doc.syntax_invalid_at, &[&language, line]),
+        Problem::Invalid { line: None } => fill(doc.syntax_invalid, &[&language]),
+    }
+}
+
+fn line_of(content: &str, offset: usize) -> usize {
+    content[..offset.min(content.len())].matches('\n').count() + 1
+}
+
+// Первый в порядке текста узел с ошибкой. Обход курсором, без рекурсии: вложенность
+// дерева повторяет вложенность скобок в файле. Поддеревья без ошибок пропускаются
+fn first_error(root: Node) -> Option<Node> {
+    if !root.has_error() {
+        return None;
+    }
+    let mut cursor = root.walk();
+    loop {
+        let node = cursor.node();
+        if node.is_error() || node.is_missing() {
+            return Some(node);
+        }
+        if node.has_error() && cursor.goto_first_child() {
+            continue;
+        }
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                return None;
+            }
+        }
+    }
+}
+
+// Узел ERROR охватывает все, что парсер пропустил; в пометку идет его первая строка
+fn snippet(text: &str) -> String {
+    let line = text.lines().next().unwrap_or_default().trim();
+    match line.char_indices().nth(MAX_SNIPPET) {
+        Some((end, _)) => format!("{}…", &line[..end]),
+        None => line.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_sources_have_no_problem() {
+        assert_eq!(check("rust", "fn main() { let s = \"}\"; }\n"), None);
+        assert_eq!(check("python", "def f():\n    return '('\n"), None);
+        assert_eq!(check("json", "{\"a\": [1, \"]\"]}\n"), None);
+    }
+
+    #[test]
+    fn languages_without_grammar_are_not_checked() {
+        assert_eq!(check("text", "unbalanced (\n"), None);
+        assert_eq!(check("markdown", "```\n"), None);
+    }
+
+    #[test]
+    fn problems_point_at_the_first_error() {
+        assert_eq!(check("python", "def f(:\n    return 1\n"), Some(Problem::Missing { token: ")".to_string(), line: 1 }));
+        assert_eq!(
+            check("javascript", "function f() {}\n}\n"),
+            Some(Problem::Unexpected { text: "}".to_string(), line: 2 })
+        );
+        assert_eq!(check("rust", "fn main() {\n    let x = vec![1, 2\n"), Some(Problem::Unfinished { line: 1 }));
+        assert_eq!(check("toml", "a = \n"), Some(Problem::Invalid { line: Some(1) }));
+    }
+
+    #[test]
+    fn long_error_snippets_are_shortened() {
+        assert_eq!(snippet(&"x".repeat(100)), format!("{}…", "x".repeat(MAX_SNIPPET)));
+        assert_eq!(snippet("  <<<<<<< HEAD\n  a();"), "<<<<<<< HEAD");
+    }
+}
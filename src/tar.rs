@@ -0,0 +1,202 @@
+use std::io::{self, Read};
+use std::path::{Component, PathBuf};
+
+use ::tar::{Archive, EntryType};
+
+// Чтение tar-потока целиком в память. Разбор заголовков (ustar, GNU длинные имена,
+// PAX-записи, разреженные файлы) делает крейт tar, здесь остаются только проверка путей
+// и перевод записей в то, что показывает снимок
+
+#[derive(Debug, PartialEq)]
+pub enum EntryKind {
+    File,
+    Directory,
+    Symlink,
+}
+
+// У символической ссылки в data лежит ее цель - так же, как в блобе git для `--at`
+pub struct Entry {
+    pub path: PathBuf,
+    pub kind: EntryKind,
+    pub data: Vec<u8>,
+}
+
+pub fn read_archive<R: Read>(reader: R) -> io::Result<Vec<Entry>> {
+    let mut entries: Vec<Entry> = Vec::new();
+
+    for entry in Archive::new(reader).entries()? {
+        let mut entry = entry?;
+        let name = String::from_utf8_lossy(&entry.path_bytes()).into_owned();
+        let Some(path) = sanitize_path(&name) else {
+            tracing::warn!("пропущен небезопасный путь '{}' в архиве", name);
+            continue;
+        };
+        if path.as_os_str().is_empty() {
+            continue;
+        }
+
+        let (kind, data) = match entry.header().entry_type() {
+            EntryType::Regular | EntryType::Continuous | EntryType::GNUSparse => {
+                let mut data = Vec::new();
+                entry.read_to_end(&mut data)?;
+                (EntryKind::File, data)
+            }
+            EntryType::Directory => (EntryKind::Directory, Vec::new()),
+            EntryType::Symlink => {
+                let target = entry.link_name_bytes().map(|target| target.into_owned()).unwrap_or_default();
+                (EntryKind::Symlink, target)
+            }
+            // Жесткая ссылка указывает на запись, прочитанную раньше, и получает ее содержимое
+            EntryType::Link => {
+                let target = entry.link_name_bytes().map(|target| String::from_utf8_lossy(&target).into_owned());
+                let original = target
+                    .as_deref()
+                    .and_then(sanitize_path)
+                    .and_then(|target| entries.iter().rev().find(|entry| entry.path == target && entry.kind == EntryKind::File));
+                match original {
+                    Some(original) => (EntryKind::File, original.data.clone()),
+                    None => {
+                        tracing::warn!("пропущена жесткая ссылка '{}': в архиве нет файла '{}'", name, target.unwrap_or_default());
+                        continue;
+                    }
+                }
+            }
+            // Устройства, FIFO, глобальные PAX-заголовки и прочие служебные записи в снимок не попадают
+            _ => continue,
+        };
+
+        entries.push(Entry { path, kind, data });
+    }
+
+    Ok(entries)
+}
+
+// Пути из архива не должны выходить за его корень
+fn sanitize_path(name: &str) -> Option<PathBuf> {
+    let mut path = PathBuf::new();
+    for component in PathBuf::from(name.trim_start_matches("./")).components() {
+        match component {
+            Component::Normal(part) => path.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    Some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::tar::{Builder, Header};
+
+    // Заголовок ustar, собранный вручную по спецификации, без участия крейта
+    fn raw_header(name: &str, typeflag: u8, size: usize, link: &str) -> [u8; 512] {
+        let mut header = [0u8; 512];
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        header[100..107].copy_from_slice(b"0000644");
+        header[124..135].copy_from_slice(format!("{:011o}", size).as_bytes());
+        header[156] = typeflag;
+        header[157..157 + link.len()].copy_from_slice(link.as_bytes());
+        header[257..263].copy_from_slice(b"ustar\0");
+        header[263..265].copy_from_slice(b"00");
+        header[148..156].fill(b' ');
+        let sum: u32 = header.iter().map(|&byte| byte as u32).sum();
+        header[148..155].copy_from_slice(format!("{:06o}\0", sum).as_bytes());
+        header
+    }
+
+    fn raw_archive(records: &[([u8; 512], &[u8])]) -> Vec<u8> {
+        let mut archive = Vec::new();
+        for (header, data) in records {
+            archive.extend_from_slice(header);
+            archive.extend_from_slice(data);
+            archive.resize(archive.len().div_ceil(512) * 512, 0);
+        }
+        archive.resize(archive.len() + 1024, 0);
+        archive
+    }
+
+    fn summary(entries: &[Entry]) -> Vec<(String, &EntryKind, String)> {
+        entries
+            .iter()
+            .map(|entry| (entry.path.display().to_string(), &entry.kind, String::from_utf8_lossy(&entry.data).into_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn reads_hand_written_ustar_records() {
+        let archive = raw_archive(&[
+            (raw_header("src/", b'5', 0, ""), b""),
+            (raw_header("src/main.rs", b'0', 13, ""), b"fn main() {}\n"),
+            (raw_header("src/old.rs", 0, 3, ""), b"old"),
+            (raw_header("latest", b'2', 0, "src/main.rs"), b""),
+            (raw_header("copy.rs", b'1', 0, "src/main.rs"), b""),
+            (raw_header("dev/null", b'3', 0, ""), b""),
+        ]);
+
+        let entries = read_archive(archive.as_slice()).unwrap();
+
+        assert_eq!(
+            summary(&entries),
+            [
+                ("src".to_string(), &EntryKind::Directory, String::new()),
+                ("src/main.rs".to_string(), &EntryKind::File, "fn main() {}\n".to_string()),
+                ("src/old.rs".to_string(), &EntryKind::File, "old".to_string()),
+                ("latest".to_string(), &EntryKind::Symlink, "src/main.rs".to_string()),
+                ("copy.rs".to_string(), &EntryKind::File, "fn main() {}\n".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn long_names_and_link_targets_survive() {
+        let long_dir = "d".repeat(120);
+        let long_target = format!("{}/{}", long_dir, "t".repeat(120));
+        let mut builder = Builder::new(Vec::new());
+        let mut header = Header::new_gnu();
+        header.set_size(2);
+        header.set_mode(0o644);
+        builder.append_data(&mut header, format!("{}/file.txt", long_dir), &b"ok"[..]).unwrap();
+        let mut link = Header::new_gnu();
+        link.set_entry_type(EntryType::Symlink);
+        link.set_size(0);
+        builder.append_link(&mut link, "link", &long_target).unwrap();
+        let archive = builder.into_inner().unwrap();
+
+        let entries = read_archive(archive.as_slice()).unwrap();
+
+        assert_eq!(
+            summary(&entries),
+            [
+                (format!("{}/file.txt", long_dir), &EntryKind::File, "ok".to_string()),
+                ("link".to_string(), &EntryKind::Symlink, long_target),
+            ]
+        );
+    }
+
+    #[test]
+    fn unsafe_paths_and_dangling_hard_links_are_skipped() {
+        let archive = raw_archive(&[
+            (raw_header("../escape.txt", b'0', 1, ""), b"x"),
+            (raw_header("/etc/passwd", b'0', 1, ""), b"x"),
+            (raw_header("hard", b'1', 0, "missing"), b""),
+            (raw_header("./kept.txt", b'0', 1, ""), b"k"),
+        ]);
+
+        let entries = read_archive(archive.as_slice()).unwrap();
+
+        assert_eq!(summary(&entries), [("kept.txt".to_string(), &EntryKind::File, "k".to_string())]);
+    }
+
+    #[test]
+    fn corrupted_and_truncated_archives_fail() {
+        let mut corrupted = raw_archive(&[(raw_header("a.txt", b'0', 1, ""), b"a")]);
+        corrupted[0] = b'b';
+        assert!(read_archive(corrupted.as_slice()).is_err());
+
+        let header = raw_header("a.txt", b'0', 1000, "");
+        let mut truncated = header.to_vec();
+        truncated.extend_from_slice(b"short");
+        assert!(read_archive(truncated.as_slice()).is_err());
+    }
+}
@@ -0,0 +1,68 @@
+use std::path::{Component, Path};
+
+// Распознавание тестового кода по общепринятым путям и именам (--split-tests).
+// Проверяется путь от корня проекта: сама целевая директория может называться tests
+
+const TEST_DIRS: [&str; 11] = [
+    "tests",
+    "test",
+    "__tests__",
+    "spec",
+    "specs",
+    "testdata",
+    "test_data",
+    "fixtures",
+    "__fixtures__",
+    "__snapshots__",
+    "__mocks__",
+];
+
+// Суффиксы имени без расширения в Java, Kotlin, C#, Swift, PHP: FooTest.java, FooTests.cs
+const CLASS_SUFFIXES: [&str; 2] = ["Test", "Tests"];
+
+pub fn is_test_path(relative_path: &Path) -> bool {
+    let mut components = relative_path.components().peekable();
+    while let Some(component) = components.next() {
+        let Component::Normal(name) = component else {
+            continue;
+        };
+        let name = name.to_string_lossy();
+        if components.peek().is_some() {
+            if is_test_dir_name(&name) {
+                return true;
+            }
+        } else {
+            return is_test_file_name(&name);
+        }
+    }
+    false
+}
+
+// Директория целиком относится к тестам: ее поддерево не делится
+pub fn is_test_dir(relative_path: &Path) -> bool {
+    relative_path.components().any(|component| match component {
+        Component::Normal(name) => is_test_dir_name(&name.to_string_lossy()),
+        _ => false,
+    })
+}
+
+fn is_test_dir_name(name: &str) -> bool {
+    TEST_DIRS.contains(&name.to_lowercase().as_str())
+}
+
+fn is_test_file_name(name: &str) -> bool {
+    let lower = name.to_lowercase();
+    // Расширений может быть несколько: app.test.tsx, user.spec.js
+    let stem = lower.split('.').next().unwrap_or_default();
+    let original_stem = name.split('.').next().unwrap_or_default();
+
+    lower == "conftest.py"
+        || lower.contains(".test.")
+        || lower.contains(".spec.")
+        || stem.starts_with("test_")
+        || ["_test", "_tests", "_spec", "_unittest"].iter().any(|suffix| stem.ends_with(suffix))
+        || stem == "tests"
+        || CLASS_SUFFIXES
+            .iter()
+            .any(|suffix| original_stem.len() > suffix.len() && original_stem.ends_with(suffix))
+}
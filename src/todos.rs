@@ -0,0 +1,133 @@
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::escape::{code_span, display_path};
+use crate::{AppConfig, Config, atomic_file, content_files, heading, relative_path, secrets, tree};
+
+// Раздел --todos: заметки TODO/FIXME/HACK/XXX в комментариях включенных файлов с путем,
+// строкой и текстом, и при необходимости CSV для разбора техдолга (--todos-csv).
+// Метка считается заметкой, только если перед ней на строке начинается комментарий:
+// идентификаторы вроде TODO_LIST и строки "XXX" в коде не попадают
+
+const MARKERS: [&str; 4] = ["TODO", "FIXME", "HACK", "XXX"];
+const COMMENT_STARTS: [&str; 6] = ["//", "#", "/*", "--", ";", "%"];
+
+struct Annotation {
+    path: String,
+    line: usize,
+    marker: &'static str,
+    text: String,
+}
+
+pub fn write_todos_section<W: Write>(
+    base_dir: &Path,
+    nodes: &[tree::TreeNode],
+    writer: &mut W,
+    app_config: &AppConfig,
+    config: &Config,
+) -> io::Result<()> {
+    let mut annotations = Vec::new();
+    for path in content_files(nodes, app_config, config) {
+        // В файлах с секретами комментарий может соседствовать со значением
+        if !app_config.unsafe_full_secrets && secrets::is_secret_file(&path, config) {
+            continue;
+        }
+        let Ok(content) = app_config.fs.read_to_string(&path) else {
+            continue;
+        };
+        for (number, line) in content.lines().enumerate() {
+            if let Some((marker, text)) = find_annotation(line) {
+                annotations.push(Annotation {
+                    path: display_path(&relative_path(base_dir, &path)),
+                    line: number + 1,
+                    marker,
+                    text,
+                });
+            }
+        }
+    }
+
+    let doc = app_config.doc;
+    writeln!(writer, "\n{} {}\n", heading(app_config, 2), doc.annotations)?;
+    writeln!(writer, "| {} | {} | {} | {} |", doc.file, doc.line, doc.marker, doc.text)?;
+    writeln!(writer, "|---|---:|---|---|")?;
+    for annotation in &annotations {
+        writeln!(
+            writer,
+            "| {} | {} | {} | {} |",
+            code_span(&annotation.path),
+            annotation.line,
+            annotation.marker,
+            table_cell(&annotation.text)
+        )?;
+    }
+
+    if let Some(csv_path) = &app_config.todos_csv {
+        write_csv(Path::new(csv_path), &annotations)?;
+        tracing::info!("Заметки ({}) записаны в {}", annotations.len(), csv_path);
+    }
+    Ok(())
+}
+
+// Первая метка на строке, отделенная от соседних символов, и текст после нее
+fn find_annotation(line: &str) -> Option<(&'static str, String)> {
+    let (start, marker) = MARKERS
+        .iter()
+        .filter_map(|&marker| {
+            line.match_indices(marker)
+                .find(|&(start, _)| {
+                    let before = line[..start].chars().next_back();
+                    let after = line[start + marker.len()..].chars().next();
+                    !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char)
+                })
+                .map(|(start, _)| (start, marker))
+        })
+        .min_by_key(|&(start, _)| start)?;
+
+    let prefix = &line[..start];
+    // `--` покрывает и <!-- в HTML и Markdown, `*` - продолжение блочного комментария
+    let in_comment =
+        COMMENT_STARTS.iter().any(|opener| prefix.contains(opener)) || prefix.trim_start().starts_with('*');
+    if !in_comment {
+        return None;
+    }
+
+    let text = line[start + marker.len()..]
+        .trim_end()
+        .trim_end_matches("*/")
+        .trim_end_matches("-->")
+        .trim_start_matches([':', '-', ' '])
+        .trim();
+    Some((marker, text.to_string()))
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn table_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+// RFC 4180: поля с запятой, кавычкой или переводом строки берутся в кавычки
+fn write_csv(path: &Path, annotations: &[Annotation]) -> io::Result<()> {
+    let mut csv = String::from("path,line,marker,text\r\n");
+    for annotation in annotations {
+        csv.push_str(&format!(
+            "{},{},{},{}\r\n",
+            csv_field(&annotation.path),
+            annotation.line,
+            annotation.marker,
+            csv_field(&annotation.text)
+        ));
+    }
+    atomic_file::write(path, csv)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::encoding;
+use crate::summarize;
+use crate::tree::{NodeKind, TreeNode};
+use crate::{AppConfig, Config, content_files, write_content_block};
+
+// Приблизительное число токенов для --tree-tokens: без токенизатора конкретной модели,
+// только чтобы было видно, куда уходит контекст. Словари BPE кодируют английский текст
+// и код примерно по 4 символа на токен, кириллицу и прочие не-ASCII символы - по 1-2
+
+pub fn estimate(content: &str) -> usize {
+    let (ascii, other) = content
+        .chars()
+        .fold((0usize, 0usize), |(ascii, other), c| if c.is_ascii() { (ascii + 1, other) } else { (ascii, other + 1) });
+    ascii.div_ceil(4) + other.div_ceil(2)
+}
+
+// Оценка для файлов раздела содержимого: у остальных файлов в документе токенов нет
+pub fn count(nodes: &[TreeNode], app_config: &AppConfig, config: &Config) -> HashMap<PathBuf, usize> {
+    let counts: HashMap<PathBuf, usize> = content_files(nodes, app_config, config)
+        .into_iter()
+        .filter_map(|path| {
+            let block = content_block(&path, app_config, config)?;
+            Some((path, estimate(&String::from_utf8_lossy(&block))))
+        })
+        .collect();
+    tracing::info!("Примерно токенов в содержимом файлов: {}", format(counts.values().sum()));
+    counts
+}
+
+// Оценивается блок в том виде, в каком он попадет в документ: сводка lock-файла, первые
+// строки по [sampling], маски секретов и нормализация пробелов. Вывод summarizers заранее
+// не известен, команды ради оценки не запускаются
+fn content_block(path: &Path, app_config: &AppConfig, config: &Config) -> Option<Vec<u8>> {
+    if summarize::find(path, app_config, config).is_some() {
+        return None;
+    }
+    let content = match app_config.fs.read_text(path) {
+        Ok((content, _)) => content,
+        Err(e) if e.kind() == io::ErrorKind::InvalidData && app_config.lossy_read => {
+            let bytes = app_config.fs.read(path).ok()?;
+            String::from_utf8_lossy(encoding::strip_utf8_bom(&bytes)).into_owned()
+        }
+        Err(_) => return None,
+    };
+    let mut block = Vec::new();
+    write_content_block(&mut block, path, &content, app_config, config).ok()?;
+    Some(block)
+}
+
+// Сумма по всем файлам внутри директории
+pub fn total(nodes: &[TreeNode], counts: &HashMap<PathBuf, usize>) -> usize {
+    nodes
+        .iter()
+        .map(|node| match &node.kind {
+            NodeKind::File(_) => counts.get(&node.path).copied().unwrap_or(0),
+            NodeKind::Directory(children) => total(children, counts),
+            _ => 0,
+        })
+        .sum()
+}
+
+pub fn format(count: usize) -> String {
+    match count {
+        0..1000 => count.to_string(),
+        1000..1_000_000 => format!("{:.1}k", count as f64 / 1000.0),
+        _ => format!("{:.1}M", count as f64 / 1_000_000.0),
+    }
+}
@@ -0,0 +1,87 @@
+use std::path::{Component, Path, PathBuf};
+
+// --ascii-paths: транслитерация путей в заголовках и якорях для систем документации,
+// которые портят кириллические и китайские имена в ссылках. Кириллица и греческий
+// передаются по правилам, близким к ICAO, латиница с диакритикой - базовой буквой,
+// остальные символы - кодом `uXXXX`, чтобы разные имена не совпадали
+
+const CYRILLIC: [(char, &str); 40] = [
+    ('а', "a"), ('б', "b"), ('в', "v"), ('г', "g"), ('д', "d"), ('е', "e"), ('ё', "e"), ('ж', "zh"),
+    ('з', "z"), ('и', "i"), ('й', "i"), ('к', "k"), ('л', "l"), ('м', "m"), ('н', "n"), ('о', "o"),
+    ('п', "p"), ('р', "r"), ('с', "s"), ('т', "t"), ('у', "u"), ('ф', "f"), ('х', "kh"), ('ц', "ts"),
+    ('ч', "ch"), ('ш', "sh"), ('щ', "shch"), ('ъ', "ie"), ('ы', "y"), ('ь', ""), ('э', "e"), ('ю', "iu"),
+    ('я', "ia"), ('є', "ie"), ('і', "i"), ('ї', "i"), ('ґ', "g"), ('ў', "u"), ('ђ', "dj"), ('ј', "j"),
+];
+
+const GREEK: [(char, &str); 36] = [
+    ('α', "a"), ('β', "v"), ('γ', "g"), ('δ', "d"), ('ε', "e"), ('ζ', "z"), ('η', "i"), ('θ', "th"),
+    ('ι', "i"), ('κ', "k"), ('λ', "l"), ('μ', "m"), ('ν', "n"), ('ξ', "x"), ('ο', "o"), ('π', "p"),
+    ('ρ', "r"), ('σ', "s"), ('ς', "s"), ('τ', "t"), ('υ', "y"), ('φ', "f"), ('χ', "ch"), ('ψ', "ps"),
+    ('ω', "o"), ('ά', "a"), ('έ', "e"), ('ή', "i"), ('ί', "i"), ('ό', "o"), ('ύ', "y"), ('ώ', "o"),
+    ('ϊ', "i"), ('ϋ', "y"), ('ΐ', "i"), ('ΰ', "y"),
+];
+
+// Латиница с диакритикой: буквы, которые не раскладываются на базовую и знак
+const LATIN: [(char, &str); 14] = [
+    ('ß', "ss"), ('æ', "ae"), ('œ', "oe"), ('ø', "o"), ('đ', "d"), ('ł', "l"), ('þ', "th"),
+    ('ð', "d"), ('ı', "i"), ('ħ', "h"), ('ŋ', "ng"), ('ſ', "s"), ('ŀ', "l"), ('ĸ', "k"),
+];
+
+// Базовые буквы для блоков Latin-1 Supplement и Latin Extended-A (U+00C0..U+017F)
+const LATIN_BASE: &str = "AAAAAAACEEEEIIIIDNOOOOO×OUUUUYÞsaaaaaaaceeeeiiiidnooooo÷ouuuuyþy\
+AaAaAaCcCcCcCcDdDdEeEeEeEeEeGgGgGgGgHhHhIiIiIiIiIiJjJjKkkLlLlLlLlLlNnNnNnnNnOoOoOoOoRrRrRrSsSsSsSsTtTtTtUuUuUuUuUuUuWwYyYZzZzZzs";
+
+pub fn transliterate(text: &str) -> String {
+    let mut result = String::new();
+    for c in text.chars() {
+        if c.is_ascii() {
+            result.push(c);
+            continue;
+        }
+        let lower = c.to_lowercase().next().unwrap_or(c);
+        let upper = lower != c;
+        match lookup(lower) {
+            Some(mapped) if upper => {
+                let mut chars = mapped.chars();
+                if let Some(first) = chars.next() {
+                    result.push(first.to_ascii_uppercase());
+                    result.extend(chars);
+                }
+            }
+            Some(mapped) => result.push_str(mapped),
+            None => match latin_base(c) {
+                Some(base) => result.push(base),
+                None => result.push_str(&format!("u{:04x}", c as u32)),
+            },
+        }
+    }
+    result
+}
+
+pub fn is_ascii_path(path: &Path) -> bool {
+    path.to_str().is_some_and(str::is_ascii)
+}
+
+// Каждый компонент пути транслитерируется отдельно, разделители не меняются
+pub fn ascii_path(path: &Path) -> PathBuf {
+    path.components()
+        .map(|component| match component {
+            Component::Normal(name) => PathBuf::from(transliterate(&name.to_string_lossy())),
+            other => PathBuf::from(other.as_os_str()),
+        })
+        .collect()
+}
+
+fn lookup(c: char) -> Option<&'static str> {
+    CYRILLIC
+        .iter()
+        .chain(GREEK.iter())
+        .chain(LATIN.iter())
+        .find(|(from, _)| *from == c)
+        .map(|(_, to)| *to)
+}
+
+fn latin_base(c: char) -> Option<char> {
+    let index = (c as u32).checked_sub(0xC0)? as usize;
+    LATIN_BASE.chars().nth(index).filter(char::is_ascii_alphabetic)
+}
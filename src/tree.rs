@@ -0,0 +1,688 @@
+use std::ffi::OsString;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread::{self, Scope, ScopedJoinHandle};
+use ignore::gitignore::Gitignore;
+use tracing::Span;
+
+use crate::anchors;
+use crate::doc_lang::{Strings, fill};
+use crate::escape::{self, display_os_str, display_path};
+use crate::interrupt;
+use crate::logging;
+use crate::notices;
+use crate::test_paths;
+use crate::tokens;
+use crate::walk_errors;
+use crate::{
+    AppConfig, Config, SkipReason, entry_is_dir, is_output_path, relative_path, sampled_out_count, should_skip_entry,
+};
+
+// Файлы-заглушки, которые держат пустую директорию в git: директория только с ними
+// считается пустой для --mark-empty и --prune-empty
+const PLACEHOLDERS: [&str; 2] = [".gitkeep", ".keep"];
+
+// Модель дерева строится целиком до вывода: только так известно, какой из видимых
+// элементов директории последний, и соединители `├──`/`└──` расставляются верно
+
+#[derive(Clone)]
+pub struct TreeNode {
+    pub path: PathBuf,
+    pub name: OsString,
+    pub kind: NodeKind,
+}
+
+// Метаданные файла из единственного stat при обходе: раздел содержимого, отчеты и
+// проверка max_file_size берут их из модели, не обращаясь к диску повторно
+#[derive(Clone, Copy)]
+pub struct FileInfo {
+    pub len: u64,
+    pub hard_link: Option<(u64, u64)>,
+    // Обычный файл: у ссылки из архива, FIFO и устройства раздела содержимого нет
+    pub regular: bool,
+}
+
+#[derive(Clone)]
+pub enum NodeKind {
+    File(FileInfo),
+    Directory(Vec<TreeNode>),
+    // Исключенная директория, которая выводится как `name/ ...` без содержимого.
+    // С --count-skipped хранит число файлов внутри и признак, что подсчет прерван по лимиту
+    Collapsed(Option<(usize, bool)>),
+    // Пропущенный файл, который с --show-skipped-files остается в дереве с пометкой
+    Skipped,
+    // Файлы, не попавшие в выборку --sample-dir
+    Omitted(usize),
+    // Элементы сверх --max-entries-per-dir
+    Truncated(usize),
+    // Элемент, который не удалось прочитать: директория без доступа, битая ссылка и т.п.
+    Failed { is_dir: bool, message: String },
+}
+
+// Директории обходятся параллельно (--threads): поддиректория уходит в отдельный поток,
+// если есть свободный, иначе обходится в текущем. Узлы собираются в порядке имен,
+// поэтому дерево не зависит от того, какой поток закончил раньше
+pub fn build_tree(
+    current_dir: &Path,
+    app_config: &AppConfig,
+    config: &Config,
+    gitignore_matcher: &Option<Gitignore>,
+) -> io::Result<Vec<TreeNode>> {
+    let walker = Walker {
+        idle_threads: AtomicUsize::new(app_config.threads.saturating_sub(1)),
+        app_config,
+        config,
+        gitignore_matcher,
+    };
+    thread::scope(|scope| walker.build_dir(scope, current_dir))
+}
+
+struct Walker<'a> {
+    idle_threads: AtomicUsize,
+    app_config: &'a AppConfig,
+    config: &'a Config,
+    gitignore_matcher: &'a Option<Gitignore>,
+}
+
+enum PendingKind<'scope> {
+    Ready(NodeKind),
+    Spawned(ScopedJoinHandle<'scope, io::Result<Vec<TreeNode>>>),
+}
+
+impl<'a> Walker<'a> {
+    fn build_dir<'scope>(&'scope self, scope: &'scope Scope<'scope, '_>, current_dir: &Path) -> io::Result<Vec<TreeNode>> {
+        let (app_config, config, gitignore_matcher) = (self.app_config, self.config, self.gitignore_matcher);
+
+        let _span = logging::directory_span(current_dir).entered();
+        let mut entries = Vec::new();
+        for entry in app_config.fs.read_dir(current_dir)? {
+            match entry {
+                Ok(entry) => entries.push(entry),
+                Err(e) => walk_errors::record(app_config, current_dir, &walk_errors::describe(&e, app_config.doc)),
+            }
+        }
+
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        // Пустота определяется по диску, а не по дереву: директория, все файлы которой
+        // исключены, не пустая. Вложенные директории проверяются после их обхода
+        let contents: Vec<PathBuf> = entries
+            .iter()
+            .filter(|entry| !PLACEHOLDERS.contains(&entry.name.to_string_lossy().as_ref()))
+            .map(|entry| entry.path.clone())
+            .collect();
+
+        let mut pending = Vec::new();
+        let mut truncated = 0;
+
+        for entry in entries {
+            if interrupt::stop_at(&entry.path) {
+                break;
+            }
+            let path = entry.path.clone();
+            let name = entry.name.clone();
+            let is_dir = entry_is_dir(&entry, app_config);
+
+            let reason =
+                should_skip_entry(&path, &name.to_string_lossy(), is_dir, app_config, config, gitignore_matcher);
+            // Исключенные файлы stat-ятся, только если их размеры нужны отчету --top-files
+            if reason == SkipReason::Skip
+                && !is_dir
+                && app_config.top_files.is_some()
+                && let Ok(metadata) = app_config.fs.metadata(&path)
+            {
+                app_config.excluded_sizes.lock().unwrap().insert(path.clone(), metadata.len);
+            }
+            // Лишние элементы только считаются: поддиректории сверх лимита не обходятся
+            if reason != SkipReason::Skip
+                && app_config.max_entries_per_dir.is_some_and(|limit| pending.len() >= limit)
+            {
+                truncated += 1;
+                continue;
+            }
+
+            let kind = match reason {
+                // Выходного файла нет до первого запуска: с маркером он менял бы дерево между запусками
+                SkipReason::Skip if is_dir || !app_config.show_skipped_files || is_output_path(&path, app_config) => continue,
+                SkipReason::Skip => PendingKind::Ready(NodeKind::Skipped),
+                SkipReason::SkipWithEllipsis => PendingKind::Ready(NodeKind::Collapsed(
+                    app_config.count_skipped.map(|limit| count_files(app_config, &path, limit)),
+                )),
+                SkipReason::NoSkip if is_dir => {
+                    if self.take_thread() {
+                        let child = path.clone();
+                        let parent = Span::current();
+                        PendingKind::Spawned(scope.spawn(move || {
+                            let result = parent.in_scope(|| self.build_dir(scope, &child));
+                            self.idle_threads.fetch_add(1, Ordering::SeqCst);
+                            result
+                        }))
+                    } else {
+                        match self.directory_kind(&path, self.build_dir(scope, &path)) {
+                            Some(kind) => PendingKind::Ready(kind),
+                            None => continue,
+                        }
+                    }
+                }
+                // Единственный stat файла за весь запуск. Ошибка у ссылки означает, что ее цель
+                // не существует или образует цикл
+                SkipReason::NoSkip => match app_config.fs.metadata(&path) {
+                    Ok(metadata) => PendingKind::Ready(NodeKind::File(FileInfo {
+                        len: metadata.len,
+                        hard_link: metadata.hard_link,
+                        regular: metadata.is_file,
+                    })),
+                    Err(e) if e.kind() == io::ErrorKind::NotFound && !entry.is_symlink => {
+                        let message = app_config.doc.deleted_during_run;
+                        notices::add(app_config, notices::Kind::Deleted, Some(&path), message);
+                        continue;
+                    }
+                    Err(e) => PendingKind::Ready(failed(app_config, &path, false, &e)),
+                },
+            };
+
+            pending.push((path, name, kind));
+        }
+
+        let mut nodes = Vec::with_capacity(pending.len());
+        for (path, name, kind) in pending {
+            let kind = match kind {
+                PendingKind::Ready(kind) => kind,
+                PendingKind::Spawned(handle) => {
+                    let result = handle.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+                    match self.directory_kind(&path, result) {
+                        Some(kind) => kind,
+                        None => continue,
+                    }
+                }
+            };
+            nodes.push(TreeNode { path, name, kind });
+        }
+
+        if app_config.mark_empty || app_config.prune_empty {
+            let mut empty_dirs = app_config.empty_dirs.lock().unwrap();
+            if contents.iter().all(|path| empty_dirs.contains(path)) {
+                empty_dirs.insert(current_dir.to_path_buf());
+            }
+        }
+
+        let omitted = sampled_out_count(current_dir, app_config, config, gitignore_matcher);
+        if omitted > 0 {
+            nodes.push(TreeNode {
+                path: current_dir.to_path_buf(),
+                name: OsString::new(),
+                kind: NodeKind::Omitted(omitted),
+            });
+        }
+        if truncated > 0 {
+            nodes.push(TreeNode {
+                path: current_dir.to_path_buf(),
+                name: OsString::new(),
+                kind: NodeKind::Truncated(truncated),
+            });
+        }
+
+        Ok(nodes)
+    }
+
+    fn take_thread(&self) -> bool {
+        self.idle_threads
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |idle| idle.checked_sub(1))
+            .is_ok()
+    }
+
+    // Директория, удаленная между чтением родителя и ее собственным обходом, в дерево
+    // не попадает: битая ссылка на директорию сюда не доходит, is_dir для нее ложно
+    fn directory_kind(&self, path: &Path, children: io::Result<Vec<TreeNode>>) -> Option<NodeKind> {
+        match children {
+            Ok(_) if self.app_config.prune_empty && is_empty_dir(path, self.app_config) => None,
+            Ok(children) => Some(NodeKind::Directory(children)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                let message = self.app_config.doc.deleted_during_run;
+                notices::add(self.app_config, notices::Kind::Deleted, Some(path), message);
+                None
+            }
+            Err(e) => Some(failed(self.app_config, path, true, &e)),
+        }
+    }
+}
+
+// Файлы дерева в порядке обхода вместе с метаданными из обхода
+pub fn file_entries(nodes: &[TreeNode], files: &mut Vec<(PathBuf, FileInfo)>) {
+    for node in nodes {
+        match &node.kind {
+            NodeKind::Directory(children) => file_entries(children, files),
+            NodeKind::File(info) => files.push((node.path.clone(), *info)),
+            NodeKind::Collapsed(_)
+            | NodeKind::Skipped
+            | NodeKind::Omitted(_)
+            | NodeKind::Truncated(_)
+            | NodeKind::Failed { .. } => {}
+        }
+    }
+}
+
+// Пути файлов дерева в порядке обхода: то же, что отдельный обход с теми же правилами
+pub fn file_paths(nodes: &[TreeNode], files: &mut Vec<PathBuf>) {
+    for node in nodes {
+        match &node.kind {
+            NodeKind::Directory(children) => file_paths(children, files),
+            NodeKind::File(_) => files.push(node.path.clone()),
+            NodeKind::Collapsed(_)
+            | NodeKind::Skipped
+            | NodeKind::Omitted(_)
+            | NodeKind::Truncated(_)
+            | NodeKind::Failed { .. } => {}
+        }
+    }
+}
+
+// Дерево делится на код и тесты (--split-tests). Директория попадает в оба дерева, если в ней
+// есть и то и другое; свернутые директории и служебные строки остаются в коде, если сами
+// не лежат в тестовой директории
+pub fn split_tests(base_dir: &Path, nodes: &[TreeNode]) -> (Vec<TreeNode>, Vec<TreeNode>) {
+    split(base_dir, nodes, &|relative, is_file| {
+        if is_file { test_paths::is_test_path(relative) } else { test_paths::is_test_dir(relative) }
+    })
+}
+
+// Общая часть --split-tests и --build-section: элементы, для которых matches (путь от
+// base_dir и признак файла) истинно, уходят во второе дерево целиком
+pub fn split(
+    base_dir: &Path,
+    nodes: &[TreeNode],
+    matches: &dyn Fn(&Path, bool) -> bool,
+) -> (Vec<TreeNode>, Vec<TreeNode>) {
+    let mut rest = Vec::new();
+    let mut matched = Vec::new();
+
+    for node in nodes {
+        let relative = node.path.strip_prefix(base_dir).unwrap_or(&node.path);
+        let is_file = matches!(node.kind, NodeKind::File(_) | NodeKind::Skipped);
+        match &node.kind {
+            _ if matches(relative, is_file) => matched.push(node.clone()),
+            NodeKind::Directory(children) => {
+                let (rest_children, matched_children) = split(base_dir, children, matches);
+                for (target, children) in [(&mut rest, rest_children), (&mut matched, matched_children)] {
+                    if !children.is_empty() {
+                        target.push(TreeNode {
+                            path: node.path.clone(),
+                            name: node.name.clone(),
+                            kind: NodeKind::Directory(children),
+                        });
+                    }
+                }
+            }
+            _ => rest.push(node.clone()),
+        }
+    }
+
+    (rest, matched)
+}
+
+// Отступ повторяет вывод классической утилиты `tree`: на уровнях, где ниже еще есть
+// соседние элементы, проводится вертикальная линия `│`
+pub fn render_pretty<W: Write>(nodes: &[TreeNode], writer: &mut W, indent: &str, app_config: &AppConfig) -> io::Result<()> {
+    let doc = app_config.doc;
+    let marker = &app_config.skip_marker;
+    for (i, node) in nodes.iter().enumerate() {
+        let is_last = i == nodes.len() - 1;
+        let prefix = if is_last { "└── " } else { "├── " };
+        let name = display_os_str(&node.name);
+
+        match &node.kind {
+            NodeKind::File(_) => writeln!(writer, "{}{}{}{}", indent, prefix, name, tokens_suffix(node, app_config))?,
+            NodeKind::Collapsed(count) => {
+                writeln!(writer, "{}{}{}/ {}{}", indent, prefix, name, marker, count_suffix(*count, doc))?
+            }
+            NodeKind::Skipped => writeln!(writer, "{}{}{} {}", indent, prefix, name, marker)?,
+            NodeKind::Omitted(count) => writeln!(writer, "{}{}{}", indent, prefix, omitted_label(*count, doc))?,
+            NodeKind::Truncated(count) => writeln!(writer, "{}{}{}", indent, prefix, fill(doc.and_more, &[count]))?,
+            NodeKind::Failed { is_dir, message } => {
+                writeln!(writer, "{}{}{}{} {}", indent, prefix, name, dir_slash(*is_dir), fill(doc.tree_error, &[message]))?
+            }
+            NodeKind::Directory(_) => {
+                let (name, node) = compact_chain(node, app_config);
+                let suffix = format!("{}{}", empty_suffix(node, app_config), tokens_suffix(node, app_config));
+                writeln!(writer, "{}{}{}/{}", indent, prefix, name, suffix)?;
+                let guide = if is_last { "    " } else { "│   " };
+                render_pretty(children_of(node), writer, &format!("{}{}", indent, guide), app_config)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// prefix - путь base_dir в режиме --paths, пустой для путей от base_dir
+pub fn render_flat<W: Write>(
+    base_dir: &Path,
+    prefix: &Path,
+    nodes: &[TreeNode],
+    writer: &mut W,
+    app_config: &AppConfig,
+) -> io::Result<()> {
+    let doc = app_config.doc;
+    let marker = &app_config.skip_marker;
+    for node in nodes {
+        let path = display_path(&prefix.join(relative_path(base_dir, &node.path)));
+
+        match &node.kind {
+            NodeKind::File(_) => writeln!(writer, "{}{}", path, tokens_suffix(node, app_config))?,
+            NodeKind::Collapsed(count) => writeln!(writer, "{}/ {}{}", path, marker, count_suffix(*count, doc))?,
+            NodeKind::Skipped => writeln!(writer, "{} {}", path, marker)?,
+            NodeKind::Omitted(count) => writeln!(writer, "{}{}", marker_dir(base_dir, prefix, node, &path), omitted_label(*count, doc))?,
+            NodeKind::Truncated(count) => {
+                writeln!(writer, "{}{}", marker_dir(base_dir, prefix, node, &path), fill(doc.and_more, &[count]))?
+            }
+            NodeKind::Failed { is_dir, message } => {
+                writeln!(writer, "{}{} {}", path, dir_slash(*is_dir), fill(doc.tree_error, &[message]))?
+            }
+            NodeKind::Directory(children) => {
+                writeln!(writer, "{}/{}{}", path, empty_suffix(node, app_config), tokens_suffix(node, app_config))?;
+                render_flat(base_dir, prefix, children, writer, app_config)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Пометки о скрытых элементах относятся к директории node.path. Для корня relative_path
+// вернул бы `./имя`, поэтому они выводятся без пути или с одним префиксом проекта
+fn marker_dir(base_dir: &Path, prefix: &Path, node: &TreeNode, path: &str) -> String {
+    let dir = if node.path == base_dir { display_path(prefix) } else { path.to_string() };
+    if dir.is_empty() { dir } else { format!("{}/", dir) }
+}
+
+// --compact-tree: цепочка директорий, в каждой из которых единственный элемент - директория,
+// выводится одной строкой `a/b/c/`, как в веб-интерфейсе GitHub. Возвращает имя цепочки
+// и последнюю директорию в ней: ее пометки и содержимое выводятся под этой строкой
+fn compact_chain<'a>(node: &'a TreeNode, app_config: &AppConfig) -> (String, &'a TreeNode) {
+    let mut name = display_os_str(&node.name);
+    let mut node = node;
+    if app_config.compact_tree {
+        while let [child @ TreeNode { kind: NodeKind::Directory(_), .. }] = children_of(node) {
+            name = format!("{}/{}", name, display_os_str(&child.name));
+            node = child;
+        }
+    }
+    (name, node)
+}
+
+fn children_of(node: &TreeNode) -> &[TreeNode] {
+    match &node.kind {
+        NodeKind::Directory(children) => children,
+        _ => &[],
+    }
+}
+
+fn failed(app_config: &AppConfig, path: &Path, is_dir: bool, error: &io::Error) -> NodeKind {
+    let message = walk_errors::describe(error, app_config.doc);
+    walk_errors::record(app_config, path, &message);
+    NodeKind::Failed { is_dir, message }
+}
+
+fn dir_slash(is_dir: bool) -> &'static str {
+    if is_dir { "/" } else { "" }
+}
+
+// Дерево для --anchors/--link-base выводится HTML-блоком <pre>: внутри блока кода ссылки
+// не работают. Имя ведет к разделу файла в документе, `↗` - к файлу в репозитории.
+// has_section сообщает, есть ли у файла раздел с содержимым
+pub fn render_linked<W: Write>(
+    base_dir: &Path,
+    nodes: &[TreeNode],
+    writer: &mut W,
+    indent: &str,
+    app_config: &AppConfig,
+    has_section: &dyn Fn(&Path) -> bool,
+) -> io::Result<()> {
+    for (i, node) in nodes.iter().enumerate() {
+        let is_last = i == nodes.len() - 1;
+        let relative = relative_path(base_dir, &node.path);
+        let (lead, name) = if app_config.flat_tree {
+            (String::new(), display_path(&relative))
+        } else {
+            let prefix = if is_last { "└── " } else { "├── " };
+            (format!("{}{}", indent, prefix), display_os_str(&node.name))
+        };
+        let name = escape::html(&name);
+
+        let target = |label: String| {
+            format!("<a href=\"#{}\">{}</a>", app_config.slugs.lock().unwrap().slug(&relative), label)
+        };
+        let repo_link = app_config
+            .link_base
+            .as_deref()
+            .map(|base| format!(" <a href=\"{}\">↗</a>", escape::html(&anchors::link_url(base, &relative))))
+            .unwrap_or_default();
+
+        match &node.kind {
+            NodeKind::File(_) if has_section(&node.path) => {
+                writeln!(writer, "{}{}{}{}", lead, target(name), tokens_suffix(node, app_config), repo_link)?
+            }
+            NodeKind::File(_) => writeln!(writer, "{}{}{}{}", lead, name, tokens_suffix(node, app_config), repo_link)?,
+            NodeKind::Collapsed(count) => {
+                let marker = escape::html(&app_config.skip_marker);
+                writeln!(writer, "{}{}/ {}{}{}", lead, name, marker, count_suffix(*count, app_config.doc), repo_link)?
+            }
+            NodeKind::Skipped => writeln!(writer, "{}{} {}", lead, name, escape::html(&app_config.skip_marker))?,
+            NodeKind::Omitted(count) => {
+                let lead = if app_config.flat_tree { format!("{}/", name) } else { lead };
+                writeln!(writer, "{}{}", lead, omitted_label(*count, app_config.doc))?
+            }
+            NodeKind::Truncated(count) => {
+                let lead = if app_config.flat_tree { format!("{}/", name) } else { lead };
+                writeln!(writer, "{}{}", lead, fill(app_config.doc.and_more, &[count]))?
+            }
+            NodeKind::Failed { is_dir, message } => {
+                let error = fill(app_config.doc.tree_error, &[&escape::html(message)]);
+                writeln!(writer, "{}{}{} {}", lead, name, dir_slash(*is_dir), error)?
+            }
+            NodeKind::Directory(_) => {
+                // Цепочка --compact-tree ведет к первой директории: файлы у них общие
+                let (name, node) = if app_config.flat_tree {
+                    (name, node)
+                } else {
+                    let (chain, node) = compact_chain(node, app_config);
+                    (escape::html(&chain), node)
+                };
+                let children = children_of(node);
+                // Якорь директории ставится у первого ее файла: без файлов ссылаться некуда
+                let label = format!("{}/", name);
+                let label = if contains_section(children, has_section) { target(label) } else { label };
+                let suffix = format!("{}{}", empty_suffix(node, app_config), tokens_suffix(node, app_config));
+                writeln!(writer, "{}{}{}{}", lead, label, suffix, repo_link)?;
+                let guide = if is_last { "    " } else { "│   " };
+                render_linked(base_dir, children, writer, &format!("{}{}", indent, guide), app_config, has_section)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn contains_section(nodes: &[TreeNode], has_section: &dyn Fn(&Path) -> bool) -> bool {
+    nodes.iter().any(|node| match &node.kind {
+        NodeKind::File(_) => has_section(&node.path),
+        NodeKind::Directory(children) => contains_section(children, has_section),
+        _ => false,
+    })
+}
+
+// Подсчет файлов в исключенной директории с ранним выходом: огромные node_modules
+// не обходятся целиком. Символические ссылки не разыменовываются. Выходной файл
+// не считается, чтобы число не менялось после первого запуска
+fn count_files(app_config: &AppConfig, dir: &Path, limit: usize) -> (usize, bool) {
+    let mut count = 0;
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = app_config.fs.read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.into_iter().flatten() {
+            if entry.is_dir {
+                pending.push(entry.path);
+            } else if !is_output_path(&entry.path, app_config) {
+                count += 1;
+                if count >= limit {
+                    return (count, true);
+                }
+            }
+        }
+    }
+
+    (count, false)
+}
+
+fn count_suffix(count: Option<(usize, bool)>, doc: &Strings) -> String {
+    match count {
+        Some((count, true)) => format!(" {}", fill(doc.skipped_files, &[&format!("{}+", count)])),
+        Some((count, false)) => format!(" {}", fill(doc.skipped_files, &[&count])),
+        None => String::new(),
+    }
+}
+
+fn is_empty_dir(path: &Path, app_config: &AppConfig) -> bool {
+    app_config.empty_dirs.lock().unwrap().contains(path)
+}
+
+// --mark-empty: пометка пустой директории (или директории только с .gitkeep)
+fn empty_suffix(node: &TreeNode, app_config: &AppConfig) -> String {
+    if app_config.mark_empty && matches!(node.kind, NodeKind::Directory(_)) && is_empty_dir(&node.path, app_config) {
+        format!(" {}", app_config.doc.empty_dir)
+    } else {
+        String::new()
+    }
+}
+
+// --tree-tokens: у файла - его оценка, у директории - сумма по файлам внутри.
+// Файлы без раздела содержимого и директории без таких файлов не помечаются
+fn tokens_suffix(node: &TreeNode, app_config: &AppConfig) -> String {
+    if !app_config.tree_tokens {
+        return String::new();
+    }
+    let counts = app_config.token_counts.lock().unwrap();
+    let count = match &node.kind {
+        NodeKind::File(_) => counts.get(&node.path).copied(),
+        NodeKind::Directory(children) => Some(tokens::total(children, &counts)).filter(|&total| total > 0),
+        _ => None,
+    };
+    count.map_or(String::new(), |count| format!(" {}", fill(app_config.doc.tree_tokens, &[&tokens::format(count)])))
+}
+
+// Подпись узла без собственной страницы (свернутая директория, пропуск, выборка, ошибка)
+// для форматов, где у файлов и директорий свои заметки
+pub fn label(node: &TreeNode, app_config: &AppConfig) -> Option<String> {
+    let doc = app_config.doc;
+    let marker = &app_config.skip_marker;
+    let name = display_os_str(&node.name);
+    match &node.kind {
+        NodeKind::File(_) | NodeKind::Directory(_) => None,
+        NodeKind::Collapsed(count) => Some(format!("{}/ {}{}", name, marker, count_suffix(*count, doc))),
+        NodeKind::Skipped => Some(format!("{} {}", name, marker)),
+        NodeKind::Omitted(count) => Some(omitted_label(*count, doc)),
+        NodeKind::Truncated(count) => Some(fill(doc.and_more, &[count])),
+        NodeKind::Failed { is_dir, message } => Some(format!("{}{} {}", name, dir_slash(*is_dir), fill(doc.tree_error, &[message]))),
+    }
+}
+
+fn omitted_label(count: usize, doc: &Strings) -> String {
+    fill(doc.sampled_out, &[&count])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vfs::{MemoryFs, MemoryKind};
+    use crate::{app_config_from_matches, cli, load_config_layers};
+
+    // Обход проекта в памяти: те же флаги, что и у CLI, но без диска и .gitignore
+    fn walk(entries: &[(&str, MemoryKind)], args: &[&str]) -> Vec<String> {
+        let mut memory = MemoryFs::default();
+        for (path, kind) in entries {
+            memory.insert(PathBuf::from(path), *kind, path.as_bytes().to_vec());
+        }
+        let matches = cli().try_get_matches_from(["proj2tree", "-G"].iter().chain(args)).unwrap();
+        let mut app_config = app_config_from_matches(&matches).unwrap();
+        app_config.fs = Box::new(memory);
+        app_config.root_device = None;
+        app_config.path_prefix = PathBuf::new();
+        app_config.gitattributes = None;
+        let config = load_config_layers(Vec::new());
+
+        let nodes = build_tree(Path::new(""), &app_config, &config, &None).unwrap();
+        let mut lines = Vec::new();
+        describe(&nodes, &mut lines);
+        lines
+    }
+
+    fn describe(nodes: &[TreeNode], lines: &mut Vec<String>) {
+        for node in nodes {
+            let path = node.path.display();
+            match &node.kind {
+                NodeKind::File(info) => lines.push(format!("{} ({})", path, info.len)),
+                NodeKind::Directory(children) => {
+                    lines.push(format!("{}/", path));
+                    describe(children, lines);
+                }
+                NodeKind::Collapsed(_) => lines.push(format!("{}/ ...", path)),
+                NodeKind::Skipped => lines.push(format!("{} skipped", path)),
+                NodeKind::Omitted(count) => lines.push(format!("{}: omitted {}", path, count)),
+                NodeKind::Truncated(count) => lines.push(format!("{}: truncated {}", path, count)),
+                NodeKind::Failed { message, .. } => lines.push(format!("{}: {}", path, message)),
+            }
+        }
+    }
+
+    #[test]
+    fn walker_sorts_entries_and_collapses_excluded_dirs() {
+        let lines = walk(
+            &[
+                ("src/main.rs", MemoryKind::File),
+                ("src/api/mod.rs", MemoryKind::File),
+                ("node_modules/left-pad/index.js", MemoryKind::File),
+                ("Cargo.toml", MemoryKind::File),
+                ("latest", MemoryKind::Symlink),
+            ],
+            &[],
+        );
+        assert_eq!(
+            lines,
+            ["Cargo.toml (10)", "latest (6)", "node_modules/ ...", "src/", "src/api/", "src/api/mod.rs (14)", "src/main.rs (11)"]
+        );
+    }
+
+    #[test]
+    fn walker_result_does_not_depend_on_threads() {
+        let entries: Vec<(String, MemoryKind)> =
+            (0..20).map(|i| (format!("dir{:02}/sub/file{}.rs", i, i), MemoryKind::File)).collect();
+        let entries: Vec<(&str, MemoryKind)> = entries.iter().map(|(path, kind)| (path.as_str(), *kind)).collect();
+
+        let single = walk(&entries, &["--threads", "1"]);
+        assert_eq!(single.len(), 60);
+        assert_eq!(walk(&entries, &["--threads", "8"]), single);
+    }
+
+    #[test]
+    fn walker_truncates_and_prunes_in_memory() {
+        let lines = walk(
+            &[
+                ("a.rs", MemoryKind::File),
+                ("b.rs", MemoryKind::File),
+                ("c.rs", MemoryKind::File),
+                ("d.rs", MemoryKind::File),
+            ],
+            &["--max-entries-per-dir", "2"],
+        );
+        assert_eq!(lines, ["a.rs (4)", "b.rs (4)", ": truncated 2"]);
+
+        let lines = walk(
+            &[("empty/.gitkeep", MemoryKind::File), ("nested/deeper", MemoryKind::Directory), ("main.rs", MemoryKind::File)],
+            &["--prune-empty"],
+        );
+        assert_eq!(lines, ["main.rs (7)"]);
+    }
+}
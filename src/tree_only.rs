@@ -0,0 +1,48 @@
+use std::path::Path;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+
+use crate::{AppConfig, Config, dotfiles, relative_path};
+
+// tree_only = ["assets/**", "*.snap"]: файлы видны в дереве, но их содержимое в документ
+// не попадает, как у бинарных. Шаблон без `/` сравнивается с именем файла на любой
+// глубине, со `/` - с путем от целевой директории (`*` не переходит через `/`, `**` - переходит)
+
+#[derive(Debug, Default)]
+pub struct Globs {
+    names: GlobSet,
+    paths: GlobSet,
+}
+
+// Шаблоны собираются один раз после загрузки конфигурации
+pub fn compile(config: &mut Config) {
+    let mut names = GlobSetBuilder::new();
+    let mut paths = GlobSetBuilder::new();
+    for pattern in &config.tree_only {
+        match GlobBuilder::new(pattern).literal_separator(true).build() {
+            Ok(glob) if pattern.contains('/') => {
+                paths.add(glob);
+            }
+            Ok(glob) => {
+                names.add(glob);
+            }
+            Err(e) => tracing::warn!("шаблон tree_only '{}' пропущен: {}", pattern, e),
+        }
+    }
+    config.tree_only_globs = Globs {
+        names: names.build().unwrap_or_default(),
+        paths: paths.build().unwrap_or_default(),
+    };
+}
+
+// Скрытые файлы с dotfiles = "list-only" выводятся так же
+pub fn is_tree_only(path: &Path, app_config: &AppConfig, config: &Config) -> bool {
+    if dotfiles::is_list_only(path, app_config, config) {
+        return true;
+    }
+    let globs = &config.tree_only_globs;
+    if globs.names.is_empty() && globs.paths.is_empty() {
+        return false;
+    }
+    path.file_name().is_some_and(|name| globs.names.is_match(name))
+        || globs.paths.is_match(relative_path(Path::new(&app_config.target_dir), path))
+}
@@ -0,0 +1,209 @@
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use crate::doc_lang;
+use crate::escape::parse_display_path;
+
+pub struct UnpackedFile {
+    pub path: PathBuf,
+    pub content: String,
+    // Содержимое в снимке отличается от файла: выборка строк, маскирование секретов,
+    // нормализация пробелов. Значение - параметр, который его изменил
+    pub modified: Option<&'static str>,
+}
+
+pub fn unpack(snapshot: &Path, out_dir: &Path) -> io::Result<usize> {
+    let document = fs::read_to_string(snapshot)?;
+    let mut written = 0;
+
+    for file in parse_snapshot(&document) {
+        // Восстановленный файл с частью строк или звездочками вместо ключей хуже отсутствующего:
+        // его легко принять за исходный
+        if let Some(reason) = file.modified {
+            tracing::warn!("{}: содержимое в снимке изменено ({}), файл не восстановлен", file.path.display(), reason);
+            continue;
+        }
+
+        // Снимок мог прийти извне (например, от языковой модели), поэтому не выходим за пределы out_dir
+        if !is_safe_relative_path(&file.path) {
+            tracing::warn!("пропущен небезопасный путь '{}'", file.path.display());
+            continue;
+        }
+
+        let target = out_dir.join(&file.path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&target, file.content)?;
+        written += 1;
+    }
+
+    Ok(written)
+}
+
+pub fn parse_snapshot(document: &str) -> Vec<UnpackedFile> {
+    let mut files: Vec<UnpackedFile> = Vec::new();
+    let mut lines = document.lines().map(|line| line.trim_end_matches('\r'));
+    let mut current_path: Option<PathBuf> = None;
+    let mut utf8_bom = false;
+    // Файл последнего блока: пометки после блока относятся к нему
+    let mut last_file: Option<usize> = None;
+
+    while let Some(line) = lines.next() {
+        if let Some(path) = parse_file_heading(line) {
+            current_path = Some(path);
+            utf8_bom = false;
+            last_file = None;
+            continue;
+        }
+
+        if let Some(index) = last_file
+            && let Some(reason) = modification(line)
+        {
+            files[index].modified.get_or_insert(reason);
+            continue;
+        }
+
+        // UTF-8 BOM возвращается в файл; UTF-16 восстанавливается уже в UTF-8
+        if matches_template(line, |doc| doc.bom_stripped) {
+            utf8_bom = true;
+            continue;
+        }
+
+        let Some(fence) = fence_prefix(line) else {
+            if !line.trim().is_empty()
+                && !is_anchor_line(line)
+                && !is_nav_line(line)
+                && !matches_template(line, |doc| doc.bom_transcoded)
+                && !matches_template(line, |doc| doc.syntax_error)
+            {
+                current_path = None;
+                last_file = None;
+            }
+            continue;
+        };
+
+        // Блок кода читается до закрывающего fence той же длины, даже если он не относится к файлу
+        let mut content = if std::mem::take(&mut utf8_bom) { String::from('\u{FEFF}') } else { String::new() };
+        for line in lines.by_ref() {
+            if line.trim_end() == fence {
+                break;
+            }
+            content.push_str(line);
+            content.push('\n');
+        }
+
+        // Файлы, которые не удалось прочитать при генерации, восстановить нельзя.
+        // Язык документа неизвестен, поэтому пометка проверяется на всех языках
+        if let Some(path) = current_path.take()
+            && !doc_lang::all()
+                .iter()
+                .any(|doc| [doc.unreadable_file, doc.cloud_placeholder].contains(&content.trim_end()))
+        {
+            files.push(UnpackedFile { path, content, modified: None });
+            last_file = Some(files.len() - 1);
+        } else {
+            last_file = None;
+        }
+    }
+
+    files
+}
+
+// Заголовок файла - `### path` или заголовок любого уровня (--heading-offset, --nest-headings),
+// целиком состоящий из code span. Заголовки директорий (`src/`) пропускаются
+pub fn parse_file_heading(line: &str) -> Option<PathBuf> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    let heading = line[level..].strip_prefix(' ')?.trim();
+    // Имена в code span экранированы escape::display_path
+    let (path, escaped) = match strip_code_span(heading).or_else(|| original_path(heading)) {
+        Some(path) => (path, true),
+        None if level == 3 => (heading, false),
+        None => return None,
+    };
+
+    if path.is_empty() || path.ends_with('/') {
+        None
+    } else if escaped {
+        Some(parse_display_path(path))
+    } else {
+        Some(PathBuf::from(path))
+    }
+}
+
+// --ascii-paths: `otchet.md` (`отчет.md`) - файл восстанавливается по исходному пути в скобках
+fn original_path(heading: &str) -> Option<&str> {
+    let heading = heading.strip_suffix(')')?;
+    heading
+        .match_indices(" (")
+        .find_map(|(index, _)| strip_code_span(&heading[..index]).and(strip_code_span(&heading[index + 2..])))
+}
+
+// Обратная операция к escape::code_span: разделитель может быть любой длины
+fn strip_code_span(text: &str) -> Option<&str> {
+    let length = text.chars().take_while(|&c| c == '`').count();
+    if length == 0 || text.len() < length * 2 {
+        return None;
+    }
+
+    let inner = text[length..].strip_suffix(&text[..length])?;
+    if inner.len() > 1 && inner.starts_with(' ') && inner.ends_with(' ') && !inner.trim().is_empty() {
+        Some(&inner[1..inner.len() - 1])
+    } else {
+        Some(inner)
+    }
+}
+
+pub fn fence_prefix(line: &str) -> Option<&str> {
+    let length = line.chars().take_while(|&c| c == '`').count();
+    if length >= 3 {
+        Some(&line[..length])
+    } else {
+        None
+    }
+}
+
+// Пометка после блока, означающая, что содержимое изменено при создании снимка
+fn modification(line: &str) -> Option<&'static str> {
+    if matches_template(line, |doc| doc.sampled_lines) {
+        Some("sampling")
+    } else if doc_lang::all().iter().any(|doc| line == doc.secrets_masked) {
+        Some("маскирование секретов")
+    } else if matches_template(line, |doc| doc.normalized) {
+        Some("нормализация")
+    } else {
+        None
+    }
+}
+
+// Пометка на любом из языков документа: текст до первой и после последней подстановки `{}`
+fn matches_template(line: &str, template: impl Fn(&doc_lang::Strings) -> &'static str) -> bool {
+    doc_lang::all().iter().any(|doc| {
+        let template = template(doc);
+        match (template.split_once("{}"), template.rsplit_once("{}")) {
+            (Some((prefix, _)), Some((_, suffix))) => {
+                line.len() >= prefix.len() + suffix.len() && line.starts_with(prefix) && line.ends_with(suffix)
+            }
+            _ => false,
+        }
+    })
+}
+
+// Строка якорей `<a id="..."></a>` (--anchors) между заголовком файла и блоком кода
+fn is_anchor_line(line: &str) -> bool {
+    let line = line.trim();
+    line.starts_with("<a id=\"") && line.ends_with("</a>")
+}
+
+// Строка ссылок `[...](#id) · [...](#id)` (--nav-links) под заголовком файла
+fn is_nav_line(line: &str) -> bool {
+    line.trim().split(" · ").all(|link| {
+        link.starts_with('[') && link.ends_with(')') && link.rsplit_once("](#").is_some_and(|(_, id)| !id.contains(' '))
+    })
+}
+
+fn is_safe_relative_path(path: &Path) -> bool {
+    path.components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
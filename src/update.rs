@@ -0,0 +1,263 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::doc_lang;
+use crate::unpack::{fence_prefix, parse_file_heading};
+
+// Частичное обновление документа: разделы файлов внутри префикса берутся из свежей генерации,
+// все остальное остается байт в байт, чтобы не плодить изменений в git
+
+enum SegmentKind {
+    File(PathBuf),
+    Tree,
+    Other,
+}
+
+// Заголовок и все строки до следующего заголовка (заголовки внутри блоков кода не считаются)
+struct Segment<'a> {
+    kind: SegmentKind,
+    text: Vec<&'a str>,
+}
+
+pub struct UpdateResult {
+    pub document: String,
+    pub updated: usize,
+    pub added: usize,
+    pub removed: usize,
+}
+
+pub fn update_document(old: &str, new: &str, prefix: &Path) -> UpdateResult {
+    let old_segments = split_segments(old);
+    let new_segments = split_segments(new);
+
+    let in_prefix = |path: &Path| path.starts_with(prefix);
+    let file_path = |segment: &Segment| match &segment.kind {
+        SegmentKind::File(path) => Some(path.clone()),
+        _ => None,
+    };
+
+    let old_files: HashSet<PathBuf> = old_segments.iter().filter_map(file_path).collect();
+    let new_files: HashSet<PathBuf> = new_segments.iter().filter_map(file_path).collect();
+
+    let added_files: Vec<&Segment> = new_segments
+        .iter()
+        .filter(|segment| file_path(segment).is_some_and(|path| in_prefix(&path) && !old_files.contains(&path)))
+        .collect();
+    let removed = old_files
+        .iter()
+        .filter(|path| in_prefix(path) && !new_files.contains(*path))
+        .count();
+
+    let mut result = UpdateResult {
+        document: String::with_capacity(old.len()),
+        updated: 0,
+        added: added_files.len(),
+        removed,
+    };
+
+    // Новый файл вставляется после ближайшего предшествующего ему в свежем документе файла,
+    // который есть и в старом; если такого нет - перед первым разделом файла
+    let mut insertions: Vec<(Option<PathBuf>, &Segment)> = Vec::new();
+    for segment in &added_files {
+        let predecessor = new_segments
+            .iter()
+            .take_while(|candidate| !std::ptr::eq(*candidate, *segment))
+            .filter_map(file_path)
+            .filter(|path| old_files.contains(path))
+            .last();
+        insertions.push((predecessor, segment));
+    }
+
+    let mut inserted_leading = false;
+    for segment in &old_segments {
+        match &segment.kind {
+            SegmentKind::File(path) => {
+                if !inserted_leading {
+                    inserted_leading = true;
+                    push_insertions(&mut result.document, &insertions, None);
+                }
+
+                if in_prefix(path) {
+                    let fresh = new_segments
+                        .iter()
+                        .find(|candidate| matches!(&candidate.kind, SegmentKind::File(p) if p == path));
+                    if let Some(fresh) = fresh {
+                        if fresh.text != segment.text {
+                            result.updated += 1;
+                        }
+                        result.document.extend(fresh.text.iter().copied());
+                    }
+                } else {
+                    result.document.extend(segment.text.iter().copied());
+                }
+
+                push_insertions(&mut result.document, &insertions, Some(path));
+            }
+            // Дерево обновляется, только если в префиксе появились или исчезли файлы
+            SegmentKind::Tree if result.added > 0 || result.removed > 0 => {
+                let fresh = new_segments.iter().find(|candidate| matches!(candidate.kind, SegmentKind::Tree));
+                result.document.extend(fresh.unwrap_or(segment).text.iter().copied());
+            }
+            _ => result.document.extend(segment.text.iter().copied()),
+        }
+    }
+
+    if !inserted_leading {
+        push_insertions(&mut result.document, &insertions, None);
+    }
+
+    result
+}
+
+fn push_insertions(document: &mut String, insertions: &[(Option<PathBuf>, &Segment)], after: Option<&PathBuf>) {
+    for (predecessor, segment) in insertions {
+        if predecessor.as_ref() == after {
+            document.extend(segment.text.iter().copied());
+        }
+    }
+}
+
+fn split_segments(document: &str) -> Vec<Segment<'_>> {
+    let mut segments = vec![Segment { kind: SegmentKind::Other, text: Vec::new() }];
+    let mut lines = document.split_inclusive('\n');
+
+    while let Some(line) = lines.next() {
+        let content = line.trim_end_matches(['\n', '\r']);
+
+        if content.starts_with('#') {
+            let kind = if let Some(path) = parse_file_heading(content) {
+                SegmentKind::File(path)
+            } else if is_tree_heading(content) {
+                SegmentKind::Tree
+            } else {
+                SegmentKind::Other
+            };
+            segments.push(Segment { kind, text: vec![line] });
+            continue;
+        }
+
+        let current = segments.last_mut().map(|segment| &mut segment.text);
+        let Some(current) = current else {
+            continue;
+        };
+        current.push(line);
+
+        // Блок кода копируется целиком: строки с '#' внутри него не являются заголовками
+        if let Some(fence) = fence_prefix(content) {
+            for line in lines.by_ref() {
+                current.push(line);
+                if line.trim_end() == fence {
+                    break;
+                }
+            }
+        }
+    }
+
+    segments
+}
+
+// Обновляемый документ мог быть создан с другим --doc-lang, в том числе двуязычным
+// (`Дерево файлов / File tree`): достаточно первого языка
+fn is_tree_heading(line: &str) -> bool {
+    let title = line.trim_start_matches('#').trim();
+    let first = title.split(" / ").next().unwrap_or(title);
+    doc_lang::all().iter().any(|doc| first == doc.file_tree)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(tree: &str, files: &[(&str, &str)]) -> String {
+        let mut document = format!("# Структура проекта: demo\n\n## Дерево файлов\n```\n{}```\n\n## Содержимое файлов\n\n", tree);
+        for (path, content) in files {
+            document.push_str(&format!("### `{}`\n```text\n{}```\n\n", path, content));
+        }
+        document
+    }
+
+    fn update(old: &str, new: &str, prefix: &str) -> UpdateResult {
+        update_document(old, new, Path::new(prefix))
+    }
+
+    #[test]
+    fn added_files_are_inserted_after_their_predecessor() {
+        let old = document("old tree\n", &[("lib/x.rs", "x\n"), ("src/b.rs", "b\n"), ("src/d.rs", "d\n")]);
+        let new = document(
+            "new tree\n",
+            &[("lib/x.rs", "x\n"), ("src/a.rs", "a\n"), ("src/b.rs", "b\n"), ("src/c.rs", "c\n"), ("src/d.rs", "d\n")],
+        );
+
+        let result = update(&old, &new, "src");
+
+        assert_eq!(result.document, new);
+        assert_eq!((result.added, result.removed, result.updated), (2, 0, 0));
+    }
+
+    #[test]
+    fn file_without_old_predecessor_goes_before_the_first_section() {
+        let old = document("old tree\n", &[("src/b.rs", "b\n")]);
+        let new = document("new tree\n", &[("src/a.rs", "a\n"), ("src/b.rs", "b\n")]);
+
+        let result = update(&old, &new, "src");
+
+        assert_eq!(result.document, new);
+        assert_eq!(result.added, 1);
+    }
+
+    #[test]
+    fn removed_files_disappear_with_their_section() {
+        let old = document("old tree\n", &[("src/a.rs", "a\n"), ("src/b.rs", "b\n"), ("src/c.rs", "c\n")]);
+        let new = document("new tree\n", &[("src/a.rs", "a\n"), ("src/c.rs", "c\n")]);
+
+        let result = update(&old, &new, "src");
+
+        assert_eq!(result.document, new);
+        assert!(!result.document.contains("src/b.rs"), "{}", result.document);
+        assert_eq!((result.added, result.removed), (0, 1));
+    }
+
+    #[test]
+    fn sections_outside_prefix_stay_byte_identical() {
+        // Раздел вне префикса отредактирован вручную: CRLF, лишняя пустая строка, заметка
+        let manual = "### `lib/x.rs`\r\n```text\r\nold x\r\n```\r\n\r\nЗаметка автора\r\n\r\n";
+        let old = format!("{}{}", document("tree\n", &[("src/a.rs", "old a\n")]), manual);
+        let new = document("tree\n", &[("src/a.rs", "new a\n"), ("lib/x.rs", "new x\n")]);
+
+        let result = update(&old, &new, "src");
+
+        assert!(result.document.ends_with(manual), "{}", result.document);
+        assert!(result.document.contains("```text\nnew a\n```"), "{}", result.document);
+        assert_eq!((result.updated, result.added, result.removed), (1, 0, 0));
+    }
+
+    #[test]
+    fn hash_lines_inside_code_blocks_are_not_headings() {
+        let old = document("tree\n", &[("src/run.sh", "# старый комментарий\n### `src/ghost.rs`\n## Дерево файлов\necho old\n")]);
+        let new = document("tree\n", &[("src/run.sh", "# новый комментарий\n### `src/ghost.rs`\n## Дерево файлов\necho new\n")]);
+
+        let result = update(&old, &new, "src");
+
+        assert_eq!(result.document, new);
+        assert_eq!((result.updated, result.added, result.removed), (1, 0, 0));
+    }
+
+    #[test]
+    fn tree_is_replaced_only_when_files_were_added_or_removed() {
+        let old = document("hand-edited tree\n", &[("src/a.rs", "a\n")]);
+
+        let changed = document("new tree\n", &[("src/a.rs", "changed\n")]);
+        let result = update(&old, &changed, "src");
+        assert!(result.document.contains("hand-edited tree\n"), "{}", result.document);
+        assert!(!result.document.contains("new tree\n"), "{}", result.document);
+
+        let added = document("new tree\n", &[("src/a.rs", "a\n"), ("src/b.rs", "b\n")]);
+        let result = update(&old, &added, "src");
+        assert!(result.document.contains("new tree\n"), "{}", result.document);
+
+        // Новый файл вне префикса не трогает ни дерево, ни содержимое
+        let outside = document("new tree\n", &[("lib/y.rs", "y\n"), ("src/a.rs", "a\n")]);
+        let result = update(&old, &outside, "src");
+        assert_eq!(result.document, old);
+    }
+}
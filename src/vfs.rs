@@ -0,0 +1,207 @@
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::device::{self, ReparsePoint};
+use crate::encoding::{self, Bom};
+
+// Файловая система, через которую обход и чтение содержимого обращаются к проекту.
+// RealFs - локальный диск, MemoryFs - проект в памяти (tar-поток из --stdin-tar).
+// Другие источники (S3, HTTP) подключаются своей реализацией трейта
+
+pub trait FileSystem: fmt::Debug + Send + Sync {
+    // Ошибка отдельного элемента не прерывает чтение остальных
+    fn read_dir(&self, dir: &Path) -> io::Result<Vec<io::Result<DirEntry>>>;
+
+    // Символические ссылки разыменовываются
+    fn metadata(&self, path: &Path) -> io::Result<Metadata>;
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+
+    // Текст без BOM (UTF-16 перекодируется) и метка, если она была
+    fn read_text(&self, path: &Path) -> io::Result<(String, Option<Bom>)> {
+        encoding::decode(self.read(path)?)
+    }
+
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        self.read_text(path).map(|(content, _)| content)
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.metadata(path).is_ok_and(|metadata| metadata.is_dir)
+    }
+}
+
+pub struct DirEntry {
+    pub path: PathBuf,
+    pub name: OsString,
+    // Тип самого элемента, без разыменования ссылки
+    pub is_dir: bool,
+    pub is_symlink: bool,
+}
+
+pub struct Metadata {
+    pub is_dir: bool,
+    pub is_file: bool,
+    pub len: u64,
+    // (устройство, inode) файла с несколькими жесткими ссылками
+    pub hard_link: Option<(u64, u64)>,
+    // Время изменения для --health; у архива в памяти его нет
+    pub modified: Option<SystemTime>,
+}
+
+#[derive(Debug)]
+pub struct RealFs;
+
+impl FileSystem for RealFs {
+    fn read_dir(&self, dir: &Path) -> io::Result<Vec<io::Result<DirEntry>>> {
+        Ok(fs::read_dir(dir)?
+            .map(|entry| {
+                let entry = entry?;
+                let file_type = entry.file_type()?;
+                Ok(DirEntry {
+                    path: entry.path(),
+                    name: entry.file_name(),
+                    is_dir: file_type.is_dir(),
+                    is_symlink: file_type.is_symlink(),
+                })
+            })
+            .collect())
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+        let metadata = fs::metadata(path)?;
+        Ok(Metadata {
+            is_dir: metadata.is_dir(),
+            is_file: metadata.is_file(),
+            len: metadata.len(),
+            hard_link: device::hard_link_id(&metadata),
+            modified: metadata.modified().ok(),
+        })
+    }
+
+    // Облачный файл-заглушка не читается: чтение скачало бы его из хранилища
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        if device::reparse_point(path) == Some(ReparsePoint::CloudPlaceholder) {
+            return Err(io::Error::new(io::ErrorKind::Unsupported, CloudPlaceholder));
+        }
+        fs::read(path)
+    }
+}
+
+#[derive(Debug)]
+struct CloudPlaceholder;
+
+impl fmt::Display for CloudPlaceholder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "облачный файл-заглушка, содержимое не загружено")
+    }
+}
+
+impl std::error::Error for CloudPlaceholder {}
+
+pub fn is_cloud_placeholder(error: &io::Error) -> bool {
+    error.get_ref().is_some_and(|inner| inner.is::<CloudPlaceholder>())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MemoryKind {
+    File,
+    Directory,
+    // Ссылка показывается в дереве, но не разыменовывается и содержимого не имеет
+    Symlink,
+}
+
+// Пути относительные, корень проекта - пустой путь
+#[derive(Debug, Default)]
+pub struct MemoryFs {
+    entries: BTreeMap<PathBuf, (MemoryKind, Vec<u8>)>,
+    children: BTreeMap<PathBuf, Vec<PathBuf>>,
+}
+
+impl MemoryFs {
+    pub fn insert(&mut self, path: PathBuf, kind: MemoryKind, data: Vec<u8>) {
+        // Не все архиваторы пишут записи директорий: недостающие родители достраиваются
+        for ancestor in path.ancestors().skip(1) {
+            if ancestor.as_os_str().is_empty() {
+                break;
+            }
+            if self.entries.contains_key(ancestor) {
+                break;
+            }
+            self.entries.insert(ancestor.to_path_buf(), (MemoryKind::Directory, Vec::new()));
+            self.link_child(ancestor);
+        }
+
+        if self.entries.insert(path.clone(), (kind, data)).is_none() {
+            self.link_child(&path);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn link_child(&mut self, path: &Path) {
+        let parent = path.parent().unwrap_or(Path::new("")).to_path_buf();
+        let children = self.children.entry(parent).or_default();
+        if let Err(position) = children.binary_search_by(|child| child.as_path().cmp(path)) {
+            children.insert(position, path.to_path_buf());
+        }
+    }
+
+    fn kind(&self, path: &Path) -> io::Result<MemoryKind> {
+        if path.as_os_str().is_empty() {
+            return Ok(MemoryKind::Directory);
+        }
+        self.entries
+            .get(path)
+            .map(|(kind, _)| *kind)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("{} нет в архиве", path.display())))
+    }
+}
+
+impl FileSystem for MemoryFs {
+    fn read_dir(&self, dir: &Path) -> io::Result<Vec<io::Result<DirEntry>>> {
+        if self.kind(dir)? != MemoryKind::Directory {
+            return Err(io::Error::new(io::ErrorKind::NotADirectory, format!("{} не директория", dir.display())));
+        }
+
+        let children = self.children.get(dir).map(Vec::as_slice).unwrap_or_default();
+        Ok(children
+            .iter()
+            .map(|path| {
+                let kind = self.kind(path)?;
+                Ok(DirEntry {
+                    path: path.clone(),
+                    name: path.file_name().unwrap_or_default().to_os_string(),
+                    is_dir: kind == MemoryKind::Directory,
+                    is_symlink: kind == MemoryKind::Symlink,
+                })
+            })
+            .collect())
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+        let kind = self.kind(path)?;
+        Ok(Metadata {
+            is_dir: kind == MemoryKind::Directory,
+            is_file: kind == MemoryKind::File,
+            len: self.entries.get(path).map_or(0, |(_, data)| data.len() as u64),
+            hard_link: None,
+            modified: None,
+        })
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        match self.entries.get(path) {
+            Some((MemoryKind::File, data)) => Ok(data.clone()),
+            Some(_) => Err(io::Error::new(io::ErrorKind::InvalidInput, format!("{} не файл", path.display()))),
+            None => Err(io::Error::new(io::ErrorKind::NotFound, format!("{} нет в архиве", path.display()))),
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::doc_lang::Strings;
+use crate::escape::{code_span, display_path};
+use crate::{AppConfig, heading, relative_path};
+
+// Ошибки отдельных элементов при обходе (нет доступа, битая ссылка, слишком длинное имя)
+// не прерывают генерацию: элемент помечается в дереве, а список выводится в конце документа
+
+// ENAMETOOLONG и ELOOP в Linux и macOS
+#[cfg(target_os = "linux")]
+const NAME_TOO_LONG: i32 = 36;
+#[cfg(not(target_os = "linux"))]
+const NAME_TOO_LONG: i32 = 63;
+#[cfg(target_os = "linux")]
+const LINK_LOOP: i32 = 40;
+#[cfg(not(target_os = "linux"))]
+const LINK_LOOP: i32 = 62;
+
+// Описание попадает в документ, поэтому дается на языке документа
+pub fn describe(error: &io::Error, doc: &Strings) -> String {
+    match error.kind() {
+        io::ErrorKind::PermissionDenied => doc.permission_denied.to_string(),
+        io::ErrorKind::NotFound => doc.broken_link.to_string(),
+        _ if error.raw_os_error() == Some(NAME_TOO_LONG) => doc.name_too_long.to_string(),
+        _ if error.raw_os_error() == Some(LINK_LOOP) => doc.link_loop.to_string(),
+        _ => error.to_string(),
+    }
+}
+
+// Один и тот же элемент встречается и при построении дерева, и при сборе файлов:
+// в сводке он учитывается один раз. В stderr ошибки попадают общей сводкой (notices.rs)
+pub fn record(app_config: &AppConfig, path: &Path, message: &str) {
+    let mut errors = app_config.walk_errors.lock().unwrap();
+    if errors.contains_key(path) {
+        return;
+    }
+    tracing::debug!("{}: {}", path.display(), message);
+    errors.insert(path.to_path_buf(), message.to_string());
+}
+
+pub fn write_summary<W: Write>(base_dir: &Path, writer: &mut W, app_config: &AppConfig) -> io::Result<()> {
+    let errors = app_config.walk_errors.lock().unwrap();
+    if errors.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(writer, "\n{} {}\n", heading(app_config, 2), app_config.doc.walk_errors)?;
+    for (path, message) in errors.iter() {
+        writeln!(writer, "- {}: {}", code_span(&display_path(&relative_path(base_dir, path))), message)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,206 @@
+use std::path::Path;
+
+use crate::doc_lang::{Strings, fill};
+
+// Нормализация переводов строк (--normalize-eol) и табуляции (--expand-tabs) во встраиваемом
+// содержимом. Смешанные переводы строк дают шумные диффы между снимками документа.
+// Для печати и PDF общий отступ снимается (--dedent), а длинные строки переносятся (--max-width)
+
+// Начало перенесенной части строки
+pub const CONTINUATION: &str = "↪ ";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Eol {
+    Keep,
+    Lf,
+    Crlf,
+}
+
+impl Eol {
+    pub fn parse(value: &str) -> Eol {
+        match value {
+            "lf" => Eol::Lf,
+            "crlf" => Eol::Crlf,
+            _ => Eol::Keep,
+        }
+    }
+}
+
+// Возвращает нормализованное содержимое и описание примененных изменений для пометки
+// у файла. Если содержимое не изменилось, описание пустое
+pub fn normalize(
+    path: &Path,
+    content: &str,
+    eol: Eol,
+    tab_width: Option<usize>,
+    dedent: bool,
+    max_width: Option<usize>,
+    doc: &Strings,
+) -> (String, Vec<String>) {
+    let mut notes = Vec::new();
+    let mut content = content.to_string();
+
+    if let Some(width) = tab_width
+        && width > 0
+        && !tabs_significant(path)
+        && content.contains('\t')
+    {
+        let (expanded, count) = expand_tabs(&content, width);
+        content = expanded;
+        notes.push(fill(doc.tabs_expanded, &[&width, &count]));
+    }
+
+    if dedent {
+        let (dedented, width) = remove_common_indent(&content);
+        if width > 0 {
+            content = dedented;
+            notes.push(fill(doc.dedented, &[&width]));
+        }
+    }
+
+    if let Some(width) = max_width {
+        let (wrapped, count) = wrap_long_lines(&content, width);
+        if count > 0 {
+            content = wrapped;
+            notes.push(fill(doc.lines_wrapped, &[&width, &count]));
+        }
+    }
+
+    if eol != Eol::Keep {
+        let crlf = content.matches("\r\n").count();
+        let lf = content.matches('\n').count() - crlf;
+        let (changed, from, to) = match eol {
+            Eol::Lf => (crlf, if lf > 0 { doc.mixed_eol } else { "CRLF" }, "LF"),
+            _ => (lf, if crlf > 0 { doc.mixed_eol } else { "LF" }, "CRLF"),
+        };
+        if changed > 0 {
+            content = content.replace("\r\n", "\n");
+            if eol == Eol::Crlf {
+                content = content.replace('\n', "\r\n");
+            }
+            notes.push(fill(doc.eol_converted, &[&from, &to, &changed]));
+        }
+    }
+
+    (content, notes)
+}
+
+// В Makefile и TSV табуляция является частью синтаксиса и не заменяется
+fn tabs_significant(path: &Path) -> bool {
+    let name = path.file_name().unwrap_or_default().to_string_lossy().to_lowercase();
+    let extension = path.extension().unwrap_or_default().to_string_lossy().to_lowercase();
+    matches!(name.as_str(), "makefile" | "gnumakefile") || matches!(extension.as_str(), "mk" | "tsv")
+}
+
+// Табуляция доводит строку до ближайшей позиции, кратной width, а не заменяется
+// фиксированным числом пробелов, чтобы выравнивание колонок сохранялось
+fn expand_tabs(content: &str, width: usize) -> (String, usize) {
+    let mut result = String::with_capacity(content.len());
+    let mut column = 0;
+    let mut count = 0;
+
+    for c in content.chars() {
+        match c {
+            '\t' => {
+                let spaces = width - column % width;
+                result.extend(std::iter::repeat_n(' ', spaces));
+                column += spaces;
+                count += 1;
+            }
+            '\n' | '\r' => {
+                result.push(c);
+                column = 0;
+            }
+            _ => {
+                result.push(c);
+                column += 1;
+            }
+        }
+    }
+
+    (result, count)
+}
+
+// Строка без перевода строки и сам перевод строки (\n, \r\n или пустой у последней строки)
+fn split_eol(line: &str) -> (&str, &str) {
+    let text = line.trim_end_matches(['\n', '\r']);
+    (text, &line[text.len()..])
+}
+
+fn is_blank(text: &str) -> bool {
+    text.chars().all(|c| c == ' ' || c == '\t')
+}
+
+// Общий для всех непустых строк префикс из пробелов и табуляции. Пробел и табуляция не
+// считаются равными: иначе смешанный отступ съехал бы
+fn remove_common_indent(content: &str) -> (String, usize) {
+    let mut common: Option<&str> = None;
+    for line in content.split_inclusive('\n') {
+        let (text, _) = split_eol(line);
+        if is_blank(text) {
+            continue;
+        }
+        let indent = &text[..text.len() - text.trim_start_matches([' ', '\t']).len()];
+        common = Some(match common {
+            None => indent,
+            Some(current) => {
+                let length = current.bytes().zip(indent.bytes()).take_while(|(a, b)| a == b).count();
+                &current[..length]
+            }
+        });
+    }
+
+    let width = common.map_or(0, str::len);
+    if width == 0 {
+        return (content.to_string(), 0);
+    }
+
+    let mut result = String::with_capacity(content.len());
+    for line in content.split_inclusive('\n') {
+        let (text, eol) = split_eol(line);
+        if is_blank(text) {
+            result.push_str(eol);
+        } else {
+            result.push_str(&text[width..]);
+            result.push_str(eol);
+        }
+    }
+    (result, width)
+}
+
+// Строки длиннее width символов режутся на части, продолжения начинаются с CONTINUATION.
+// Разрез по возможности приходится на пробел во второй половине части
+fn wrap_long_lines(content: &str, width: usize) -> (String, usize) {
+    let marker_width = CONTINUATION.chars().count();
+    let mut result = String::with_capacity(content.len());
+    let mut count = 0;
+
+    for line in content.split_inclusive('\n') {
+        let (text, eol) = split_eol(line);
+        let mut rest: Vec<char> = text.chars().collect();
+        if rest.len() <= width {
+            result.push_str(line);
+            continue;
+        }
+
+        count += 1;
+        let mut limit = width;
+        loop {
+            if rest.len() <= limit {
+                result.extend(&rest);
+                break;
+            }
+            let cut = rest[limit / 2..limit]
+                .iter()
+                .rposition(|&c| c == ' ')
+                .map_or(limit, |position| limit / 2 + position + 1);
+            result.extend(&rest[..cut]);
+            result.push('\n');
+            result.push_str(CONTINUATION);
+            rest.drain(..cut);
+            limit = width - marker_width;
+        }
+        result.push_str(eol);
+    }
+    (result, count)
+}
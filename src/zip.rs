@@ -0,0 +1,163 @@
+use std::io::{self, Write};
+
+// Минимальный ZIP-архиватор без сжатия (метод stored): для markdown и уже сжатых
+// изображений выигрыш от deflate невелик, а внешняя зависимость не нужна
+
+const LOCAL_HEADER_SIGNATURE: u32 = 0x0403_4b50;
+const CENTRAL_HEADER_SIGNATURE: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x0605_4b50;
+const VERSION: u16 = 20;
+// Бит 11: имена файлов в UTF-8
+const FLAGS: u16 = 0x0800;
+// Фиксированная дата 1980-01-01 00:00 делает архив воспроизводимым
+const DOS_TIME: u16 = 0;
+const DOS_DATE: u16 = (1 << 5) | 1;
+
+struct CentralEntry {
+    name: String,
+    crc: u32,
+    size: u32,
+    offset: u32,
+}
+
+pub struct ZipWriter<W: Write> {
+    writer: W,
+    entries: Vec<CentralEntry>,
+    offset: u64,
+}
+
+impl<W: Write> ZipWriter<W> {
+    pub fn new(writer: W) -> Self {
+        ZipWriter {
+            writer,
+            entries: Vec::new(),
+            offset: 0,
+        }
+    }
+
+    pub fn add_file(&mut self, name: &str, data: &[u8]) -> io::Result<()> {
+        let size = to_u32(data.len() as u64)?;
+        let offset = to_u32(self.offset)?;
+        let crc = crc32(data);
+
+        let mut header = Vec::with_capacity(30 + name.len());
+        header.extend_from_slice(&LOCAL_HEADER_SIGNATURE.to_le_bytes());
+        header.extend_from_slice(&VERSION.to_le_bytes());
+        header.extend_from_slice(&FLAGS.to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes());
+        header.extend_from_slice(&DOS_TIME.to_le_bytes());
+        header.extend_from_slice(&DOS_DATE.to_le_bytes());
+        header.extend_from_slice(&crc.to_le_bytes());
+        header.extend_from_slice(&size.to_le_bytes());
+        header.extend_from_slice(&size.to_le_bytes());
+        header.extend_from_slice(&to_u16(name.len())?.to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes());
+        header.extend_from_slice(name.as_bytes());
+
+        self.writer.write_all(&header)?;
+        self.writer.write_all(data)?;
+        self.offset += (header.len() + data.len()) as u64;
+
+        self.entries.push(CentralEntry {
+            name: name.to_string(),
+            crc,
+            size,
+            offset,
+        });
+
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        let central_offset = to_u32(self.offset)?;
+        let mut central = Vec::new();
+
+        for entry in &self.entries {
+            central.extend_from_slice(&CENTRAL_HEADER_SIGNATURE.to_le_bytes());
+            central.extend_from_slice(&VERSION.to_le_bytes());
+            central.extend_from_slice(&VERSION.to_le_bytes());
+            central.extend_from_slice(&FLAGS.to_le_bytes());
+            central.extend_from_slice(&0u16.to_le_bytes());
+            central.extend_from_slice(&DOS_TIME.to_le_bytes());
+            central.extend_from_slice(&DOS_DATE.to_le_bytes());
+            central.extend_from_slice(&entry.crc.to_le_bytes());
+            central.extend_from_slice(&entry.size.to_le_bytes());
+            central.extend_from_slice(&entry.size.to_le_bytes());
+            central.extend_from_slice(&to_u16(entry.name.len())?.to_le_bytes());
+            // Длины extra и комментария, номер диска, внутренние и внешние атрибуты
+            central.extend_from_slice(&[0; 12]);
+            central.extend_from_slice(&entry.offset.to_le_bytes());
+            central.extend_from_slice(entry.name.as_bytes());
+        }
+
+        let entry_count = to_u16(self.entries.len())?;
+        let mut end = Vec::with_capacity(22);
+        end.extend_from_slice(&END_OF_CENTRAL_DIRECTORY_SIGNATURE.to_le_bytes());
+        end.extend_from_slice(&[0; 4]);
+        end.extend_from_slice(&entry_count.to_le_bytes());
+        end.extend_from_slice(&entry_count.to_le_bytes());
+        end.extend_from_slice(&to_u32(central.len() as u64)?.to_le_bytes());
+        end.extend_from_slice(&central_offset.to_le_bytes());
+        end.extend_from_slice(&0u16.to_le_bytes());
+
+        self.writer.write_all(&central)?;
+        self.writer.write_all(&end)?;
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+}
+
+// ZIP64 не поддерживается: архивы больше 4 ГБ для снимков проекта не нужны
+fn to_u32(value: u64) -> io::Result<u32> {
+    u32::try_from(value)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "архив превышает 4 ГБ"))
+}
+
+fn to_u16(value: usize) -> io::Result<u16> {
+    u16::try_from(value)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "слишком много элементов в архиве"))
+}
+
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc32_known_answers() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"a"), 0xE8B7_BE43);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b"The quick brown fox jumps over the lazy dog"), 0x414F_A339);
+    }
+
+    #[test]
+    fn archive_layout_matches_entries() {
+        let mut zip = ZipWriter::new(Vec::new());
+        zip.add_file("a.txt", b"hello").unwrap();
+        let archive = zip.finish().unwrap();
+
+        // Локальный заголовок 30 байт + имя + данные, затем центральный каталог и его конец
+        assert_eq!(&archive[..4], &LOCAL_HEADER_SIGNATURE.to_le_bytes());
+        assert_eq!(&archive[14..18], &crc32(b"hello").to_le_bytes());
+        assert_eq!(&archive[30..35], b"a.txt");
+        assert_eq!(&archive[35..40], b"hello");
+        assert_eq!(&archive[40..44], &CENTRAL_HEADER_SIGNATURE.to_le_bytes());
+        let end = &archive[archive.len() - 22..];
+        assert_eq!(&end[..4], &END_OF_CENTRAL_DIRECTORY_SIGNATURE.to_le_bytes());
+        assert_eq!(&end[10..12], &1u16.to_le_bytes());
+        assert_eq!(&end[16..20], &40u32.to_le_bytes());
+    }
+}
@@ -0,0 +1,92 @@
+use std::fs;
+use std::path::Path;
+
+mod common;
+use common::{proj2tree, success, temp_dir, write};
+
+fn run_in(dir: &Path, args: &[&str]) -> String {
+    let output = success(proj2tree(dir).arg("-G").args(args));
+    String::from_utf8(output.stderr).unwrap()
+}
+
+#[test]
+fn anchor_map_lists_anchors_placed_in_document() {
+    let root = temp_dir("markdown");
+    write(&root, "src/main.rs", "fn main() {}\n");
+    write(&root, "docs/logo.png", "");
+
+    run_in(&root, &["--anchor-map", "anchors.json"]);
+    let map = fs::read_to_string(root.join("anchors.json")).unwrap();
+    assert_eq!(
+        map,
+        "{\"schema_version\":1,\"document\":\"tree.md\",\"anchors\":{\"src\":\"src\",\"src/main.rs\":\"src-main-rs\"}}\n"
+    );
+
+    let document = fs::read_to_string(root.join("tree.md")).unwrap();
+    assert!(document.contains("<a id=\"src\"></a><a id=\"src-main-rs\"></a>"), "{}", document);
+    assert!(!document.contains("anchors.json"), "{}", document);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn anchors_from_previous_map_survive_new_collisions() {
+    let root = temp_dir("reuse");
+    write(&root, "src/a-b.rs", "fn ab() {}\n");
+    run_in(&root, &["--anchor-map", "anchors.json"]);
+    assert!(fs::read_to_string(root.join("anchors.json")).unwrap().contains("\"src/a-b.rs\":\"src-a-b-rs\""));
+
+    // src/a/b.rs идет в дереве раньше и без карты занял бы src-a-b-rs
+    write(&root, "src/a/b.rs", "fn b() {}\n");
+    run_in(&root, &["--anchor-map", "anchors.json"]);
+    let map = fs::read_to_string(root.join("anchors.json")).unwrap();
+    assert!(map.contains("\"src/a-b.rs\":\"src-a-b-rs\""), "{}", map);
+    assert!(map.contains("\"src/a/b.rs\":\"src-a-b-rs-1\""), "{}", map);
+
+    let document = fs::read_to_string(root.join("tree.md")).unwrap();
+    assert!(document.contains("`src/a-b.rs`\n<a id=\"src-a-b-rs\"></a>"), "{}", document);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn anchor_map_covers_html_headings() {
+    let root = temp_dir("html");
+    write(&root, "lib.rs", "pub fn lib() {}\n");
+
+    run_in(&root, &["--format", "html", "-o", "tree.html", "--anchor-map", "anchors.json"]);
+    let map = fs::read_to_string(root.join("anchors.json")).unwrap();
+    assert_eq!(map, "{\"schema_version\":1,\"document\":\"tree.html\",\"anchors\":{\"lib.rs\":\"lib-rs\"}}\n");
+    assert!(fs::read_to_string(root.join("tree.html")).unwrap().contains("<h3 id=\"lib-rs\">"));
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn anchor_map_is_ignored_for_other_formats() {
+    let root = temp_dir("json");
+    write(&root, "lib.rs", "pub fn lib() {}\n");
+
+    let stderr = run_in(&root, &["-p", "--format", "json", "--anchor-map", "anchors.json"]);
+    assert!(stderr.contains("--anchor-map"), "{}", stderr);
+    assert!(!root.join("anchors.json").exists());
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+// Ссылки ↗ в дереве ведут в репозиторий: базовый адрес без двойного `/`, сегменты пути экранированы
+#[test]
+fn link_base_links_tree_entries_with_encoded_paths() {
+    let root = temp_dir("link-base");
+    write(&root, "a.rs", "fn a() {}\n");
+    write(&root, "my dir/ü#1.rs", "fn b() {}\n");
+
+    let output = success(proj2tree(&root).args(["-p", "-G", "--link-base", "https://git.example.com/r/blob/main/"])).stdout;
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains("<a href=\"https://git.example.com/r/blob/main/a.rs\">↗</a>\n"), "{}", output);
+    assert!(output.contains("my dir/</a> <a href=\"https://git.example.com/r/blob/main/my%20dir\">↗</a>\n"), "{}", output);
+    assert!(output.contains("<a href=\"https://git.example.com/r/blob/main/my%20dir/%C3%BC%231.rs\">↗</a>\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,80 @@
+use std::fs;
+use std::process::Command;
+
+mod common;
+use common::{run_in, temp_dir, write};
+
+#[test]
+fn headings_show_transliterated_and_original_paths() {
+    let root = temp_dir("headings");
+    write(&root, "документы/Отчет.md", "итоги\n");
+    write(&root, "Ελληνικά.txt", "alpha\n");
+    write(&root, "café.txt", "menu\n");
+    write(&root, "中文.txt", "zh\n");
+    write(&root, "plain.txt", "ascii\n");
+
+    let output = run_in(&root, &["--no-tree", "--ascii-paths"]);
+    assert!(output.contains("### `dokumenty/Otchet.md` (`документы/Отчет.md`)"), "{}", output);
+    assert!(output.contains("### `Ellinika.txt` (`Ελληνικά.txt`)"), "{}", output);
+    assert!(output.contains("### `cafe.txt` (`café.txt`)"), "{}", output);
+    assert!(output.contains("### `u4e2du6587.txt` (`中文.txt`)"), "{}", output);
+    // ASCII-пути не меняются
+    assert!(output.contains("### `plain.txt`\n"), "{}", output);
+
+    let output = run_in(&root, &["--no-tree", "--ascii-paths", "--nest-headings"]);
+    assert!(output.contains("### `dokumenty/` (`документы/`)"), "{}", output);
+    assert!(output.contains("#### `dokumenty/Otchet.md` (`документы/Отчет.md`)"), "{}", output);
+
+    let output = run_in(&root, &["--no-tree"]);
+    assert!(output.contains("### `документы/Отчет.md`\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn anchors_use_transliterated_paths() {
+    let root = temp_dir("anchors");
+    write(&root, "заметки/план.md", "шаги\n");
+
+    let output = run_in(&root, &["--anchors", "--ascii-paths"]);
+    assert!(output.contains("<a id=\"zametki\"></a><a id=\"zametki-plan-md\"></a>"), "{}", output);
+    assert!(output.contains("<a href=\"#zametki-plan-md\">план.md</a>"), "{}", output);
+
+    let output = run_in(&root, &["--anchors"]);
+    assert!(output.contains("<a id=\"заметки-план-md\"></a>"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn unpack_restores_original_paths() {
+    let root = temp_dir("unpack");
+    let project = root.join("project");
+    write(&project, "документы/Отчет.md", "итоги\n");
+    write(&project, "main.rs", "fn main() {}\n");
+
+    let snapshot = root.join("snapshot.md");
+    let output = Command::new(env!("CARGO_BIN_EXE_proj2tree"))
+        .arg(&project)
+        .args(["-G", "--ascii-paths", "--nest-headings", "-o"])
+        .arg(&snapshot)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+
+    let restored = root.join("restored");
+    let status = Command::new(env!("CARGO_BIN_EXE_proj2tree"))
+        .arg("unpack")
+        .arg(&snapshot)
+        .arg("-d")
+        .arg(&restored)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    assert_eq!(fs::read_to_string(restored.join("документы/Отчет.md")).unwrap(), "итоги\n");
+    assert_eq!(fs::read_to_string(restored.join("main.rs")).unwrap(), "fn main() {}\n");
+    assert!(!restored.join("dokumenty").exists());
+
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,140 @@
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+mod common;
+use common::{proj2tree, temp_dir, write};
+
+fn git(dir: &Path, args: &[&str]) {
+    let output = Command::new("git").current_dir(dir).args(args).output().unwrap();
+    assert!(output.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&output.stderr));
+}
+
+fn batch(dir: &Path, manifest: &str) -> Output {
+    proj2tree(dir)
+        .args(["batch", manifest])
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn snapshots_every_project_with_its_options() {
+    let root = temp_dir("local");
+    write(&root, "service-a/main.rs", "fn main() {}\n");
+    write(&root, "service-b/lib.py", "x = 1\n");
+    write(
+        &root,
+        "repos.toml",
+        "[defaults]\noutput_dir = \"snapshots\"\nargs = [\"-G\"]\n\n\
+         [[repo]]\npath = \"service-a\"\n\n\
+         [[repo]]\nname = \"b\"\npath = \"service-b\"\noutput = \"b-tree.md\"\nargs = [\"-T\"]\n",
+    );
+
+    let output = batch(&root, "repos.toml");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains("Успешно: 2, с ошибками: 0"), "{}", stdout);
+
+    let a = fs::read_to_string(root.join("snapshots/service-a.md")).unwrap();
+    assert!(a.contains("└── main.rs\n"), "{}", a);
+    assert!(a.contains("fn main() {}"), "{}", a);
+    let b = fs::read_to_string(root.join("snapshots/b-tree.md")).unwrap();
+    assert!(!b.contains("## Дерево файлов"), "{}", b);
+    assert!(b.contains("x = 1"), "{}", b);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn failures_are_summarized_without_stopping_the_batch() {
+    let root = temp_dir("failures");
+    write(&root, "ok/main.rs", "fn main() {}\n");
+    write(
+        &root,
+        "repos.toml",
+        "[[repo]]\nname = \"gone\"\npath = \"missing\"\n\n[[repo]]\npath = \"ok\"\n",
+    );
+
+    let output = batch(&root, "repos.toml");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success());
+    assert!(stdout.contains("ошибка gone: 'missing' не является существующей директорией"), "{}", stdout);
+    assert!(stdout.contains("Успешно: 1, с ошибками: 1"), "{}", stdout);
+    assert!(root.join("ok.md").exists());
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn remote_repositories_are_cloned() {
+    let root = temp_dir("remote");
+    let upstream = root.join("upstream");
+    write(&upstream, "README.md", "upstream\n");
+    git(&upstream, &["init", "-q"]);
+    git(&upstream, &["add", "README.md"]);
+    git(&upstream, &["-c", "user.name=test", "-c", "user.email=test@example.com", "commit", "-qm", "init"]);
+    let url = format!("file://{}", upstream.display());
+    write(&root, "repos.toml", format!("[[repo]]\nurl = \"{}\"\n", url));
+
+    let output = batch(&root, "repos.toml");
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let document = fs::read_to_string(root.join("upstream.md")).unwrap();
+    assert!(document.starts_with("# Структура проекта: upstream\n"), "{}", document);
+    assert!(document.contains("upstream\n"), "{}", document);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn urls_without_git_binary_fail_before_any_snapshot() {
+    let root = temp_dir("no-git");
+    write(&root, "local/main.rs", "fn main() {}\n");
+    write(&root, "repos.toml", "[[repo]]\npath = \"local\"\n\n[[repo]]\nurl = \"https://example.com/org/remote.git\"\n");
+    fs::create_dir_all(root.join("empty-path")).unwrap();
+
+    let output = proj2tree(&root).env("PATH", root.join("empty-path")).args(["batch", "repos.toml"]).output().unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(stderr.contains("нужен бинарный git, но он не найден в PATH"), "{}", stderr);
+    assert!(!root.join("local.md").exists());
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn invalid_manifest_is_rejected_before_any_snapshot() {
+    let root = temp_dir("invalid");
+    write(&root, "a/main.rs", "fn main() {}\n");
+    write(&root, "repos.toml", "[[repo]]\npath = \"a\"\n\n[[repo]]\npath = \"a\"\nurl = \"https://example.com/a.git\"\n");
+
+    let output = batch(&root, "repos.toml");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("проект 2: нужен ровно один из ключей path и url"));
+    assert!(!root.join("a.md").exists());
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn unsafe_names_and_option_like_values_are_rejected() {
+    let root = temp_dir("unsafe");
+    write(&root, "victim/keep.txt", "keep\n");
+    let cases = [
+        "[[repo]]\nname = \"../victim\"\nurl = \"https://example.com/a.git\"\n",
+        "[[repo]]\nname = \"/tmp\"\nurl = \"https://example.com/a.git\"\n",
+        "[[repo]]\nname = \"..\"\nurl = \"https://example.com/a.git\"\n",
+        "[[repo]]\nname = \"a\"\nurl = \"--upload-pack=touch pwned\"\n",
+        "[[repo]]\nname = \"a\"\nurl = \"https://example.com/a.git\"\nbranch = \"--upload-pack=touch pwned\"\n",
+    ];
+
+    for manifest in cases {
+        write(&root, "repos.toml", manifest);
+        let output = batch(&root, "repos.toml");
+        assert!(!output.status.success(), "{}", manifest);
+        assert!(String::from_utf8_lossy(&output.stderr).contains("проект 1:"), "{}", manifest);
+    }
+    assert!(root.join("victim/keep.txt").exists());
+    assert!(!root.join("pwned").exists());
+
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,32 @@
+use std::fs;
+
+mod common;
+use common::{proj2tree, success, temp_dir, write};
+
+// bench проходит весь конвейер на маленьком дереве, печатает таблицу этапов и ничего не записывает
+#[test]
+fn bench_prints_stage_table_without_writing_document() {
+    let root = temp_dir("bench");
+    write(&root, "src/main.rs", "fn main() {}\n");
+    write(&root, "a.txt", "hi\n");
+    write(&root, "b.bin", [0u8, 1, 2]);
+
+    let output = success(proj2tree(&root).args(["-G", "bench", "."]));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    let mut lines = stdout.lines();
+    assert_eq!(lines.next(), Some("Файлов: 2 (прочитано 2, 16 B)"), "{}", stdout);
+    assert_eq!(lines.next(), Some(""), "{}", stdout);
+    for stage in ["обход", "чтение", "расчет fence", "форматирование и запись"] {
+        let line = lines.next().unwrap_or_default();
+        assert!(line.starts_with(stage) && line.ends_with('%') && line.contains(" мс "), "{}", stdout);
+    }
+    let total = lines.next().unwrap_or_default();
+    assert!(total.starts_with("всего") && total.ends_with(" мс"), "{}", stdout);
+    assert_eq!(lines.next(), None, "{}", stdout);
+
+    assert!(!stdout.contains("fn main"), "{}", stdout);
+    assert!(!root.join("tree.md").exists());
+
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,83 @@
+use std::fs;
+use std::path::Path;
+
+mod common;
+use common::{proj2tree, temp_dir, write};
+
+fn run_in(dir: &Path, args: &[&str]) -> std::process::Output {
+    proj2tree(dir).arg("-G").args(args).output().unwrap()
+}
+
+#[test]
+fn two_languages_give_bilingual_headings() {
+    let root = temp_dir("headings");
+    write(&root, "src/main.rs", "fn main() {}\n");
+
+    let output = run_in(&root, &["-p", "--doc-lang", "ru+en"]);
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let document = String::from_utf8(output.stdout).unwrap();
+    assert!(document.contains("\n## Дерево файлов / File tree\n"), "{}", document);
+    assert!(document.contains("\n## Содержимое файлов / File contents\n"), "{}", document);
+
+    let output = run_in(&root, &["-p", "--doc-lang", "en+de"]);
+    let document = String::from_utf8(output.stdout).unwrap();
+    assert!(document.starts_with("# Project structure / Projektstruktur: "), "{}", document);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn section_subheadings_are_bilingual() {
+    let root = temp_dir("subheadings");
+    let project = root.join("project");
+    write(&project, "package.json", "{\"name\": \"bare\"}");
+    write(&project, "empty.txt", "");
+    write(&project, ".proj2tree.toml", "include_external = [\"../shared\", \"../notes.md\"]\n");
+    write(&root, "shared/lib.rs", "pub fn shared() {}\n");
+    write(&root, "notes.md", "# notes\n");
+
+    let output = run_in(&project, &["-p", "--doc-lang", "ru+en", "--health", "--deps"]);
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let document = String::from_utf8(output.stdout).unwrap();
+    assert!(document.contains("\n### Пустые файлы / Empty files\n"), "{}", document);
+    assert!(document.contains("| Пустые файлы | 1 |"), "{}", document);
+    assert!(document.contains("### `package.json` - пакет / package bare\n"), "{}", document);
+    assert!(document.contains("## Внешняя директория / External directory: `../shared`"), "{}", document);
+    assert!(document.contains("## Внешний файл / External file: `../notes.md`"), "{}", document);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn bilingual_document_can_be_updated_and_unpacked() {
+    let root = temp_dir("update");
+    write(&root, "src/lib.rs", "pub fn old() {}\n");
+
+    assert!(run_in(&root, &["--doc-lang", "ru+en"]).status.success());
+    write(&root, "src/new.rs", "pub fn new() {}\n");
+    let output = run_in(&root, &["--doc-lang", "ru+en", "--update", "src"]);
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let document = fs::read_to_string(root.join("tree.md")).unwrap();
+    assert_eq!(document.matches("File tree").count(), 1, "{}", document);
+    assert!(document.contains("new.rs\n"), "{}", document);
+
+    let restored = root.join("restored");
+    let output = run_in(&root, &["unpack", "tree.md", "-d", restored.to_str().unwrap()]);
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(fs::read_to_string(restored.join("src/new.rs")).unwrap(), "pub fn new() {}\n");
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn invalid_language_pairs_are_rejected() {
+    let root = temp_dir("invalid");
+
+    for value in ["ru+ru", "ru+fr", "ru+en+de"] {
+        let output = run_in(&root, &["-p", "--doc-lang", value]);
+        assert!(!output.status.success(), "{}", value);
+        assert!(String::from_utf8_lossy(&output.stderr).contains("--doc-lang"), "{}", value);
+    }
+
+    fs::remove_dir_all(root).unwrap();
+}
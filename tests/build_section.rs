@@ -0,0 +1,89 @@
+use std::fs;
+use std::path::PathBuf;
+
+mod common;
+use common::{run_in, temp_dir, write};
+
+fn project(name: &str) -> PathBuf {
+    let root = temp_dir(name);
+    write(&root, "src/main.rs", "fn main() {}\n");
+    write(&root, "Makefile", "all:\n\tcargo build\n");
+    write(&root, "docker/Dockerfile.dev", "FROM rust\n");
+    write(&root, "Jenkinsfile", "pipeline {}\n");
+    write(&root, ".github/workflows/ci.yml", "on: push\n");
+    write(&root, ".github/ISSUE_TEMPLATE/bug.md", "bug\n");
+    write(&root, "config.yml", "key: value\n");
+    root
+}
+
+#[test]
+fn build_files_get_their_own_section() {
+    let root = project("section");
+
+    let output = run_in(&root, &["--build-section"]);
+    let contents = output.find("## Содержимое файлов").unwrap();
+    let build = output.find("## Сборка и CI").expect(&output);
+    assert!(contents < build);
+
+    let (main, build_part) = output.split_at(build);
+    for file in ["Makefile", "docker/Dockerfile.dev", "Jenkinsfile", ".github/workflows/ci.yml"] {
+        assert!(build_part.contains(&format!("### `{}`", file)), "{}: {}", file, output);
+        assert!(!main[contents..].contains(&format!("### `{}`", file)), "{}: {}", file, output);
+    }
+    assert!(main.contains("### `src/main.rs`"), "{}", output);
+    assert!(main.contains("### `config.yml`"), "{}", output);
+
+    // Конвейеры видны в дереве, остальное из .github - нет
+    assert!(output.contains("ci.yml"), "{}", output);
+    assert!(!output.contains("ISSUE_TEMPLATE"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn without_the_flag_output_is_unchanged() {
+    let root = project("plain");
+
+    let output = run_in(&root, &[]);
+    assert!(!output.contains("## Сборка и CI"), "{}", output);
+    assert!(!output.contains(".github"), "{}", output);
+    assert!(output.contains("### `Makefile`"), "{}", output);
+
+    // Без файлов сборки раздела нет
+    let empty = temp_dir("empty");
+    write(&empty, "lib.rs", "pub fn f() {}\n");
+    let output = run_in(&empty, &["--build-section"]);
+    assert!(!output.contains("## Сборка и CI"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+    fs::remove_dir_all(empty).unwrap();
+}
+
+#[test]
+fn combines_with_split_tests() {
+    let root = project("split");
+    write(&root, "tests/it.rs", "#[test]\nfn it() {}\n");
+
+    let output = run_in(&root, &["--build-section", "--split-tests", "--doc-lang", "en"]);
+    let tests = output.find("## Test contents").expect(&output);
+    let build = output.find("## Build & CI").expect(&output);
+    assert!(tests < build, "{}", output);
+    assert!(output[tests..build].contains("### `tests/it.rs`"), "{}", output);
+    assert!(output[build..].contains("### `Makefile`"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+// Остальное из .github учитывается в сводке отдельной причиной, а не как скрытый файл
+#[test]
+fn github_outside_workflows_has_its_own_skip_cause() {
+    let root = project("skip-cause");
+    write(&root, ".env", "KEY=value\n");
+
+    let output = common::success(common::proj2tree(&root).args(["-p", "-G", "-C", "--build-section"]));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains(".github вне workflows: 1"), "{}", stderr);
+    assert!(stderr.contains("скрытый файл: 1"), "{}", stderr);
+
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,89 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+mod common;
+use common::{proj2tree, run_in, success, temp_dir, write};
+
+// Архив bundle пишется без сжатия: элементы читаются по локальным заголовкам подряд
+fn unzip(archive: &[u8]) -> BTreeMap<String, Vec<u8>> {
+    let mut entries = BTreeMap::new();
+    let mut offset = 0;
+    while archive[offset..].starts_with(&[0x50, 0x4b, 0x03, 0x04]) {
+        let field = |at: usize, len: usize| {
+            archive[offset + at..offset + at + len].iter().rev().fold(0usize, |value, &byte| value << 8 | byte as usize)
+        };
+        assert_eq!(field(8, 2), 0, "метод сжатия");
+        let (size, name_len, extra_len) = (field(18, 4), field(26, 2), field(28, 2));
+        let name_start = offset + 30;
+        let data_start = name_start + name_len + extra_len;
+        let name = String::from_utf8(archive[name_start..name_start + name_len].to_vec()).unwrap();
+        entries.insert(name, archive[data_start..data_start + size].to_vec());
+        offset = data_start + size;
+    }
+    entries
+}
+
+fn project(name: &str) -> PathBuf {
+    let root = temp_dir(name);
+    write(&root, "README.md", "# Demo\n\n![logo](docs/logo.png)\n![outside](../../secret.png)\n");
+    write(&root, "docs/guide.md", "![diagram](<img/my diagram.png>)\n");
+    write(&root, "docs/page.html", "<img src=\"../shots/s.png\">\n");
+    write(&root, "docs/logo.png", [0x89, b'P', b'N', b'G', 1]);
+    write(&root, "docs/img/my diagram.png", [0x89, b'P', b'N', b'G', 2]);
+    write(&root, "shots/s.png", [0x89, b'P', b'N', b'G', 3]);
+    write(&root, "unused.png", [0x89, b'P', b'N', b'G', 4]);
+    root
+}
+
+fn bundle(root: &Path, args: &[&str]) -> BTreeMap<String, Vec<u8>> {
+    success(proj2tree(root).args(["-G", "--format", "bundle", "out.zip"]).args(args));
+    unzip(&fs::read(root.join("out.zip")).unwrap())
+}
+
+#[test]
+fn bundle_packs_referenced_images_only() {
+    let root = project("referenced");
+    let document = run_in(&root, &[]);
+
+    let entries = bundle(&root, &[]);
+    let names: Vec<&str> = entries.keys().map(String::as_str).collect();
+    assert_eq!(names, ["assets/docs/img/my diagram.png", "assets/docs/logo.png", "assets/shots/s.png", "tree.md"]);
+    for (asset, original) in [
+        ("assets/docs/img/my diagram.png", "docs/img/my diagram.png"),
+        ("assets/docs/logo.png", "docs/logo.png"),
+        ("assets/shots/s.png", "shots/s.png"),
+    ] {
+        assert_eq!(entries[asset], fs::read(root.join(original)).unwrap(), "{}", asset);
+    }
+
+    // Документ в архиве - тот же документ с разделом изображений в конце
+    let packed = String::from_utf8(entries["tree.md"].clone()).unwrap();
+    let images = packed.strip_prefix(&document).unwrap_or_else(|| panic!("{}", packed));
+    assert_eq!(
+        images,
+        "\n## Изображения\n\n\
+         ### `docs/img/my diagram.png`\n\n![my diagram.png](<assets/docs/img/my%20diagram.png>)\n\n\
+         ### `docs/logo.png`\n\n![logo.png](<assets/docs/logo.png>)\n\n\
+         ### `shots/s.png`\n\n![s.png](<assets/shots/s.png>)\n"
+    );
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn bundle_document_can_be_html() {
+    let root = project("html");
+
+    let entries = bundle(&root, &["--bundle-format", "html"]);
+    assert!(entries.contains_key("assets/docs/logo.png"));
+    let document = String::from_utf8(entries["tree.html"].clone()).unwrap();
+    assert!(
+        document.ends_with("<p><img src=\"assets/shots/s.png\" alt=\"s.png\"></p>\n</body>\n</html>\n"),
+        "{}",
+        document
+    );
+    assert!(document.contains("<h2>Изображения</h2>"), "{}", document);
+
+    fs::remove_dir_all(root).unwrap();
+}
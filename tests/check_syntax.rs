@@ -0,0 +1,147 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+mod common;
+use common::{proj2tree, success, temp_dir, write};
+
+fn run_in(dir: &Path, args: &[&str]) -> String {
+    let output = success(proj2tree(dir).args(["-p", "-G", "--no-tree"]).args(args));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+// Пометка перед блоком файла или None, если ее нет
+fn note_for(output: &str, file: &str) -> Option<String> {
+    let section = output.split(&format!("### `{}`\n", file)).nth(1)?;
+    let note = section.trim_start().lines().next()?;
+    note.starts_with('_').then(|| note.to_string())
+}
+
+#[test]
+fn broken_files_are_annotated() {
+    let root = temp_dir("broken");
+    write(&root, "truncated.rs", "fn main() {\n    let x = vec![1, 2\n");
+    write(&root, "extra.js", "function f() {\n  return 1;\n}}\n");
+    write(&root, "mismatch.c", "int main() {\n  foo(1];\n}\n");
+    write(&root, "string.py", "print('never closed)\n");
+    write(&root, "comment.java", "class A {}\n/* trailing\n");
+    write(&root, "config.toml", "[package]\nname = \"app\"\nversion = \n");
+    write(&root, "data.json", "{\"a\": 1,}\n");
+
+    let output = run_in(&root, &["--check-syntax"]);
+    assert_eq!(
+        note_for(&output, "truncated.rs").as_deref(),
+        Some("_⚠️ Файл не разбирается: конструкция из строки 1 обрывается в конце файла_"),
+        "{}",
+        output
+    );
+    assert_eq!(
+        note_for(&output, "extra.js").as_deref(),
+        Some("_⚠️ Файл не разбирается: неожиданное `}` в строке 3_")
+    );
+    assert_eq!(
+        note_for(&output, "mismatch.c").as_deref(),
+        Some("_⚠️ Файл не разбирается: неожиданное `(1]` в строке 2_")
+    );
+    assert_eq!(
+        note_for(&output, "string.py").as_deref(),
+        Some("_⚠️ Файл не разбирается: неожиданное `'never` в строке 1_")
+    );
+    assert_eq!(
+        note_for(&output, "comment.java").as_deref(),
+        Some("_⚠️ Файл не разбирается: неожиданное `/*` в строке 2_")
+    );
+    assert_eq!(
+        note_for(&output, "config.toml").as_deref(),
+        Some("_⚠️ Файл не разбирается: некорректный toml (строка 3)_")
+    );
+    assert_eq!(note_for(&output, "data.json").as_deref(), Some("_⚠️ Файл не разбирается: неожиданное `,` в строке 1_"));
+
+    let output = run_in(&root, &["--check-syntax", "--doc-lang", "en"]);
+    assert_eq!(
+        note_for(&output, "truncated.rs").as_deref(),
+        Some("_⚠️ File does not parse: construct from line 1 is cut off at the end of the file_")
+    );
+
+    // Маркеры конфликта слияния внутри функции
+    write(&root, "merged.rs", "fn main() {\n<<<<<<< HEAD\n    a();\n=======\n    b();\n>>>>>>> feature\n}\n");
+    write(&root, "missing.py", "def f(:\n    return 1\n");
+    let output = run_in(&root, &["--check-syntax"]);
+    assert_eq!(
+        note_for(&output, "merged.rs").as_deref(),
+        Some("_⚠️ Файл не разбирается: неожиданное `<<<<<<< HEAD` в строке 2_")
+    );
+    assert_eq!(note_for(&output, "missing.py").as_deref(), Some("_⚠️ Файл не разбирается: не хватает `)` в строке 1_"));
+
+    // Без параметра файлы не проверяются
+    let output = run_in(&root, &[]);
+    assert_eq!(note_for(&output, "truncated.rs"), None);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn valid_files_with_tricky_literals_are_not_annotated() {
+    let root = temp_dir("valid");
+    write(
+        &root,
+        "lib.rs",
+        "/* outer /* nested } */ */\nfn f<'a>(s: &'a str) -> char {\n    let _ = r#\"raw \" { string\"#;\n    let _ = \"multi\n    line (\";\n    if s.is_empty() { '{' } else { '\\'' }\n}\n",
+    );
+    write(&root, "app.js", "const re = /[(}]/g;\nconst t = `a ${1}\n(`;\nconst half = 4 / 2;\n");
+    write(&root, "script.py", "def f():\n    \"\"\"Doc with ( and\n    ' quote\"\"\"\n    return '#'  # comment (\n");
+    write(&root, "main.go", "package main\n\nvar s = `raw\n{`\nvar r = '}'\n");
+    write(&root, "data.json", "{\"a\": [1, \"}\"]}\n");
+    write(&root, "notes.txt", "unbalanced ( is fine here\n");
+
+    let output = run_in(&root, &["--check-syntax"]);
+    assert!(!output.contains("⚠️"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn annotated_files_round_trip() {
+    let root = temp_dir("unpack");
+    let project = root.join("project");
+    write(&project, "broken.rs", "fn main() {\n");
+
+    let snapshot = root.join("snapshot.md");
+    let output = Command::new(env!("CARGO_BIN_EXE_proj2tree"))
+        .arg(&project)
+        .args(["-G", "--check-syntax", "-o"])
+        .arg(&snapshot)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(fs::read_to_string(&snapshot).unwrap().contains("⚠️"));
+
+    let restored = root.join("restored");
+    let status = Command::new(env!("CARGO_BIN_EXE_proj2tree"))
+        .arg("unpack")
+        .arg(&snapshot)
+        .arg("-d")
+        .arg(&restored)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    assert_eq!(fs::read_to_string(restored.join("broken.rs")).unwrap(), "fn main() {\n");
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn deeply_nested_brackets_do_not_crash() {
+    let root = temp_dir("nested");
+    let nested = format!("{}{}\n", "[".repeat(200_000), "]".repeat(200_000));
+    write(&root, "payload", &nested);
+    write(&root, "payload.json", &nested);
+
+    // Файл без расширения проходит через определение языка, .json - через проверку синтаксиса
+    let output = run_in(&root, &[]);
+    assert!(output.contains("### `payload`"), "{}", &output[..200]);
+    let output = run_in(&root, &["--check-syntax"]);
+    assert!(output.contains("### `payload.json`"), "{}", &output[..200]);
+
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,76 @@
+use std::fs;
+use std::path::Path;
+
+mod common;
+use common::{proj2tree, temp_dir, write};
+
+fn run_in(dir: &Path, args: &[&str]) -> std::process::Output {
+    proj2tree(dir)
+        .args(["-G", "--doc-lang", "en"])
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+// Число открытых блоков кода в части должно быть равно числу закрытых
+fn fences_balanced(part: &str) -> bool {
+    let mut open: Option<usize> = None;
+    for line in part.lines() {
+        let backticks = line.len() - line.trim_start_matches('`').len();
+        match open {
+            None if backticks >= 3 => open = Some(backticks),
+            Some(length) if backticks >= length && backticks == line.len() => open = None,
+            _ => {}
+        }
+    }
+    open.is_none()
+}
+
+#[test]
+fn parts_respect_limit_and_keep_code_blocks_closed() {
+    let root = temp_dir("files");
+    for index in 0..6 {
+        let body: String = (0..40).map(|line| format!("let value_{} = {};\n", line, index)).collect();
+        write(&root, &format!("src/module_{}.rs", index), &body);
+    }
+
+    let output = run_in(&root, &["--chunk", "chars:1500"]);
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+
+    let mut parts = Vec::new();
+    while let Ok(part) = fs::read_to_string(root.join(format!("tree.part{}.md", parts.len() + 1))) {
+        parts.push(part);
+    }
+    assert!(parts.len() > 2, "{}", parts.len());
+    assert!(!root.join("tree.md").exists());
+
+    for (index, part) in parts.iter().enumerate() {
+        assert!(part.starts_with(&format!("Part {}/{}\n\n", index + 1, parts.len())), "{}", part);
+        assert!(part.chars().count() <= 1500, "{}", part.chars().count());
+        assert!(fences_balanced(part), "{}", part);
+    }
+
+    let joined: String = parts.concat();
+    for index in 0..6 {
+        assert!(joined.contains(&format!("let value_39 = {};", index)), "{}", joined);
+    }
+
+    // Части прошлого запуска не попадают в следующий снимок
+    let output = run_in(&root, &["-p", "--chunk", "chars:1500"]);
+    let output = String::from_utf8(output.stdout).unwrap();
+    assert!(!output.contains("tree.part1.md"), "{}", output);
+    assert!(output.starts_with("Part 1/"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn invalid_chunk_spec_is_rejected() {
+    let root = temp_dir("spec");
+
+    let output = run_in(&root, &["-p", "--chunk", "12000"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("chars:N"));
+
+    fs::remove_dir_all(root).unwrap();
+}
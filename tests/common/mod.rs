@@ -0,0 +1,41 @@
+// Общие помощники интеграционных тестов. Каждый файл tests/*.rs - отдельный крейт,
+// и часть помощников в нем не используется
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+// Пустая временная директория. Тестовые файлы запускаются отдельными процессами,
+// поэтому имени теста и номера процесса достаточно для уникальности
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("proj2tree-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::canonicalize(dir).unwrap()
+}
+
+pub fn write(dir: &Path, path: &str, content: impl AsRef<[u8]>) {
+    let path = dir.join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+pub fn proj2tree(dir: &Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_proj2tree"));
+    command.current_dir(dir);
+    command
+}
+
+// Запуск с проверкой кода завершения: ошибка проваливает тест и показывает stderr
+pub fn success(command: &mut Command) -> Output {
+    let output = command.output().unwrap();
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    output
+}
+
+// Документ в stdout (-p) без учета .gitignore (-G) - так запускается большинство тестов
+pub fn run_in(dir: &Path, args: &[&str]) -> String {
+    let output = success(proj2tree(dir).args(["-p", "-G"]).args(args));
+    String::from_utf8(output.stdout).unwrap()
+}
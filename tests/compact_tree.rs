@@ -0,0 +1,63 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+mod common;
+use common::{proj2tree, success, temp_dir, write};
+
+fn run_in(dir: &Path, args: &[&str]) -> String {
+    let output = success(proj2tree(dir).env("PROJ2TREE_THREADS", "1").args(["-p", "-G", "-C"]).args(args));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn project(name: &str) -> PathBuf {
+    let root = temp_dir(name);
+    write(&root, "src/main/java/com/example/App.java", "class App {}\n");
+    write(&root, "src/main/java/com/example/util/Strings.java", "class Strings {}\n");
+    write(&root, "src/main/resources/app.yml", "a: 1\n");
+    write(&root, "README.md", "# demo\n");
+    root
+}
+
+#[test]
+fn compact_tree_joins_single_child_directory_chains() {
+    let root = project("chains");
+
+    let output = run_in(&root, &["--compact-tree", "."]);
+
+    let expected = "\
+├── README.md
+└── src/main/
+    ├── java/com/example/
+    │   ├── App.java
+    │   └── util/
+    │       └── Strings.java
+    └── resources/
+        └── app.yml
+";
+    assert!(output.contains(expected), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn chain_takes_empty_mark_from_its_last_directory() {
+    let root = project("empty");
+    fs::create_dir_all(root.join("var/cache/sessions")).unwrap();
+
+    let output = run_in(&root, &["--compact-tree", "--mark-empty", "."]);
+
+    assert!(output.contains("└── var/cache/sessions/ (пусто)\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn tree_is_not_compacted_by_default() {
+    let root = project("default");
+
+    let output = run_in(&root, &["."]);
+
+    assert!(output.contains("└── src/\n    └── main/\n        ├── java/\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
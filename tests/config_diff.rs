@@ -0,0 +1,59 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+mod common;
+use common::{temp_dir, write};
+
+fn diff(dir: &Path, changes: &[&str]) -> std::process::Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_proj2tree"));
+    command.current_dir(dir).args(["-G", "config", "diff"]);
+    for change in changes {
+        command.args(["--set", change]);
+    }
+    command.output().unwrap()
+}
+
+fn project(name: &str) -> PathBuf {
+    let root = temp_dir(name);
+    write(&root, "src/main.rs", "fn main() {}\n");
+    write(&root, "generated/api.rs", "// generated\n");
+    write(&root, "fixtures/data.json", "{}\n");
+    write(&root, ".proj2tree.toml", "exclude_dirs = [\"fixtures\"]\n");
+    root
+}
+
+#[test]
+fn changes_are_previewed_without_writing_output() {
+    let root = project("preview");
+
+    let output = diff(&root, &["exclude_dirs+=generated", "exclude_dirs-=fixtures"]);
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        stdout,
+        "Будут исключены: 1\n- generated/api.rs\nБудут включены: 1\n+ fixtures/data.json\nФайлов с содержимым: 2 -> 2\n"
+    );
+    assert!(!root.join("tree.md").exists());
+
+    // Значение разбирается как TOML: число заменяет порог целиком
+    let stdout = String::from_utf8(diff(&root, &["max_file_size=5"]).stdout).unwrap();
+    assert!(stdout.starts_with("Будут исключены: 2\n- generated/api.rs\n- src/main.rs\n"), "{}", stdout);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn malformed_change_is_rejected() {
+    let root = project("malformed");
+
+    let output = diff(&root, &["exclude_dirs"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("key+=value"));
+
+    let output = diff(&root, &["max_file_size+=1"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("не является списком"));
+
+    fs::remove_dir_all(root).unwrap();
+}
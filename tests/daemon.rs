@@ -0,0 +1,124 @@
+#![cfg(unix)]
+
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+mod common;
+use common::{temp_dir, write};
+
+fn connect(socket: &Path) -> UnixStream {
+    for _ in 0..100 {
+        if let Ok(stream) = UnixStream::connect(socket) {
+            return stream;
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    panic!("демон не открыл сокет {}", socket.display());
+}
+
+#[test]
+fn daemon_serves_json_rpc_with_cached_tree() {
+    let root = temp_dir("rpc");
+    let project = root.join("project");
+    write(&project, "src/main.rs", "fn main() {}\n");
+    let socket = root.join("p2t.sock");
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_proj2tree"))
+        .args(["-G", "daemon", "--socket"])
+        .arg(&socket)
+        .arg(&project)
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let stream = connect(&socket);
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut writer = stream;
+    let mut call = |request: &str| {
+        writeln!(writer, "{}", request).unwrap();
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        line
+    };
+
+    let response = call(r#"{"jsonrpc":"2.0","id":1,"method":"get-tree"}"#);
+    assert_eq!(response, "{\"jsonrpc\":\"2.0\",\"id\":1,\"result\":{\"tree\":\"└── src/\\n    └── main.rs\\n\"}}\n");
+
+    // Дерево берется из кэша, пока не запрошен scan
+    write(&project, "src/lib.rs", "pub fn lib() {}\n");
+    let response = call(r#"{"jsonrpc":"2.0","id":2,"method":"get-tree"}"#);
+    assert!(!response.contains("lib.rs"), "{}", response);
+    let response = call(r#"{"jsonrpc":"2.0","id":3,"method":"scan"}"#);
+    assert!(response.contains("\"result\":{\"files\":2,\"included\":2}"), "{}", response);
+
+    let response = call(r#"{"jsonrpc":"2.0","id":"f","method":"get-file","params":{"path":"src/lib.rs"}}"#);
+    assert!(response.contains("\"id\":\"f\""), "{}", response);
+    assert!(response.contains("\"language\":\"rust\",\"content\":\"pub fn lib() {}\\n\""), "{}", response);
+
+    let response = call(r#"{"jsonrpc":"2.0","id":4,"method":"get-file","params":{"path":"../p2t.sock"}}"#);
+    assert!(response.contains("\"error\":{\"code\":-32602"), "{}", response);
+
+    let response = call(r#"{"jsonrpc":"2.0","id":5,"method":"regenerate"}"#);
+    assert!(response.contains("\"bytes\":"), "{}", response);
+    let document = fs::read_to_string(project.join("tree.md")).unwrap();
+    assert!(document.contains("### `src/lib.rs`"), "{}", document);
+
+    let response = call(r#"{"jsonrpc":"2.0","id":6,"method":"unknown"}"#);
+    assert!(response.contains("\"error\":{\"code\":-32601"), "{}", response);
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn daemon_serves_clients_concurrently_and_restricts_output() {
+    let root = temp_dir("clients");
+    let project = root.join("project");
+    write(&project, "src/main.rs", "fn main() {}\n");
+    let socket = root.join("p2t.sock");
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_proj2tree"))
+        .args(["-G", "daemon", "--socket"])
+        .arg(&socket)
+        .arg(&project)
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    // Первое соединение остается открытым и не мешает второму
+    let idle = connect(&socket);
+    let stream = connect(&socket);
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut writer = stream;
+
+    // На уведомление без id ответа нет: следующая строка - ответ на запрос с id 1
+    writeln!(writer, r#"{{"jsonrpc":"2.0","method":"scan"}}"#).unwrap();
+    writeln!(writer, r#"{{"jsonrpc":"2.0","id":1,"method":"regenerate","params":{{"output":"{}"}}}}"#, root.join("outside.md").display()).unwrap();
+    writeln!(writer, r#"{{"jsonrpc":"2.0","id":2,"method":"regenerate","params":{{"output":"../outside.md"}}}}"#).unwrap();
+    writeln!(writer, r#"{{"jsonrpc":"2.0","id":3,"method":"regenerate","params":{{"output":"docs.md"}}}}"#).unwrap();
+    let mut call = || {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        line
+    };
+
+    let response = call();
+    assert!(response.starts_with("{\"jsonrpc\":\"2.0\",\"id\":1,\"error\":{\"code\":-32602"), "{}", response);
+    let response = call();
+    assert!(response.starts_with("{\"jsonrpc\":\"2.0\",\"id\":2,\"error\":{\"code\":-32602"), "{}", response);
+    let response = call();
+    assert!(response.starts_with("{\"jsonrpc\":\"2.0\",\"id\":3,\"result\""), "{}", response);
+    assert!(!root.join("outside.md").exists());
+    assert!(project.join("docs.md").exists());
+
+    drop(idle);
+    child.kill().unwrap();
+    child.wait().unwrap();
+    fs::remove_dir_all(root).unwrap();
+}
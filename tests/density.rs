@@ -0,0 +1,37 @@
+use std::fs;
+use std::path::Path;
+
+mod common;
+use common::{proj2tree, success, temp_dir};
+
+fn run_in(dir: &Path, args: &[&str]) -> String {
+    let output = success(proj2tree(dir).args(["-p", "-G", "-T", "-C"]).args(args));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn repetitive_files_are_flagged_as_low_density() {
+    let dir = temp_dir("generated");
+    let generated: String = (0..500).map(|i| format!("  {{\"id\": {}, \"enabled\": true}},\n", i)).collect();
+    fs::write(dir.join("generated.json"), generated).unwrap();
+    // Псевдослучайный текст без повторов сжимается плохо
+    let mut state = 12345u32;
+    let varied: String = (0..4000)
+        .map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            char::from(b'a' + (state >> 16) as u8 % 26)
+        })
+        .collect();
+    fs::write(dir.join("varied.txt"), varied).unwrap();
+
+    let output = run_in(&dir, &["--top-files", "5"]);
+    assert!(!output.contains("Плотность"), "{}", output);
+
+    let output = run_in(&dir, &["--top-files", "5", "--density"]);
+    assert!(output.contains("| Размер | Плотность | Файл |"), "{}", output);
+    let low_density = output.split("### Низкая плотность").nth(1).unwrap().split("###").next().unwrap();
+    assert!(low_density.contains("`generated.json`"), "{}", output);
+    assert!(!low_density.contains("`varied.txt`"), "{}", output);
+
+    fs::remove_dir_all(dir).unwrap();
+}
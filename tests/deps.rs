@@ -0,0 +1,74 @@
+use std::fs;
+use std::path::Path;
+
+mod common;
+use common::{proj2tree, success, temp_dir, write};
+
+fn run_in(dir: &Path, args: &[&str]) -> String {
+    let output = success(proj2tree(dir).args(["-p", "-G", "--no-tree", "-C"]).args(args));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn manifests_are_summarized_per_package() {
+    let root = temp_dir("section");
+    write(
+        &root,
+        "Cargo.toml",
+        "[package]\nname = \"app\"\n\n[dependencies]\nserde = \"1\"\nlocal = { path = \"../local\" }\n\n[dev-dependencies]\ntempfile = { version = \"3.2\" }\n\n[target.'cfg(unix)'.dependencies]\nlibc = \"0.2\"\n",
+    );
+    write(
+        &root,
+        "web/package.json",
+        "{\"name\": \"web\", \"dependencies\": {\"react\": \"^18.2.0\"}, \"devDependencies\": {\"vite\": \"~5.0\"}}",
+    );
+    write(
+        &root,
+        "py/pyproject.toml",
+        "[project]\nname = \"tool\"\ndependencies = [\"requests>=2.0\", \"click\"]\n\n[tool.poetry.group.dev.dependencies]\npytest = \"^8\"\n",
+    );
+    write(
+        &root,
+        "go/go.mod",
+        "module example.com/svc\n\ngo 1.22\n\nrequire github.com/pkg/errors v0.9.1\n\nrequire (\n\tgolang.org/x/text v0.14.0 // indirect\n)\n",
+    );
+    write(&root, "node_modules/dep/package.json", "{\"name\": \"dep\", \"dependencies\": {\"left-pad\": \"1\"}}");
+
+    let output = run_in(&root, &["--deps"]);
+    let section = &output[output.find("## Зависимости").unwrap()..];
+
+    assert!(section.contains("### `Cargo.toml` - пакет app\n"), "{}", section);
+    assert!(section.contains("| `serde` | 1 | dependencies |"), "{}", section);
+    assert!(section.contains("| `local` | path = ../local | dependencies |"), "{}", section);
+    assert!(section.contains("| `tempfile` | 3.2 | dev-dependencies |"), "{}", section);
+    assert!(section.contains("| `libc` | 0.2 | dependencies (cfg(unix)) |"), "{}", section);
+
+    assert!(section.contains("### `web/package.json` - пакет web\n"), "{}", section);
+    assert!(section.contains("| `react` | ^18.2.0 | dependencies |"), "{}", section);
+    assert!(section.contains("| `vite` | ~5.0 | devDependencies |"), "{}", section);
+
+    assert!(section.contains("### `py/pyproject.toml` - пакет tool\n"), "{}", section);
+    assert!(section.contains("| `requests` | >=2.0 | dependencies |"), "{}", section);
+    assert!(section.contains("| `click` | * | dependencies |"), "{}", section);
+    assert!(section.contains("| `pytest` | ^8 | tool.poetry (dev) |"), "{}", section);
+
+    assert!(section.contains("### `go/go.mod` - пакет example.com/svc\n"), "{}", section);
+    assert!(section.contains("| `github.com/pkg/errors` | v0.9.1 | require |"), "{}", section);
+    assert!(section.contains("| `golang.org/x/text` | v0.14.0 | require (indirect) |"), "{}", section);
+
+    // node_modules исключается по умолчанию, вместе с манифестами внутри
+    assert!(!section.contains("left-pad"), "{}", section);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn manifest_without_dependencies_is_marked() {
+    let root = temp_dir("empty");
+    write(&root, "package.json", "{\"name\": \"bare\"}");
+
+    let output = run_in(&root, &["--deps", "--doc-lang", "en"]);
+    assert!(output.contains("## Dependencies\n\n### `package.json` - package bare\n\n*No dependencies*\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,87 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+mod common;
+use common::{proj2tree, success, temp_dir, write};
+
+fn run_in(dir: &Path, args: &[&str]) -> String {
+    let output = success(proj2tree(dir).env("PROJ2TREE_THREADS", "1").arg("-p").args(args));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn project(name: &str) -> PathBuf {
+    let root = temp_dir(name);
+    write(&root, "src/main.rs", "fn main() {}\n");
+    write(&root, "node_modules/pkg/index.js", "module.exports = 1;\n");
+    write(&root, "docs/guide.md", "# guide\n");
+    write(&root, "docs/notes.md", "# notes\n");
+    write(&root, ".env", "TOKEN=1\n");
+    write(&root, ".gitignore", "src/\n");
+    write(&root, "Dockerfile", "FROM rust\nCOPY --chown=app \\\n    src/ /app/src\nCOPY --from=build /out /out\nRUN cargo build\n");
+    root
+}
+
+#[test]
+fn dockerignore_replaces_gitignore_and_default_exclusions() {
+    let root = project("ignore");
+    write(&root, ".dockerignore", "node_modules\n**/*.md\n!docs/guide.md\n");
+
+    let output = run_in(&root, &["-C", "--docker-context", "."]);
+
+    assert!(output.contains("── .env\n"), "{}", output);
+    assert!(output.contains("── node_modules/ ...\n"), "{}", output);
+    assert!(output.contains("── docs/\n│   └── guide.md\n"), "{}", output);
+    assert!(!output.contains("notes.md"), "{}", output);
+    assert!(output.contains("└── src/\n    └── main.rs\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn section_counts_files_per_copy_instruction() {
+    let root = project("copy");
+    write(&root, ".dockerignore", "node_modules\n");
+
+    let output = run_in(&root, &["-C", "--docker-context", "."]);
+
+    assert!(output.contains("## Контекст сборки Docker\n"), "{}", output);
+    assert!(output.contains("Dockerfile: `Dockerfile`, правила исключения: `.dockerignore`\n"), "{}", output);
+    assert!(output.contains("- `COPY --chown=app src/ /app/src` (строка 2): файлов 1, 13 B\n"), "{}", output);
+    assert!(!output.contains("--from"), "{}", output);
+    assert!(output.contains("Не копируются в образ файлов: 6"), "{}", output);
+    assert!(output.contains("- `docs/guide.md` (8 B)\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn dockerfile_specific_ignore_file_takes_precedence() {
+    let root = project("own-ignore");
+    write(&root, ".dockerignore", "docs\n");
+    write(&root, "build/app.Dockerfile", "FROM scratch\nCOPY docs /docs\n");
+    write(&root, "build/app.Dockerfile.dockerignore", "node_modules\n");
+
+    let output = run_in(&root, &["-C", "--docker-context=build/app.Dockerfile", "."]);
+
+    assert!(output.contains("── docs/\n"), "{}", output);
+    assert!(output.contains("правила исключения: `build/app.Dockerfile.dockerignore`\n"), "{}", output);
+    assert!(output.contains("- `COPY docs /docs` (строка 2): файлов 2, 16 B\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn missing_dockerfile_is_an_error() {
+    let root = temp_dir("missing");
+    write(&root, "main.rs", "fn main() {}\n");
+
+    let output = proj2tree(&root)
+        .args(["-p", "--docker-context", "."])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Dockerfile"));
+
+    fs::remove_dir_all(root).unwrap();
+}
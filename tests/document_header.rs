@@ -0,0 +1,41 @@
+use std::fs;
+
+mod common;
+use common::{proj2tree, run_in, temp_dir, write};
+
+// Вступление идет сразу после заголовка, {title}, {source} и {date} заменяются, прочие скобки остаются
+#[test]
+fn header_file_follows_the_title_with_placeholders_filled() {
+    let root = temp_dir("header-file");
+    write(&root, "project/main.rs", "fn main() {}\n");
+    write(&root, "intro.md", "Снимок «{title}» из {source} от {date}.\n\nФормат: {json}\n\n");
+
+    let output = run_in(&root.join("project"), &["--title", "Backend", "--header-file", "../intro.md"]);
+
+    let rest = output.strip_prefix("# Backend\n\nСнимок «Backend» из текущая директория от ").unwrap_or_else(|| panic!("{}", output));
+    let (date, rest) = rest.split_at(10);
+    assert!(
+        date.char_indices().all(|(index, c)| if index == 4 || index == 7 { c == '-' } else { c.is_ascii_digit() }),
+        "{}",
+        output
+    );
+    assert!(rest.starts_with(".\n\nФормат: {json}\n\n## Дерево файлов\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+// Отсутствующий файл - ошибка до начала вывода, а не оборванный документ
+#[test]
+fn missing_header_file_fails_before_writing() {
+    let root = temp_dir("header-file-missing");
+    write(&root, "main.rs", "fn main() {}\n");
+
+    let output = proj2tree(&root).args(["-p", "-G", "--header-file", "absent.md"]).output().unwrap();
+
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty(), "{}", String::from_utf8_lossy(&output.stdout));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("не удалось прочитать absent.md"), "{}", stderr);
+
+    fs::remove_dir_all(root).unwrap();
+}
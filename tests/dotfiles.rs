@@ -0,0 +1,58 @@
+use std::fs;
+use std::path::PathBuf;
+
+mod common;
+use common::{run_in, temp_dir, write};
+
+fn project(name: &str, config: &str) -> PathBuf {
+    let root = temp_dir(name);
+    write(&root, ".proj2tree.toml", config);
+    write(&root, ".github/workflows/ci.yml", "name: ci\n");
+    write(&root, ".env.example", "API_URL=\n");
+    write(&root, ".eslintrc", "{\"root\": true}\n");
+    write(&root, ".cache/state.txt", "cached state\n");
+    write(&root, "src/main.rs", "fn main() {}\n");
+    root
+}
+
+#[test]
+fn skip_policy_keeps_only_allowlisted_dotfiles() {
+    let root = project("skip", "keep_dotfiles = [\".github\", \".env.example\"]\n");
+
+    let output = run_in(&root, &[]);
+    assert!(output.contains("name: ci"), "{}", output);
+    assert!(output.contains("API_URL="), "{}", output);
+    assert!(!output.contains(".eslintrc"), "{}", output);
+    assert!(!output.contains(".cache"), "{}", output);
+    assert!(!output.contains(".proj2tree.toml"), "{}", output);
+    assert!(output.contains("fn main() {}"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn include_policy_treats_dotfiles_as_regular_files() {
+    let root = project("include", "dotfiles = \"include\"\n");
+
+    let output = run_in(&root, &[]);
+    assert!(output.contains("name: ci"), "{}", output);
+    assert!(output.contains("\"root\": true"), "{}", output);
+    assert!(output.contains("cached state"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn list_only_policy_shows_dotfiles_in_tree_without_contents() {
+    let root = project("list-only", "dotfiles = \"list-only\"\nkeep_dotfiles = [\".github\"]\n");
+
+    let output = run_in(&root, &[]);
+    assert!(output.contains("├── .eslintrc\n"), "{}", output);
+    assert!(output.contains("│   └── state.txt\n"), "{}", output);
+    assert!(!output.contains("\"root\": true"), "{}", output);
+    assert!(!output.contains("cached state"), "{}", output);
+    assert!(!output.contains("API_URL="), "{}", output);
+    assert!(output.contains("name: ci"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,79 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+mod common;
+use common::{proj2tree, success, temp_dir, write};
+
+fn run_in(dir: &Path, args: &[&str]) -> Output {
+    success(proj2tree(dir).env("PROJ2TREE_THREADS", "1").arg("-G").args(args))
+}
+
+// Снимок сервиса и проект, в который он встраивается
+fn setup(name: &str) -> (PathBuf, PathBuf) {
+    let root = temp_dir(name);
+    let service = root.join("auth");
+    write(&service, "src/lib.rs", "pub fn login() {}\n");
+    write(&service, "Cargo.toml", "[package]\nname = \"auth\"\n");
+    run_in(&service, &["-o", "../auth.md"]);
+
+    let system = root.join("system");
+    write(&system, "services/gateway.py", "print('gateway')\n");
+    (root, system)
+}
+
+#[test]
+fn embedded_snapshot_is_spliced_under_prefix() {
+    let (root, system) = setup("splice");
+
+    let output = run_in(&system, &["-p", "--embed", "../auth.md@services/auth", "."]);
+    let document = String::from_utf8_lossy(&output.stdout);
+
+    let tree = "\
+└── services/
+    ├── auth/
+    │   ├── Cargo.toml
+    │   └── src/
+    │       └── lib.rs
+    └── gateway.py
+";
+    assert!(document.contains(tree), "{}", document);
+    assert!(document.contains("### `services/auth/src/lib.rs`\n\n```rust\npub fn login() {}\n```\n"), "{}", document);
+    // Заголовки встроенного документа не повторяются
+    assert_eq!(document.matches("## Дерево файлов").count(), 1, "{}", document);
+    assert_eq!(document.matches("## Содержимое файлов").count(), 1, "{}", document);
+    assert!(!document.contains("Структура проекта: auth"), "{}", document);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn duplicate_paths_are_written_once() {
+    let (root, system) = setup("duplicates");
+    write(&system, "services/auth/Cargo.toml", "[package]\nname = \"local\"\n");
+
+    let output = run_in(
+        &system,
+        &["-p", "--embed", "../auth.md@services/auth", "--embed", "../auth.md@services/auth", "."],
+    );
+    let document = String::from_utf8_lossy(&output.stdout);
+
+    assert_eq!(document.matches("### `services/auth/Cargo.toml`").count(), 1, "{}", document);
+    assert_eq!(document.matches("### `services/auth/src/lib.rs`").count(), 1, "{}", document);
+    assert!(document.contains("name = \"local\""), "{}", document);
+    assert_eq!(document.matches("── Cargo.toml").count(), 1, "{}", document);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn prefix_defaults_to_snapshot_name() {
+    let (root, system) = setup("default-prefix");
+
+    let output = run_in(&system, &["-p", "-C", "--embed", "../auth.md", "."]);
+    let document = String::from_utf8_lossy(&output.stdout);
+
+    assert!(document.contains("├── auth/\n│   ├── Cargo.toml\n"), "{}", document);
+
+    fs::remove_dir_all(root).unwrap();
+}
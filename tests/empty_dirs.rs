@@ -0,0 +1,61 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+mod common;
+use common::{proj2tree, success, temp_dir, write};
+
+fn run_in(dir: &Path, args: &[&str]) -> String {
+    let output = success(proj2tree(dir).env("PROJ2TREE_THREADS", "1").args(["-p", "-G", "-C"]).args(args));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn project(name: &str) -> PathBuf {
+    let root = temp_dir(name);
+    fs::create_dir_all(root.join("cache/nested")).unwrap();
+    write(&root, "logs/.gitkeep", "");
+    write(&root, "src/main.rs", "fn main() {}\n");
+    // Все файлы исключены фильтрами, но на диске директория не пустая
+    write(&root, "classes/App.class", "");
+    root
+}
+
+#[test]
+fn mark_empty_annotates_empty_and_placeholder_only_dirs() {
+    let root = project("mark");
+
+    let output = run_in(&root, &["--mark-empty", "."]);
+
+    assert!(output.contains("├── cache/ (пусто)\n│   └── nested/ (пусто)\n"), "{}", output);
+    assert!(output.contains("── logs/ (пусто)\n"), "{}", output);
+    assert!(output.contains("── classes/\n"), "{}", output);
+    assert!(output.contains("── src/\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn prune_empty_hides_empty_dirs() {
+    let root = project("prune");
+
+    let output = run_in(&root, &["--prune-empty", "."]);
+
+    assert!(!output.contains("cache/"), "{}", output);
+    assert!(!output.contains("nested/"), "{}", output);
+    assert!(!output.contains("logs/"), "{}", output);
+    assert!(output.contains("── classes/\n"), "{}", output);
+    assert!(output.contains("└── src/\n    └── main.rs\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn empty_dirs_are_shown_plainly_by_default() {
+    let root = project("default");
+
+    let output = run_in(&root, &["."]);
+
+    assert!(output.contains("├── cache/\n│   └── nested/\n"), "{}", output);
+    assert!(!output.contains("(пусто)"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,61 @@
+use std::fs;
+use std::path::Path;
+
+mod common;
+use common::{proj2tree, success, temp_dir};
+
+fn run_in(dir: &Path, args: &[&str]) -> String {
+    let output = success(proj2tree(dir).args(["-p", "-G", "--no-tree", "--doc-lang", "en"]).args(args));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn utf16(text: &str, little_endian: bool) -> Vec<u8> {
+    let mut bytes = if little_endian { vec![0xFF, 0xFE] } else { vec![0xFE, 0xFF] };
+    for unit in text.encode_utf16() {
+        bytes.extend(if little_endian { unit.to_le_bytes() } else { unit.to_be_bytes() });
+    }
+    bytes
+}
+
+#[test]
+fn boms_are_stripped_and_utf16_is_transcoded() {
+    let root = temp_dir("bom");
+    fs::write(root.join("utf8.rs"), b"\xEF\xBB\xBFstruct A;\n").unwrap();
+    fs::write(root.join("le.txt"), utf16("Привет, мир\n", true)).unwrap();
+    fs::write(root.join("be.txt"), utf16("hello\n", false)).unwrap();
+    fs::write(root.join("plain.txt"), "plain\n").unwrap();
+
+    let output = run_in(&root, &[]);
+    assert!(!output.contains('\u{FEFF}'), "{}", output);
+    assert!(
+        output.contains("### `utf8.rs`\n\n_File starts with a BOM (UTF-8), removed from the contents_\n\n```rust\nstruct A;\n```"),
+        "{}",
+        output
+    );
+    assert!(
+        output.contains("### `le.txt`\n\n_File is UTF-16LE with a BOM, contents transcoded to UTF-8_\n\n```text\nПривет, мир\n```"),
+        "{}",
+        output
+    );
+    assert!(
+        output.contains("### `be.txt`\n\n_File is UTF-16BE with a BOM, contents transcoded to UTF-8_\n\n```text\nhello\n```"),
+        "{}",
+        output
+    );
+    assert!(output.contains("### `plain.txt`\n\n```text\nplain\n```"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn truncated_utf16_is_reported_as_unreadable() {
+    let root = temp_dir("odd");
+    let mut bytes = utf16("abc", true);
+    bytes.push(0x41);
+    fs::write(root.join("odd.txt"), bytes).unwrap();
+
+    let output = run_in(&root, &[]);
+    assert!(output.contains("valid UTF-16LE"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
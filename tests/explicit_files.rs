@@ -0,0 +1,45 @@
+use std::fs;
+use std::path::Path;
+
+mod common;
+use common::{proj2tree, temp_dir, write};
+
+fn run_in(dir: &Path, args: &[&str]) -> std::process::Output {
+    proj2tree(dir)
+        .args(["-p", "-G"])
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn explicit_files_bypass_filters_and_are_not_repeated() {
+    let root = temp_dir("mixed");
+    write(&root, "project/src/main.rs", "fn main() {}\n");
+    write(&root, "project/debug.log", "excluded by extension\n");
+    write(&root, "ops/deploy.sh", "#!/bin/sh\n");
+    let project = root.join("project");
+
+    let output = run_in(&project, &["src", "../ops/deploy.sh", "debug.log", "src/main.rs"]);
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let output = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(output.matches("fn main() {}").count(), 1, "{}", output);
+    assert!(output.contains("### `../ops/deploy.sh`\n\n```bash\n#!/bin/sh\n```"), "{}", output);
+    assert!(output.contains("### `debug.log`\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn only_one_directory_is_accepted() {
+    let root = temp_dir("dirs");
+    fs::create_dir_all(root.join("a")).unwrap();
+    fs::create_dir_all(root.join("b")).unwrap();
+
+    let output = run_in(&root, &["a", "b"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("только одну директорию"));
+
+    fs::remove_dir_all(root).unwrap();
+}
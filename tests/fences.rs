@@ -0,0 +1,123 @@
+use std::fs;
+
+mod common;
+use common::{run_in, temp_dir};
+
+// Блок кода должен заканчиваться ровно там, где кончается содержимое файла, в любом
+// рендерере по CommonMark. Проверка ниже не использует разбор из unpack: она повторяет
+// правила спецификации для закрывающего fence независимо от реализации
+
+// Закрывающий fence по CommonMark: до трех пробелов отступа, серия бэктиков не короче
+// открывающей и только пробельные символы после нее. Одиночный \r тоже завершает строку
+fn closes(line: &str, length: usize) -> bool {
+    line.split('\r').any(|line| {
+        let indent = line.len() - line.trim_start_matches(' ').len();
+        let rest = &line[indent..];
+        let run = rest.len() - rest.trim_start_matches('`').len();
+        indent <= 3 && run >= length && rest[run..].trim_matches([' ', '\t']).is_empty()
+    })
+}
+
+// Содержимое блока после заголовка `### `name`` так, как его увидит рендерер
+fn rendered_block<'a>(document: &'a str, name: &str) -> (&'a str, String) {
+    let heading = format!("### `{}`\n\n", name);
+    let start = document.find(&heading).unwrap_or_else(|| panic!("нет раздела {}:\n{}", name, document)) + heading.len();
+    let mut lines = document[start..].split('\n');
+
+    let opening = lines.next().unwrap();
+    let length = opening.len() - opening.trim_start_matches('`').len();
+    assert!(length >= 3, "{}", opening);
+    let info = &opening[length..];
+    assert!(!info.contains('`'), "бэктик в строке информации: {}", opening);
+
+    let mut content = String::new();
+    for line in lines {
+        if closes(line, length) {
+            return (opening, content);
+        }
+        content.push_str(line);
+        content.push('\n');
+    }
+    panic!("блок {} не закрыт:\n{}", name, document);
+}
+
+fn with_newline(content: &str) -> String {
+    if content.ends_with('\n') { content.to_string() } else { format!("{}\n", content) }
+}
+
+#[test]
+fn tricky_contents_stay_inside_their_block() {
+    let cases: [(&str, &str); 10] = [
+        ("nested.md", "# Doc\n\n```rust\nfn main() {}\n```\n\n````\nquad\n````\n"),
+        ("info.md", "```js title=\"a.js\" {1,3}\nlet x;\n```\n"),
+        ("eof.md", "text\n```"),
+        ("eof_long.md", "```````"),
+        ("strings.js", "const a = \"``\";\nconst b = `${a}`;\nconst c = '```';\n"),
+        ("indented.md", "   ````\n    `````\n\t```\n"),
+        ("tildes.md", "~~~\ninside\n~~~~\n"),
+        ("crlf.md", "line\r\n```\r\nmore\r\n"),
+        ("cr_only.md", "a\r```\rb\n"),
+        ("trailing.md", "```   \n``` x\n"),
+    ];
+
+    let dir = temp_dir("cases");
+    for (name, content) in &cases {
+        fs::write(dir.join(name), content).unwrap();
+    }
+
+    let document = run_in(&dir, &[]);
+    for (name, content) in &cases {
+        let (_, rendered) = rendered_block(&document, name);
+        assert_eq!(rendered, with_newline(content), "{}", name);
+    }
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn min_fence_lengthens_every_block() {
+    let dir = temp_dir("min");
+    fs::write(dir.join("plain.rs"), "fn main() {}\n").unwrap();
+    fs::write(dir.join("nested.md"), "``````\n").unwrap();
+
+    let document = run_in(&dir, &["--min-fence", "5"]);
+    assert_eq!(rendered_block(&document, "plain.rs").0, "`````rust");
+    assert_eq!(rendered_block(&document, "nested.md").0, "```````markdown");
+
+    let document = run_in(&dir, &["--min-fence", "5", "-C"]);
+    assert!(document.contains("`````\n├── nested.md\n└── plain.rs\n`````\n"), "{}", document);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+// Случайное содержимое из «опасного» алфавита: серии бэктиков, отступы, переводы строк.
+// Генератор детерминирован, упавший случай воспроизводится по номеру файла
+#[test]
+fn random_contents_never_escape_the_block() {
+    const ALPHABET: &[u8] = b"```````~ \t\n\r$x{}#>-";
+    let dir = temp_dir("fuzz");
+    let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+    let mut next = || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    let mut cases = Vec::new();
+    for i in 0..300 {
+        let length = (next() % 60) as usize;
+        let content: String = (0..length).map(|_| ALPHABET[(next() % ALPHABET.len() as u64) as usize] as char).collect();
+        let name = format!("case{:03}.txt", i);
+        fs::write(dir.join(&name), &content).unwrap();
+        cases.push((name, content));
+    }
+
+    let document = run_in(&dir, &[]);
+    for (name, content) in &cases {
+        let (_, rendered) = rendered_block(&document, name);
+        assert_eq!(rendered, with_newline(content), "{}: {:?}", name, content);
+    }
+
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,73 @@
+use std::ffi::{CStr, CString, c_char};
+use std::fs;
+
+mod common;
+use common::{temp_dir, write};
+
+// Символы C ABI берутся из rlib библиотеки
+extern crate proj2tree;
+
+unsafe extern "C" {
+    fn proj2tree_scan_project(path: *const c_char, options: *const c_char) -> *mut c_char;
+    fn proj2tree_free_string(value: *mut c_char);
+}
+
+fn scan(path: &str, options: &str) -> String {
+    let path = CString::new(path).unwrap();
+    let options = CString::new(options).unwrap();
+    unsafe {
+        let result = proj2tree_scan_project(path.as_ptr(), options.as_ptr());
+        assert!(!result.is_null());
+        let json = CStr::from_ptr(result).to_str().unwrap().to_string();
+        proj2tree_free_string(result);
+        json
+    }
+}
+
+#[test]
+fn scan_project_returns_tree_and_contents_as_json() {
+    let dir = temp_dir("ffi");
+    write(&dir, "src/main.rs", "fn main() {}\n");
+    write(&dir, "notes.txt", "\"quoted\"\n");
+
+    let json = scan(dir.to_str().unwrap(), r#"{"no_gitignore": true, "only_ext": "rs"}"#);
+
+    assert!(json.contains(r#"{"type":"directory","path":"src","children":[{"type":"file","path":"src/main.rs"}]}"#));
+    assert!(json.contains(r#""content_id":"sha256:536e506bb90914c243a12b397b9a998f85ae2cbd9ba02dfd03a9e155ca5ca0f4","size":13,"language":"rust","content":"fn main() {}\n"}"#));
+    assert!(!json.contains("notes.txt"));
+
+    let json = scan(dir.to_str().unwrap(), r#"{"no_gitignore": true}"#);
+    assert!(json.contains(r#""content":"\"quoted\"\n""#));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn scan_project_reports_errors_as_json() {
+    let json = scan("/nonexistent/proj2tree", "");
+    assert!(json.starts_with(r#"{"error":"#));
+
+    let json = scan(".", "[1, 2]");
+    assert!(json.starts_with(r#"{"error":"#));
+}
+
+#[test]
+fn content_id_survives_rename_and_id_does_not() {
+    let dir = temp_dir("ffi-ids");
+    write(&dir, "old.rs", "fn main() {}\n");
+
+    let field = |json: &str, name: &str| {
+        let start = json.find(&format!("\"{}\":\"", name)).unwrap() + name.len() + 4;
+        json[start..start + 71].to_string()
+    };
+
+    let before = scan(dir.to_str().unwrap(), r#"{"no_gitignore": true}"#);
+    fs::rename(dir.join("old.rs"), dir.join("new.rs")).unwrap();
+    let after = scan(dir.to_str().unwrap(), r#"{"no_gitignore": true}"#);
+
+    assert_eq!(field(&before, "content_id"), field(&after, "content_id"));
+    assert_ne!(field(&before, "id"), field(&after, "id"));
+    assert!(field(&after, "id").starts_with("sha256:"));
+
+    fs::remove_dir_all(dir).unwrap();
+}
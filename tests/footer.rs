@@ -0,0 +1,49 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+mod common;
+use common::{proj2tree, success, temp_dir};
+
+fn run_in(dir: &Path, args: &[&str]) -> String {
+    let output = success(proj2tree(dir).env("PROJ2TREE_THREADS", "1").args(["-p", "-G"]).args(args));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+// Независимая проверка хеша через coreutils
+fn sha256sum(data: &[u8]) -> String {
+    let mut child = Command::new("sha256sum").stdin(Stdio::piped()).stdout(Stdio::piped()).spawn().unwrap();
+    child.stdin.take().unwrap().write_all(data).unwrap();
+    let output = child.wait_with_output().unwrap();
+    String::from_utf8(output.stdout).unwrap().split_whitespace().next().unwrap().to_string()
+}
+
+#[test]
+fn footer_records_options_and_hash_of_the_body() {
+    let root = temp_dir("hash");
+    fs::write(root.join("main.rs"), "fn main() {}\n---\n").unwrap();
+
+    let output = run_in(&root, &["--footer", "--title", "Release notes"]);
+    let separator = output.rfind("\n---\n").unwrap();
+    let (body, footer) = output.split_at(separator);
+
+    assert!(body.contains("fn main() {}\n---\n"), "{}", body);
+    assert!(footer.contains(&format!("- Сгенерировано: proj2tree {}\n", env!("CARGO_PKG_VERSION"))), "{}", footer);
+    assert!(
+        footer.contains("- Параметры: `PROJ2TREE_THREADS=1 proj2tree -p -G --footer --title 'Release notes'`\n"),
+        "{}",
+        footer
+    );
+    assert!(footer.contains("- Время генерации: "), "{}", footer);
+    assert!(
+        footer.contains(&format!("- SHA-256 документа до разделителя: `{}`\n", sha256sum(body.as_bytes()))),
+        "{}",
+        footer
+    );
+
+    let plain = run_in(&root, &["--title", "Release notes"]);
+    assert_eq!(plain, body);
+
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,68 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+mod common;
+use common::{proj2tree, success, temp_dir, write};
+
+fn run_in(dir: &Path, args: &[&str]) -> (String, String) {
+    let output = success(proj2tree(dir).args(["-p", "-G"]).args(args));
+    (String::from_utf8(output.stdout).unwrap(), String::from_utf8(output.stderr).unwrap())
+}
+
+fn project(name: &str) -> PathBuf {
+    let root = temp_dir(name);
+    write(
+        &root,
+        ".gitattributes",
+        "# сгенерированный код\n\
+         *.pb.go linguist-generated=true\n\
+         api/gen/** linguist-generated\n\
+         api/gen/keep.go -linguist-generated\n\
+         /third_party/** linguist-vendored\n\
+         *.go text eol=lf\n",
+    );
+    write(&root, "main.go", "package main\n");
+    write(&root, "proto/user.pb.go", "package proto\n");
+    write(&root, "api/gen/client.go", "package gen\n");
+    write(&root, "api/gen/keep.go", "package gen\n");
+    write(&root, "third_party/lib/lib.go", "package lib\n");
+    root
+}
+
+#[test]
+fn generated_and_vendored_files_are_skipped() {
+    let root = project("skip");
+
+    let (document, _) = run_in(&root, &[]);
+    assert!(document.contains("main.go"), "{}", document);
+    assert!(document.contains("keep.go"), "{}", document);
+    assert!(!document.contains("user.pb.go"), "{}", document);
+    assert!(!document.contains("client.go"), "{}", document);
+    assert!(!document.contains("lib.go"), "{}", document);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn no_gitattributes_keeps_generated_files() {
+    let root = project("opt-out");
+
+    let (document, _) = run_in(&root, &["--no-gitattributes"]);
+    assert!(document.contains("user.pb.go"), "{}", document);
+    assert!(document.contains("client.go"), "{}", document);
+    assert!(document.contains("lib.go"), "{}", document);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn skip_summary_names_gitattributes_patterns() {
+    let root = project("summary");
+
+    let (_, stderr) = run_in(&root, &[]);
+    assert!(stderr.contains("linguist-generated: 2"), "{}", stderr);
+    assert!(stderr.contains("linguist-vendored: 1"), "{}", stderr);
+    assert!(stderr.contains("*.pb.go (linguist-generated): 1"), "{}", stderr);
+
+    fs::remove_dir_all(root).unwrap();
+}
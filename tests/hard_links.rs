@@ -0,0 +1,54 @@
+#![cfg(unix)]
+
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+
+mod common;
+use common::{run_in, temp_dir, write};
+
+// Два имени одного inode: содержимое выводится один раз, второе имя ссылается на первое
+#[test]
+fn hard_links_share_one_content_block() {
+    let root = temp_dir("hard-links");
+    write(&root, "a.txt", "shared\n");
+    write(&root, "d.txt", "own\n");
+    fs::create_dir_all(root.join("b")).unwrap();
+    fs::hard_link(root.join("a.txt"), root.join("b/c.txt")).unwrap();
+
+    let output = run_in(&root, &[]);
+
+    assert_eq!(output.matches("shared\n").count(), 1, "{}", output);
+    assert!(output.contains("### `a.txt`\n\n```text\nshared\n```\n"), "{}", output);
+    assert!(output.contains("### `b/c.txt`\n\n*Жесткая ссылка на `a.txt`, содержимое не повторяется*\n"), "{}", output);
+    assert!(output.contains("### `d.txt`\n\n```text\nown\n```\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+// Ссылка на директорию другой файловой системы (tmpfs в /dev/shm) сворачивается,
+// без флага она обходится как обычно
+#[test]
+fn one_file_system_collapses_other_devices() {
+    let shm = std::path::Path::new("/dev/shm");
+    let root = temp_dir("one-file-system");
+    let Ok(shm_metadata) = fs::metadata(shm) else { return };
+    if shm_metadata.dev() == fs::metadata(&root).unwrap().dev() {
+        fs::remove_dir_all(root).unwrap();
+        return;
+    }
+    let mounted = shm.join(format!("proj2tree-one-file-system-{}", std::process::id()));
+    write(&mounted, "m.txt", "elsewhere\n");
+    write(&root, "a.txt", "here\n");
+    std::os::unix::fs::symlink(&mounted, root.join("mnt")).unwrap();
+
+    let output = run_in(&root, &["--one-file-system"]);
+    assert!(output.contains("└── mnt/ ...\n"), "{}", output);
+    assert!(!output.contains("elsewhere"), "{}", output);
+
+    let output = run_in(&root, &[]);
+    assert!(output.contains("└── mnt/\n    └── m.txt\n"), "{}", output);
+    assert!(output.contains("elsewhere\n"), "{}", output);
+
+    fs::remove_dir_all(mounted).unwrap();
+    fs::remove_dir_all(root).unwrap();
+}
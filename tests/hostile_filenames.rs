@@ -0,0 +1,128 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+mod common;
+use common::temp_dir;
+
+fn run_print(dir: &PathBuf) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_proj2tree"))
+        .arg(dir)
+        .args(["-p", "-G"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn backticks_in_names_do_not_break_headings_or_tree() {
+    let dir = temp_dir("backticks");
+    fs::write(dir.join("a`b.txt"), "one\n").unwrap();
+    fs::write(dir.join("```fence.txt"), "two\n").unwrap();
+
+    let output = run_print(&dir);
+
+    assert!(output.contains("### ``a`b.txt``\n"));
+    assert!(output.contains("### ```` ```fence.txt ````\n"));
+    // Дерево обернуто в fence длиннее любой серии бэктиков в именах
+    assert!(output.contains("## Дерево файлов\n\n````\n"));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn markdown_characters_are_kept_inside_code_spans() {
+    let dir = temp_dir("markdown-chars");
+    fs::write(dir.join("#hash.txt"), "").unwrap();
+    fs::write(dir.join("pi|pe.txt"), "").unwrap();
+
+    let output = run_print(&dir);
+
+    assert!(output.contains("### `#hash.txt`\n"));
+    assert!(output.contains("### `pi|pe.txt`\n"));
+    assert!(output.contains("├── #hash.txt\n"));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn control_characters_and_invalid_utf8_are_escaped() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let dir = temp_dir("invalid-utf8");
+    fs::write(dir.join("new\nline.txt"), "").unwrap();
+    fs::write(dir.join(OsStr::from_bytes(b"bad\xFF\xFEname.txt")), "").unwrap();
+
+    let output = run_print(&dir);
+
+    assert!(output.contains("├── bad\\xFF\\xFEname.txt\n"));
+    assert!(output.contains("└── new\\nline.txt\n"));
+    assert!(output.contains("### `new\\nline.txt`\n"));
+    assert!(!output.contains('\u{FFFD}'));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn broken_symlinks_are_annotated_and_summarized() {
+    let dir = temp_dir("broken-symlink");
+    fs::create_dir(dir.join("sub")).unwrap();
+    fs::write(dir.join("sub/a.txt"), "x\n").unwrap();
+    std::os::unix::fs::symlink("nowhere", dir.join("sub/dead")).unwrap();
+
+    let output = run_print(&dir);
+
+    assert!(output.contains("    └── dead [ошибка: битая ссылка]\n"));
+    assert!(output.contains("### `sub/a.txt`\n"));
+    assert!(!output.contains("### `sub/dead`"));
+    assert!(output.contains("## Ошибки обхода\n\n- `sub/dead`: битая ссылка\n"));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn colliding_anchor_slugs_get_numeric_suffixes() {
+    let dir = temp_dir("anchors");
+    fs::create_dir(dir.join("a")).unwrap();
+    fs::write(dir.join("a/b.rs"), "x\n").unwrap();
+    fs::write(dir.join("a-b.rs"), "y\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_proj2tree"))
+        .arg(&dir)
+        .args(["-p", "-G", "--anchors"])
+        .output()
+        .unwrap();
+    let output = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.contains("├── <a href=\"#a\">a/</a>\n│   └── <a href=\"#a-b-rs\">b.rs</a>\n"));
+    assert!(output.contains("└── <a href=\"#a-b-rs-1\">a-b.rs</a>\n"));
+    assert!(output.contains("### `a/b.rs`\n<a id=\"a\"></a><a id=\"a-b-rs\"></a>\n"));
+    assert!(output.contains("### `a-b.rs`\n<a id=\"a-b-rs-1\"></a>\n"));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn fence_info_template_quotes_paths_and_rejects_backticks() {
+    let dir = temp_dir("fence-info");
+    fs::create_dir(dir.join("src")).unwrap();
+    fs::write(dir.join("src/say \"hi\".rs"), "x\n").unwrap();
+    fs::write(dir.join("a`b.rs"), "y\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_proj2tree"))
+        .arg(&dir)
+        .args(["-p", "-G", "--fence-info", "{lang} title=\"{path}\" lineNumbers"])
+        .output()
+        .unwrap();
+    let output = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.contains("```rust title=\"src/say \\\"hi\\\".rs\" lineNumbers\nx\n```"), "{}", output);
+    // Бэктик в строке информации сломал бы блок: остается только язык
+    assert!(output.contains("```rust\ny\n```"), "{}", output);
+
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,160 @@
+use std::fs;
+use std::process::Command;
+
+mod common;
+use common::{proj2tree, temp_dir};
+
+#[test]
+fn ignore_file_outside_the_project_is_applied_from_its_root() {
+    let root = temp_dir("ignore-file");
+    let project = root.join("project");
+    fs::create_dir_all(project.join("src/generated")).unwrap();
+    fs::write(project.join("src/main.rs"), "fn main() {}\n").unwrap();
+    fs::write(project.join("src/generated/api.rs"), "// generated\n").unwrap();
+    fs::write(project.join("notes.txt"), "draft\n").unwrap();
+    fs::write(root.join("llm-ignore.txt"), "/notes.txt\nsrc/generated/\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_proj2tree"))
+        .arg(&project)
+        .args(["-p", "--ignore-file"])
+        .arg(root.join("llm-ignore.txt"))
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let output = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.contains("### `src/main.rs`"));
+    assert!(output.contains("generated/ ..."));
+    assert!(!output.contains("notes.txt"));
+    assert!(!output.contains("api.rs"));
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn missing_ignore_file_is_an_error() {
+    let dir = temp_dir("ignore-file-missing");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_proj2tree"))
+        .arg(&dir)
+        .args(["-p", "--ignore-file"])
+        .arg(dir.join("absent.txt"))
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn case_insensitive_matching_catches_differently_cased_names() {
+    let dir = temp_dir("case-insensitive");
+    fs::create_dir_all(dir.join("Node_Modules/pkg")).unwrap();
+    fs::write(dir.join("Node_Modules/pkg/index.js"), "module.exports = 1;\n").unwrap();
+    fs::write(dir.join(".gitignore"), "*.log\n").unwrap();
+    fs::write(dir.join("BUILD.LOG"), "build output\n").unwrap();
+    fs::write(dir.join("main.rs"), "fn main() {}\n").unwrap();
+
+    let run = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_proj2tree"))
+            .arg(&dir)
+            .arg("-p")
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let output = run(&["--case-insensitive"]);
+    assert!(output.contains("Node_Modules/ ..."), "{}", output);
+    assert!(!output.contains("BUILD.LOG"), "{}", output);
+    assert!(output.contains("### `main.rs`"), "{}", output);
+
+    if cfg!(target_os = "linux") {
+        let output = run(&[]);
+        assert!(output.contains("### `Node_Modules/pkg/index.js`"), "{}", output);
+        assert!(output.contains("### `BUILD.LOG`"), "{}", output);
+    }
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+// Одни и те же правила при любой записи целевой директории: `.`, `./`, завершающий `/`,
+// `p/.`, абсолютный путь
+#[test]
+fn gitignore_semantics_do_not_depend_on_target_spelling() {
+    let root = temp_dir("gitignore-matrix");
+    let project = root.join("p");
+    let files = [
+        "artifacts/root.txt",
+        "src/artifacts/nested.txt",
+        "docs/guide.md",
+        "docs/api/deep.md",
+        "gen/x/generated.txt",
+        "src/gen/generated.rs",
+        "logs",
+        "data/logs/app.txt",
+        "backup.bak",
+        "src/keep.bak",
+        "a/one/two/b.txt",
+        "a/b.txt",
+        "src/main.rs",
+    ];
+    for file in files {
+        let path = project.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, format!("// {}\n", file)).unwrap();
+    }
+    fs::write(
+        project.join(".gitignore"),
+        "/artifacts\ndocs/*.md\n**/gen\nlogs/\n*.bak\n!src/keep.bak\na/**/b.txt\n",
+    )
+    .unwrap();
+
+    // Привязанный шаблон - только у корня, шаблон с `/` - от корня, `*` не переходит через `/`,
+    // `**/` - на любой глубине, `name/` - только директории, `!` возвращает файл
+    let included = ["src/artifacts/nested.txt", "docs/api/deep.md", "logs", "src/keep.bak", "src/main.rs"];
+    let excluded = [
+        "artifacts/root.txt",
+        "docs/guide.md",
+        "gen/x/generated.txt",
+        "src/gen/generated.rs",
+        "data/logs/app.txt",
+        "backup.bak",
+        "a/one/two/b.txt",
+        "a/b.txt",
+    ];
+
+    let absolute = project.to_string_lossy().to_string();
+    let cases = [
+        (&project, "."),
+        (&project, "./"),
+        (&project, ".//"),
+        (&project, "./."),
+        (&root, "p"),
+        (&root, "p/"),
+        (&root, "./p"),
+        (&root, "././p"),
+        (&root, "p/."),
+        (&root, "p//"),
+        (&root, absolute.as_str()),
+    ];
+    for (dir, target) in cases {
+        let output = proj2tree(dir)
+            .args(["-p", target])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+        let output = String::from_utf8(output.stdout).unwrap();
+        for file in included {
+            assert!(output.contains(&format!("// {}\n", file)), "{} должен попасть при '{}':\n{}", file, target, output);
+        }
+        for file in excluded {
+            assert!(!output.contains(&format!("// {}\n", file)), "{} не должен попасть при '{}':\n{}", file, target, output);
+        }
+    }
+
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,68 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+mod common;
+use common::{proj2tree, success, temp_dir, write};
+
+fn run_in(dir: &Path, args: &[&str]) -> String {
+    let output = success(proj2tree(dir).args(["-p", "-G", "-C"]).args(args));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn project(name: &str) -> PathBuf {
+    let root = temp_dir(name);
+    write(&root, "src/main.rs", "mod config;\nmod net;\nuse std::io;\n\nfn main() {}\n");
+    write(&root, "src/config.rs", "use crate::net::Client;\n");
+    write(&root, "src/net/mod.rs", "pub struct Client;\n");
+    write(&root, "web/app.ts", "import { api } from './api';\nimport React from 'react';\n");
+    write(&root, "web/api.ts", "export const api = 1;\n");
+    root
+}
+
+#[test]
+fn adjacency_list_shows_project_imports_only() {
+    let root = project("list");
+
+    let output = run_in(&root, &["--import-graph"]);
+    assert!(output.contains("## Граф импортов\n\n"), "{}", output);
+    assert!(output.contains("- `src/config.rs` → `src/net/mod.rs`\n"), "{}", output);
+    assert!(output.contains("- `src/main.rs` → `src/config.rs`, `src/net/mod.rs`\n"), "{}", output);
+    assert!(output.contains("- `web/app.ts` → `web/api.ts`\n"), "{}", output);
+    assert!(!output.contains("react"), "{}", output);
+    assert!(!output.contains("- `web/api.ts`"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn mermaid_graph_uses_numbered_nodes() {
+    let root = project("mermaid");
+
+    let output = run_in(&root, &["--import-graph=mermaid"]);
+    assert!(output.contains("```mermaid\ngraph LR\n"), "{}", output);
+    assert!(output.contains("[\"src/config.rs\"] --> "), "{}", output);
+    assert!(output.contains("[\"web/api.ts\"]\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn project_without_imports_has_note() {
+    let root = temp_dir("empty");
+    write(&root, "README.md", "# readme\n");
+
+    let output = run_in(&root, &["--import-graph"]);
+    assert!(output.contains("*Связей между файлами проекта не найдено*"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn section_is_absent_without_flag() {
+    let root = project("default");
+
+    let output = run_in(&root, &[]);
+    assert!(!output.contains("Граф импортов"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,65 @@
+#![cfg(unix)]
+
+use std::fs::{self, File};
+use std::io::Read;
+use std::process::{Command, Stdio};
+
+mod common;
+use common::temp_dir;
+
+// Документ пишется в FIFO: пока тест не читает, запуск стоит на записи в середине
+// содержимого, и сигнал гарантированно приходит до конца генерации
+#[test]
+fn interrupted_run_finishes_document_with_note() {
+    let root = temp_dir("sigint");
+    let project = root.join("project");
+    for i in 0..100 {
+        let path = project.join(format!("src/file{:03}.rs", i));
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "// строка\n".repeat(500)).unwrap();
+    }
+    let fifo = root.join("tree.md");
+    assert!(Command::new("mkfifo").arg(&fifo).status().unwrap().success());
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_proj2tree"))
+        .arg(&project)
+        .args(["-G", "-o"])
+        .arg(&fifo)
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let mut reader = File::open(&fifo).unwrap();
+    let mut document = vec![0; 4096];
+    reader.read_exact(&mut document).unwrap();
+    let status = Command::new("kill").args(["-INT", &child.id().to_string()]).status().unwrap();
+    assert!(status.success());
+    reader.read_to_end(&mut document).unwrap();
+    let document = String::from_utf8_lossy(&document);
+
+    let status = child.wait().unwrap();
+    assert_eq!(status.code(), Some(130));
+    assert!(document.contains("**Генерация прервана на `src/file"), "{}", tail(&document));
+    assert!(!document.contains("### `src/file099.rs`"), "{}", tail(&document));
+    assert_eq!(document.matches("```").count() % 2, 0, "{}", tail(&document));
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+fn tail(document: &str) -> &str {
+    let start = document.char_indices().rev().nth(500).map_or(0, |(index, _)| index);
+    &document[start..]
+}
+
+#[test]
+fn completed_run_has_no_note() {
+    let root = temp_dir("complete");
+    fs::write(root.join("main.rs"), "fn main() {}\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_proj2tree")).arg(&root).args(["-p", "-G"]).output().unwrap();
+    assert!(output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("прервана"));
+
+    fs::remove_dir_all(root).unwrap();
+}
+
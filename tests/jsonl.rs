@@ -0,0 +1,58 @@
+use std::fs;
+use std::path::Path;
+
+mod common;
+use common::{proj2tree, success, temp_dir, write};
+
+fn run_in(dir: &Path, args: &[&str]) -> String {
+    let output = success(proj2tree(dir).args(["-G", "--format", "jsonl"]).args(args));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn events_follow_traversal_order() {
+    let root = temp_dir("order");
+    write(&root, "src/main.rs", "fn main() {\n    println!(\"hi\");\n}\n");
+    write(&root, "src/logo.png", "not really");
+    write(&root, "target/debug/app", "binary");
+    write(&root, "README.md", "# App\n");
+
+    let output = run_in(&root, &["-p"]);
+    let lines: Vec<&str> = output.lines().collect();
+    assert!(lines.iter().all(|line| line.starts_with("{\"event\":\"") && line.ends_with('}')), "{}", output);
+    assert_eq!(lines[0], "{\"event\":\"start\",\"schema_version\":1,\"version\":1,\"root\":\".\"}");
+    assert_eq!(
+        &lines[1..],
+        [
+            "{\"event\":\"dir-enter\",\"path\":\".\"}",
+            "{\"event\":\"file\",\"path\":\"README.md\",\"size\":6,\"language\":\"markdown\",\"content\":\"# App\\n\"}",
+            "{\"event\":\"dir-enter\",\"path\":\"src\"}",
+            "{\"event\":\"file\",\"path\":\"src/logo.png\",\"size\":10}",
+            "{\"event\":\"file\",\"path\":\"src/main.rs\",\"size\":34,\"language\":\"rust\",\"content\":\"fn main() {\\n    println!(\\\"hi\\\");\\n}\\n\"}",
+            "{\"event\":\"dir-exit\",\"path\":\"src\"}",
+            "{\"event\":\"skip\",\"path\":\"target\",\"kind\":\"dir\",\"reason\":\"exclude_dirs\",\"collapsed\":true}",
+            "{\"event\":\"dir-exit\",\"path\":\".\"}",
+            "{\"event\":\"end\",\"dirs\":2,\"files\":3,\"skipped\":1}",
+        ]
+    );
+
+    // Без содержимого события файлов содержат только размер
+    let output = run_in(&root, &["-p", "--no-contents"]);
+    assert!(output.contains("{\"event\":\"file\",\"path\":\"src/main.rs\",\"size\":34}\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn events_are_written_to_tree_jsonl_by_default() {
+    let root = temp_dir("file");
+    write(&root, "a.txt", "a\n");
+
+    let output = run_in(&root, &[]);
+    assert!(output.is_empty(), "{}", output);
+    let events = fs::read_to_string(root.join("tree.jsonl")).unwrap();
+    assert!(events.contains("\"path\":\"a.txt\""), "{}", events);
+    assert!(!events.contains("{\"event\":\"file\",\"path\":\"tree.jsonl\""), "{}", events);
+
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,74 @@
+use std::fs;
+
+mod common;
+use common::{run_in, temp_dir, write};
+
+const OID: &str = "4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393";
+
+fn pointer(oid: &str, size: u64) -> String {
+    format!("version https://git-lfs.github.com/spec/v1\noid sha256:{}\nsize {}\n", oid, size)
+}
+
+#[test]
+fn lfs_object_is_read_from_local_store() {
+    let root = temp_dir("lfs-local");
+    fs::create_dir_all(root.join(".git")).unwrap();
+    write(&root, &format!(".git/lfs/objects/4d/7a/{}", OID), "real content\n");
+    write(&root, "data.txt", pointer(OID, 13));
+
+    let document = run_in(&root, &["--lfs-fetch"]);
+    assert!(document.contains("```text\nreal content\n```"), "{}", document);
+
+    // Без --lfs-fetch - пометка, и ее ограда следует --min-fence
+    let document = run_in(&root, &["--min-fence", "5"]);
+    assert!(document.contains(&format!("`````\n[LFS-объект, 13 B, sha256:{}]\n`````", OID)), "{}", document);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn lfs_size_limit_uses_the_real_object_size() {
+    let root = temp_dir("lfs-size");
+    fs::create_dir_all(root.join(".git")).unwrap();
+    write(&root, &format!(".git/lfs/objects/4d/7a/{}", OID), "x".repeat(4096));
+    // Указатель занижает размер
+    write(&root, "data.txt", pointer(OID, 10));
+    write(&root, ".proj2tree.toml", "max_file_size = 1024\n");
+
+    let document = run_in(&root, &["--lfs-fetch"]);
+    assert!(document.contains("больше max_file_size]"), "{}", document);
+    assert!(!document.contains("xxxx"), "{}", document);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn oid_with_path_components_is_not_a_pointer() {
+    let root = temp_dir("lfs-traversal");
+    fs::create_dir_all(root.join(".git/lfs/objects")).unwrap();
+    let outside = temp_dir("lfs-traversal-outside");
+    write(&outside, "secret.txt", "outside secret\n");
+    // `..` и `//` сбросили бы путь к корню файловой системы
+    let oid = format!("..//{}", outside.join("secret.txt").to_string_lossy().trim_start_matches('/'));
+    write(&root, "data.txt", pointer(&oid, 15));
+
+    let document = run_in(&root, &["--lfs-fetch"]);
+    assert!(!document.contains("outside secret"), "{}", document);
+    assert!(!document.contains("LFS-объект"), "{}", document);
+
+    fs::remove_dir_all(root).unwrap();
+    fs::remove_dir_all(outside).unwrap();
+}
+
+#[test]
+fn non_ascii_oid_is_not_a_pointer() {
+    let root = temp_dir("lfs-non-ascii");
+    fs::create_dir_all(root.join(".git")).unwrap();
+    write(&root, "data.txt", pointer("aéaaaa", 6));
+
+    let document = run_in(&root, &["--lfs-fetch"]);
+    assert!(document.contains("oid sha256:aéaaaa"), "{}", document);
+    assert!(!document.contains("LFS-объект"), "{}", document);
+
+    fs::remove_dir_all(root).unwrap();
+}
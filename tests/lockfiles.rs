@@ -0,0 +1,57 @@
+use std::fs;
+
+mod common;
+use common::{run_in, temp_dir, write};
+
+const CARGO_LOCK: &str = r#"version = 4
+
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = ["core", "shared", "serde"]
+
+[[package]]
+name = "core"
+version = "0.1.0"
+dependencies = ["regex"]
+
+[[package]]
+name = "shared"
+version = "0.3.0"
+dependencies = ["log"]
+
+[[package]]
+name = "serde"
+version = "1.0.200"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "regex"
+version = "1.10.4"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "log"
+version = "0.4.21"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#;
+
+#[test]
+fn cargo_lock_path_dependency_outside_workspace_is_a_package() {
+    let root = temp_dir("cargo-workspace");
+    write(&root, "Cargo.toml", "[package]\nname = \"app\"\n\n[workspace]\nmembers = [\"crates/*\"]\nexclude = [\"crates/old\"]\n");
+    write(&root, "crates/core/Cargo.toml", "[package]\nname = \"core\"\n");
+    write(&root, "crates/old/Cargo.toml", "[package]\nname = \"old\"\n");
+    write(&root, "Cargo.lock", CARGO_LOCK);
+
+    let document = run_in(&root, &[]);
+    // shared подключен по пути вне рабочего пространства: он пакет, а не член, и его log не прямая зависимость
+    assert!(document.contains("Всего пакетов: 4\n"), "{}", document);
+    assert!(
+        document.contains("Прямые зависимости (3):\n\n- `regex` 1.10.4\n- `serde` 1.0.200\n- `shared` 0.3.0\n"),
+        "{}",
+        document
+    );
+
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,42 @@
+use std::fs;
+use std::path::Path;
+
+mod common;
+use common::{proj2tree, success, temp_dir, write};
+
+fn run_in(dir: &Path, args: &[&str], rust_log: &str) -> String {
+    let output = success(proj2tree(dir).args(["-p", "-G"]).args(args).env("RUST_LOG", rust_log));
+    String::from_utf8(output.stderr).unwrap()
+}
+
+#[test]
+fn json_events_carry_directory_spans_from_walker_threads() {
+    let root = temp_dir("json");
+    for dir in ["a", "b", "c", "d"] {
+        write(&root, &format!("{}/nested/debug.log", dir), "log\n");
+    }
+
+    let stderr = run_in(&root, &["--log-format", "json", "--threads", "4"], "warn,proj2tree::skip=debug");
+    let events: Vec<&str> = stderr.lines().filter(|line| line.contains("debug.log")).collect();
+    assert_eq!(events.len(), 4, "{}", stderr);
+    for dir in ["a", "b", "c", "d"] {
+        let expected = format!(
+            "\"span\":{{\"directory\":\"./{0}/nested\"}},\"spans\":[{{\"directory\":\".\"}},{{\"directory\":\"./{0}\"}},{{\"directory\":\"./{0}/nested\"}}]",
+            dir
+        );
+        assert!(events.iter().any(|event| event.contains(&expected)), "{}", stderr);
+    }
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn pretty_debug_events_name_their_directory() {
+    let root = temp_dir("pretty");
+    write(&root, "src/debug.log", "log\n");
+
+    let stderr = run_in(&root, &[], "proj2tree::skip=debug");
+    assert!(stderr.contains("[DEBUG proj2tree::skip] ./src/debug.log пропущен: exclude_files (в ./src)"), "{}", stderr);
+
+    fs::remove_dir_all(root).unwrap();
+}
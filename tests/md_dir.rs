@@ -0,0 +1,37 @@
+use std::fs;
+use std::path::Path;
+
+mod common;
+use common::{proj2tree, success, temp_dir, write};
+
+fn run_in(dir: &Path, args: &[&str]) {
+    success(proj2tree(dir).arg("-G").args(args));
+}
+
+#[test]
+fn pages_mirror_the_directory_structure() {
+    let root = temp_dir("pages");
+    write(&root, "src/main.rs", "fn main() {}\n");
+    write(&root, "docs/my notes.md", "# Notes\n");
+    write(&root, "README.md", "hello\n");
+
+    run_in(&root, &["--format", "md-dir", "wiki"]);
+    let wiki = root.join("wiki");
+
+    let index = fs::read_to_string(wiki.join("index.md")).unwrap();
+    assert!(index.contains("- [`src/main.rs`](<src/main.rs.md>)\n"), "{}", index);
+    assert!(index.contains("- [`docs/my notes.md`](<docs/my%20notes.md.md>)\n"), "{}", index);
+    assert!(index.contains("- [`README.md`](<README.md.md>)\n"), "{}", index);
+    // Выходная директория внутри проекта не попадает в дерево
+    assert!(!index.contains("wiki"), "{}", index);
+
+    let page = fs::read_to_string(wiki.join("src/main.rs.md")).unwrap();
+    assert!(page.starts_with("# `src/main.rs`\n\n"), "{}", page);
+    assert!(page.contains("[index.md](../index.md)"), "{}", page);
+    assert!(page.contains("```rust\nfn main() {}\n```"), "{}", page);
+
+    let page = fs::read_to_string(wiki.join("README.md.md")).unwrap();
+    assert!(page.contains("[index.md](index.md)"), "{}", page);
+
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,49 @@
+use std::fs;
+
+mod common;
+use common::{run_in, temp_dir, write};
+
+const CONFLICT: &str = "fn main() {\n<<<<<<< HEAD\n    ours();\n=======\n    theirs();\n>>>>>>> feature\n}\n";
+
+#[test]
+fn conflicts_and_leftovers_are_listed_before_the_tree() {
+    let root = temp_dir("listed");
+    write(&root, "src/main.rs", CONFLICT);
+    write(&root, "src/lib.rs", CONFLICT.repeat(2));
+    write(&root, "src/main.rs.orig", "fn main() {}\n");
+    write(&root, "fix.rej", "@@ -1 +1 @@\n");
+    write(&root, "src/util_BACKUP_4242.rs", "fn util() {}\n");
+    write(&root, "clean.rs", "fn clean() {}\n");
+
+    let output = run_in(&root, &[]);
+    let section = output.find("## ⚠️ Незавершенное слияние").expect(&output);
+    assert!(section < output.find("## Дерево").unwrap(), "{}", output);
+    assert!(output.contains("- `fix.rej` - файл, оставшийся после слияния или применения патча\n"), "{}", output);
+    assert!(output.contains("- `src/lib.rs` - маркеры конфликта: 2 (первый в строке 2)\n"), "{}", output);
+    assert!(output.contains("- `src/main.rs` - маркеры конфликта: 1 (первый в строке 2)\n"), "{}", output);
+    assert!(output.contains("- `src/main.rs.orig` - файл, оставшийся"), "{}", output);
+    assert!(output.contains("- `src/util_BACKUP_4242.rs` - файл, оставшийся"), "{}", output);
+    assert!(!output.contains("- `clean.rs` -"), "{}", output);
+
+    let output = run_in(&root, &["--doc-lang", "en"]);
+    assert!(output.contains("## ⚠️ Unresolved merge"), "{}", output);
+    assert!(output.contains("- `src/main.rs` - conflict markers: 1 (first on line 2)\n"), "{}", output);
+
+    let output = run_in(&root, &["--no-merge-check"]);
+    assert!(!output.contains("⚠️"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn setext_headings_and_quoted_markers_are_not_conflicts() {
+    let root = temp_dir("clean");
+    write(&root, "README.md", "Title\n=======\n\n> quote\n");
+    write(&root, "notes.txt", "<<<<<<<< eight is not a marker\n>>>>>>>\n");
+    write(&root, "half.txt", "<<<<<<< HEAD\nno closing marker\n");
+
+    let output = run_in(&root, &[]);
+    assert!(!output.contains("⚠️"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
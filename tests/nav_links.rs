@@ -0,0 +1,98 @@
+use std::fs;
+use std::path::PathBuf;
+
+mod common;
+use common::{proj2tree, run_in, temp_dir, write};
+
+fn project(name: &str) -> PathBuf {
+    let root = temp_dir(name);
+    write(&root, "a.rs", "fn a() {}\n");
+    write(&root, "b.rs", "fn b() {}\n");
+    write(&root, "c.rs", "fn c() {}\n");
+    root
+}
+
+#[test]
+fn file_sections_link_to_tree_and_neighbours() {
+    let root = project("markdown");
+
+    let output = run_in(&root, &["--nav-links"]);
+    assert!(output.contains("## Дерево файлов\n<a id=\"file-tree\"></a>\n"), "{}", output);
+    assert!(
+        output.contains("### `a.rs`\n<a id=\"a-rs\"></a>\n\n[⬆ К дереву](#file-tree) · [`b.rs` →](#b-rs)\n\n```rust\n"),
+        "{}",
+        output
+    );
+    assert!(
+        output.contains("```\n\n[⬆ К дереву](#file-tree) · [← `a.rs`](#a-rs) · [`c.rs` →](#c-rs)\n"),
+        "{}",
+        output
+    );
+    assert!(output.contains("[⬆ К дереву](#file-tree) · [← `b.rs`](#b-rs)\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn document_is_unchanged_without_flag() {
+    let root = project("default");
+
+    let output = run_in(&root, &[]);
+    assert!(!output.contains("file-tree"), "{}", output);
+    assert!(!output.contains("К дереву"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn tree_link_is_omitted_without_tree() {
+    let root = project("no-tree");
+
+    let output = run_in(&root, &["--nav-links", "-T"]);
+    assert!(!output.contains("К дереву"), "{}", output);
+    assert!(output.contains("[`b.rs` →](#b-rs)\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn file_named_like_tree_anchor_gets_suffix() {
+    let root = temp_dir("collision");
+    write(&root, "file-tree", "text\n");
+
+    let output = run_in(&root, &["--nav-links"]);
+    assert!(output.contains("<a id=\"file-tree-1\"></a>"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn unpack_skips_navigation_lines() {
+    let root = project("unpack");
+    let document = run_in(&root, &["--nav-links"]);
+    fs::write(root.join("snapshot.md"), document).unwrap();
+
+    let output = proj2tree(&root)
+        .args(["unpack", "snapshot.md", "-d", "out"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(fs::read_to_string(root.join("out/b.rs")).unwrap(), "fn b() {}\n");
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn html_sections_have_navigation() {
+    let root = project("html");
+
+    let output = run_in(&root, &["--nav-links", "--format", "html"]);
+    assert!(output.contains("<h2 id=\"file-tree\">"), "{}", output);
+    assert!(
+        output.contains("<nav><a href=\"#file-tree\">⬆ К дереву</a> &middot; <a href=\"#a-rs\">&larr; a.rs</a>"),
+        "{}",
+        output
+    );
+
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,77 @@
+use std::fs;
+use std::path::Path;
+
+mod common;
+use common::{proj2tree, success, temp_dir};
+
+fn run_in(dir: &Path, args: &[&str]) -> String {
+    let output = success(proj2tree(dir).args(["-p", "-G", "--no-tree"]).args(args));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn mixed_line_endings_are_normalized_and_noted() {
+    let dir = temp_dir("eol");
+    fs::write(dir.join("mixed.txt"), "one\r\ntwo\nthree\r\n").unwrap();
+    fs::write(dir.join("plain.txt"), "plain\n").unwrap();
+
+    let output = run_in(&dir, &["--normalize-eol", "lf"]);
+    assert!(output.contains("```text\none\ntwo\nthree\n```\n"), "{}", output);
+    assert!(output.contains("_Нормализовано: переводы строк смешанные → LF (2 стр.)_"), "{}", output);
+    assert_eq!(output.matches("_Нормализовано").count(), 1, "{}", output);
+
+    let output = run_in(&dir, &[]);
+    assert!(output.contains("one\r\ntwo\nthree\r\n"), "{}", output);
+    assert!(!output.contains("_Нормализовано"), "{}", output);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn tabs_expand_to_tab_stops_except_in_makefiles() {
+    let dir = temp_dir("tabs");
+    fs::write(dir.join("main.c"), "\tint x;\nab\tc\n").unwrap();
+    fs::write(dir.join("Makefile"), "all:\n\tcc main.c\n").unwrap();
+
+    let output = run_in(&dir, &["--expand-tabs", "4"]);
+    assert!(output.contains("    int x;\nab  c\n"), "{}", output);
+    assert!(output.contains("_Нормализовано: табуляция → 4 пробел. (2)_"), "{}", output);
+    assert!(output.contains("all:\n\tcc main.c\n"), "{}", output);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn lossy_read_keeps_mostly_text_files() {
+    let dir = temp_dir("lossy");
+    fs::write(dir.join("latin1.txt"), b"caf\xe9 au lait\nsecond \xff\xfe line\n").unwrap();
+
+    let output = run_in(&dir, &[]);
+    assert!(output.contains("[Не удалось прочитать файл]"), "{}", output);
+
+    let output = run_in(&dir, &["--lossy-read"]);
+    assert!(output.contains("```text\ncaf\u{fffd} au lait\nsecond \u{fffd}\u{fffd} line\n```\n"), "{}", output);
+    assert!(output.contains("_Некорректные байты UTF-8 заменены на U+FFFD: 3_"), "{}", output);
+    assert!(!output.contains("[Не удалось прочитать файл]"), "{}", output);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn dedent_and_wrap_long_lines_for_print() {
+    let dir = temp_dir("layout");
+    fs::write(dir.join("snippet.rs"), "    fn a() {\n\n        let x = 1;\n    }\n").unwrap();
+    fs::write(dir.join("long.txt"), format!("{}\nshort\n", "word ".repeat(12).trim_end())).unwrap();
+
+    let output = run_in(&dir, &["--dedent", "--max-width", "30"]);
+    assert!(output.contains("```rust\nfn a() {\n\n    let x = 1;\n}\n```\n"), "{}", output);
+    assert!(output.contains("_Нормализовано: общий отступ снят (4 симв.)_"), "{}", output);
+    assert!(output.contains("```text\nword word word word word word \n↪ word word word word word \n↪ word\nshort\n```\n"), "{}", output);
+    assert!(output.contains("_Нормализовано: длинные строки перенесены по ширине 30 (1)_"), "{}", output);
+
+    let output = run_in(&dir, &[]);
+    assert!(output.contains("    fn a() {\n"), "{}", output);
+    assert!(!output.contains('↪'), "{}", output);
+
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,108 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+mod common;
+use common::{proj2tree, temp_dir, write};
+
+fn run_in(dir: &Path, args: &[&str]) -> Output {
+    proj2tree(dir)
+        .arg("-p")
+        .args(args)
+        .env_remove("RUST_LOG")
+        .output()
+        .unwrap()
+}
+
+fn project(name: &str) -> PathBuf {
+    let root = temp_dir(name);
+    write(&root, "src/main.rs", "fn main() {}\n");
+    for i in 0..7 {
+        write(&root, &format!("src/broken{}.rs", i), "fn f( {\n");
+    }
+    root
+}
+
+#[test]
+fn warnings_are_summarized_once_at_the_end() {
+    let root = project("summary");
+
+    let output = run_in(&root, &["--check-syntax"]);
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(stderr.matches(".gitignore").count(), 1, "{}", stderr);
+    // Семь файлов - одна строка со списком первых путей
+    let lines: Vec<&str> = stderr.lines().filter(|line| line.contains("не разбираются")).collect();
+    assert_eq!(lines.len(), 1, "{}", stderr);
+    assert!(lines[0].contains("(7)") && lines[0].contains("src/broken0.rs"), "{}", stderr);
+    assert!(lines[0].contains("и еще 2"), "{}", stderr);
+    // Сводка выводится после завершения работы
+    let summary = stderr.find("не разбираются").unwrap();
+    assert!(stderr.find("Проанализирована директория").unwrap() < summary, "{}", stderr);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn json_report_lists_notices() {
+    let root = project("json");
+    write(&root, ".gitignore", "target/\n");
+
+    let output = run_in(&root, &["--check-syntax", "--report", "json"]);
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let report = stderr.lines().find(|line| line.starts_with("{\"schema_version\":1,\"notices\":")).expect(&stderr);
+    assert!(report.contains("\"kind\":\"syntax\",\"path\":\"src/broken3.rs\""), "{}", report);
+    assert!(report.contains("\"count\":7,\"skipped\":"), "{}", report);
+    assert!(!report.contains("no-gitignore"), "{}", report);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn fail_on_warning_fails_only_with_notices() {
+    let root = project("fail");
+
+    let output = run_in(&root, &["--check-syntax", "--fail-on-warning"]);
+    assert!(!output.status.success());
+    // Документ при этом выводится целиком
+    assert!(String::from_utf8_lossy(&output.stdout).contains("### `src/main.rs`"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--fail-on-warning"));
+
+    let clean = temp_dir("clean");
+    write(&clean, ".gitignore", "target/\n");
+    write(&clean, "main.rs", "fn main() {}\n");
+    let output = run_in(&clean, &["--check-syntax", "--fail-on-warning"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    fs::remove_dir_all(root).unwrap();
+    fs::remove_dir_all(clean).unwrap();
+}
+
+#[test]
+fn summary_breaks_down_skipped_entries_by_cause_and_rule() {
+    let root = temp_dir("skipped");
+    write(&root, ".gitignore", "*.log\nvendor/\n");
+    write(&root, "src/main.rs", "fn main() {}\n");
+    for name in ["a.log", "b.log", "src/c.log", "vendor/lib.rs", "dist/app.js", "app.pyc", ".env"] {
+        write(&root, name, "");
+    }
+
+    let output = run_in(&root, &["-C"]);
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Пропущено элементов: 7 (.gitignore: 4, exclude_dirs: 1, exclude_files: 1, скрытый файл: 1)"),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("Больше всего исключили правила: *.log (.gitignore): 3, "), "{}", stderr);
+
+    let output = run_in(&root, &["-C", "--report", "json"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let report = stderr.lines().find(|line| line.starts_with("{\"schema_version\":1,")).expect(&stderr);
+    assert!(report.contains("\"skipped\":{\"total\":7,\"by_cause\":{\".gitignore\":4,"), "{}", report);
+    assert!(report.contains("\"top_rules\":[{\"rule\":\"*.log\",\"cause\":\".gitignore\",\"count\":3}"), "{}", report);
+
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,64 @@
+use std::fs;
+use std::path::Path;
+
+mod common;
+use common::{proj2tree, success, temp_dir, write};
+
+fn run_in(dir: &Path, args: &[&str]) {
+    success(proj2tree(dir).arg("-G").args(args));
+}
+
+#[test]
+fn vault_links_notes_by_directory_structure() {
+    let root = temp_dir("vault");
+    write(&root, "src/main.rs", "fn main() {}\n");
+    write(&root, "src/image.png", [0x89, b'P', b'N', b'G', 0, 0]);
+    write(&root, "node_modules/pkg/index.js", "module.exports = 1;\n");
+    write(&root, "README.md", "hello\n");
+
+    run_in(&root, &["--format", "obsidian", "vault"]);
+    let vault = root.join("vault");
+    let name = root.file_name().unwrap().to_string_lossy().to_string();
+
+    let moc = fs::read_to_string(vault.join(format!("{} MOC.md", name))).unwrap();
+    assert!(moc.starts_with("---\ntags: [proj2tree/moc]\n---\n"), "{}", moc);
+    assert!(moc.contains("- [[README.md.md|README.md]]\n"), "{}", moc);
+    assert!(moc.contains("- node_modules/ ...\n"), "{}", moc);
+    assert!(moc.contains("- [[src.md|src/]]\n"), "{}", moc);
+    // Хранилище внутри проекта не попадает в собственные заметки
+    assert!(!moc.contains("[[vault"), "{}", moc);
+    assert!(!vault.join("node_modules.md").exists());
+
+    let dir = fs::read_to_string(vault.join("src.md")).unwrap();
+    assert!(dir.contains(&format!("[[{} MOC.md|{}]]", name, name)), "{}", dir);
+    assert!(dir.contains("- [[src/image.png.md|image.png]]\n- [[src/main.rs.md|main.rs]]\n"), "{}", dir);
+
+    let note = fs::read_to_string(vault.join("src/main.rs.md")).unwrap();
+    assert!(note.starts_with("---\ntags: [proj2tree/file, lang/rust]\n---\n\n# main.rs\n"), "{}", note);
+    assert!(note.contains("[[src.md|src/]]"), "{}", note);
+    assert!(note.contains("```rust\nfn main() {}\n```"), "{}", note);
+
+    let image = fs::read_to_string(vault.join("src/image.png.md")).unwrap();
+    assert!(!image.contains("```"), "{}", image);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn note_paths_and_aliases_are_escaped() {
+    let root = temp_dir("vault-names");
+    write(&root, "docs/a|b#c^d]]e.md", "text\n");
+    write(&root, "docs/[draft]: v1.md", "draft\n");
+
+    run_in(&root, &["--format", "obsidian", "vault"]);
+    let vault = root.join("vault");
+
+    let dir = fs::read_to_string(vault.join("docs.md")).unwrap();
+    // Цель ссылки без []|#^\: , псевдоним - с экранированными '|' и ']]'
+    assert!(dir.contains("- [[docs/_draft__ v1.md.md|[draft]: v1.md]]\n"), "{}", dir);
+    assert!(dir.contains("- [[docs/a_b_c_d__e.md.md|a\\|b#c^d] ]e.md]]\n"), "{}", dir);
+    assert!(fs::read_to_string(vault.join("docs/a_b_c_d__e.md.md")).unwrap().contains("text\n"));
+    assert!(fs::read_to_string(vault.join("docs/_draft__ v1.md.md")).unwrap().contains("draft\n"));
+
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,119 @@
+use std::fs;
+use std::path::Path;
+use std::process::Output;
+
+mod common;
+use common::{proj2tree, success, temp_dir, write};
+
+fn run_in(dir: &Path, args: &[&str]) -> Output {
+    success(proj2tree(dir).arg("-G").args(args))
+}
+
+#[test]
+fn output_in_excluded_directory_is_stable_across_runs() {
+    let root = temp_dir("excluded");
+    write(&root, "src/main.rs", "fn main() {}\n");
+    write(&root, "build/app.o", "obj\n");
+
+    let first = run_in(&root, &["--count-skipped", "-o", "build/tree.md"]);
+    let first_document = fs::read_to_string(root.join("build/tree.md")).unwrap();
+    run_in(&root, &["--count-skipped", "-o", "build/tree.md"]);
+    let second_document = fs::read_to_string(root.join("build/tree.md")).unwrap();
+
+    assert_eq!(first_document, second_document);
+    assert!(first_document.contains("├── build/ ... (файлов: 1)\n"), "{}", first_document);
+    let stderr = String::from_utf8_lossy(&first.stderr);
+    assert!(stderr.contains("записывается в исключенную директорию"), "{}", stderr);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn output_is_excluded_by_resolved_path() {
+    let root = temp_dir("resolved");
+    write(&root, "src/main.rs", "fn main() {}\n");
+    let output = root.join("docs/../snapshot.md");
+    fs::create_dir_all(root.join("docs")).unwrap();
+
+    run_in(&root, &["-o", &output.to_string_lossy()]);
+    run_in(&root, &["--show-skipped-files", "-o", &output.to_string_lossy()]);
+    let document = fs::read_to_string(root.join("snapshot.md")).unwrap();
+    assert!(!document.contains("snapshot.md"), "{}", document);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn output_outside_excluded_directories_has_no_warning() {
+    let root = temp_dir("plain");
+    write(&root, "src/main.rs", "fn main() {}\n");
+
+    let output = run_in(&root, &["-o", "tree.md"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("исключенную директорию"), "{}", stderr);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn output_is_replaced_without_leaving_temporary_files() {
+    let root = temp_dir("atomic");
+    write(&root, "src/main.rs", "fn main() {}\n");
+    write(&root, "tree.md", "old\n");
+
+    run_in(&root, &["--show-skipped-files"]);
+    let document = fs::read_to_string(root.join("tree.md")).unwrap();
+    assert!(document.contains("main.rs"), "{}", document);
+    assert!(!document.contains(".tmp"), "{}", document);
+
+    let names: Vec<String> = fs::read_dir(&root)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    assert!(names.iter().all(|name| !name.ends_with(".tmp")), "{:?}", names);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn failed_replace_removes_temporary_file() {
+    let root = temp_dir("atomic-failed");
+    write(&root, "src/main.rs", "fn main() {}\n");
+    write(&root, "out/keep.txt", "keep\n");
+
+    let output = proj2tree(&root)
+        .args(["-G", "-o", "out"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+
+    let names: Vec<String> = fs::read_dir(&root)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    assert!(names.iter().all(|name| !name.ends_with(".tmp")), "{:?}", names);
+    assert_eq!(fs::read_to_string(root.join("out/keep.txt")).unwrap(), "keep\n");
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn symlinked_output_keeps_link_and_permissions() {
+    use std::os::unix::fs::{PermissionsExt, symlink};
+
+    let root = temp_dir("symlinked");
+    write(&root, "src/main.rs", "fn main() {}\n");
+    write(&root, "docs/tree.md", "old\n");
+    fs::set_permissions(root.join("docs/tree.md"), fs::Permissions::from_mode(0o640)).unwrap();
+    symlink("docs/tree.md", root.join("tree.md")).unwrap();
+
+    run_in(&root, &["-o", "tree.md"]);
+    assert!(fs::symlink_metadata(root.join("tree.md")).unwrap().file_type().is_symlink());
+    let document = fs::read_to_string(root.join("docs/tree.md")).unwrap();
+    assert!(document.contains("fn main() {}"), "{}", document);
+    let mode = fs::metadata(root.join("docs/tree.md")).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o640);
+
+    fs::remove_dir_all(root).unwrap();
+}
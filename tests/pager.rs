@@ -0,0 +1,23 @@
+use std::fs;
+
+mod common;
+use common::{proj2tree, success, temp_dir, write};
+
+// Вывод в канал - не терминал: пейджер не запускается, документ целиком идет в stdout.
+// Пейджером назначена команда, которая оставила бы след, если бы ее запустили
+#[test]
+fn pager_is_skipped_when_stdout_is_not_a_terminal() {
+    let root = temp_dir("pager");
+    write(&root, "main.rs", "fn main() {}\n");
+    let marker = root.join("pager-started");
+    let pager = format!("touch {}", marker.display());
+
+    for variable in ["PROJ2TREE_PAGER", "PAGER"] {
+        let output = success(proj2tree(&root).args(["-p", "-G"]).env_remove("PROJ2TREE_PAGER").env(variable, &pager));
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains("### `main.rs`\n\n```rust\nfn main() {}\n```\n"), "{}", stdout);
+        assert!(!marker.exists(), "{} started the pager", variable);
+    }
+
+    fs::remove_dir_all(root).unwrap();
+}
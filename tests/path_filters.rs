@@ -0,0 +1,90 @@
+use std::fs;
+use std::path::Path;
+use std::process::Output;
+
+mod common;
+use common::{proj2tree, run_in, temp_dir, write};
+
+fn run(dir: &Path, args: &[&str]) -> Output {
+    proj2tree(dir)
+        .args(["-p", "-G"])
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn max_path_depth_collapses_deep_directories() {
+    let root = temp_dir("depth");
+    write(&root, "top.txt", "top\n");
+    write(&root, "a/mid.txt", "mid\n");
+    write(&root, "a/b/deep.txt", "deep\n");
+
+    let output = run_in(&root, &["--max-path-depth", "1"]);
+    assert!(output.contains("a/ ..."), "{}", output);
+    assert!(output.contains("### `top.txt`"), "{}", output);
+    assert!(!output.contains("mid.txt"), "{}", output);
+
+    let output = run_in(&root, &["--max-path-depth", "2"]);
+    assert!(output.contains("b/ ..."), "{}", output);
+    assert!(output.contains("### `a/mid.txt`"), "{}", output);
+    assert!(!output.contains("deep.txt"), "{}", output);
+
+    // Глубина считается от целевой директории, а не от текущей
+    let output = run_in(&root, &["a", "--max-path-depth", "1"]);
+    assert!(output.contains("b/ ..."), "{}", output);
+    assert!(output.contains("mid.txt"), "{}", output);
+
+    assert!(!run(&root, &["--max-path-depth", "0"]).status.success());
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn owner_keeps_only_own_entries() {
+    use std::os::unix::fs::MetadataExt;
+
+    let root = temp_dir("owner");
+    write(&root, "mine.txt", "mine\n");
+    write(&root, "sub/inner.txt", "inner\n");
+    let uid = fs::metadata(root.join("mine.txt")).unwrap().uid().to_string();
+
+    let output = run_in(&root, &["--owner", &uid]);
+    assert!(output.contains("### `mine.txt`"), "{}", output);
+    assert!(output.contains("### `sub/inner.txt`"), "{}", output);
+
+    // Чужие директории сворачиваются, чужие файлы пропускаются
+    let other = if uid == "4242" { "4243" } else { "4242" };
+    let output = run_in(&root, &["--owner", other]);
+    assert!(output.contains("sub/ ..."), "{}", output);
+    assert!(!output.contains("mine.txt"), "{}", output);
+
+    let output = run(&root, &["--owner", "no-such-user-proj2tree"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no-such-user-proj2tree"));
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn fuzzy_filter_keeps_matching_files_and_their_directories() {
+    let root = temp_dir("fuzzy-filter");
+    write(&root, "src/auth/request_handler.rs", "// handler\n");
+    write(&root, "src/auth/mod.rs", "// mod\n");
+    write(&root, "src/db/pool.rs", "// pool\n");
+    write(&root, "Документы/Отчеты/2024.md", "отчет\n");
+
+    let output = run_in(&root, &["--filter", "auth handler"]);
+    assert!(output.contains("request_handler.rs"), "{}", output);
+    assert!(!output.contains("mod.rs"), "{}", output);
+    assert!(!output.contains("db/"), "{}", output);
+    assert!(!output.contains("Документы"), "{}", output);
+
+    let output = run_in(&root, &["--filter", "отчет"]);
+    assert!(output.contains("Документы/\n"), "{}", output);
+    assert!(output.contains("2024.md"), "{}", output);
+    assert!(!output.contains("src/"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,38 @@
+use std::fs;
+
+mod common;
+use common::{run_in, temp_dir, write};
+
+#[test]
+fn absolute_paths_in_tree_and_headings() {
+    let root = temp_dir("absolute");
+    write(&root, "src/main.rs", "fn main() {}\n");
+    let root_display = root.to_string_lossy().to_string();
+
+    let output = run_in(&root, &["--paths", "absolute"]);
+    assert!(output.contains(&format!("```\n{}/\n└── src/\n    └── main.rs\n```", root_display)), "{}", output);
+    assert!(output.contains(&format!("### `{}/src/main.rs`", root_display)), "{}", output);
+
+    let output = run_in(&root, &["--paths", "absolute", "--flat", "-C"]);
+    assert!(output.contains(&format!("{}/src/\n{}/src/main.rs\n", root_display, root_display)), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn git_root_relative_paths_for_subdirectory() {
+    let root = temp_dir("git-root");
+    fs::create_dir_all(root.join(".git")).unwrap();
+    write(&root, "crates/core/src/lib.rs", "pub fn core() {}\n");
+
+    let output = run_in(&root.join("crates/core"), &["--paths", "from-git-root", "--nest-headings"]);
+    assert!(output.contains("```\ncrates/core/\n└── src/\n    └── lib.rs\n```"), "{}", output);
+    assert!(output.contains("`crates/core/src/`"), "{}", output);
+    assert!(output.contains("`crates/core/src/lib.rs`"), "{}", output);
+
+    // Пути по умолчанию не меняются
+    let output = run_in(&root.join("crates/core"), &[]);
+    assert!(output.contains("### `src/lib.rs`"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
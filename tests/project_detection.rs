@@ -0,0 +1,77 @@
+use std::fs;
+
+mod common;
+use common::{run_in, temp_dir, write};
+
+// Встроенный exclude_dirs уже содержит target/, node_modules/ и т.п., поэтому в фикстурах
+// он очищается: иначе не видно, что директорию исключило именно определение проекта
+fn fixture(name: &str, manifest: &str, build_dir: &str) -> std::path::PathBuf {
+    let root = temp_dir(name);
+    write(&root, ".proj2tree.toml", "exclude_dirs = []\n");
+    write(&root, manifest, "\n");
+    write(&root, &format!("{}/artifact.txt", build_dir), "built\n");
+    write(&root, "src/lib.txt", "source\n");
+    root
+}
+
+#[test]
+fn build_dirs_of_each_ecosystem_are_excluded() {
+    let cases = [
+        ("Cargo.toml", "target"),
+        ("package.json", "node_modules"),
+        ("go.mod", "vendor"),
+        ("pom.xml", "target"),
+        ("build.gradle.kts", "build"),
+        ("pyproject.toml", "venv"),
+        ("composer.json", "vendor"),
+    ];
+
+    for (manifest, build_dir) in cases {
+        let root = fixture(&format!("detect-{}", manifest), manifest, build_dir);
+
+        let output = run_in(&root, &[]);
+        assert!(output.contains(&format!("── {}/ ...\n", build_dir)), "{}: {}", manifest, output);
+        assert!(!output.contains("built\n"), "{}: {}", manifest, output);
+        assert!(output.contains("source\n"), "{}: {}", manifest, output);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
+
+// Манифест в директории первого уровня (монорепозиторий) тоже определяет экосистему
+#[test]
+fn manifests_one_level_down_are_detected() {
+    let root = fixture("detect-monorepo", "crates/core/Cargo.toml", "target");
+    write(&root, "web/package.json", "{}\n");
+    write(&root, "web/node_modules/left-pad/index.js", "module.exports = 1;\n");
+
+    let output = run_in(&root, &[]);
+    assert!(output.contains("node_modules/ ...\n"), "{}", output);
+    assert!(!output.contains("module.exports"), "{}", output);
+    // Cargo.toml лежит глубже первого уровня - target/ остается
+    assert!(output.contains("built\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn no_auto_exclude_keeps_build_dirs() {
+    let root = fixture("detect-disabled", "Cargo.toml", "target");
+
+    let output = run_in(&root, &["--no-auto-exclude"]);
+    assert!(output.contains("└── target/\n    └── artifact.txt\n"), "{}", output);
+    assert!(output.contains("built\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+// Без манифестов ничего не добавляется
+#[test]
+fn unknown_projects_exclude_nothing() {
+    let root = fixture("detect-none", "notes.txt", "target");
+
+    let output = run_in(&root, &[]);
+    assert!(output.contains("built\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,60 @@
+use std::fs;
+
+mod common;
+use common::{run_in, temp_dir, write};
+
+fn headings(output: &str) -> Vec<&str> {
+    output.lines().filter(|line| line.starts_with("### ")).collect()
+}
+
+#[test]
+fn quick_shows_important_files_in_order() {
+    let root = temp_dir("order");
+    write(&root, "README.md", "# App\n");
+    write(&root, "Cargo.toml", "[package]\nname = \"app\"\n");
+    write(&root, "config.yaml", "port: 8080\n");
+    write(&root, "src/main.rs", "fn main() {}\n");
+    write(&root, "src/util.rs", "pub fn util() {}\n");
+    write(&root, "src/big.rs", "// padding\n".repeat(2000));
+    write(&root, "docs/guide.md", "guide\n");
+    write(&root, "scripts/run.sh", "echo run\n");
+    write(&root, "src/a/b/deep.rs", "fn deep() {}\n");
+
+    let output = run_in(&root, &["--quick"]);
+    assert_eq!(
+        headings(&output),
+        [
+            "### `README.md`",
+            "### `Cargo.toml`",
+            "### `src/main.rs`",
+            "### `config.yaml`",
+            "### `src/util.rs`",
+        ],
+        "{}",
+        output
+    );
+    assert!(output.contains("_Быстрый обзор (--quick): содержимое 5 из 8 файлов, остальные показаны только в дереве_"), "{}", output);
+    // Остальное видно в дереве, глубже третьего уровня - свернуто
+    assert!(output.contains("guide.md"), "{}", output);
+    assert!(output.contains("big.rs"), "{}", output);
+    assert!(output.contains("b/ ..."), "{}", output);
+
+    let output = run_in(&root, &[]);
+    assert!(headings(&output).contains(&"### `docs/guide.md`"), "{}", output);
+    assert!(!output.contains("--quick"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn quick_respects_the_budget() {
+    let root = temp_dir("budget");
+    for i in 0..10 {
+        write(&root, &format!("src/module{}.rs", i), "x".repeat(10 * 1024));
+    }
+
+    let output = run_in(&root, &["--quick"]);
+    assert_eq!(headings(&output).len(), 6, "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
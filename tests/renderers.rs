@@ -0,0 +1,113 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+mod common;
+use common::{proj2tree, success, temp_dir, write};
+
+fn run_in(dir: &Path, args: &[&str]) -> String {
+    let output = success(proj2tree(dir).arg("-G").args(args));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn project(name: &str) -> PathBuf {
+    let root = temp_dir(name);
+    write(&root, "src/main.rs", "fn main() { if 1 < 2 {} }\n");
+    write(&root, ".env", "TOKEN=secret\n");
+    write(&root, "node_modules/pkg/index.js", "module.exports = 1;\n");
+    root
+}
+
+#[test]
+fn html_is_a_single_escaped_document() {
+    let root = project("html");
+
+    let output = run_in(&root, &["-p", "--format", "html", "--title", "A & B"]);
+    assert!(output.starts_with("<!DOCTYPE html>"), "{}", output);
+    assert!(output.contains("<title>A &amp; B</title>"), "{}", output);
+    assert!(output.contains("├── node_modules/ ...\n└── src/\n    └── main.rs\n</pre>"), "{}", output);
+    assert!(output.contains("<h3 id=\"src-main-rs\"><code>src/main.rs</code></h3>"), "{}", output);
+    assert!(output.contains("<code class=\"language-rust\">fn main() { if 1 &lt; 2 {} }\n</code>"), "{}", output);
+    assert!(output.trim_end().ends_with("</html>"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn html_theme_embeds_stylesheet_and_highlighting() {
+    let root = project("theme");
+
+    let output = run_in(&root, &["-p", "--format", "html", "--theme", "auto"]);
+    assert!(output.contains("@media (prefers-color-scheme:dark)"), "{}", output);
+    assert!(output.contains(".hl-keyword{"), "{}", output);
+    let keyword = |word: &str| format!("<span class=\"hl-keyword\">{}</span>", word);
+    assert!(output.contains(&format!("{} main() {{ {}", keyword("fn"), keyword("if"))), "{}", output);
+    assert!(output.contains("<span class=\"hl-number\">1</span> &lt; <span class=\"hl-number\">2</span>"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn custom_css_replaces_builtin_style() {
+    let root = project("css");
+    write(&root, "brand.css", "body{font-family:Corporate}.hl-keyword{color:#123456}</style>");
+
+    let output = run_in(&root, &["-p", "--format", "html", "--css", "brand.css", "--only-ext", "rs"]);
+    assert!(output.contains("<style>body{font-family:Corporate}.hl-keyword{color:#123456}<\\/style></style>"), "{}", output);
+    assert!(!output.contains("#f6f8fa"), "{}", output);
+    assert!(output.contains("<span class=\"hl-keyword\">fn</span>"), "{}", output);
+
+    let output = run_in(&root, &["-p", "--format", "html", "--theme", "dark", "--css", "brand.css", "--only-ext", "rs"]);
+    let theme = output.find("#0d1117").unwrap();
+    let custom = output.find("font-family:Corporate").unwrap();
+    assert!(theme < custom, "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn json_has_tree_and_files() {
+    let root = project("json");
+
+    let output = run_in(&root, &["-p", "--format", "json", "--only-ext", "rs"]);
+    assert!(output.starts_with("{\"schema_version\":1,\"root\":"), "{}", output);
+    assert!(output.contains("{\"type\":\"collapsed\",\"path\":\"node_modules\",\"files\":null}"), "{}", output);
+    assert!(
+        output.contains("{\"path\":\"src/main.rs\",\"language\":\"rust\",\"content\":\"fn main() { if 1 < 2 {} }\\n\"}"),
+        "{}",
+        output
+    );
+    assert!(output.trim_end().ends_with("]}"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn default_output_is_named_after_the_format() {
+    let root = project("default");
+
+    run_in(&root, &["--format", "html"]);
+    let html = fs::read_to_string(root.join("tree.html")).unwrap();
+    assert!(html.contains("<code>src/main.rs</code>"), "{}", html);
+
+    // Предыдущий результат не попадает в следующий
+    run_in(&root, &["--format", "html"]);
+    let html = fs::read_to_string(root.join("tree.html")).unwrap();
+    assert!(!html.contains("tree.html"), "{}", html);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn unknown_format_is_rejected_before_writing() {
+    let root = project("unknown");
+
+    let output = proj2tree(&root)
+        .args(["--format", "yaml"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("неизвестный формат вывода 'yaml'"));
+    assert!(!root.join("tree.yaml").exists());
+
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,42 @@
+use std::fs;
+
+mod common;
+use common::{run_in, temp_dir, write};
+
+// Junction на родительскую директорию не зацикливает обход и выводится свернутой
+#[cfg(windows)]
+#[test]
+fn junctions_are_not_traversed_by_default() {
+    let root = temp_dir("junction");
+    write(&root, "src/main.rs", "fn main() {}\n");
+    let status = Command::new("cmd")
+        .args(["/C", "mklink", "/J", "loop", "."])
+        .current_dir(root.join("src"))
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let output = run_in(&root, &[]);
+    assert!(output.contains("├── loop/ ...\n"), "{}", output);
+    assert_eq!(output.matches("### `src/").count(), 1, "{}", output);
+
+    fs::remove_dir(root.join("src/loop")).unwrap();
+    fs::remove_dir_all(root).unwrap();
+}
+
+// Вне Windows точек повторной обработки нет: ссылки на директории обходятся, как раньше
+#[cfg(unix)]
+#[test]
+fn symlinked_directories_are_still_followed() {
+    let root = temp_dir("symlink");
+    write(&root, "shared/lib.rs", "pub fn f() {}\n");
+    std::os::unix::fs::symlink("shared", root.join("linked")).unwrap();
+
+    for args in [&[][..], &["--follow-reparse-points"][..]] {
+        let output = run_in(&root, args);
+        assert!(output.contains("### `linked/lib.rs`"), "{}", output);
+        assert!(output.contains("### `shared/lib.rs`"), "{}", output);
+    }
+
+    fs::remove_dir_all(root).unwrap();
+}
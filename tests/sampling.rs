@@ -0,0 +1,52 @@
+use std::fs;
+
+mod common;
+use common::{run_in, temp_dir, write};
+
+fn rows(prefix: &str, count: usize) -> String {
+    (1..=count).map(|i| format!("{}{}\n", prefix, i)).collect()
+}
+
+#[test]
+fn data_files_keep_only_head_lines_with_row_count() {
+    let root = temp_dir("head");
+    write(&root, ".proj2tree.toml", "[sampling]\n\"*.log\" = 3\n");
+    write(&root, "logs/app.log", rows("event ", 100));
+    write(&root, "data/users.csv", format!("id,name\n{}", rows("row,", 60)));
+    write(&root, "data/small.csv", "id,name\n1,a\n");
+
+    let output = run_in(&root, &[]);
+    assert!(output.contains("```text\nevent 1\nevent 2\nevent 3\n```\n\n_Показано строк: 3 из 100 (sampling)_"), "{}", output);
+    assert!(!output.contains("event 4\n"), "{}", output);
+    assert!(output.contains("row,19\n```\n\n_Показано строк: 20 из 61 (sampling)_"), "{}", output);
+    assert!(output.contains("id,name\n1,a\n```\n"), "{}", output);
+    assert_eq!(output.matches("(sampling)").count(), 2, "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn zero_disables_builtin_sampling() {
+    let root = temp_dir("zero");
+    write(&root, ".proj2tree.toml", "[sampling]\n\"data/*.csv\" = 0\n");
+    write(&root, "data/full.csv", rows("row,", 30));
+    write(&root, "other.csv", rows("other,", 30));
+
+    let output = run_in(&root, &[]);
+    assert!(output.contains("row,30\n"), "{}", output);
+    assert!(!output.contains("other,30\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn own_exclude_files_beats_sampling() {
+    let root = temp_dir("own-exclude");
+    write(&root, ".proj2tree.toml", "exclude_files = [\"*.log\"]\n\n[sampling]\n\"*.log\" = 3\n");
+    write(&root, "logs/app.log", rows("event ", 10));
+
+    let output = run_in(&root, &[]);
+    assert!(!output.contains("event 1\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
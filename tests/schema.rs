@@ -0,0 +1,52 @@
+use std::fs;
+use std::path::Path;
+
+mod common;
+use common::{proj2tree, success, temp_dir, write};
+
+fn run_in(dir: &Path, args: &[&str]) -> (String, String) {
+    let output = success(proj2tree(dir).arg("-G").args(args));
+    (String::from_utf8(output.stdout).unwrap(), String::from_utf8(output.stderr).unwrap())
+}
+
+#[test]
+fn published_schemas_match_embedded_ones() {
+    let root = temp_dir("print");
+
+    for name in ["json", "jsonl", "index", "sidecar", "report", "scan", "anchors"] {
+        let (schema, _) = run_in(&root, &["--print-schema", name]);
+        let published = Path::new(env!("CARGO_MANIFEST_DIR")).join(format!("schemas/{}.schema.json", name));
+        assert_eq!(schema, fs::read_to_string(published).unwrap(), "{}", name);
+        assert!(schema.contains(&format!("\"$id\": \"urn:proj2tree:schema:{}:1\"", name)), "{}", schema);
+        assert!(schema.contains("\"schema_version\": { \"const\": 1 }"), "{}", schema);
+    }
+    assert!(!root.join("tree.md").exists());
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn structured_outputs_start_with_schema_version() {
+    let root = temp_dir("outputs");
+    write(&root, "src/main.rs", "fn main() {}\n");
+    write(&root, "bad.json", "{\"unterminated\": \n");
+
+    let (json, _) = run_in(&root, &["-p", "--format", "json"]);
+    assert!(json.starts_with("{\"schema_version\":1,\"root\":"), "{}", json);
+
+    let (jsonl, _) = run_in(&root, &["-p", "--format", "jsonl"]);
+    assert!(jsonl.starts_with("{\"event\":\"start\",\"schema_version\":1,"), "{}", jsonl);
+
+    run_in(&root, &["--format", "index"]);
+    let index = fs::read_to_string(root.join("tree.index.json")).unwrap();
+    assert!(index.starts_with("{\"schema_version\":1,\"version\":1,"), "{}", index);
+
+    run_in(&root, &["--sidecar", "-o", "out.md"]);
+    let sidecar = fs::read_to_string(root.join("out.md.meta.json")).unwrap();
+    assert!(sidecar.starts_with("{\"schema_version\":1,\"version\":1,"), "{}", sidecar);
+
+    let (_, report) = run_in(&root, &["-p", "--check-syntax", "--report", "json", "--only-ext", "json"]);
+    assert!(report.contains("{\"schema_version\":1,\"notices\":["), "{}", report);
+
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,45 @@
+use std::fs;
+use std::path::Path;
+
+mod common;
+use common::{proj2tree, temp_dir, write};
+
+fn run_in(dir: &Path, args: &[&str]) -> std::process::Output {
+    proj2tree(dir)
+        .args(["-G", "--format", "index"])
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn index_maps_terms_and_identifier_parts_to_lines() {
+    let root = temp_dir("terms");
+    write(&root, "README.md", "Hello world\n");
+    write(&root, "src/main.rs", "fn parseConfig() {}\nlet http_server = 1;\n// parse again\n");
+    write(&root, ".env", "API_TOKEN=supersecret\n");
+
+    let output = run_in(&root, &[]);
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let index = fs::read_to_string(root.join("tree.index.json")).unwrap();
+
+    assert!(
+        index.starts_with("{\"schema_version\":1,\"version\":1,\"files\":[{\"path\":\"README.md\",\"content_id\":\"sha256:"),
+        "{}",
+        index
+    );
+    assert!(index.contains("{\"path\":\"src/main.rs\""), "{}", index);
+    assert!(index.contains("\"hello\":[[0,[1]]]"), "{}", index);
+    assert!(index.contains("\"parse\":[[1,[1,3]]]"), "{}", index);
+    assert!(index.contains("\"parseconfig\":[[1,[1]]]"), "{}", index);
+    assert!(index.contains("\"http_server\":[[1,[2]]]"), "{}", index);
+    assert!(index.contains("\"server\":[[1,[2]]]"), "{}", index);
+    assert!(!index.contains("supersecret"), "{}", index);
+
+    // Индекс прошлого запуска не индексируется сам
+    let output = run_in(&root, &["-p"]);
+    let index = String::from_utf8(output.stdout).unwrap();
+    assert!(!index.contains("tree.index.json"), "{}", index);
+
+    fs::remove_dir_all(root).unwrap();
+}
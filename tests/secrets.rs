@@ -0,0 +1,46 @@
+use std::fs;
+
+mod common;
+use common::{run_in, temp_dir, write};
+
+#[test]
+fn env_with_certificate_is_masked() {
+    let root = temp_dir("env");
+    write(&root, ".proj2tree.toml", "dotfiles = \"include\"\n");
+    write(&root, ".env", "API_KEY=abc123\nCERT=\"-----BEGIN CERTIFICATE-----\nMIIBszCCAVmg\n-----END CERTIFICATE-----\"\n");
+
+    let document = run_in(&root, &[]);
+    assert!(document.contains("API_KEY=********"), "{}", document);
+    assert!(!document.contains("abc123"), "{}", document);
+    assert!(!document.contains("MIIBszCCAVmg"), "{}", document);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn credentials_json_is_masked() {
+    let root = temp_dir("credentials");
+    write(&root, "credentials.json", "{\n  \"client_secret\": \"s3cr3t\"\n}\n");
+    write(&root, "config.yml", "TOKEN: xyz\n");
+
+    let document = run_in(&root, &[]);
+    assert!(document.contains("\"client_secret\": \"********\""), "{}", document);
+    assert!(!document.contains("s3cr3t"), "{}", document);
+    // config.yml не входит в secret_files
+    assert!(document.contains("TOKEN: xyz"), "{}", document);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn npmrc_auth_token_is_masked() {
+    let root = temp_dir("npmrc");
+    write(&root, ".proj2tree.toml", "dotfiles = \"include\"\n");
+    write(&root, ".npmrc", "registry=https://registry.npmjs.org/\n//registry.npmjs.org/:_authToken=npm_SECRET\n");
+
+    let document = run_in(&root, &[]);
+    assert!(document.contains("//registry.npmjs.org/:********"), "{}", document);
+    assert!(!document.contains("npm_SECRET"), "{}", document);
+
+    fs::remove_dir_all(root).unwrap();
+}
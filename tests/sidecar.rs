@@ -0,0 +1,92 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+
+mod common;
+use common::{proj2tree, success, temp_dir, write};
+
+fn run_in(dir: &Path, args: &[&str]) -> Output {
+    success(proj2tree(dir).env("PROJ2TREE_THREADS", "1").arg("-G").args(args))
+}
+
+fn sha256sum(data: &[u8]) -> String {
+    let mut child = Command::new("sha256sum").stdin(Stdio::piped()).stdout(Stdio::piped()).spawn().unwrap();
+    child.stdin.take().unwrap().write_all(data).unwrap();
+    let output = child.wait_with_output().unwrap();
+    String::from_utf8(output.stdout).unwrap().split_whitespace().next().unwrap().to_string()
+}
+
+#[test]
+fn sidecar_lists_content_files_with_hashes() {
+    let root = temp_dir("files");
+    write(&root, "src/main.rs", "fn main() {}\n");
+    write(&root, "README.md", "# demo\n");
+
+    run_in(&root, &["--sidecar", "-o", "tree.md", "."]);
+    let meta = fs::read_to_string(root.join("tree.md.meta.json")).unwrap();
+
+    assert!(meta.starts_with("{\"schema_version\":1,\"version\":1,\"generator\":\"proj2tree "), "{}", meta);
+    assert!(meta.contains("\"options\":\"PROJ2TREE_THREADS=1 proj2tree -G --sidecar -o tree.md .\""), "{}", meta);
+    assert!(
+        meta.contains(&format!(
+            "{{\"path\":\"README.md\",\"size\":7,\"sha256\":\"sha256:{}\"}},{{\"path\":\"src/main.rs\",\"size\":13,\"sha256\":\"sha256:{}\"}}",
+            sha256sum(b"# demo\n"),
+            sha256sum(b"fn main() {}\n")
+        )),
+        "{}",
+        meta
+    );
+    let document = fs::read(root.join("tree.md")).unwrap();
+    assert!(meta.contains(&format!("\"output_sha256\":\"sha256:{}\"", sha256sum(&document))), "{}", meta);
+    assert!(meta.contains("\"git\":null"), "{}", meta);
+    assert!(meta.contains("\"timings\":{\"walk_ms\":"), "{}", meta);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn sidecar_records_effective_config() {
+    let root = temp_dir("config");
+    write(&root, "main.rs", "fn main() {}\n");
+    write(&root, ".proj2tree.toml", "exclude_dirs = [\"vendor\"]\ntree_only = [\"*.lock\"]\nmax_file_size = 2048\n");
+
+    run_in(&root, &["--sidecar", "-o", "tree.md", "."]);
+    let meta = fs::read_to_string(root.join("tree.md.meta.json")).unwrap();
+
+    assert!(meta.contains("\"exclude_dirs\":[\"vendor\"]"), "{}", meta);
+    assert!(meta.contains("\"max_file_size\":2048"), "{}", meta);
+    assert!(meta.contains("\"tree_only\":[\"*.lock\"]"), "{}", meta);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn sidecar_of_previous_run_is_not_in_the_snapshot() {
+    let root = temp_dir("self");
+    write(&root, "main.rs", "fn main() {}\n");
+
+    run_in(&root, &["--sidecar", "."]);
+    run_in(&root, &["--sidecar", "."]);
+    let document = fs::read_to_string(root.join("tree.md")).unwrap();
+    let meta = fs::read_to_string(root.join("tree.md.meta.json")).unwrap();
+
+    assert!(!document.contains("meta.json"), "{}", document);
+    assert!(!meta.contains("\"path\":\"tree.md"), "{}", meta);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn sidecar_is_ignored_when_printing() {
+    let root = temp_dir("print");
+    write(&root, "main.rs", "fn main() {}\n");
+
+    let output = run_in(&root, &["-p", "--sidecar", "."]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(stderr.contains("--sidecar записывается только рядом с документом в файле"), "{}", stderr);
+    assert!(!root.join("tree.md.meta.json").exists());
+
+    fs::remove_dir_all(root).unwrap();
+}
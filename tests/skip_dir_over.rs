@@ -0,0 +1,56 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+mod common;
+use common::{proj2tree, success, temp_dir, write};
+
+fn run_in(dir: &Path, args: &[&str]) -> String {
+    let output = success(proj2tree(dir).args(["-p", "-G", "-C"]).args(args));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn project(name: &str) -> PathBuf {
+    let root = temp_dir(name);
+    write(&root, "src/main.rs", b"fn main() {}\n");
+    // 3 KiB в двух уровнях вложенности: порог сравнивается с суммой, а не с отдельными файлами
+    write(&root, "samples/a.bin", [0; 1024]);
+    write(&root, "samples/raw/b.bin", [0; 2048]);
+    root
+}
+
+#[test]
+fn directories_over_the_threshold_are_collapsed() {
+    let root = project("cli");
+
+    let output = run_in(&root, &["--skip-dir-over", "2K"]);
+    assert!(output.contains("├── samples/ ...\n"), "{}", output);
+    assert!(output.contains("└── src/\n    └── main.rs\n"), "{}", output);
+
+    let output = run_in(&root, &["--skip-dir-over", "3K"]);
+    assert!(output.contains("samples/\n│   ├── a.bin\n│   └── raw/\n│       └── b.bin\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn threshold_can_be_set_in_config() {
+    let root = project("config");
+    write(&root, ".proj2tree.toml", b"skip_dir_over = \"1.5K\"\n");
+
+    let output = run_in(&root, &[]);
+    assert!(output.contains("├── samples/ ...\n"), "{}", output);
+
+    // Флаг приоритетнее конфигурации
+    let output = run_in(&root, &["--skip-dir-over", "1M"]);
+    assert!(output.contains("│   └── raw/\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn malformed_size_is_rejected() {
+    let output = Command::new(env!("CARGO_BIN_EXE_proj2tree")).args(["--skip-dir-over", "big"]).output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("200M"));
+}
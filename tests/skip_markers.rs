@@ -0,0 +1,55 @@
+use std::fs;
+use std::path::PathBuf;
+
+mod common;
+use common::{run_in, temp_dir, write};
+
+fn project(name: &str) -> PathBuf {
+    let root = temp_dir(name);
+    write(&root, "src/main.rs", "fn main() {}\n");
+    write(&root, ".env", "TOKEN=1\n");
+    write(&root, "node_modules/pkg/index.js", "module.exports = 1;\n");
+    root
+}
+
+#[test]
+fn default_output_is_unchanged() {
+    let root = project("default");
+
+    let output = run_in(&root, &[]);
+    assert!(output.contains("├── node_modules/ ...\n"), "{}", output);
+    assert!(!output.contains(".env"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn custom_marker_replaces_ellipsis() {
+    let root = project("custom");
+
+    let output = run_in(&root, &["--skip-marker", "(excluded)"]);
+    assert!(output.contains("├── node_modules/ (excluded)\n"), "{}", output);
+    assert!(!output.contains("/ ..."), "{}", output);
+
+    let output = run_in(&root, &["--skip-marker", "(excluded)", "--flat"]);
+    assert!(output.contains("node_modules/ (excluded)\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn skipped_files_stay_in_tree_with_marker() {
+    let root = project("shown");
+
+    let output = run_in(&root, &["--show-skipped-files"]);
+    assert!(output.contains("├── .env ...\n"), "{}", output);
+    // В содержимое пропущенный файл не попадает
+    assert!(!output.contains("TOKEN=1"), "{}", output);
+    assert!(output.contains("### `src/main.rs`"), "{}", output);
+
+    let output = run_in(&root, &["--show-skipped-files", "--skip-marker", "[skipped]", "--flat"]);
+    assert!(output.contains(".env [skipped]\n"), "{}", output);
+    assert!(output.contains("node_modules/ [skipped]\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
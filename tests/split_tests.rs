@@ -0,0 +1,41 @@
+use std::fs;
+
+mod common;
+use common::{run_in, temp_dir, write};
+
+#[test]
+fn tests_get_their_own_tree_and_section() {
+    let dir = temp_dir("mixed");
+    write(&dir, "src/main.rs", "fn main() {}\n");
+    write(&dir, "src/parser/tests.rs", "// unit tests\n");
+    write(&dir, "tests/cli.rs", "// integration\n");
+    write(&dir, "web/app.ts", "export const app = 1;\n");
+    write(&dir, "web/app.test.ts", "// jest\n");
+    write(&dir, "pkg/server_test.go", "// go test\n");
+
+    let output = run_in(&dir, &["--split-tests"]);
+    assert!(
+        output.contains("### Код\n\n```\n├── src/\n│   └── main.rs\n└── web/\n    └── app.ts\n```"),
+        "{}",
+        output
+    );
+    assert!(output.contains("### Тесты\n\n```\n├── pkg/\n│   └── server_test.go\n├── src/\n"), "{}", output);
+
+    let (source, tests) = output.split_once("## Содержимое тестов").unwrap();
+    assert!(source.contains("fn main() {}") && source.contains("export const app"), "{}", output);
+    for marker in ["// unit tests", "// integration", "// jest", "// go test"] {
+        assert!(tests.contains(marker) && !source.contains(marker), "{}", output);
+    }
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn project_without_tests_is_not_split() {
+    let dir = temp_dir("plain");
+    write(&dir, "src/main.rs", "fn main() {}\n");
+
+    assert_eq!(run_in(&dir, &["--split-tests"]), run_in(&dir, &[]));
+
+    fs::remove_dir_all(dir).unwrap();
+}
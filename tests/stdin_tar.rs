@@ -0,0 +1,82 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+// Минимальный ustar-архив: только записи файлов, директории достраиваются при чтении
+fn tar(files: &[(&str, &str)]) -> Vec<u8> {
+    let mut archive = Vec::new();
+    for (path, content) in files {
+        let mut header = [0u8; 512];
+        header[..path.len()].copy_from_slice(path.as_bytes());
+        header[100..107].copy_from_slice(b"0000644");
+        header[124..135].copy_from_slice(format!("{:011o}", content.len()).as_bytes());
+        header[156] = b'0';
+        header[257..263].copy_from_slice(b"ustar\0");
+        header[263..265].copy_from_slice(b"00");
+        header[148..156].fill(b' ');
+        let sum: u32 = header.iter().map(|&byte| byte as u32).sum();
+        header[148..155].copy_from_slice(format!("{:06o}\0", sum).as_bytes());
+
+        archive.extend_from_slice(&header);
+        archive.extend_from_slice(content.as_bytes());
+        archive.resize(archive.len().div_ceil(512) * 512, 0);
+    }
+    archive.resize(archive.len() + 1024, 0);
+    archive
+}
+
+fn run_with_stdin(archive: &[u8], args: &[&str]) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_proj2tree"))
+        .current_dir(std::env::temp_dir())
+        .args(["--stdin-tar", "-p"])
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(archive).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn archive_is_walked_with_the_shared_rules() {
+    let archive = tar(&[
+        (".gitignore", "generated/\n"),
+        ("src/main.rs", "fn main() {}\n"),
+        ("generated/out.rs", "// generated\n"),
+        ("node_modules/pkg/index.js", "module.exports = 1;\n"),
+        ("fixtures/a.txt", "aaaa\n"),
+        ("fixtures/b.txt", "b\n"),
+        ("fixtures/c.txt", "cc\n"),
+    ]);
+
+    let output = run_with_stdin(&archive, &["--sample-dir", "fixtures:2", "--count-skipped"]);
+
+    assert!(output.contains("fn main() {}"), "{}", output);
+    assert!(output.contains("generated/ ... (файлов: 1)"), "{}", output);
+    assert!(!output.contains("// generated"), "{}", output);
+    assert!(output.contains("node_modules/ ... (файлов: 1)"), "{}", output);
+    assert!(output.contains("b.txt") && output.contains("c.txt"), "{}", output);
+    assert!(!output.contains("a.txt"), "{}", output);
+    assert!(output.contains("… еще файлов: 1 (--sample-dir)"), "{}", output);
+}
+
+#[test]
+fn archive_document_has_the_same_sections_as_disk() {
+    let archive = tar(&[
+        ("src/main.rs", "fn main() {\n    // TODO: разобрать аргументы\n"),
+        ("tests/cli.rs", "#[test]\nfn cli() {}\n"),
+        ("Makefile", "all:\n\tcargo build\n"),
+    ]);
+
+    let output = run_with_stdin(&archive, &["--split-tests", "--build-section", "--todos", "--check-syntax", "--footer"]);
+
+    assert!(output.starts_with("# Структура проекта: архив из stdin\n"), "{}", output);
+    assert!(output.contains("### Тесты"), "{}", output);
+    assert!(output.contains("## Сборка и CI"), "{}", output);
+    assert!(output.contains("TODO: разобрать аргументы"), "{}", output);
+    assert!(output.contains("⚠️"), "{}", output);
+    assert!(output.contains("SHA-256 документа"), "{}", output);
+}
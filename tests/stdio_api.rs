@@ -0,0 +1,103 @@
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+mod common;
+use common::{temp_dir, write};
+
+// Отправляет запросы по одному на строку и возвращает ответы в том же порядке
+fn run_session(project: &Path, requests: &[&str]) -> Vec<String> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_proj2tree"))
+        .args(["-G", "--stdio-api"])
+        .arg(project)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut responses = Vec::new();
+    for request in requests {
+        writeln!(stdin, "{}", request).unwrap();
+        let mut line = String::new();
+        stdout.read_line(&mut line).unwrap();
+        responses.push(line);
+    }
+    drop(stdin);
+    assert!(child.wait().unwrap().success());
+    responses
+}
+
+#[test]
+fn stdio_api_returns_tree_model() {
+    let project = temp_dir("tree");
+    write(&project, "src/main.rs", "fn main() {}\n");
+
+    let responses = run_session(&project, &[r#"{"jsonrpc":"2.0","id":1,"method":"get_tree"}"#]);
+    assert_eq!(
+        responses[0],
+        "{\"jsonrpc\":\"2.0\",\"id\":1,\"result\":{\"tree\":[{\"type\":\"directory\",\"path\":\"src\",\"children\":[{\"type\":\"file\",\"path\":\"src/main.rs\"}]}]}}\n"
+    );
+}
+
+#[test]
+fn stdio_api_renders_subtree_and_file() {
+    let project = temp_dir("render");
+    write(&project, "src/api/handler.rs", "pub fn handle() {}\n");
+    write(&project, "src/main.rs", "fn main() {}\n");
+    write(&project, "README.md", "# Demo\n");
+
+    let responses = run_session(
+        &project,
+        &[
+            r#"{"jsonrpc":"2.0","id":1,"method":"render_subtree","params":{"path":"src/api"}}"#,
+            r#"{"jsonrpc":"2.0","id":2,"method":"render_subtree","params":{"path":"src/api","format":"html"}}"#,
+            r#"{"jsonrpc":"2.0","id":3,"method":"render_file","params":{"path":"src/main.rs"}}"#,
+        ],
+    );
+
+    assert!(responses[0].contains("\"path\":\"src/api\",\"format\":\"markdown\""), "{}", responses[0]);
+    assert!(responses[0].contains("handler.rs"), "{}", responses[0]);
+    assert!(responses[0].contains("pub fn handle() {}"), "{}", responses[0]);
+    assert!(!responses[0].contains("fn main()"), "{}", responses[0]);
+    assert!(!responses[0].contains("README.md"), "{}", responses[0]);
+
+    assert!(responses[1].contains("\"format\":\"html\""), "{}", responses[1]);
+    assert!(responses[1].contains("<html"), "{}", responses[1]);
+
+    assert!(responses[2].starts_with("{\"jsonrpc\":\"2.0\",\"id\":3,\"result\":{\"path\":\"src/main.rs\",\"markdown\":"));
+    assert!(responses[2].contains("main.rs"), "{}", responses[2]);
+    assert!(responses[2].contains("```rust\\nfn main() {}\\n```"), "{}", responses[2]);
+}
+
+#[test]
+fn stdio_api_rejects_unknown_paths() {
+    let project = temp_dir("errors");
+    write(&project, "src/main.rs", "fn main() {}\n");
+    write(&project, ".env", "TOKEN=1\n");
+
+    let responses = run_session(
+        &project,
+        &[
+            r#"{"jsonrpc":"2.0","id":1,"method":"render_subtree","params":{"path":"missing"}}"#,
+            r#"{"jsonrpc":"2.0","id":2,"method":"render_file","params":{"path":".env"}}"#,
+            r#"{"jsonrpc":"2.0","id":3,"method":"render_subtree","params":{"format":"docx"}}"#,
+        ],
+    );
+    for response in &responses {
+        assert!(response.contains("\"error\":{\"code\":-32602"), "{}", response);
+    }
+}
+
+#[test]
+fn stdio_api_rejects_deeply_nested_requests() {
+    let project = temp_dir("nested");
+    write(&project, "src/main.rs", "fn main() {}\n");
+
+    let nested = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+    let responses = run_session(&project, &[&nested, r#"{"jsonrpc":"2.0","id":2,"method":"get_tree"}"#]);
+    assert!(responses[0].contains("\"error\":{\"code\":-32700"), "{}", responses[0]);
+    assert!(responses[1].contains("\"id\":2,\"result\""), "{}", responses[1]);
+}
@@ -0,0 +1,66 @@
+#![cfg(unix)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+mod common;
+use common::{proj2tree, success, temp_dir, write};
+
+fn run_in(dir: &Path, args: &[&str]) -> Output {
+    success(proj2tree(dir).args(["-p", "-G", "-T"]).args(args))
+}
+
+// Дамп больше max_file_size: без сводки он не попал бы в документ
+fn project(name: &str, command: &str) -> PathBuf {
+    let root = temp_dir(name);
+    let config = format!("max_file_size = 100\n\n[[summarizers]]\nglob = \"*.dump\"\ncommand = \"{}\"\n", command);
+    write(&root, ".proj2tree.toml", &config);
+    let rows: String = (1..=50).map(|i| format!("INSERT INTO t VALUES ({});\n", i)).collect();
+    write(&root, "db/schema.dump", &rows);
+    write(&root, "src/main.rs", "fn main() {}\n");
+    root
+}
+
+#[test]
+fn oversized_file_is_replaced_by_command_output() {
+    let root = project("replace", "head -n 2");
+
+    let output = run_in(&root, &["--run-summarizers"]);
+    let document = String::from_utf8_lossy(&output.stdout);
+
+    let expected = "### `db/schema.dump`\n\n_Сводка команды `head -n 2` вместо содержимого (summarizers)_\n\n```text\n\
+                    INSERT INTO t VALUES (1);\nINSERT INTO t VALUES (2);\n```\n";
+    assert!(document.contains(expected), "{}", document);
+    assert!(!document.contains("VALUES (3)"), "{}", document);
+    assert!(document.contains("### `src/main.rs`"), "{}", document);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn commands_do_not_run_without_flag() {
+    let root = project("no-flag", "head -n 2");
+
+    let output = run_in(&root, &[]);
+    let document = String::from_utf8_lossy(&output.stdout);
+
+    assert!(!document.contains("schema.dump"), "{}", document);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--run-summarizers"));
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn failed_command_is_noted_and_reported() {
+    let root = project("failed", "false");
+
+    let output = run_in(&root, &["--run-summarizers"]);
+    let document = String::from_utf8_lossy(&output.stdout);
+
+    assert!(document.contains("_Сводка команды `false` не создана: exit status: 1_"), "{}", document);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("команды summarizers завершились с ошибкой (1): db/schema.dump"), "{}", stderr);
+
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,49 @@
+use std::fs;
+use std::path::Path;
+
+mod common;
+use common::{proj2tree, success, temp_dir, write};
+
+fn run_in(dir: &Path, args: &[&str]) -> String {
+    let output = success(proj2tree(dir).args(["-p", "-G", "--no-tree", "-C"]).args(args));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn annotations_in_comments_are_listed_and_exported() {
+    let root = temp_dir("section");
+    write(
+        &root,
+        "src/main.rs",
+        "fn main() {\n    // TODO(alice): handle errors, properly\n    let TODO_LIST = \"XXX\";\n    /* FIXME: leak | here */\n}\n",
+    );
+    write(&root, "run.py", "# HACK - temporary\n");
+    write(&root, "notes.txt", "TODO outside of a comment\n");
+
+    let output = run_in(&root, &["--todos", "--todos-csv", "todos.csv"]);
+    assert!(
+        output.contains(
+            "| `run.py` | 1 | HACK | temporary |\n\
+             | `src/main.rs` | 2 | TODO | (alice): handle errors, properly |\n\
+             | `src/main.rs` | 4 | FIXME | leak \\| here |\n"
+        ),
+        "{}",
+        output
+    );
+    assert!(!output.contains("notes.txt") && !output.contains("| 3 |"), "{}", output);
+
+    let csv = fs::read_to_string(root.join("todos.csv")).unwrap();
+    assert_eq!(
+        csv,
+        "path,line,marker,text\r\n\
+         run.py,1,HACK,temporary\r\n\
+         src/main.rs,2,TODO,\"(alice): handle errors, properly\"\r\n\
+         src/main.rs,4,FIXME,leak | here\r\n"
+    );
+
+    // CSV прошлого запуска не сканируется как проектный файл
+    let output = run_in(&root, &["--todos", "--todos-csv", "todos.csv"]);
+    assert!(!output.contains("todos.csv"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,23 @@
+use std::fs;
+
+mod common;
+use common::{run_in, temp_dir, write};
+
+#[test]
+fn size_report_lists_included_and_excluded_files() {
+    let root = temp_dir("top-files");
+    write(&root, "src/main.rs", "fn main() {}\n");
+    write(&root, "debug.log", "x".repeat(2048));
+    write(&root, "logo.png", [0u8; 300]);
+    write(&root, "node_modules/pkg/index.js", "y".repeat(4096));
+
+    let document = run_in(&root, &["--top-files", "5"]);
+    assert!(document.contains("| 13 B | `src/main.rs` |\n"), "{}", document);
+    // Исключенный правилами файл в дереве не остается, но его размер известен из обхода
+    assert!(document.contains("| 2.0 KB | `debug.log` | правила исключения |\n"), "{}", document);
+    assert!(document.contains("| 300 B | `logo.png` | бинарный файл |\n"), "{}", document);
+    // Свернутая директория не обходится
+    assert!(!document.contains("index.js"), "{}", document);
+
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,126 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+mod common;
+use common::{run_in, temp_dir, write};
+
+// Эталонные выводы лежат в tests/golden. После намеренного изменения формата их можно
+// обновить запуском `PROJ2TREE_UPDATE_GOLDEN=1 cargo test`
+
+// Проект, в котором последние элементы директорий пропускаются: скрытые файлы,
+// исключения по имени и выходной файл
+fn sample_project(name: &str) -> PathBuf {
+    let dir = temp_dir(name);
+    write(&dir, "Cargo.toml", "[package]\nname = \"sample\"\n");
+    write(&dir, "README.md", "# sample\n");
+    write(&dir, "docs/guide.md", "guide\n");
+    write(&dir, "docs/debug.log", "log\n");
+    write(&dir, "src/lib.rs", "pub mod parser;\n");
+    write(&dir, "src/main.rs", "fn main() {}\n");
+    write(&dir, "src/parser/mod.rs", "\n");
+    write(&dir, "src/parser/.cache", "\n");
+    write(&dir, "target/debug/sample", "\n");
+    write(&dir, "tree.md", "old snapshot\n");
+    dir
+}
+
+fn assert_golden(name: &str, actual: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden").join(name);
+
+    if std::env::var_os("PROJ2TREE_UPDATE_GOLDEN").is_some() {
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+    assert_eq!(actual, expected, "вывод отличается от эталона {}", path.display());
+}
+
+#[test]
+fn pretty_tree_uses_last_visible_sibling_connector() {
+    let dir = sample_project("pretty");
+
+    assert_golden("pretty_tree.md", &run_in(&dir, &["-C"]));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn flat_tree_lists_relative_paths() {
+    let dir = sample_project("flat");
+
+    assert_golden("flat_tree.md", &run_in(&dir, &["-C", "--flat"]));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn tree_and_contents_snapshot() {
+    let dir = sample_project("full");
+
+    assert_golden("full_snapshot.md", &run_in(&dir, &[]));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn parallel_walk_matches_single_thread() {
+    let dir = sample_project("threads");
+
+    assert_eq!(run_in(&dir, &["--threads", "4"]), run_in(&dir, &["--threads", "1"]));
+    assert_golden("full_snapshot.md", &run_in(&dir, &["--threads", "4"]));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn english_document_strings() {
+    let dir = sample_project("english");
+
+    assert_golden("full_snapshot_en.md", &run_in(&dir, &["--doc-lang", "en"]));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn max_entries_per_dir_truncates_tree_and_contents() {
+    let dir = sample_project("max-entries");
+
+    assert_golden("max_entries.md", &run_in(&dir, &["--max-entries-per-dir", "2"]));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn content_order_from_config() {
+    let dir = sample_project("content-order");
+    write(&dir, ".proj2tree.toml", "content_order = [\"README.md\", \"Cargo.toml\", \"src/*\"]\n");
+
+    let output = run_in(&dir, &["-T"]);
+    let headings: Vec<&str> = output.lines().filter(|line| line.starts_with("### ")).collect();
+    // src/* не заходит в src/parser: вложенные файлы идут после групп по алфавиту
+    assert_eq!(
+        headings,
+        [
+            "### `README.md`",
+            "### `Cargo.toml`",
+            "### `src/lib.rs`",
+            "### `src/main.rs`",
+            "### `docs/guide.md`",
+            "### `src/parser/mod.rs`",
+        ]
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn flat_tree_marks_truncated_root_without_path() {
+    let dir = sample_project("flat-max-entries");
+
+    let output = run_in(&dir, &["-C", "--flat", "--max-entries-per-dir", "2"]);
+    assert!(!output.contains("./"), "{}", output);
+    assert!(output.contains("\nREADME.md\n… и еще 3\n"), "{}", output);
+
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,51 @@
+use std::fs;
+use std::path::PathBuf;
+
+mod common;
+use common::{run_in, temp_dir, write};
+
+fn project(name: &str) -> PathBuf {
+    let root = temp_dir(name);
+    write(&root, ".proj2tree.toml", "tree_only = [\"assets/**\", \"*.snap\"]\n");
+    write(&root, "assets/data/config.json", "{\"asset\": true}\n");
+    write(&root, "src/main.rs", "fn main() {}\n");
+    write(&root, "src/__snapshots__/main.snap", "snapshot body\n");
+    root
+}
+
+#[test]
+fn matching_files_are_in_tree_without_contents() {
+    let root = project("markdown");
+
+    let output = run_in(&root, &[]);
+    assert!(output.contains("│       └── config.json\n"), "{}", output);
+    assert!(output.contains("    │   └── main.snap\n"), "{}", output);
+    assert!(output.contains("### `src/main.rs`"), "{}", output);
+    assert!(!output.contains("config.json`"), "{}", output);
+    assert!(!output.contains("asset\": true"), "{}", output);
+    assert!(!output.contains("snapshot body"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn path_patterns_are_relative_to_target_directory() {
+    let root = project("relative");
+    write(&root, "src/assets/logo.txt", "inline asset\n");
+
+    let output = run_in(&root, &[]);
+    assert!(output.contains("inline asset"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn json_output_lists_files_without_contents() {
+    let root = project("json");
+
+    let output = run_in(&root, &["--format", "json"]);
+    assert!(output.contains("fn main() {}"), "{}", output);
+    assert!(!output.contains("snapshot body"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,89 @@
+use std::fs;
+use std::path::PathBuf;
+
+mod common;
+use common::{run_in, temp_dir, write};
+
+fn project(name: &str) -> PathBuf {
+    let root = temp_dir(name);
+    // Считается блок целиком: ```rust, 17 символов содержимого с переводом строки и ``` -
+    // 29 ASCII символов, 8 токенов; у ru.txt 13 ASCII символов ограды и 6 символов кириллицы - 7
+    write(&root, "src/lib.rs", b"fn a() {}\n// 123");
+    write(&root, "src/ru.txt", "привет".as_bytes());
+    write(&root, "README.md", b"12345678");
+    root
+}
+
+#[test]
+fn files_and_directories_are_annotated() {
+    let root = project("annotated");
+
+    let output = run_in(&root, &["--tree-tokens"]);
+    assert!(output.contains("├── README.md (токенов: ~7)\n"), "{}", output);
+    assert!(output.contains("└── src/ (токенов: ~15)\n"), "{}", output);
+    assert!(output.contains("    ├── lib.rs (токенов: ~8)\n"), "{}", output);
+    assert!(output.contains("    └── ru.txt (токенов: ~7)\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn tree_is_unchanged_without_flag() {
+    let root = project("default");
+
+    let output = run_in(&root, &[]);
+    assert!(!output.contains("токенов"), "{}", output);
+    assert!(output.contains("└── src/\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn flat_tree_is_annotated() {
+    let root = project("flat");
+
+    let output = run_in(&root, &["--tree-tokens", "--flat"]);
+    assert!(output.contains("src/ (токенов: ~15)\n"), "{}", output);
+    assert!(output.contains("src/lib.rs (токенов: ~8)\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn files_without_contents_are_not_annotated() {
+    let root = project("binary");
+    write(&root, "assets/logo.png", [0x89, b'P', b'N', b'G', 0, 0, 0, 0]);
+
+    let output = run_in(&root, &["--tree-tokens"]);
+    assert!(output.contains("├── assets/\n"), "{}", output);
+    assert!(output.contains("│   └── logo.png\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn large_counts_are_abbreviated() {
+    let root = temp_dir("large");
+    write(&root, "big.txt", [b'a'; 6000]);
+
+    let output = run_in(&root, &["--tree-tokens"]);
+    assert!(output.contains("└── big.txt (токенов: ~1.5k)\n"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn counts_follow_the_embedded_block() {
+    let root = temp_dir("sampled");
+    write(&root, ".proj2tree.toml", "[sampling]\n\"*.csv\" = 2\n");
+    let rows: String = (0..1000).map(|i| format!("{},{}\n", i, i * 2)).collect();
+    write(&root, "data.csv", &rows);
+
+    // В документ попадают заголовок и две строки, а не 1000
+    let output = run_in(&root, &["--tree-tokens"]);
+    assert!(output.contains("data.csv (токенов: ~"), "{}", output);
+    assert!(!output.contains("k)"), "{}", output);
+    assert!(output.contains("0,0\n1,2\n```"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}
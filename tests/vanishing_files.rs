@@ -0,0 +1,41 @@
+use std::fs;
+use std::io::{BufRead, BufReader, Read};
+use std::process::Stdio;
+
+mod common;
+use common::{proj2tree, temp_dir};
+
+#[test]
+fn file_deleted_after_walk_is_annotated() {
+    let root = temp_dir("deleted");
+    // Содержимое a.txt намного больше буфера канала: пока тест не читает stdout,
+    // процесс стоит на записи a.txt, а список файлов уже снят
+    fs::write(root.join("a.txt"), "line of text\n".repeat(40_000)).unwrap();
+    fs::write(root.join("b.txt"), "second\n").unwrap();
+
+    let mut child = proj2tree(&root)
+        .args(["-p", "-G"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut output = String::new();
+    while !output.contains("### `a.txt`") {
+        assert!(stdout.read_line(&mut output).unwrap() > 0, "{}", output);
+    }
+    fs::remove_file(root.join("b.txt")).unwrap();
+    stdout.read_to_string(&mut output).unwrap();
+
+    let mut stderr = String::new();
+    child.stderr.take().unwrap().read_to_string(&mut stderr).unwrap();
+    assert!(child.wait().unwrap().success(), "{}", stderr);
+
+    // Дерево и содержимое описывают один и тот же список файлов
+    assert!(output.contains("├── a.txt\n└── b.txt\n"), "{}", output);
+    assert!(output.contains("### `b.txt`\n\n```\n[Файл удален во время создания документа]\n```\n"), "{}", output);
+    assert!(output.contains("`b.txt`: удален во время создания документа"), "{}", output);
+
+    fs::remove_dir_all(root).unwrap();
+}